@@ -573,18 +573,18 @@ _rtx() {
             return 0
             ;;
         rtx__activate)
-            opts="-s -q -j -r -y -v -h --shell --status --quiet --debug --install-missing --jobs --log-level --raw --yes --trace --verbose --help bash fish nu xonsh zsh"
+            opts="-s -q -j -r -y -v -h --shell --status --command-not-found --quiet --debug --install-missing --jobs --log-level --raw --yes --trace --verbose --help bash csh fish nu xonsh zsh"
             if [[ ${cur} == -* || ${COMP_CWORD} -eq 2 ]] ; then
                 COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
                 return 0
             fi
             case "${prev}" in
                 --shell)
-                    COMPREPLY=($(compgen -W "bash fish nu xonsh zsh" -- "${cur}"))
+                    COMPREPLY=($(compgen -W "bash csh fish nu xonsh zsh" -- "${cur}"))
                     return 0
                     ;;
                 -s)
-                    COMPREPLY=($(compgen -W "bash fish nu xonsh zsh" -- "${cur}"))
+                    COMPREPLY=($(compgen -W "bash csh fish nu xonsh zsh" -- "${cur}"))
                     return 0
                     ;;
                 --jobs)
@@ -1276,11 +1276,11 @@ _rtx() {
             fi
             case "${prev}" in
                 --shell)
-                    COMPREPLY=($(compgen -W "bash fish nu xonsh zsh" -- "${cur}"))
+                    COMPREPLY=($(compgen -W "bash csh fish nu xonsh zsh" -- "${cur}"))
                     return 0
                     ;;
                 -s)
-                    COMPREPLY=($(compgen -W "bash fish nu xonsh zsh" -- "${cur}"))
+                    COMPREPLY=($(compgen -W "bash csh fish nu xonsh zsh" -- "${cur}"))
                     return 0
                     ;;
                 --jobs)
@@ -2252,11 +2252,11 @@ _rtx() {
             fi
             case "${prev}" in
                 --shell)
-                    COMPREPLY=($(compgen -W "bash fish nu xonsh zsh" -- "${cur}"))
+                    COMPREPLY=($(compgen -W "bash csh fish nu xonsh zsh" -- "${cur}"))
                     return 0
                     ;;
                 -s)
-                    COMPREPLY=($(compgen -W "bash fish nu xonsh zsh" -- "${cur}"))
+                    COMPREPLY=($(compgen -W "bash csh fish nu xonsh zsh" -- "${cur}"))
                     return 0
                     ;;
                 --jobs)
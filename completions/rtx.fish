@@ -49,11 +49,13 @@ complete -c rtx -n "__fish_use_subcommand" -f -a "where" -d 'Display the install
 complete -c rtx -n "__fish_use_subcommand" -f -a "which" -d 'Shows the path that a bin name points to'
 complete -c rtx -n "__fish_use_subcommand" -f -a "render-help" -d 'internal command to generate markdown from help'
 complete -c rtx -n "__fish_use_subcommand" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
-complete -c rtx -n "__fish_seen_subcommand_from activate" -s s -l shell -d 'Shell type to generate the script for' -r -f -a "{bash	'',fish	'',nu	'',xonsh	'',zsh	''}"
+complete -c rtx -n "__fish_seen_subcommand_from activate" -s s -l shell -d 'Shell type to generate the script for' -r -f -a "{bash	'',csh	'',fish	'',nu	'',xonsh	'',zsh	''}"
 complete -c rtx -n "__fish_seen_subcommand_from activate" -s j -l jobs -d 'Number of plugins and runtimes to install in parallel
 [default: 4]' -r
 complete -c rtx -n "__fish_seen_subcommand_from activate" -l log-level -d 'Set the log output verbosity' -r
 complete -c rtx -n "__fish_seen_subcommand_from activate" -l status -d 'Show "rtx: <PLUGIN>@<VERSION>" message when changing directories'
+complete -c rtx -n "__fish_seen_subcommand_from activate" -l command-not-found -d 'Offer to run commands that are not found with an installed tool that provides them
+only supported in bash, zsh, and fish'
 complete -c rtx -n "__fish_seen_subcommand_from activate" -s q -l quiet -d 'noop'
 complete -c rtx -n "__fish_seen_subcommand_from activate" -l debug -d 'Sets log level to debug'
 complete -c rtx -n "__fish_seen_subcommand_from activate" -l install-missing -d 'Automatically install missing tools'
@@ -283,7 +285,7 @@ complete -c rtx -n "__fish_seen_subcommand_from doctor" -s y -l yes -d 'Answer y
 complete -c rtx -n "__fish_seen_subcommand_from doctor" -l trace -d 'Sets log level to trace'
 complete -c rtx -n "__fish_seen_subcommand_from doctor" -s v -l verbose -d 'Show installation output'
 complete -c rtx -n "__fish_seen_subcommand_from doctor" -s h -l help -d 'Print help (see more with \'--help\')'
-complete -c rtx -n "__fish_seen_subcommand_from env" -s s -l shell -d 'Shell type to generate environment variables for' -r -f -a "{bash	'',fish	'',nu	'',xonsh	'',zsh	''}"
+complete -c rtx -n "__fish_seen_subcommand_from env" -s s -l shell -d 'Shell type to generate environment variables for' -r -f -a "{bash	'',csh	'',fish	'',nu	'',xonsh	'',zsh	''}"
 complete -c rtx -n "__fish_seen_subcommand_from env" -s j -l jobs -d 'Number of plugins and runtimes to install in parallel
 [default: 4]' -r
 complete -c rtx -n "__fish_seen_subcommand_from env" -l log-level -d 'Set the log output verbosity' -r
@@ -340,7 +342,7 @@ complete -c rtx -n "__fish_seen_subcommand_from global" -s y -l yes -d 'Answer y
 complete -c rtx -n "__fish_seen_subcommand_from global" -l trace -d 'Sets log level to trace'
 complete -c rtx -n "__fish_seen_subcommand_from global" -s v -l verbose -d 'Show installation output'
 complete -c rtx -n "__fish_seen_subcommand_from global" -s h -l help -d 'Print help (see more with \'--help\')'
-complete -c rtx -n "__fish_seen_subcommand_from hook-env" -s s -l shell -d 'Shell type to generate script for' -r -f -a "{bash	'',fish	'',nu	'',xonsh	'',zsh	''}"
+complete -c rtx -n "__fish_seen_subcommand_from hook-env" -s s -l shell -d 'Shell type to generate script for' -r -f -a "{bash	'',csh	'',fish	'',nu	'',xonsh	'',zsh	''}"
 complete -c rtx -n "__fish_seen_subcommand_from hook-env" -s j -l jobs -d 'Number of plugins and runtimes to install in parallel
 [default: 4]' -r
 complete -c rtx -n "__fish_seen_subcommand_from hook-env" -l log-level -d 'Set the log output verbosity' -r
@@ -0,0 +1,122 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use color_eyre::eyre::Result;
+use once_cell::sync::Lazy;
+
+use crate::dirs;
+use crate::file;
+use crate::plugins::PluginName;
+
+/// maps executable names to the plugins that provide them
+/// built from the shipped list below plus a cache that is refreshed on every reshim
+pub type BinProviders = BTreeMap<String, BTreeSet<String>>;
+
+static CACHE_FILE: Lazy<PathBuf> = Lazy::new(|| dirs::CACHE.join("bin-providers.json"));
+
+#[rustfmt::skip]
+const SHIPPED_BIN_PROVIDERS: &[(&str, &str)] = &[
+    ("bun", "bun"),
+    ("bundle", "ruby"),
+    ("bunx", "bun"),
+    ("cargo", "rust"),
+    ("corepack", "node"),
+    ("deno", "deno"),
+    ("elixir", "elixir"),
+    ("erl", "erlang"),
+    ("gem", "ruby"),
+    ("go", "go"),
+    ("gofmt", "go"),
+    ("iex", "elixir"),
+    ("irb", "ruby"),
+    ("jar", "java"),
+    ("java", "java"),
+    ("javac", "java"),
+    ("kubectl", "kubectl"),
+    ("mix", "elixir"),
+    ("node", "node"),
+    ("npm", "node"),
+    ("npx", "node"),
+    ("pip", "python"),
+    ("pip3", "python"),
+    ("python", "python"),
+    ("python3", "python"),
+    ("rake", "ruby"),
+    ("ruby", "ruby"),
+    ("rustc", "rust"),
+    ("terraform", "terraform"),
+];
+
+/// lists the plugins known to provide `bin`, cached (from installed tools) first
+pub fn providers(bin: &str) -> Vec<String> {
+    let mut out: Vec<String> = read_cache()
+        .remove(bin)
+        .map(|p| p.into_iter().collect())
+        .unwrap_or_default();
+    for (b, plugin) in SHIPPED_BIN_PROVIDERS {
+        if *b == bin && !out.iter().any(|p| p == plugin) {
+            out.push(plugin.to_string());
+        }
+    }
+    out
+}
+
+/// records which plugin provides each bin so they can be looked up later
+/// even when the tool is not active in the current directory
+pub fn update_cache(bins: &[(String, PluginName)]) -> Result<()> {
+    save(add_bins(read_cache(), bins))
+}
+
+/// replaces the cache with `bins`, for when the bins of every installed tool are known
+pub fn write_cache(bins: &[(String, PluginName)]) -> Result<()> {
+    save(add_bins(BinProviders::new(), bins))
+}
+
+fn add_bins(mut providers: BinProviders, bins: &[(String, PluginName)]) -> BinProviders {
+    for (bin, plugin) in bins {
+        providers
+            .entry(bin.clone())
+            .or_default()
+            .insert(plugin.clone());
+    }
+    providers
+}
+
+fn save(providers: BinProviders) -> Result<()> {
+    file::create_dir_all(&*dirs::CACHE)?;
+    file::write(&*CACHE_FILE, serde_json::to_string(&providers)?)?;
+    Ok(())
+}
+
+/// hint to append to "not found" errors, e.g.: "node is provided by: node"
+pub fn hint(bin: &str) -> Option<String> {
+    let providers = providers(bin);
+    if providers.is_empty() {
+        return None;
+    }
+    let cmds = providers.iter().map(|p| format!("  rtx use {p}@latest"));
+    Some(format!(
+        "{bin} is provided by: {}\nInstall it with one of:\n{}",
+        providers.join(", "),
+        cmds.collect::<Vec<_>>().join("\n")
+    ))
+}
+
+pub fn read_cache() -> BinProviders {
+    file::read_to_string(&*CACHE_FILE)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shipped_providers() {
+        assert!(providers("npx").contains(&"node".to_string()));
+        assert!(providers("not-a-real-bin").is_empty());
+        assert!(hint("gofmt").unwrap().contains("rtx use go@latest"));
+    }
+}
@@ -0,0 +1,145 @@
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{bail, Result};
+use itertools::Itertools;
+
+use crate::{env, file};
+
+/// a system library a tool links against when it is compiled from source
+struct Dependency {
+    name: &'static str,
+    /// any of these headers satisfies the dependency
+    headers: &'static [&'static str],
+    /// package providing the headers, in the same order as PACKAGE_MANAGERS
+    packages: [&'static str; 4],
+}
+
+const OPENSSL: Dependency = Dependency {
+    name: "openssl",
+    headers: &["openssl/ssl.h"],
+    packages: ["libssl-dev", "openssl-devel", "openssl-dev", "openssl"],
+};
+const READLINE: Dependency = Dependency {
+    name: "readline",
+    headers: &["readline/readline.h"],
+    packages: [
+        "libreadline-dev",
+        "readline-devel",
+        "readline-dev",
+        "readline",
+    ],
+};
+const NCURSES: Dependency = Dependency {
+    name: "ncurses",
+    headers: &["ncurses.h", "curses.h", "ncursesw/ncurses.h"],
+    packages: ["libncurses-dev", "ncurses-devel", "ncurses-dev", "ncurses"],
+};
+
+/// (binary, install command)
+const PACKAGE_MANAGERS: [(&str, &str); 4] = [
+    ("apt-get", "sudo apt-get install"),
+    ("dnf", "sudo dnf install"),
+    ("apk", "sudo apk add"),
+    ("pacman", "sudo pacman -S"),
+];
+
+fn dependencies(plugin: &str) -> &'static [Dependency] {
+    match plugin {
+        "python" => &[OPENSSL, READLINE, NCURSES],
+        "ruby" => &[OPENSSL, READLINE],
+        "erlang" => &[OPENSSL, NCURSES],
+        _ => &[],
+    }
+}
+
+/// fails before compiling a tool that needs headers which are not installed, instead of
+/// partway through the build
+/// only done on linux, on macOS the headers come from the SDK or homebrew
+pub fn check(plugin: &str) -> Result<()> {
+    if !cfg!(target_os = "linux") || *env::RTX_SKIP_DEPENDENCY_CHECK {
+        return Ok(());
+    }
+    let missing = missing(plugin, &include_dirs());
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let names = missing.iter().map(|d| d.name).join(", ");
+    let hint = match package_manager() {
+        Some(i) => format!(
+            "\ninstall them with: {} {}",
+            PACKAGE_MANAGERS[i].1,
+            missing.iter().map(|d| d.packages[i]).join(" ")
+        ),
+        None => String::new(),
+    };
+    bail!(
+        "{plugin} is compiled from source and needs the headers for {names} which were not found{hint}\n\
+        set RTX_SKIP_DEPENDENCY_CHECK=1 to install anyway"
+    );
+}
+
+fn missing(plugin: &str, include_dirs: &[PathBuf]) -> Vec<&'static Dependency> {
+    dependencies(plugin)
+        .iter()
+        .filter(|d| {
+            !d.headers
+                .iter()
+                .any(|h| include_dirs.iter().any(|dir| dir.join(h).exists()))
+        })
+        .collect()
+}
+
+fn include_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![];
+    for key in ["CPATH", "C_INCLUDE_PATH"] {
+        if let Some(paths) = env::var_os(key) {
+            dirs.extend(env::split_paths(&paths));
+        }
+    }
+    for prefix in ["/usr", "/usr/local", "/home/linuxbrew/.linuxbrew"] {
+        let include = Path::new(prefix).join("include");
+        // multiarch headers, e.g.: /usr/include/x86_64-linux-gnu
+        if let Ok(entries) = include.read_dir() {
+            dirs.extend(
+                entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.path())
+                    .filter(|p| p.to_string_lossy().contains("-linux-")),
+            );
+        }
+        dirs.push(include);
+    }
+    dirs
+}
+
+fn package_manager() -> Option<usize> {
+    PACKAGE_MANAGERS
+        .iter()
+        .position(|(bin, _)| file::which(bin).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dirs;
+
+    use super::*;
+
+    #[test]
+    fn test_missing() {
+        let include = dirs::CACHE.join("test-build-deps-include");
+        file::remove_all(&include).unwrap();
+        file::create_dir_all(include.join("openssl")).unwrap();
+        file::write(include.join("openssl/ssl.h"), "").unwrap();
+        file::write(include.join("curses.h"), "").unwrap();
+        let names = |plugin| {
+            missing(plugin, &[include.clone()])
+                .iter()
+                .map(|d| d.name)
+                .collect_vec()
+        };
+        assert_eq!(names("python"), ["readline"]);
+        assert!(names("erlang").is_empty());
+        assert!(missing("node", &[]).is_empty());
+        file::remove_all(&include).unwrap();
+    }
+}
@@ -1,8 +1,9 @@
-use std::cmp::min;
-
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 use std::fs::File;
 use std::io::{Read, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
 use color_eyre::eyre::Result;
@@ -13,9 +14,58 @@ use once_cell::sync::OnceCell;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use crate::file;
 use crate::file::{display_path, modified_duration};
 use crate::rand::random_string;
+use crate::{dirs, env, file, hash, http};
+
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// `--verbose`: log why each cache is refreshed
+pub fn enable_verbose() {
+    VERBOSE.store(true, Ordering::Relaxed);
+}
+
+/// why a cache file could not be used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StaleReason {
+    Missing,
+    Expired { age: Duration, ttl: Duration },
+    FileChanged(PathBuf),
+    FileMissing(PathBuf),
+    HashesMissing,
+}
+
+impl Display for StaleReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing => write!(f, "not cached yet"),
+            Self::Expired { age, ttl } => write!(
+                f,
+                "expired, updated {} ago (ttl: {})",
+                format_duration(*age),
+                format_duration(*ttl)
+            ),
+            Self::FileChanged(path) => {
+                write!(f, "{} changed since it was cached", display_path(path))
+            }
+            Self::FileMissing(path) => write!(f, "{} does not exist", display_path(path)),
+            Self::HashesMissing => write!(f, "no content hashes were recorded"),
+        }
+    }
+}
+
+/// freshness of a cache file, see `rtx cache status`
+#[derive(Debug)]
+pub struct CacheStatus {
+    pub path: PathBuf,
+    pub age: Option<Duration>,
+    pub ttl: Option<Duration>,
+    pub stale: Option<StaleReason>,
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    humantime::format_duration(Duration::from_secs(duration.as_secs())).to_string()
+}
 
 #[derive(Debug, Clone)]
 pub struct CacheManager<T>
@@ -27,6 +77,7 @@ where
     fresh_files: Vec<PathBuf>,
     cache: Box<OnceCell<T>>,
     no_cache: bool,
+    content_hash: bool,
 }
 
 impl<T> CacheManager<T>
@@ -40,6 +91,7 @@ where
             fresh_files: Vec::new(),
             fresh_duration: None,
             no_cache: false,
+            content_hash: *env::RTX_CACHE_CONTENT_HASH,
         }
     }
 
@@ -59,15 +111,25 @@ where
     {
         let val = self.cache.get_or_try_init(|| {
             let path = &self.cache_file_path;
-            if !self.no_cache && self.is_fresh() {
-                match self.parse() {
-                    Ok(val) => return Ok::<_, color_eyre::Report>(val),
-                    Err(err) => {
-                        warn!("failed to parse cache file: {} {:#}", path.display(), err);
-                    }
+            if !self.no_cache {
+                match self.stale_reason() {
+                    None => match self.parse() {
+                        Ok(val) => return Ok::<_, color_eyre::Report>(val),
+                        Err(err) => {
+                            warn!("failed to parse cache file: {} {:#}", path.display(), err);
+                        }
+                    },
+                    Some(reason) => log_refresh(path, &reason),
                 }
             }
-            let val = (fetch)()?;
+            let val = match (fetch)() {
+                Ok(val) => val,
+                // the hot path cannot refresh remote data, a stale cache is better than nothing
+                Err(err) if http::is_network_disabled() => {
+                    return self.parse().map_err(|_| err);
+                }
+                Err(err) => return Err(err),
+            };
             if let Err(err) = self.write(&val) {
                 warn!("failed to write cache file: {} {:#}", path.display(), err);
             }
@@ -76,6 +138,11 @@ where
         Ok(val)
     }
 
+    /// the cached value without checking if it is fresh or fetching it
+    pub fn peek(&self) -> Option<T> {
+        self.parse().ok()
+    }
+
     fn parse(&self) -> Result<T> {
         let path = &self.cache_file_path;
         trace!("reading {}", display_path(path));
@@ -86,6 +153,13 @@ where
     }
 
     pub fn write(&self, val: &T) -> Result<()> {
+        if *dirs::CACHE_READ_ONLY && self.cache_file_path.starts_with(&*dirs::CACHE) {
+            trace!(
+                "cache is read-only, not writing {}",
+                display_path(&self.cache_file_path)
+            );
+            return Ok(());
+        }
         trace!("writing {}", display_path(&self.cache_file_path));
         if let Some(parent) = self.cache_file_path.parent() {
             file::create_dir_all(parent)?;
@@ -96,44 +170,127 @@ where
         let mut zlib = ZlibEncoder::new(File::create(&partial_path)?, Compression::fast());
         zlib.write_all(&rmp_serde::to_vec_named(&val)?[..])?;
         file::rename(&partial_path, &self.cache_file_path)?;
+        if self.hashes_fresh_files() {
+            file::write(self.hashes_path(), self.hash_fresh_files().join("\n"))?;
+        }
 
         Ok(())
     }
 
-    #[allow(dead_code)]
     pub fn clear(&self) -> Result<()> {
         let path = &self.cache_file_path;
         trace!("clearing cache {}", path.display());
         if path.exists() {
             file::remove_file(path)?;
         }
+        if self.hashes_path().exists() {
+            file::remove_file(self.hashes_path())?;
+        }
         Ok(())
     }
 
-    fn is_fresh(&self) -> bool {
+    pub fn status(&self) -> CacheStatus {
+        CacheStatus {
+            path: self.cache_file_path.clone(),
+            age: modified_duration(&self.cache_file_path).ok(),
+            ttl: self.fresh_duration,
+            stale: self.stale_reason(),
+        }
+    }
+
+    /// the cache is stale if it is older than the ttl or than any of the fresh files
+    fn stale_reason(&self) -> Option<StaleReason> {
         if !self.cache_file_path.exists() {
-            return false;
+            return Some(StaleReason::Missing);
         }
-        if let Some(fresh_duration) = self.freshest_duration() {
-            if let Ok(metadata) = self.cache_file_path.metadata() {
-                if let Ok(modified) = metadata.modified() {
-                    return modified.elapsed().unwrap_or_default() < fresh_duration;
-                }
+        let age = modified_duration(&self.cache_file_path).ok();
+        if let (Some(age), Some(ttl)) = (age, self.fresh_duration) {
+            if age >= ttl {
+                return Some(StaleReason::Expired { age, ttl });
             }
         }
-        true
+        if self.hashes_fresh_files() {
+            return self.changed_fresh_file();
+        }
+        // if the mtime cannot be read, treat the cache as fresh
+        let modified = self
+            .cache_file_path
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()?;
+        for path in &self.fresh_files {
+            // a file modified in the same mtime tick as the cache may have changed after it
+            match path.metadata().and_then(|m| m.modified()) {
+                Ok(file_modified) if file_modified < modified => {}
+                _ if !path.exists() => return Some(StaleReason::FileMissing(path.clone())),
+                _ => return Some(StaleReason::FileChanged(path.clone())),
+            }
+        }
+        None
     }
 
-    fn freshest_duration(&self) -> Option<Duration> {
-        let mut freshest = self.fresh_duration;
+    /// with RTX_CACHE_CONTENT_HASH, fresh files are compared by content hashes
+    /// instead of mtimes which are unreliable on some network filesystems
+    fn hashes_fresh_files(&self) -> bool {
+        self.content_hash && !self.fresh_files.is_empty()
+    }
+
+    fn changed_fresh_file(&self) -> Option<StaleReason> {
+        let Ok(hashes) = file::read_to_string(self.hashes_path()) else {
+            return Some(StaleReason::HashesMissing);
+        };
+        let hashes: HashMap<&str, &str> = hashes
+            .lines()
+            .filter_map(|l| l.split_once(' '))
+            .map(|(hash, path)| (path, hash))
+            .collect();
         for path in &self.fresh_files {
-            let duration = modified_duration(path).unwrap_or_default();
-            freshest = Some(match freshest {
-                None => duration,
-                Some(freshest) => min(freshest, duration),
-            })
+            let Some(hash) = content_hash(path) else {
+                return Some(StaleReason::FileMissing(path.clone()));
+            };
+            if hashes.get(path.to_string_lossy().as_ref()) != Some(&hash.as_str()) {
+                return Some(StaleReason::FileChanged(path.clone()));
+            }
         }
-        freshest
+        None
+    }
+
+    /// "<hash> <path>" for each fresh file, stored next to the cache file
+    fn hash_fresh_files(&self) -> Vec<String> {
+        self.fresh_files
+            .iter()
+            .filter_map(|path| Some(format!("{} {}", content_hash(path)?, path.display())))
+            .collect()
+    }
+
+    fn hashes_path(&self) -> PathBuf {
+        let mut path = self.cache_file_path.clone().into_os_string();
+        path.push(".hashes");
+        path.into()
+    }
+}
+
+/// sha256 of a file, or for a directory, a hash of its entry names since that is
+/// what its mtime would track
+fn content_hash(path: &Path) -> Option<String> {
+    if path.is_dir() {
+        let mut names = path
+            .read_dir()
+            .ok()?
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name())
+            .collect::<Vec<_>>();
+        names.sort();
+        return Some(hash::hash_to_str(&names));
+    }
+    hash::file_hash_sha256(path).ok()
+}
+
+fn log_refresh(path: &Path, reason: &StaleReason) {
+    if VERBOSE.load(Ordering::Relaxed) || *env::RTX_VERBOSE {
+        info!("refreshing {}: {}", display_path(path), reason);
+    } else {
+        debug!("refreshing {}: {}", display_path(path), reason);
     }
 }
 
@@ -151,4 +308,53 @@ mod tests {
         let val = cache.get_or_try_init(|| Ok(2)).unwrap();
         assert_eq!(val, &1);
     }
+
+    #[test]
+    fn test_stale_reason() {
+        let path = env::RTX_CACHE_DIR.join("test-stale-reason.msgpack.z");
+        let fresh_file = env::RTX_CACHE_DIR.join("test-stale-reason-fresh-file");
+        let cache = CacheManager::<i32>::new(path.clone()).with_fresh_file(fresh_file.clone());
+        cache.clear().unwrap();
+        assert_eq!(cache.stale_reason(), Some(StaleReason::Missing));
+        cache.write(&1).unwrap();
+        assert_eq!(
+            cache.stale_reason(),
+            Some(StaleReason::FileMissing(fresh_file.clone()))
+        );
+        file::write(&fresh_file, "").unwrap();
+        assert_eq!(
+            cache.stale_reason(),
+            Some(StaleReason::FileChanged(fresh_file.clone()))
+        );
+        let cache = cache.with_fresh_duration(Some(Duration::ZERO));
+        assert!(matches!(
+            cache.stale_reason(),
+            Some(StaleReason::Expired { .. })
+        ));
+        cache.clear().unwrap();
+        file::remove_file(&fresh_file).unwrap();
+    }
+
+    #[test]
+    fn test_stale_reason_content_hash() {
+        let path = env::RTX_CACHE_DIR.join("test-stale-reason-hash.msgpack.z");
+        let fresh_file = env::RTX_CACHE_DIR.join("test-stale-reason-hash-fresh-file");
+        file::write(&fresh_file, "foo").unwrap();
+        let mut cache = CacheManager::<i32>::new(path).with_fresh_file(fresh_file.clone());
+        cache.content_hash = true;
+        cache.clear().unwrap();
+        cache.write(&1).unwrap();
+        assert_eq!(cache.stale_reason(), None);
+        // touching the file without changing it keeps the cache fresh
+        file::write(&fresh_file, "foo").unwrap();
+        assert_eq!(cache.stale_reason(), None);
+        file::write(&fresh_file, "bar").unwrap();
+        assert_eq!(
+            cache.stale_reason(),
+            Some(StaleReason::FileChanged(fresh_file.clone()))
+        );
+        cache.clear().unwrap();
+        assert_eq!(cache.stale_reason(), Some(StaleReason::Missing));
+        file::remove_file(&fresh_file).unwrap();
+    }
 }
@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{bail, Result};
+
+use crate::file::display_path;
+use crate::toolset::ToolVersion;
+use crate::{dirs, file, hash};
+
+/// sha256 checksums of the files a plugin's bin/download script writes to RTX_DOWNLOAD_PATH, by
+/// version then by path relative to RTX_DOWNLOAD_PATH, e.g.: "sha256:2c26b4...". This is the
+/// format `rtx plugins checksums` generates. They come from a checksums.toml shipped with the
+/// plugin, `[checksums."<version>"]` in rtx.plugin.toml and `rtx checksum`, which records them
+/// in RTX_STATE_DIR.
+pub type Checksums = BTreeMap<String, BTreeMap<String, String>>;
+
+/// checksums.toml in the root of a plugin
+pub const PLUGIN_FILENAME: &str = "checksums.toml";
+
+fn recorded_path(plugin_name: &str) -> PathBuf {
+    dirs::STATE
+        .join("checksums")
+        .join(format!("{plugin_name}.toml"))
+}
+
+/// empty if the file does not exist
+pub fn read(path: &Path) -> Result<Checksums> {
+    if !path.exists() {
+        return Ok(Checksums::new());
+    }
+    Ok(toml::from_str(&file::read_to_string(path)?)?)
+}
+
+/// hashes every file in the download dir of `tv` and records them, returns the number of files
+pub fn record(tv: &ToolVersion) -> Result<usize> {
+    let download_path = tv.download_path();
+    let files = match download_path.is_dir() {
+        true => checksum_dir(&download_path)?,
+        false => BTreeMap::new(),
+    };
+    if files.is_empty() {
+        bail!(
+            "{tv} has no downloads in {}, reinstall it with `RTX_ALWAYS_KEEP_DOWNLOAD=1 rtx install --force {tv}`",
+            display_path(&download_path)
+        );
+    }
+    let count = files.len();
+    let path = recorded_path(&tv.plugin_name);
+    let mut checksums = read(&path)?;
+    checksums.insert(tv.version.clone(), files);
+    file::create_dir_all(path.parent().unwrap())?;
+    file::write(path, toml::to_string_pretty(&checksums)?)?;
+    Ok(count)
+}
+
+/// run after bin/download and before bin/install, fails if a downloaded file does not match.
+/// Files which were not downloaded are skipped, plugins may list files for every platform, but
+/// at least one of them must have been downloaded.
+/// The checksums shipped with the plugin take precedence, recorded ones which disagree with them
+/// are an error.
+/// `expected_for_all` is set for plugins with the checksums capability, which ship them for
+/// every version.
+pub fn verify(
+    declared: &Checksums,
+    plugin_path: &Path,
+    tv: &ToolVersion,
+    expected_for_all: bool,
+) -> Result<()> {
+    let mut expected = BTreeMap::new();
+    for checksums in [read(&plugin_path.join(PLUGIN_FILENAME))?, declared.clone()] {
+        if let Some(files) = checksums.get(&tv.version) {
+            expected.extend(files.clone());
+        }
+    }
+    let recorded_path = recorded_path(&tv.plugin_name);
+    if let Some(files) = read(&recorded_path)?.remove(&tv.version) {
+        for (name, sha) in files {
+            match expected.get(&name) {
+                Some(shipped) if shipped != &sha => bail!(
+                    "{tv}: the checksum of {name} in {} does not match the one shipped with the plugin, remove it and run `rtx checksum` again",
+                    display_path(&recorded_path)
+                ),
+                Some(_) => {}
+                None => {
+                    expected.insert(name, sha);
+                }
+            }
+        }
+    }
+    if expected.is_empty() {
+        if expected_for_all {
+            warn!(
+                "{tv}: the plugin has no checksums for this version, the download is not verified"
+            );
+        }
+        return Ok(());
+    }
+    let download_path = tv.download_path();
+    let mut verified = 0;
+    for (name, sha) in &expected {
+        let path = download_path.join(name);
+        if !path.is_file() {
+            trace!("{tv}: {name} was not downloaded, not verifying it");
+            continue;
+        }
+        let sha = sha.strip_prefix("sha256:").unwrap_or(sha);
+        hash::ensure_checksum_sha256(&path, sha)?;
+        verified += 1;
+    }
+    if verified == 0 {
+        bail!(
+            "{tv}: none of the files with checksums were downloaded, expected one of: {}",
+            expected.keys().cloned().collect::<Vec<_>>().join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// sha256 of every file under `dir`, keyed by the path relative to `dir`
+pub fn checksum_dir(dir: &Path) -> Result<BTreeMap<String, String>> {
+    let mut checksums = BTreeMap::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(d) = dirs.pop() {
+        for entry in d.read_dir()? {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.is_file() {
+                let rel = path.strip_prefix(dir)?.to_string_lossy().to_string();
+                let sha = hash::file_hash_sha256(&path)?;
+                checksums.insert(rel, format!("sha256:{sha}"));
+            }
+        }
+    }
+    Ok(checksums)
+}
@@ -42,6 +42,12 @@ pub struct Activate {
     #[clap(long)]
     status: bool,
 
+    /// Offer to run commands that are not found with a tool that provides them
+    /// installs the tool with `rtx use <tool>@latest` first if no version of it is installed
+    /// only supported in bash, zsh, and fish
+    #[clap(long, verbatim_doc_comment)]
+    command_not_found: bool,
+
     /// noop
     #[clap(long, short, hide = true)]
     quiet: bool,
@@ -57,6 +63,9 @@ impl Command for Activate {
 
         let output = shell.activate(&RTX_EXE, self.status);
         out.stdout.write(output);
+        if self.command_not_found {
+            out.stdout.write(shell.command_not_found_handler());
+        }
 
         Ok(())
     }
@@ -68,5 +77,7 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
   $ <bold>eval "$(rtx activate zsh)"</bold>
   $ <bold>rtx activate fish | source</bold>
   $ <bold>execx($(rtx activate xonsh))</bold>
+  $ <bold>eval `rtx activate csh`</bold>
+  $ <bold>eval "$(rtx activate zsh --command-not-found)"</bold>
 "#
 );
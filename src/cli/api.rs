@@ -0,0 +1,250 @@
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, Write};
+use std::os::unix::io::FromRawFd;
+
+use color_eyre::eyre::{bail, eyre, Result};
+use serde_derive::Deserialize;
+use serde_json::{json, Value};
+
+use crate::cli::args::tool::ToolArg;
+use crate::cli::command::Command;
+use crate::config::Config;
+use crate::output::Output;
+use crate::plugins::unalias_plugin;
+use crate::toolset::{ToolVersion, ToolsetBuilder};
+use crate::ui::multi_progress_report::MultiProgressReport;
+use crate::{dirs, embed};
+
+/// Serve a JSON-RPC 2.0 API on stdin/stdout for editor integrations
+///
+/// Every request and response is a single line of JSON.
+/// Tools are resolved from the `dir` param of each request, or the directory rtx was
+/// started in without it. Config files are read again for every request so edits to them
+/// are picked up. Output of plugins while installing goes to stderr.
+///
+/// Methods:
+///   toolset {dir?}                     active tool versions with their source and install status
+///   env {dir?}                         env vars (including PATH) for the active tools
+///   ls-remote {plugin, prefix?, dir?}  versions available to install for a plugin
+///   install {tools?, dir?}             install the given tools or all missing ones
+///                                      sends "install/progress" notifications as each tool starts and finishes
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Api {}
+
+impl Command for Api {
+    fn run(self, _config: Config, _out: &mut Output) -> Result<()> {
+        let mut out = take_stdout()?;
+        for line in io::stdin().lock().lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                handle_line(&mut out, &line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// plugins and the tools they run may print to stdout while installing, which would corrupt
+/// the responses. Responses are written to a copy of stdout and stdout is pointed at stderr.
+fn take_stdout() -> Result<File> {
+    io::stdout().flush()?;
+    let fd = unsafe { libc::dup(libc::STDOUT_FILENO) };
+    if fd == -1 {
+        bail!("failed to duplicate stdout: {}", io::Error::last_os_error());
+    }
+    if unsafe { libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) } == -1 {
+        bail!("failed to redirect stdout: {}", io::Error::last_os_error());
+    }
+    Ok(unsafe { File::from_raw_fd(fd) })
+}
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl From<color_eyre::Report> for RpcError {
+    fn from(err: color_eyre::Report) -> Self {
+        Self {
+            code: -32000,
+            message: format!("{err:#}"),
+        }
+    }
+}
+
+fn handle_line(out: &mut dyn Write, line: &str) -> io::Result<()> {
+    let (id, result) = match serde_json::from_str::<Request>(line) {
+        Ok(req) => (req.id.clone(), handle_request(out, req)),
+        Err(err) => (
+            Value::Null,
+            Err(RpcError {
+                code: -32700,
+                message: format!("parse error: {err}"),
+            }),
+        ),
+    };
+    let response = match result {
+        // requests without an id are notifications and get no response
+        Ok(_) if id.is_null() => return Ok(()),
+        Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+        Err(err) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {"code": err.code, "message": err.message},
+        }),
+    };
+    writeln!(out, "{}", response)
+}
+
+fn handle_request(out: &mut dyn Write, req: Request) -> Result<Value, RpcError> {
+    let method = req.method.as_str();
+    if !["toolset", "env", "ls-remote", "install"].contains(&method) {
+        return Err(RpcError {
+            code: -32601,
+            message: format!("method not found: {method}"),
+        });
+    }
+    // config files may have changed since the last request
+    let config = &mut load_config(&req.params)?;
+    match method {
+        "toolset" => Ok(toolset(config)?),
+        "env" => Ok(env(config)?),
+        "ls-remote" => Ok(ls_remote(config, &req.params)?),
+        _ => Ok(install(config, out, &req.params)?),
+    }
+}
+
+fn load_config(params: &Value) -> Result<Config> {
+    match params["dir"].as_str() {
+        Some(dir) => Config::load_for_dir(&dirs::CURRENT.join(dir)),
+        None => Config::load(),
+    }
+}
+
+fn toolset(config: &mut Config) -> Result<Value> {
+    Ok(json!(embed::resolve_tools(config)?))
+}
+
+fn env(config: &mut Config) -> Result<Value> {
+    Ok(json!(embed::resolve_env(config)?))
+}
+
+fn ls_remote(config: &mut Config, params: &Value) -> Result<Value> {
+    let plugin = params["plugin"]
+        .as_str()
+        .ok_or_else(|| eyre!("missing param: plugin"))?;
+    let tool = config.get_or_create_tool(&unalias_plugin(plugin).to_string());
+    let versions = tool.list_remote_versions(&config.settings)?;
+    let versions = match params["prefix"].as_str() {
+        Some(prefix) => versions
+            .into_iter()
+            .filter(|v| v.starts_with(prefix))
+            .collect(),
+        None => versions,
+    };
+    Ok(json!(versions))
+}
+
+fn install(config: &mut Config, out: &mut dyn Write, params: &Value) -> Result<Value> {
+    let args = match params["tools"].as_array() {
+        Some(tools) => tools
+            .iter()
+            .map(|t| t.as_str().map(ToolArg::parse))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| eyre!("invalid param: tools must be strings"))?,
+        None => vec![],
+    };
+    let mut ts = ToolsetBuilder::new().with_args(&args).build(config)?;
+    let versions: Vec<ToolVersion> = ts
+        .list_missing_versions(config)
+        .into_iter()
+        .filter(|tv| args.is_empty() || args.iter().any(|a| a.plugin == tv.plugin_name))
+        .cloned()
+        .collect();
+    let mpr = MultiProgressReport::new(false);
+    let mut installed = vec![];
+    for tv in versions {
+        notify_progress(out, &tv, "installing")?;
+        if let Err(err) = ts.install_versions(config, vec![tv.clone()], &mpr, false) {
+            notify_progress(out, &tv, "failed")?;
+            return Err(err);
+        }
+        notify_progress(out, &tv, "installed")?;
+        installed.push(tv.to_string());
+    }
+    Ok(json!({ "installed": installed }))
+}
+
+fn notify_progress(out: &mut dyn Write, tv: &ToolVersion, status: &str) -> io::Result<()> {
+    let notification = json!({
+        "jsonrpc": "2.0",
+        "method": "install/progress",
+        "params": {"tool": tv.to_string(), "status": status},
+    });
+    writeln!(out, "{}", notification)
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  $ <bold>echo '{"jsonrpc": "2.0", "id": 1, "method": "env"}' | rtx api</bold>
+  {"id":1,"jsonrpc":"2.0","result":{"PATH":"..."}}
+  $ <bold>echo '{"jsonrpc": "2.0", "id": 2, "method": "ls-remote", "params": {"plugin": "node", "prefix": "20"}}' | rtx api</bold>
+  {"id":2,"jsonrpc":"2.0","result":["20.0.0","20.1.0"]}
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_str_eq;
+
+    use super::handle_line;
+
+    fn call(line: &str) -> String {
+        let mut out = vec![];
+        handle_line(&mut out, line).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_api_ls_remote() {
+        let out = call(
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "ls-remote", "params": {"plugin": "dummy", "prefix": "2"}}"#,
+        );
+        assert_str_eq!(
+            out,
+            "{\"id\":1,\"jsonrpc\":\"2.0\",\"result\":[\"2.0.0\"]}\n"
+        );
+    }
+
+    #[test]
+    fn test_api_toolset_dir() {
+        // "tiny 3" in .test-tool-versions of the current directory, "tiny 2" in the parent
+        let out = call(r#"{"jsonrpc": "2.0", "id": 1, "method": "toolset"}"#);
+        assert!(out.contains("\"requested_version\":\"3\""));
+        let out =
+            call(r#"{"jsonrpc": "2.0", "id": 1, "method": "toolset", "params": {"dir": ".."}}"#);
+        assert!(out.contains("\"requested_version\":\"2\""));
+        assert!(!out.contains("\"requested_version\":\"3\""));
+    }
+
+    #[test]
+    fn test_api_errors() {
+        let out = call(r#"{"jsonrpc": "2.0", "id": 1, "method": "nope"}"#);
+        assert!(out.contains("\"code\":-32601"));
+        let out = call("not json");
+        assert!(out.contains("\"code\":-32700"));
+        assert_str_eq!(call(r#"{"jsonrpc": "2.0", "method": "env"}"#), "");
+    }
+}
@@ -0,0 +1,18 @@
+use std::path::PathBuf;
+
+use clap::{Arg, ValueHint};
+
+pub struct Cd(pub PathBuf);
+
+impl Cd {
+    pub fn arg() -> Arg {
+        Arg::new("cd")
+            .short('C')
+            .long("cd")
+            .value_name("DIR")
+            .help("Change directory before running command")
+            .value_parser(clap::value_parser!(PathBuf))
+            .value_hint(ValueHint::DirPath)
+            .global(true)
+    }
+}
@@ -1,8 +1,12 @@
+pub mod cd;
 pub mod env_var;
 pub mod install_missing;
 pub mod jobs;
 pub mod log_level;
+pub mod no;
+pub mod progress_format;
 pub mod raw;
 pub mod tool;
 pub mod verbose;
+pub mod version_list;
 pub mod yes;
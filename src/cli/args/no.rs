@@ -0,0 +1,14 @@
+use clap::{Arg, ArgAction};
+
+pub struct No(pub bool);
+
+impl No {
+    pub fn arg() -> Arg {
+        Arg::new("no")
+            .long("no")
+            .help("Answer no to prompts for destructive operations, e.g.: rtx prune")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("yes")
+            .global(true)
+    }
+}
@@ -0,0 +1,21 @@
+use clap::{Arg, ValueEnum};
+
+use crate::env;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProgressFormat {
+    Text,
+    Json,
+}
+
+impl ProgressFormat {
+    pub fn arg() -> clap::Arg {
+        Arg::new("progress-format")
+            .long("progress-format")
+            .value_name("FORMAT")
+            .help("How to report progress of installs and plugin updates\njson writes one event per line to RTX_PROGRESS_FD [default: 2]")
+            .default_value(env::RTX_PROGRESS_FORMAT.as_str())
+            .value_parser(clap::value_parser!(ProgressFormat))
+            .global(true)
+    }
+}
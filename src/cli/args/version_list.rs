@@ -0,0 +1,100 @@
+use itertools::Itertools;
+use versions::Versioning;
+
+/// Options for sorting and trimming a list of versions
+#[derive(Debug, Default, clap::Args)]
+pub struct VersionListArgs {
+    /// Only show the last N versions (or the first N with --reverse)
+    #[clap(long, value_name = "N")]
+    pub limit: Option<usize>,
+
+    /// Show newest versions first
+    #[clap(long)]
+    pub reverse: bool,
+
+    /// Only show the newest version of each major.minor release
+    #[clap(long)]
+    pub latest_per_minor: bool,
+}
+
+impl VersionListArgs {
+    /// sorts versions by version comparison (not by the order plugins output them)
+    /// then applies --latest-per-minor, --reverse, and --limit
+    pub fn apply(&self, versions: Vec<String>) -> Vec<String> {
+        let mut versions = sort_versions(versions);
+        if self.latest_per_minor {
+            versions = versions
+                .into_iter()
+                .rev()
+                .unique_by(|v| v.split('.').take(2).join("."))
+                .collect_vec();
+            versions.reverse();
+        }
+        if self.reverse {
+            versions.reverse();
+        }
+        if let Some(limit) = self.limit {
+            if self.reverse {
+                versions.truncate(limit);
+            } else {
+                versions = versions.split_off(versions.len().saturating_sub(limit));
+            }
+        }
+        versions
+    }
+}
+
+pub fn sort_versions(versions: Vec<String>) -> Vec<String> {
+    versions
+        .into_iter()
+        .sorted_by_cached_key(|v| Versioning::new(v).unwrap_or_default())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versions() -> Vec<String> {
+        ["1.10.0", "1.2.0", "1.2.1", "2.0.0", "1.9.3"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_sort() {
+        let args = VersionListArgs::default();
+        assert_eq!(
+            args.apply(versions()),
+            vec!["1.2.0", "1.2.1", "1.9.3", "1.10.0", "2.0.0"]
+        );
+    }
+
+    #[test]
+    fn test_limit_reverse() {
+        let args = VersionListArgs {
+            limit: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(args.apply(versions()), vec!["1.10.0", "2.0.0"]);
+        let args = VersionListArgs {
+            limit: Some(2),
+            reverse: true,
+            ..Default::default()
+        };
+        assert_eq!(args.apply(versions()), vec!["2.0.0", "1.10.0"]);
+    }
+
+    #[test]
+    fn test_latest_per_minor() {
+        let args = VersionListArgs {
+            latest_per_minor: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            args.apply(versions()),
+            vec!["1.2.1", "1.9.3", "1.10.0", "2.0.0"]
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use clap::ValueHint;
+use color_eyre::eyre::Result;
+use console::style;
+use itertools::Itertools;
+
+use crate::cli::command::Command;
+use crate::config::{config_file, Config};
+use crate::env;
+use crate::file::display_path;
+use crate::output::Output;
+use crate::toolset::{ToolVersion, ToolVersionRequest, ToolsetBuilder};
+use crate::ui::multi_progress_report::MultiProgressReport;
+use crate::ui::prompt;
+
+/// Install every missing tool version across a directory tree
+///
+/// Finds all .tool-versions and .rtx.toml files under DIR and installs the
+/// versions they need which are not installed yet, in parallel.
+/// Useful after setting up a new machine, e.g.: `rtx backfill ~/src`.
+/// Hidden directories, node_modules, target, and vendor are not searched.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Backfill {
+    /// Directory to search for config files
+    #[clap(value_hint = ValueHint::DirPath, default_value = ".")]
+    dir: PathBuf,
+
+    /// How many directories deep to search
+    #[clap(long, default_value = "5")]
+    max_depth: usize,
+
+    /// Only show what would be installed
+    #[clap(long, short = 'n')]
+    dry_run: bool,
+}
+
+const SKIP_DIRS: &[&str] = &["node_modules", "target", "vendor"];
+
+impl Command for Backfill {
+    fn run(self, mut config: Config, out: &mut Output) -> Result<()> {
+        let mut paths = vec![];
+        find_config_files(&self.dir, self.max_depth, &mut paths);
+        paths.sort();
+
+        // "plugin@version" => (version, config files that need it)
+        let mut plan: BTreeMap<String, (ToolVersion, Vec<PathBuf>)> = BTreeMap::new();
+        for path in &paths {
+            let is_trusted = config_file::is_trusted(&config.settings, path);
+            let cf = match config_file::parse(path, is_trusted) {
+                Ok(cf) => cf,
+                Err(err) => {
+                    warn!("skipping {}: {:#}", display_path(path), err);
+                    continue;
+                }
+            };
+            let mut ts = cf.to_toolset().clone();
+            ts.resolve(&mut config);
+            for tv in ts.list_missing_versions(&config) {
+                if matches!(
+                    tv.request,
+                    ToolVersionRequest::System(_) | ToolVersionRequest::Path(_, _)
+                ) {
+                    continue;
+                }
+                plan.entry(tv.to_string())
+                    .or_insert_with(|| (tv.clone(), vec![]))
+                    .1
+                    .push(path.clone());
+            }
+        }
+
+        rtxstatusln!(
+            out,
+            "found {} config files in {}",
+            paths.len(),
+            display_path(&self.dir)
+        );
+        if plan.is_empty() {
+            rtxstatusln!(out, "all tool versions are installed");
+            return Ok(());
+        }
+        rtxprintln!(out, "{} versions to install:", plan.len());
+        for (name, (_, paths)) in &plan {
+            let paths = paths.iter().map(|p| display_path(p)).join(", ");
+            rtxprintln!(out, "  {} {}", style(name).cyan(), style(paths).dim());
+        }
+        if self.dry_run {
+            return Ok(());
+        }
+        if !config.settings.yes && !prompt::confirm(&format!("install {} versions?", plan.len()))? {
+            return Ok(());
+        }
+
+        let versions = plan
+            .into_values()
+            .map(|(tv, _)| tv)
+            .sorted_by(|a, b| a.plugin_name.cmp(&b.plugin_name))
+            .collect_vec();
+        let mut ts = ToolsetBuilder::new().build(&mut config)?;
+        let mpr = MultiProgressReport::new(config.show_progress_bars());
+        ts.install_versions(&mut config, versions, &mpr, false)
+    }
+}
+
+fn find_config_files(dir: &Path, depth: usize, paths: &mut Vec<PathBuf>) {
+    let Ok(entries) = dir.read_dir() else {
+        return;
+    };
+    let filenames = [
+        env::RTX_DEFAULT_TOOL_VERSIONS_FILENAME.as_str(),
+        env::RTX_DEFAULT_CONFIG_FILENAME.as_str(),
+    ];
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        // symlinks are not followed to avoid cycles
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            if depth > 0 && !name.starts_with('.') && !SKIP_DIRS.contains(&name.as_str()) {
+                find_config_files(&path, depth - 1, paths);
+            }
+        } else if filenames.contains(&name.as_str()) {
+            paths.push(path);
+        }
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  $ <bold>rtx backfill ~/src --dry-run</bold>
+  found 12 config files in ~/src
+  2 versions to install:
+    node@18.18.2 ~/src/api/.tool-versions
+    python@3.11.6 ~/src/ml/.rtx.toml, ~/src/scripts/.tool-versions
+
+  $ <bold>rtx backfill ~/src --yes</bold>
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_cli, dirs, file};
+
+    #[test]
+    fn test_backfill() {
+        let dir = dirs::CACHE.join("test-backfill");
+        file::create_dir_all(dir.join("project")).unwrap();
+        file::write(dir.join("project/.test-tool-versions"), "tiny 3.0.0\n").unwrap();
+        let stdout = assert_cli!("backfill", dir.to_str().unwrap(), "--dry-run");
+        assert!(stdout.contains("1 versions to install"));
+        assert!(stdout.contains("tiny@3.0.0"));
+        file::remove_all(&dir).unwrap();
+    }
+}
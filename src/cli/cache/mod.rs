@@ -7,6 +7,7 @@ use crate::env;
 use crate::output::Output;
 
 mod clear;
+mod status;
 
 /// Manage the rtx cache
 ///
@@ -21,12 +22,14 @@ pub struct Cache {
 #[derive(Debug, Subcommand)]
 enum Commands {
     Clear(clear::CacheClear),
+    Status(status::CacheStatus),
 }
 
 impl Commands {
     pub fn run(self, config: Config, out: &mut Output) -> Result<()> {
         match self {
             Self::Clear(cmd) => cmd.run(config, out),
+            Self::Status(cmd) => cmd.run(config, out),
         }
     }
 }
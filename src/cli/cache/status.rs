@@ -0,0 +1,68 @@
+use color_eyre::eyre::{eyre, Result};
+use console::style;
+
+use crate::cache::format_duration;
+use crate::cli::command::Command;
+use crate::config::Config;
+use crate::file::display_path;
+use crate::output::Output;
+use crate::plugins::unalias_plugin;
+
+/// Show how fresh a plugin's caches are
+///
+/// Lists each cache file with its age, ttl, and if it is stale, the reason
+/// it will be refreshed the next time it is read.
+/// Run any command with --verbose to see when caches are refreshed.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct CacheStatus {
+    /// Plugin to show cache status for
+    #[clap()]
+    plugin: String,
+}
+
+impl Command for CacheStatus {
+    fn run(self, config: Config, out: &mut Output) -> Result<()> {
+        let name = unalias_plugin(&self.plugin);
+        let tool = config
+            .tools
+            .get(name)
+            .ok_or_else(|| eyre!("plugin {} not found", style(name).cyan().for_stderr()))?;
+        for status in tool.plugin.cache_status() {
+            rtxprintln!(out, "{}", display_path(&status.path));
+            if let Some(age) = status.age {
+                rtxprintln!(out, "  updated: {} ago", format_duration(age));
+            }
+            if let Some(ttl) = status.ttl {
+                rtxprintln!(out, "  ttl:     {}", format_duration(ttl));
+            }
+            match status.stale {
+                Some(reason) => rtxprintln!(out, "  status:  stale, {}", reason),
+                None => rtxprintln!(out, "  status:  fresh"),
+            }
+        }
+        Ok(())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  $ <bold>rtx cache status node</bold>
+  ~/.cache/rtx/node/remote_versions.msgpack.z
+    updated: 2h 4m 10s ago
+    ttl:     1day
+    status:  fresh
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_cli;
+
+    #[test]
+    fn test_cache_status() {
+        let stdout = assert_cli!("cache", "status", "tiny");
+        assert!(stdout.contains("remote_versions.msgpack.z"));
+        assert!(stdout.contains("  status:  "));
+    }
+}
@@ -0,0 +1,74 @@
+use color_eyre::eyre::Result;
+
+use crate::checksums;
+use crate::cli::args::tool::{ToolArg, ToolArgParser};
+use crate::cli::command::Command;
+use crate::config::Config;
+use crate::output::Output;
+use crate::plugins::PluginType;
+use crate::toolset::ToolsetBuilder;
+
+/// Records sha256 checksums of the downloads of installed versions
+///
+/// When the version is installed again, e.g.: on another machine with the same RTX_STATE_DIR
+/// or after `rtx install --force`, the files from the plugin's bin/download script are checked
+/// against these before bin/install runs. Plugins can also ship checksums, see `rtx plugins checksums`
+/// or declare them in rtx.plugin.toml:
+///
+///     [checksums."20.3.0"]
+///     "node-v20.3.0-linux-x64.tar.gz" = "sha256:<sha256>"
+///
+/// Downloads are removed after installing unless RTX_ALWAYS_KEEP_DOWNLOAD is set, so this only
+/// works for versions installed with it.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Checksum {
+    /// Tool(s) to record checksums for
+    /// e.g.: node@20
+    /// If not specified, all current versions of plugins with downloads kept are used
+    #[clap(value_name="TOOL@VERSION", value_parser = ToolArgParser, verbatim_doc_comment)]
+    tool: Vec<ToolArg>,
+}
+
+impl Command for Checksum {
+    fn run(self, mut config: Config, out: &mut Output) -> Result<()> {
+        let ts = ToolsetBuilder::new()
+            .with_args(&self.tool)
+            .build(&mut config)?;
+        for (tool, tv) in ts.list_current_installed_versions(&config) {
+            if !matches!(tool.plugin.get_type(), PluginType::External) {
+                continue;
+            }
+            let requested = self.tool.iter().any(|t| t.plugin == tv.plugin_name);
+            if !self.tool.is_empty() && !requested {
+                continue;
+            }
+            match checksums::record(&tv) {
+                Ok(count) => rtxprintln!(out, "{tv}: {count} files"),
+                Err(err) if requested => return Err(err),
+                Err(err) => debug!("{err:#}"),
+            }
+        }
+        Ok(())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  $ <bold>RTX_ALWAYS_KEEP_DOWNLOAD=1 rtx install node@20</bold>
+  $ <bold>rtx checksum node@20</bold>
+  node@20.3.0: 1 files
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_cli, assert_cli_err};
+
+    #[test]
+    fn test_checksum_without_downloads() {
+        assert_cli!("install", "tiny");
+        let err = assert_cli_err!("checksum", "tiny");
+        assert!(err.to_string().contains("has no downloads"));
+    }
+}
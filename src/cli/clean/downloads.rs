@@ -0,0 +1,147 @@
+use std::time::Duration;
+
+use color_eyre::eyre::{eyre, Result};
+use console::style;
+use indicatif::HumanBytes;
+
+use crate::cli::command::Command;
+use crate::config::Config;
+use crate::output::Output;
+use crate::plugins::{unalias_plugin, PluginName};
+use crate::{dirs, file};
+
+/// Delete downloaded tarballs and build trees
+///
+/// Downloads are deleted after installing unless always_keep_download,
+/// always_keep_download_tools or always_keep_install are set, or the install failed.
+/// This removes what is left over in RTX_DATA_DIR/downloads.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct CleanDownloads {
+    /// Only delete downloads of these plugins
+    #[clap()]
+    plugins: Vec<PluginName>,
+
+    /// Only delete downloads last modified longer ago than this, e.g.: 30d
+    #[clap(long, value_parser = humantime::parse_duration, value_name = "DURATION")]
+    older_than: Option<Duration>,
+
+    /// Only delete downloads bigger than this, e.g.: 100M or 1G
+    #[clap(long, value_parser = parse_size, value_name = "SIZE")]
+    larger_than: Option<u64>,
+
+    /// List what would be deleted without deleting it
+    #[clap(long, short = 'n')]
+    dry_run: bool,
+}
+
+impl Command for CleanDownloads {
+    fn run(self, _config: Config, out: &mut Output) -> Result<()> {
+        let plugins = self
+            .plugins
+            .iter()
+            .map(|p| unalias_plugin(p).to_string())
+            .collect::<Vec<_>>();
+        if !self.dry_run {
+            dirs::ensure_root_writable("cleaning downloads")?;
+        }
+        let mut total = 0;
+        for plugin in file::dir_subdirs(&dirs::DOWNLOADS)? {
+            if !plugins.is_empty() && !plugins.contains(&plugin) {
+                continue;
+            }
+            let plugin_dir = dirs::DOWNLOADS.join(&plugin);
+            for version in file::dir_subdirs(&plugin_dir)? {
+                let dir = plugin_dir.join(&version);
+                let size = file::dir_size(&dir)?;
+                if self.larger_than.map_or(false, |min| size <= min) {
+                    continue;
+                }
+                if let Some(older_than) = self.older_than {
+                    if file::modified_duration(&dir)? <= older_than {
+                        continue;
+                    }
+                }
+                let dryrun = match self.dry_run {
+                    true => format!("{} ", style("[dryrun]").bold()),
+                    false => String::new(),
+                };
+                rtxprintln!(
+                    out,
+                    "{dryrun}removed {plugin}@{version} ({})",
+                    HumanBytes(size)
+                );
+                if !self.dry_run {
+                    file::remove_all(&dir)?;
+                }
+                total += size;
+            }
+            if !self.dry_run && file::dir_subdirs(&plugin_dir)?.is_empty() {
+                file::remove_all(&plugin_dir)?;
+            }
+        }
+        rtxstatusln!(out, "{} freed", HumanBytes(total));
+        Ok(())
+    }
+}
+
+/// "100M" -> 100 * 1024 * 1024, plain numbers are bytes
+fn parse_size(s: &str) -> Result<u64> {
+    let s = s.trim().to_uppercase();
+    let s = s.trim_end_matches('B').trim_end_matches('I');
+    let (num, unit) = match s.find(|c: char| !c.is_ascii_digit() && c != '.') {
+        Some(i) => s.split_at(i),
+        None => (s, ""),
+    };
+    let multiplier: u64 = match unit.trim() {
+        "" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        unit => return Err(eyre!("invalid size unit: {unit}, use K, M or G")),
+    };
+    let num: f64 = num
+        .parse()
+        .map_err(|_| eyre!("invalid size: {num}, e.g.: 100M"))?;
+    Ok((num * multiplier as f64) as u64)
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  $ <bold>rtx clean downloads</bold>
+  removed node@20.0.0 (42.1 MiB)
+  removed python@3.11.0 (312.7 MiB)
+
+  $ <bold>rtx clean downloads python --older-than 30d --larger-than 100M</bold>
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_cli, dirs, file};
+
+    #[test]
+    fn test_clean_downloads() {
+        let dir = dirs::DOWNLOADS.join("clean-test").join("1.0.0");
+        file::create_dir_all(&dir).unwrap();
+        file::write(dir.join("clean-test.tar.gz"), "x".repeat(2048)).unwrap();
+
+        let stdout = assert_cli!("clean", "downloads", "clean-test", "--larger-than", "4K");
+        assert!(!stdout.contains("clean-test@1.0.0"));
+        let stdout = assert_cli!("clean", "downloads", "clean-test", "--dry-run");
+        assert!(stdout.contains("clean-test@1.0.0 (2.00 KiB)"));
+        assert!(dir.exists());
+        let stdout = assert_cli!("clean", "downloads", "clean-test");
+        assert!(stdout.contains("removed clean-test@1.0.0"));
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(super::parse_size("100").unwrap(), 100);
+        assert_eq!(super::parse_size("4K").unwrap(), 4096);
+        assert_eq!(super::parse_size("1.5MiB").unwrap(), 1572864);
+        assert_eq!(super::parse_size("1g").unwrap(), 1 << 30);
+        assert!(super::parse_size("10X").is_err());
+    }
+}
@@ -0,0 +1,35 @@
+use clap::Subcommand;
+use color_eyre::eyre::Result;
+
+use crate::cli::command::Command;
+use crate::config::Config;
+use crate::output::Output;
+
+mod downloads;
+
+/// Delete files rtx keeps around which are not needed to run tools
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct Clean {
+    #[clap(subcommand)]
+    command: Commands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    Downloads(downloads::CleanDownloads),
+}
+
+impl Commands {
+    pub fn run(self, config: Config, out: &mut Output) -> Result<()> {
+        match self {
+            Self::Downloads(cmd) => cmd.run(config, out),
+        }
+    }
+}
+
+impl Command for Clean {
+    fn run(self, config: Config, out: &mut Output) -> Result<()> {
+        self.command.run(config, out)
+    }
+}
@@ -1,14 +1,21 @@
 use std::io::Cursor;
+use std::path::{Path, PathBuf};
 
 use clap_complete::generate;
 use color_eyre::eyre::Result;
 
 use crate::cli::command::Command;
+use crate::cli::external;
+use crate::cli::version::VERSION;
 use crate::cli::Cli;
 use crate::config::Config;
 use crate::output::Output;
+use crate::{dirs, file, hash};
 
 /// Generate shell completions
+///
+/// The generated script is cached per shell, keyed on the rtx version and the set of
+/// installed plugins, so it is only regenerated when one of those changes.
 #[derive(Debug, clap::Args)]
 #[clap(aliases = ["complete", "completions"], verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
 pub struct Completion {
@@ -19,23 +26,65 @@ pub struct Completion {
     /// Shell type to generate completions for
     #[clap(long = "shell", short = 's', hide = true)]
     shell_type: Option<clap_complete::Shell>,
+
+    /// Regenerate the completions even if a cached copy exists
+    #[clap(long)]
+    no_cache: bool,
 }
 
 impl Command for Completion {
-    fn run(self, _config: Config, out: &mut Output) -> Result<()> {
+    fn run(self, config: Config, out: &mut Output) -> Result<()> {
         let shell = match self.shell.or(self.shell_type) {
             Some(shell) => shell,
             None => panic!("no shell provided"),
         };
 
-        let mut c = Cursor::new(Vec::new());
-        generate(shell, &mut Cli::command(), "rtx", &mut c);
-        rtxprintln!(out, "{}", String::from_utf8(c.into_inner()).unwrap());
+        let cache_file = cache_file(&config, shell);
+        let script = match file::read_to_string(&cache_file) {
+            Ok(script) if !self.no_cache => script,
+            _ => {
+                let script = generate_script(&config, shell);
+                if let Err(err) = write_cache(&cache_file, shell, &script) {
+                    warn!("failed to write completions cache: {:#}", err);
+                }
+                script
+            }
+        };
+        rtxprintln!(out, "{}", script);
 
         Ok(())
     }
 }
 
+fn generate_script(config: &Config, shell: clap_complete::Shell) -> String {
+    let mut c = Cursor::new(Vec::new());
+    let mut cmd = Cli::command().subcommands(external::commands(config));
+    generate(shell, &mut cmd, "rtx", &mut c);
+    String::from_utf8(c.into_inner()).unwrap()
+}
+
+fn cache_file(config: &Config, shell: clap_complete::Shell) -> PathBuf {
+    let plugins = config.tools.keys().collect::<Vec<_>>();
+    let key = hash::hash_to_str(&(&*VERSION, plugins));
+    dirs::CACHE
+        .join("completions")
+        .join(format!("{shell}-{key}"))
+}
+
+/// writes the new script and removes stale ones for the same shell
+fn write_cache(cache_file: &Path, shell: clap_complete::Shell, script: &str) -> Result<()> {
+    let dir = cache_file.parent().unwrap();
+    file::create_dir_all(dir)?;
+    for entry in dir.read_dir()? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if name.starts_with(&format!("{shell}-")) && path != *cache_file {
+            file::remove_file(&path)?;
+        }
+    }
+    file::write(cache_file, script)
+}
+
 static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
   $ <bold>rtx completion bash > /etc/bash_completion.d/rtx</bold>
@@ -43,3 +92,15 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
   $ <bold>rtx completion fish > ~/.config/fish/completions/rtx.fish</bold>
 "#
 );
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_cli;
+
+    #[test]
+    fn test_completion_cached() {
+        let fresh = assert_cli!("completion", "bash", "--no-cache");
+        let cached = assert_cli!("completion", "bash");
+        assert_eq!(fresh, cached);
+    }
+}
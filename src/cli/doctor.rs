@@ -1,10 +1,13 @@
 use std::fmt::Write;
+use std::path::PathBuf;
 use std::process::exit;
 
 use color_eyre::eyre::Result;
 use console::{pad_str, style, Alignment};
 use indenter::indented;
 use indoc::formatdoc;
+use itertools::Itertools;
+use serde_derive::Serialize;
 
 use crate::build_time::built_info;
 use crate::cli::command::Command;
@@ -14,51 +17,122 @@ use crate::git::Git;
 use crate::output::Output;
 use crate::plugins::PluginType;
 use crate::shell::ShellType;
-use crate::toolset::ToolsetBuilder;
-use crate::{cli, cmd, dirs};
+use crate::toolset::{ToolSource, Toolset, ToolsetBuilder};
+use crate::{cli, cmd, dirs, file, http, relocate, shims};
 use crate::{duration, env};
 
 /// Check rtx installation for possible problems.
+///
+/// Exits with a non-zero code if any check with "error" status fails.
+/// Warnings (e.g.: a new rtx version is available) are reported but do not fail.
 #[derive(Debug, clap::Args)]
 #[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
-pub struct Doctor {}
+pub struct Doctor {
+    /// Check PATH for binaries shadowing rtx-managed tools
+    /// shows which binary would win for each bin of each active tool
+    #[clap(long, verbatim_doc_comment)]
+    paths: bool,
+
+    /// Output in JSON format with the status of each check
+    #[clap(long, visible_short_alias = 'J')]
+    json: bool,
+}
+
+/// a single doctor check, `id` is stable so scripts can match on it
+#[derive(Debug, Serialize)]
+struct Check {
+    id: &'static str,
+    status: CheckStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+impl Check {
+    fn error(id: &'static str, message: String) -> Self {
+        Self {
+            id,
+            status: CheckStatus::Error,
+            message: Some(message),
+        }
+    }
+    fn warning(id: &'static str, message: String) -> Self {
+        Self {
+            id,
+            status: CheckStatus::Warning,
+            message: Some(message),
+        }
+    }
+}
+
+const CHECKS: &[&str] = &[
+    "plugin_not_installed",
+    "new_version_available",
+    "not_activated",
+    "activate_inherited",
+    "activated_twice",
+    "hook_env_network",
+    "data_dir_read_only",
+    "data_dir_moved",
+];
+const PATH_CHECKS: &[&str] = &[
+    "path_duplicate",
+    "path_other_version_manager",
+    "bin_shadowed",
+];
 
 impl Command for Doctor {
     fn run(self, mut config: Config, out: &mut Output) -> Result<()> {
         let ts = ToolsetBuilder::new().build(&mut config)?;
-        rtxprintln!(out, "{}", rtx_version());
-        rtxprintln!(out, "{}", build_info());
-        rtxprintln!(out, "{}", shell());
-        rtxprintln!(out, "{}", rtx_data_dir());
-        rtxprintln!(out, "{}", rtx_env_vars());
-        rtxprintln!(
-            out,
-            "{}\n{}\n",
-            style("settings:").bold(),
-            indent(config.settings.to_string())
-        );
-        rtxprintln!(out, "{}", render_config_files(&config));
-        rtxprintln!(out, "{}", render_plugins(&config));
-        rtxprintln!(
-            out,
-            "{}\n{}\n",
-            style("toolset:").bold(),
-            indent(ts.to_string())
-        );
+        if !self.json {
+            rtxprintln!(out, "{}", rtx_version());
+            rtxprintln!(out, "{}", build_info());
+            rtxprintln!(out, "{}", shell());
+            rtxprintln!(out, "{}", rtx_data_dir());
+            rtxprintln!(out, "{}", rtx_env_vars());
+            rtxprintln!(
+                out,
+                "{}\n{}\n",
+                style("settings:").bold(),
+                indent(config.settings.to_string())
+            );
+            rtxprintln!(out, "{}", render_config_files(&config));
+            rtxprintln!(out, "{}", render_plugins(&config));
+            rtxprintln!(
+                out,
+                "{}\n{}\n",
+                style("toolset:").bold(),
+                indent(ts.to_string())
+            );
+            rtxprintln!(out, "{}", render_env_overrides(&ts));
+        }
 
         let mut checks = Vec::new();
         for plugin in config.tools.values() {
             if !plugin.is_installed() {
-                checks.push(format!("plugin {} is not installed", &plugin.name));
+                checks.push(Check::error(
+                    "plugin_not_installed",
+                    format!("plugin {} is not installed", &plugin.name),
+                ));
                 continue;
             }
         }
 
         if let Some(latest) = cli::version::check_for_new_version(duration::HOURLY) {
-            checks.push(format!(
-                "new rtx version {} available, currently on {}",
-                latest,
-                env!("CARGO_PKG_VERSION")
+            checks.push(Check::warning(
+                "new_version_available",
+                format!(
+                    "new rtx version {} available, currently on {}",
+                    latest,
+                    env!("CARGO_PKG_VERSION")
+                ),
             ));
         }
 
@@ -66,23 +140,89 @@ impl Command for Doctor {
             let cmd = style("rtx help activate").yellow().for_stderr();
             let url = style("https://rtx.pub").underlined().for_stderr();
             let shims = style(dirs::SHIMS.display()).cyan().for_stderr();
-            checks.push(formatdoc!(
-                r#"rtx is not activated, run {cmd} or
+            checks.push(Check::error(
+                "not_activated",
+                formatdoc!(
+                    r#"rtx is not activated, run {cmd} or
                    read documentation at {url} for activation instructions.
                    Alternatively, add the shims directory {shims} to PATH.
                    Using the shims directory is preferred for non-interactive setups."#
+                ),
             ));
         }
 
-        if checks.is_empty() {
+        if config.is_activated() {
+            if let Some(session) = inherited_session() {
+                checks.push(Check::warning(
+                    "activate_inherited",
+                    formatdoc!(
+                        r#"rtx was activated in another shell (pid {session}) and this shell only
+                       inherited its environment, PATH will not change with the current directory.
+                       Run `rtx activate` in this shell's rc file, even if RTX_SHELL is already set."#
+                    ),
+                ));
+            }
+            if let Some(paths) = activated_twice() {
+                checks.push(Check::warning(
+                    "activated_twice",
+                    format!(
+                        "rtx tool paths are on PATH more than once, rtx is likely activated twice \
+                        (e.g.: in both ~/.bash_profile and ~/.bashrc, or with `rtx env` as well):\n{}",
+                        paths.join("\n")
+                    ),
+                ));
+            }
+        }
+
+        if let Some(blocked) = hook_env_network() {
+            checks.push(Check::warning("hook_env_network", blocked));
+        }
+
+        if *dirs::ROOT_READ_ONLY {
+            checks.push(Check::warning(
+                "data_dir_read_only",
+                format!(
+                    "RTX_DATA_DIR ({}) is read-only. Installed tools can be used but installing, \
+                    uninstalling, plugin changes and reshims need a writable overlay on it.",
+                    file::display_path(&dirs::ROOT)
+                ),
+            ));
+        }
+
+        if let Some(previous) = relocate::previous_path() {
+            checks.push(Check::warning(
+                "data_dir_moved",
+                format!(
+                    "tools were installed in {} but RTX_DATA_DIR has moved. \
+                    Run `rtx relocate` to fix scripts which point to the old location.",
+                    previous.display()
+                ),
+            ));
+        }
+
+        let mut check_ids = CHECKS.to_vec();
+        if self.paths {
+            let paths = render_paths(&config, &ts, &mut checks)?;
+            if !self.json {
+                rtxprintln!(out, "{}", paths);
+            }
+            check_ids.extend(PATH_CHECKS);
+        }
+
+        let failed = checks.iter().any(|c| c.status == CheckStatus::Error);
+        if self.json {
+            self.output_json(&config, check_ids, checks, out)?;
+        } else if checks.is_empty() {
             rtxprintln!(out, "No problems found");
         } else {
             let checks_plural = if checks.len() == 1 { "" } else { "s" };
             let summary = format!("{} problem{checks_plural} found:", checks.len());
             rtxprintln!(out, "{}", style(summary).red().bold());
             for check in &checks {
-                rtxprintln!(out, "{}\n", check);
+                rtxprintln!(out, "{}\n", check.message.as_deref().unwrap_or(check.id));
             }
+        }
+        if failed {
             exit(1);
         }
 
@@ -90,10 +230,172 @@ impl Command for Doctor {
     }
 }
 
+impl Doctor {
+    fn output_json(
+        &self,
+        config: &Config,
+        check_ids: Vec<&'static str>,
+        mut checks: Vec<Check>,
+        out: &mut Output,
+    ) -> Result<()> {
+        for id in check_ids {
+            if !checks.iter().any(|c| c.id == id) {
+                checks.push(Check {
+                    id,
+                    status: CheckStatus::Ok,
+                    message: None,
+                });
+            }
+        }
+        let ok = !checks.iter().any(|c| c.status == CheckStatus::Error);
+        let plugins = config
+            .tools
+            .values()
+            .filter(|p| p.is_installed())
+            .map(|p| p.name.clone())
+            .collect_vec();
+        let json = serde_json::json!({
+            "ok": ok,
+            "version": *VERSION,
+            "data_dir": *env::RTX_DATA_DIR,
+            "activated": config.is_activated(),
+            "shims_on_path": shims_on_path(),
+            "shell": ShellType::load().map(|s| s.to_string()),
+            "config_files": config.config_files.keys().rev().collect_vec(),
+            "plugins": plugins,
+            "checks": checks,
+        });
+        rtxprintln!(out, "{}", serde_json::to_string_pretty(&json)?);
+        Ok(())
+    }
+}
+
+/// runs hook-env the way a new shell would and reports what it needed the network for
+/// hook-env and shims only read remote data from caches, so these stay unresolved until
+/// a command like `rtx install` refreshes them
+fn hook_env_network() -> Option<String> {
+    let output = cmd!(&*env::RTX_EXE, "hook-env", "--shell", "bash")
+        .env_remove("__RTX_WATCH")
+        .env("RTX_ACTIVATE_INSTALL_MISSING", "off")
+        .env("RTX_MISSING_RUNTIME_BEHAVIOR", "ignore")
+        .stdin_null()
+        .stdout_null()
+        .stderr_capture()
+        .unchecked()
+        .run()
+        .ok()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let blocked = stderr
+        .lines()
+        .filter(|l| l.contains(http::NETWORK_DISABLED_MSG))
+        .map(|l| format!("  {}", l.trim()))
+        .collect_vec();
+    if blocked.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "hook-env needed network access, these tools will not resolve until their caches are refreshed:\n{}",
+        blocked.join("\n")
+    ))
+}
+
+/// the activating shell's pid if it isn't the shell running doctor
+fn inherited_session() -> Option<String> {
+    let session = env::__RTX_SESSION.clone()?;
+    let shell_pid = std::os::unix::process::parent_id().to_string();
+    (session != shell_pid).then_some(session)
+}
+
+/// rtx paths which appear more than once on PATH, or tool install dirs which the current
+/// activation did not add (those are not removed when the active versions change)
+fn activated_twice() -> Option<Vec<String>> {
+    let path = env::var_os("PATH").unwrap_or_default();
+    let path = env::split_paths(&path).collect::<Vec<_>>();
+    let managed = &env::__RTX_DIFF.path;
+    let paths = path
+        .iter()
+        .duplicates()
+        .filter(|p| managed.contains(p))
+        .chain(
+            path.iter()
+                .filter(|p| p.starts_with(&*dirs::INSTALLS) && !managed.contains(p)),
+        )
+        .unique()
+        .map(|p| format!("  {}", file::display_path(p)))
+        .collect_vec();
+    (!paths.is_empty()).then_some(paths)
+}
+
 fn shims_on_path() -> bool {
     env::PATH.contains(&*dirs::SHIMS)
 }
 
+/// shim directories of other version managers that commonly conflict with rtx
+const OTHER_VERSION_MANAGERS: &[&str] = &[
+    ".asdf/shims",
+    ".nodenv/shims",
+    ".pyenv/shims",
+    ".rbenv/shims",
+    ".goenv/shims",
+    ".jenv/shims",
+    ".volta/bin",
+    ".nvm/versions",
+];
+
+fn render_paths(config: &Config, ts: &Toolset, checks: &mut Vec<Check>) -> Result<String> {
+    let mut s = style("paths:\n").bold().to_string();
+    let path = env::var_os("PATH").unwrap_or_default();
+    let path = env::split_paths(&path).collect::<Vec<_>>();
+    for dup in path.iter().duplicates() {
+        checks.push(Check::warning(
+            "path_duplicate",
+            format!("PATH contains {} more than once", dup.display()),
+        ));
+    }
+    for p in &path {
+        let p_str = p.to_string_lossy();
+        if let Some(vm) = OTHER_VERSION_MANAGERS.iter().find(|vm| p_str.contains(*vm)) {
+            checks.push(Check::warning(
+                "path_other_version_manager",
+                format!(
+                    "PATH contains {} from another version manager ({vm}), it may shadow rtx tools",
+                    p.display()
+                ),
+            ));
+        }
+    }
+    for (t, tv) in ts.list_current_installed_versions(config) {
+        let bin_paths = t.list_bin_paths(config, &tv)?;
+        s.push_str(&format!("  {}\n", style(&tv).cyan()));
+        for bin in shims::list_tool_bins(config, &t, &tv)?.into_iter().sorted() {
+            let managed = |p: &PathBuf| {
+                p.starts_with(&*dirs::SHIMS) || bin_paths.iter().any(|bp| p.starts_with(bp))
+            };
+            match file::which_all(&bin).first() {
+                Some(winner) if managed(winner) => {
+                    s.push_str(&format!("    {bin} -> {}\n", winner.display()));
+                }
+                Some(winner) => {
+                    s.push_str(&format!(
+                        "    {bin} -> {} {}\n",
+                        winner.display(),
+                        style("(shadows rtx)").yellow()
+                    ));
+                    checks.push(Check::warning(
+                        "bin_shadowed",
+                        format!(
+                            "{bin} from {tv} is shadowed by {} which is earlier in PATH",
+                            winner.display()
+                        ),
+                    ));
+                }
+                None => s.push_str(&format!("    {bin} -> {}\n", style("(not on PATH)").dim())),
+            }
+        }
+    }
+    Ok(s)
+}
+
 fn rtx_data_dir() -> String {
     let mut s = style("rtx data directory:\n").bold().to_string();
     s.push_str(&format!("  {}\n", env::RTX_DATA_DIR.to_string_lossy()));
@@ -114,6 +416,31 @@ fn rtx_env_vars() -> String {
     s
 }
 
+fn render_env_overrides(ts: &Toolset) -> String {
+    let mut s = style("environment overrides:\n").bold().to_string();
+    let overrides = ts
+        .versions
+        .values()
+        .filter(|tvl| matches!(tvl.source, ToolSource::Environment(..)))
+        .collect_vec();
+    if overrides.is_empty() {
+        s.push_str("  (none)\n");
+    }
+    for tvl in overrides {
+        let versions = tvl.requests.iter().map(|(tvr, _)| tvr.version()).join(" ");
+        s.push_str(&format!(
+            "  {} {} ({})",
+            tvl.plugin_name, versions, tvl.source
+        ));
+        if let Some((_, opts)) = tvl.requests.first().filter(|(_, opts)| !opts.is_empty()) {
+            let opts = opts.iter().map(|(k, v)| format!("{k}={v}")).join(",");
+            s.push_str(&format!(" opts: {opts}"));
+        }
+        s.push('\n');
+    }
+    s
+}
+
 fn render_config_files(config: &Config) -> String {
     let mut s = style("config files:\n").bold().to_string();
     for f in config.config_files.keys().rev() {
@@ -146,6 +473,12 @@ fn render_plugins(config: &Config) -> String {
                 }
             }
             PluginType::Core => format!("  {padded_name} (core)\n"),
+            PluginType::Cargo => format!("  {padded_name} (cargo)\n"),
+            PluginType::GithubRelease => format!("  {padded_name} (github release)\n"),
+            PluginType::Go => format!("  {padded_name} (go install)\n"),
+            PluginType::Http => format!("  {padded_name} (http)\n"),
+            PluginType::Npm => format!("  {padded_name} (npm)\n"),
+            PluginType::Pipx => format!("  {padded_name} (pipx)\n"),
         };
         s.push_str(&si);
     }
@@ -198,5 +531,17 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
   $ <bold>rtx doctor</bold>
   [WARN] plugin node is not installed
+
+  $ <bold>rtx doctor --json | jq '.checks[] | select(.status != "ok")'</bold>
+  {
+    "id": "plugin_not_installed",
+    "status": "error",
+    "message": "plugin node is not installed"
+  }
+
+  $ <bold>rtx doctor --paths</bold>
+  paths:
+    node@20.0.0
+      node -> /usr/local/bin/node (shadows rtx)
 "#
 );
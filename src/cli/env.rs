@@ -1,9 +1,18 @@
-use color_eyre::eyre::Result;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use clap::{Subcommand, ValueHint};
+use color_eyre::eyre::{Result, WrapErr};
+use itertools::Itertools;
+use serde_derive::{Deserialize, Serialize};
 
 use crate::cli::args::tool::{ToolArg, ToolArgParser};
 use crate::cli::command::Command;
+use crate::cli::version::VERSION;
 use crate::config::Config;
+use crate::file;
 use crate::output::Output;
+use crate::runtime_symlinks;
 use crate::shell::{get_shell, ShellType};
 use crate::toolset::{Toolset, ToolsetBuilder};
 
@@ -12,10 +21,18 @@ use crate::toolset::{Toolset, ToolsetBuilder};
 /// Use this if you don't want to permanently install rtx. It's not necessary to
 /// use this if you have `rtx activate` in your shell rc file.
 #[derive(Debug, clap::Args)]
-#[clap(visible_alias = "e", verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+#[clap(
+    visible_alias = "e",
+    verbatim_doc_comment,
+    args_conflicts_with_subcommands = true,
+    after_long_help = AFTER_LONG_HELP
+)]
 pub struct Env {
+    #[clap(subcommand)]
+    command: Option<Commands>,
+
     /// Shell type to generate environment variables for
-    #[clap(long, short, overrides_with = "json")]
+    #[clap(long, short, overrides_with_all = ["json", "dotenv"])]
     shell: Option<ShellType>,
 
     /// Tool(s) to use
@@ -23,41 +40,199 @@ pub struct Env {
     tool: Vec<ToolArg>,
 
     /// Output in JSON format
-    #[clap(long, visible_short_alias = 'J', overrides_with = "shell")]
+    #[clap(long, visible_short_alias = 'J', overrides_with_all = ["shell", "dotenv"])]
     json: bool,
+
+    /// Output as KEY=VALUE lines for `docker run --env-file` and docker-compose `env_file`
+    #[clap(long, overrides_with_all = ["shell", "json"])]
+    dotenv: bool,
+
+    /// Use the symlink for the requested version in paths, e.g.: installs/node/20 instead of
+    /// installs/node/20.3.0 for node@20, so IDE configs keep working after a patch upgrade
+    /// Versions without a symlink pointing to them keep the versioned path
+    #[clap(long, verbatim_doc_comment, conflicts_with = "resolve_symlinks")]
+    stable_paths: bool,
+
+    /// Resolve symlinks in paths, e.g.: tools added with `rtx link` or a symlinked RTX_DATA_DIR
+    #[clap(long)]
+    resolve_symlinks: bool,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    Snapshot(EnvSnapshot),
+}
+
+/// Save the resolved tools and env vars to a file
+///
+/// The snapshot has every env var rtx would set, including PATH, so
+/// `rtx exec --snapshot <FILE>` can later run a command in exactly that
+/// environment. Useful for bug reports and bisecting environment-related failures.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+struct EnvSnapshot {
+    /// Tool(s) to use
+    #[clap(value_name="TOOL@VERSION", value_parser = ToolArgParser)]
+    tool: Vec<ToolArg>,
+
+    /// Write the snapshot to this file instead of stdout
+    #[clap(long, short, value_hint = ValueHint::FilePath)]
+    output: Option<PathBuf>,
+}
+
+/// the format written by `rtx env snapshot`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub rtx_version: String,
+    pub tools: Vec<String>,
+    pub env: BTreeMap<String, String>,
+}
+
+impl Snapshot {
+    pub fn read(path: &Path) -> Result<Self> {
+        let raw = file::read_to_string(path)?;
+        let snapshot: Self = serde_json::from_str(&raw)
+            .wrap_err_with(|| format!("invalid env snapshot: {}", path.display()))?;
+        if snapshot.rtx_version != *VERSION {
+            warn!(
+                "env snapshot was created with rtx {}, currently on {}",
+                snapshot.rtx_version, *VERSION
+            );
+        }
+        Ok(snapshot)
+    }
+}
+
+impl Command for EnvSnapshot {
+    fn run(self, mut config: Config, out: &mut Output) -> Result<()> {
+        let ts = ToolsetBuilder::new()
+            .with_install_missing()
+            .with_args(&self.tool)
+            .build(&mut config)?;
+        let snapshot = Snapshot {
+            rtx_version: VERSION.to_string(),
+            tools: ts
+                .list_current_versions(&config)
+                .into_iter()
+                .map(|(_, tv)| tv.to_string())
+                .collect(),
+            env: ts.env_with_path(&config),
+        };
+        let json = serde_json::to_string_pretty(&snapshot)?;
+        match &self.output {
+            Some(path) => file::write(path, json + "\n")?,
+            None => rtxprintln!(out, "{}", json),
+        }
+        Ok(())
+    }
 }
 
 impl Command for Env {
     fn run(self, mut config: Config, out: &mut Output) -> Result<()> {
+        if let Some(Commands::Snapshot(cmd)) = self.command {
+            return cmd.run(config, out);
+        }
         let ts = ToolsetBuilder::new()
             .with_install_missing()
             .with_args(&self.tool)
             .build(&mut config)?;
+        let env = self.rewrite_paths(&config, &ts, ts.env_with_path(&config));
         if self.json {
-            self.output_json(config, out, ts)
+            self.output_json(out, env)
+        } else if self.dotenv {
+            rtxprint!(out, "{}", to_dotenv(&env));
+            Ok(())
         } else {
-            self.output_shell(config, out, ts)
+            self.output_shell(out, env)
         }
     }
 }
 
 impl Env {
-    fn output_json(&self, config: Config, out: &mut Output, ts: Toolset) -> Result<()> {
-        let env = ts.env_with_path(&config);
+    fn output_json(&self, out: &mut Output, env: BTreeMap<String, String>) -> Result<()> {
         rtxprintln!(out, "{}", serde_json::to_string_pretty(&env)?);
         Ok(())
     }
 
-    fn output_shell(&self, config: Config, out: &mut Output, ts: Toolset) -> Result<()> {
+    fn output_shell(&self, out: &mut Output, env: BTreeMap<String, String>) -> Result<()> {
         let default_shell = get_shell(Some(ShellType::Bash)).unwrap();
         let shell = get_shell(self.shell).unwrap_or(default_shell);
-        for (k, v) in ts.env_with_path(&config) {
-            let k = k.to_string();
-            let v = v.to_string();
+        for (k, v) in env {
             rtxprint!(out, "{}", shell.set_env(&k, &v));
         }
         Ok(())
     }
+
+    /// applies --stable-paths and --resolve-symlinks to every value
+    fn rewrite_paths(
+        &self,
+        config: &Config,
+        ts: &Toolset,
+        mut env: BTreeMap<String, String>,
+    ) -> BTreeMap<String, String> {
+        if self.stable_paths {
+            for (_, tv) in ts.list_current_installed_versions(config) {
+                if let Some(stable) = runtime_symlinks::stable_path(&tv) {
+                    let versioned = tv.install_path();
+                    for v in env.values_mut() {
+                        *v = replace_path_prefix(v, &versioned, &stable);
+                    }
+                }
+            }
+        }
+        if self.resolve_symlinks {
+            for v in env.values_mut() {
+                *v = resolve_symlinks(v);
+            }
+        }
+        env
+    }
+}
+
+/// replaces `from` in `value` where it's a whole path or the start of one
+/// e.g.: installs/node/20.1.0 is replaced in installs/node/20.1.0/bin but not in installs/node/20.1.01
+fn replace_path_prefix(value: &str, from: &Path, to: &Path) -> String {
+    let from = from.to_string_lossy();
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(i) = rest.find(from.as_ref()) {
+        let end = i + from.len();
+        out.push_str(&rest[..i]);
+        match rest[end..].chars().next() {
+            None | Some('/') | Some(':') => out.push_str(&to.to_string_lossy()),
+            _ => out.push_str(&from),
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// canonicalizes each absolute path in a value, which may be a single path or a list like PATH
+fn resolve_symlinks(value: &str) -> String {
+    value
+        .split(':')
+        .map(|p| match p.starts_with('/') {
+            true => std::fs::canonicalize(p)
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_else(|_| p.to_string()),
+            false => p.to_string(),
+        })
+        .join(":")
+}
+
+/// KEY=VALUE lines as read by docker's --env-file, which takes values literally (no quoting)
+/// values with newlines can't be represented so they are skipped
+pub fn to_dotenv(env: &BTreeMap<String, String>) -> String {
+    let mut out = String::new();
+    for (k, v) in env {
+        if v.contains('\n') {
+            warn!("skipping {k} in env file: value contains a newline");
+            continue;
+        }
+        out.push_str(&format!("{k}={v}\n"));
+    }
+    out
 }
 
 static AFTER_LONG_HELP: &str = color_print::cstr!(
@@ -66,12 +241,17 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
   $ <bold>eval "$(rtx env -s zsh)"</bold>
   $ <bold>rtx env -s fish | source</bold>
   $ <bold>execx($(rtx env -s xonsh))</bold>
+  $ <bold>eval `rtx env -s csh`</bold>
+  $ <bold>rtx env --dotenv > .env && docker run --env-file .env ...</bold>
+  $ <bold>rtx env --json --stable-paths node@20</bold>
+  $ <bold>rtx env snapshot -o env.json && rtx exec --snapshot env.json -- npm test</bold>
 "#
 );
 
 #[cfg(test)]
 mod tests {
     use std::env;
+    use std::path::Path;
 
     use pretty_assertions::assert_str_eq;
 
@@ -116,6 +296,36 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_env_stable_paths() {
+        assert_cli!("install", "tiny@3");
+        let stdout = assert_cli!("env", "tiny@3", "--stable-paths", "-s", "bash");
+        assert!(stdout.contains(
+            dirs::ROOT
+                .join("installs/tiny/3/bin")
+                .to_string_lossy()
+                .as_ref()
+        ));
+    }
+
+    #[test]
+    fn test_replace_path_prefix() {
+        let from = Path::new("/rtx/installs/node/20.1.0");
+        let to = Path::new("/rtx/installs/node/20");
+        assert_str_eq!(
+            super::replace_path_prefix(
+                "/rtx/installs/node/20.1.0/bin:/rtx/installs/node/20.1.01/bin:/usr/bin",
+                from,
+                to
+            ),
+            "/rtx/installs/node/20/bin:/rtx/installs/node/20.1.01/bin:/usr/bin"
+        );
+        assert_str_eq!(
+            super::replace_path_prefix("/rtx/installs/node/20.1.0", from, to),
+            "/rtx/installs/node/20"
+        );
+    }
+
     #[test]
     fn test_env_tiny() {
         let stdout = assert_cli!("env", "tiny@2", "tiny@1", "tiny@3", "-s", "bash");
@@ -129,8 +339,33 @@ mod tests {
         assert!(stdout.contains("export PATH="));
     }
 
+    #[test]
+    fn test_env_dotenv() {
+        let stdout = assert_cli!("env", "tiny@2", "--dotenv");
+        assert_str_eq!(grep(stdout, "JDXCODE"), "JDXCODE_TINY=2.1.0");
+    }
+
     #[test]
     fn test_env_json() {
         assert_cli_snapshot!("env", "-J");
     }
+
+    #[test]
+    fn test_env_snapshot() {
+        let path = dirs::CACHE.join("test-env-snapshot.json");
+        assert_cli!("env", "snapshot", "tiny@2", "-o", path.to_str().unwrap());
+        let snapshot = super::Snapshot::read(&path).unwrap();
+        assert_eq!(snapshot.env["JDXCODE_TINY"], "2.1.0");
+        assert!(snapshot.tools.contains(&"tiny@2.1.0".to_string()));
+        assert_cli!(
+            "exec",
+            "--snapshot",
+            path.to_str().unwrap(),
+            "--",
+            "sh",
+            "-c",
+            "test \"$JDXCODE_TINY\" = 2.1.0"
+        );
+        crate::file::remove_file(&path).unwrap();
+    }
 }
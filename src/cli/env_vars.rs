@@ -1,3 +1,4 @@
+use clap::Subcommand;
 use color_eyre::Result;
 
 use crate::cli::command::Command;
@@ -14,13 +15,17 @@ use super::args::env_var::{EnvVarArg, EnvVarArgParser};
 ///
 /// By default this command modifies ".rtx.toml" in the current directory.
 /// You can specify the file name by either setting the RTX_DEFAULT_CONFIG_FILENAME environment variable, or by using the --file option.
+/// Only the [env] table is changed, the rest of the file's formatting and comments are kept.
 #[derive(Debug, clap::Args)]
-#[clap(verbatim_doc_comment)]
+#[clap(verbatim_doc_comment, args_conflicts_with_subcommands = true, subcommand_negates_reqs = true, after_long_help = AFTER_LONG_HELP)]
 pub struct EnvVars {
+    #[clap(subcommand)]
+    command: Option<Commands>,
+
     /// The TOML file to update
     ///
     /// Defaults to RTX_DEFAULT_CONFIG_FILENAME environment variable, or ".rtx.toml".
-    #[clap(long, verbatim_doc_comment, required = false)]
+    #[clap(long, verbatim_doc_comment, required = false, global = true)]
     file: Option<String>,
 
     /// Remove the environment variable from config file
@@ -35,21 +40,49 @@ pub struct EnvVars {
     env_vars: Vec<EnvVarArg>,
 }
 
+#[derive(Debug, Subcommand)]
+enum Commands {
+    Set(EnvVarsSet),
+    Remove(EnvVarsRemove),
+}
+
+/// Set environment variable(s) in the config file
+#[derive(Debug, clap::Args)]
+struct EnvVarsSet {
+    /// Environment variable(s) to set
+    /// e.g.: NODE_ENV=production
+    #[clap(value_parser = EnvVarArgParser, verbatim_doc_comment, required = true)]
+    env_vars: Vec<EnvVarArg>,
+}
+
+/// Remove environment variable(s) from the config file
+#[derive(Debug, clap::Args)]
+#[clap(visible_alias = "rm", alias = "unset")]
+struct EnvVarsRemove {
+    /// Environment variable(s) to remove
+    #[clap(value_name = "ENV_VAR", required = true)]
+    env_vars: Vec<String>,
+}
+
 impl Command for EnvVars {
     fn run(self, config: Config, _out: &mut Output) -> Result<()> {
         let filename = self
             .file
             .unwrap_or_else(|| RTX_DEFAULT_CONFIG_FILENAME.to_string());
 
+        let (env_vars, remove) = match self.command {
+            Some(Commands::Set(cmd)) => (cmd.env_vars, vec![]),
+            Some(Commands::Remove(cmd)) => (vec![], cmd.env_vars),
+            None => (self.env_vars, self.remove.unwrap_or_default()),
+        };
+
         let mut rtx_toml = get_rtx_toml(&config, filename.as_str())?;
 
-        if let Some(env_names) = &self.remove {
-            for name in env_names {
-                rtx_toml.remove_env(name);
-            }
+        for name in &remove {
+            rtx_toml.remove_env(name);
         }
 
-        for ev in self.env_vars {
+        for ev in env_vars {
             rtx_toml.update_env(&ev.key, ev.value);
         }
         rtx_toml.save()
@@ -68,6 +101,14 @@ fn get_rtx_toml(config: &Config, filename: &str) -> Result<RtxToml> {
     Ok(rtx_toml)
 }
 
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  $ <bold>rtx env-vars set NODE_ENV=production</bold>
+  $ <bold>rtx env-vars remove NODE_ENV</bold>
+  $ <bold>rtx env-vars set --file .rtx.local.toml API_URL=http://localhost:3000</bold>
+"#
+);
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
@@ -117,4 +158,20 @@ mod tests {
         assert_snapshot!(file::read_to_string(cf_path).unwrap());
         remove_config_file(filename);
     }
+
+    #[test]
+    fn test_env_vars_subcommands() {
+        let filename = ".test-subcommands.rtx.toml";
+        let cf_path = remove_config_file(filename);
+        file::write(&cf_path, "# my config\n[env]\nKEEP = \"me\" # comment\n").unwrap();
+        assert_cli!("env-vars", "set", "--file", filename, "FOO=bar", "BAZ=quux");
+        assert_cli!("env-vars", "remove", "--file", filename, "BAZ");
+        assert_snapshot!(file::read_to_string(&cf_path).unwrap(), @r###"
+        # my config
+        [env]
+        KEEP = "me" # comment
+        FOO = "bar"
+        "###);
+        remove_config_file(filename);
+    }
 }
@@ -1,20 +1,26 @@
 use std::collections::BTreeMap;
 use std::ffi::{OsStr, OsString};
-use std::path::PathBuf;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
 
 use clap::ValueHint;
-use color_eyre::eyre::{eyre, Result};
+use color_eyre::eyre::{bail, eyre, Result};
+use console::style;
 use duct::IntoExecutablePath;
+use itertools::Itertools;
 
 use crate::cli::args::tool::{ToolArg, ToolArgParser};
 use crate::cli::command::Command;
-#[cfg(test)]
+use crate::cli::env::{to_dotenv, Snapshot};
 use crate::cmd;
 use crate::config::Config;
 use crate::config::MissingRuntimeBehavior::Ignore;
-use crate::env;
 use crate::output::Output;
-use crate::toolset::ToolsetBuilder;
+use crate::toolset::{Toolset, ToolsetBuilder};
+use crate::{dirs, env, file, last_used};
 
 /// Execute a command with tool(s) set
 ///
@@ -25,6 +31,12 @@ use crate::toolset::ToolsetBuilder;
 /// includes "node 20" but you run `rtx exec python@3.11`; it will still load node@20.
 ///
 /// The "--" separates runtimes from the commands to pass along to the subprocess.
+///
+/// rtx can also be used as a script interpreter. Tools can be declared in a header
+/// comment at the top of a script whose shebang runs rtx and will be installed if missing:
+///
+///     #!/usr/bin/env -S rtx x -- node
+///     // rtx: tools: node@20, jq@1.7
 #[derive(Debug, clap::Args)]
 #[clap(visible_alias = "x", verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
 pub struct Exec {
@@ -34,58 +46,317 @@ pub struct Exec {
     pub tool: Vec<ToolArg>,
 
     /// Command string to execute (same as --command)
-    #[clap(conflicts_with = "c", required_unless_present = "c", last = true)]
+    #[clap(conflicts_with = "c", required_unless_present_any = ["c", "emit_env_file"], last = true)]
     pub command: Option<Vec<OsString>>,
 
     /// Command string to execute
     #[clap(short, long = "command", value_hint = ValueHint::CommandString, conflicts_with = "command")]
     pub c: Option<OsString>,
 
-    /// Change to this directory before executing the command
-    #[clap(visible_short_alias = 'C', value_hint = ValueHint::DirPath, long)]
-    pub cd: Option<PathBuf>,
+    /// Write the environment to a KEY=VALUE file for `docker run --env-file`
+    /// The command may be omitted when this is set
+    #[clap(long, value_name = "PATH", value_hint = ValueHint::FilePath, verbatim_doc_comment)]
+    pub emit_env_file: Option<PathBuf>,
+
+    /// Run the command once for each TOOL@VERSION instead of once with all of them
+    /// up to --jobs commands run in parallel, their output is shown when each finishes
+    /// exits non-zero if any of the commands fail
+    #[clap(
+        long,
+        requires = "tool",
+        conflicts_with = "emit_env_file",
+        verbatim_doc_comment
+    )]
+    pub each: bool,
+
+    /// Run the command with the environment saved by `rtx env snapshot`
+    /// instead of the tools from the current config
+    #[clap(long, value_name = "FILE", value_hint = ValueHint::FilePath, conflicts_with_all = ["tool", "each"], verbatim_doc_comment)]
+    pub snapshot: Option<PathBuf>,
+
+    /// Run the command in a docker container from IMAGE
+    /// the current directory is mounted at the same path and used as the working directory
+    #[clap(long, value_name = "IMAGE", conflicts_with_all = ["each", "snapshot", "emit_env_file"], verbatim_doc_comment)]
+    pub docker: Option<String>,
+
+    /// How --docker gets the tools into the container
+    /// mount: install on this host and mount RTX_DATA_DIR read-only, linux hosts only [default]
+    /// install: run rtx in the container, installs are kept in the "rtx-docker" volume
+    #[clap(long, value_name = "MODE", requires = "docker", verbatim_doc_comment)]
+    pub docker_tools: Option<DockerTools>,
+
+    /// Fail if any of the tools are not installed instead of running the command without them
+    /// this is the default with the exec_strict setting
+    #[clap(long, verbatim_doc_comment)]
+    pub strict: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DockerTools {
+    Mount,
+    Install,
 }
 
+/// what `docker run` uses when an image does not set PATH
+const DOCKER_DEFAULT_PATH: &str = "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin";
+
 impl Command for Exec {
-    fn run(self, mut config: Config, _out: &mut Output) -> Result<()> {
-        let ts = ToolsetBuilder::new()
-            .with_args(&self.tool)
-            .with_install_missing()
-            .build(&mut config)?;
-        let (program, args) = parse_command(&env::SHELL, &self.command, &self.c);
-        let mut env = ts.env_with_path(&config);
+    fn run(self, mut config: Config, out: &mut Output) -> Result<()> {
+        let mut tools = vec![];
+        let mut command = self.command.clone();
+        if let Some(c) = &mut command {
+            if let Some(header) = ScriptHeader::find(c) {
+                tools.extend(header.tools);
+                if header.reinvokes_rtx {
+                    // the script's shebang is rtx itself, executing it directly would loop
+                    let interpreter = header.interpreter.unwrap_or_else(|| "sh".into());
+                    c.insert(0, interpreter.into());
+                }
+            }
+        }
+        if let Some(image) = &self.docker {
+            return self.exec_docker(config, image, tools, &command);
+        }
+        if self.each {
+            return self.exec_each(config, tools, &command, out);
+        }
+        let mut env = match &self.snapshot {
+            Some(path) => Snapshot::read(path)?.env,
+            None => {
+                tools.extend(self.tool.iter().cloned());
+                let ts = ToolsetBuilder::new()
+                    .with_args(&tools)
+                    .with_install_missing()
+                    .build(&mut config)?;
+                self.check_strict(&config, &ts)?;
+                last_used::touch_toolset(&config, &ts);
+                match config.settings.env_clear {
+                    true => ts.full_env(&config),
+                    false => ts.env_with_path(&config),
+                }
+            }
+        };
+        if let Some(path) = &self.emit_env_file {
+            file::write(path, to_dotenv(&env))?;
+            if command.is_none() && self.c.is_none() {
+                return Ok(());
+            }
+        }
+        let (program, args) = parse_command(&env::SHELL, &command, &self.c);
         if config.settings.missing_runtime_behavior != Ignore {
             // prevent rtx from auto-installing inside a shim
             env.insert("RTX_MISSING_RUNTIME_BEHAVIOR".into(), "warn".into());
         }
 
-        self.exec(program, args, env)
+        // a snapshot only has the env of the tools
+        let clear = config.settings.env_clear && self.snapshot.is_none();
+        self.exec(program, args, env, clear)
     }
 }
 
 impl Exec {
+    /// `--strict`: errors if a tool could not be installed rather than letting the command find
+    /// another version on PATH
+    fn check_strict(&self, config: &Config, ts: &Toolset) -> Result<()> {
+        if !self.strict && !config.settings.exec_strict {
+            return Ok(());
+        }
+        let missing = ts.list_missing_versions(config);
+        if missing.is_empty() {
+            return Ok(());
+        }
+        bail!(
+            "Tool{} not installed: {}\nInstall all missing tools with: rtx install",
+            if missing.len() > 1 { "s" } else { "" },
+            missing.iter().join(", ")
+        );
+    }
+
+    /// `--docker`: runs the command in a container with the tools mounted or installed in it
+    fn exec_docker(
+        &self,
+        mut config: Config,
+        image: &str,
+        mut tools: Vec<ToolArg>,
+        command: &Option<Vec<OsString>>,
+    ) -> Result<()> {
+        tools.extend(self.tool.iter().cloned());
+        // the host's shell may not exist in the image
+        let (program, args) = parse_command("sh", command, &self.c);
+        let docker_tools = self.docker_tools.unwrap_or(DockerTools::Mount);
+        let mut docker = docker_run_args(docker_tools, console::user_attended());
+        match docker_tools {
+            DockerTools::Mount => {
+                if std::env::consts::OS != "linux" {
+                    bail!(
+                        "tools installed on {} can't run in a linux container, use --docker-tools=install",
+                        std::env::consts::OS
+                    );
+                }
+                let ts = ToolsetBuilder::new()
+                    .with_args(&tools)
+                    .with_install_missing()
+                    .build(&mut config)?;
+                self.check_strict(&config, &ts)?;
+                let root = dirs::ROOT.to_string_lossy();
+                docker.extend(["-v".into(), format!("{root}:{root}:ro")]);
+                let mut env = ts.env(&config);
+                let mut path = ts.list_paths(&config);
+                path.extend(env::split_paths(DOCKER_DEFAULT_PATH));
+                env.insert(
+                    "PATH".into(),
+                    env::join_paths(path)?.to_string_lossy().to_string(),
+                );
+                for (k, v) in env {
+                    docker.extend(["-e".into(), format!("{k}={v}")]);
+                }
+                docker.push(image.into());
+            }
+            DockerTools::Install => {
+                // a linux rtx can be mounted, otherwise the image has to provide it
+                if std::env::consts::OS == "linux" {
+                    let exe = env::RTX_EXE.to_string_lossy();
+                    docker.extend(["-v".into(), format!("{exe}:/usr/local/bin/rtx:ro")]);
+                }
+                docker.push(image.into());
+                docker.extend(["rtx".into(), "exec".into()]);
+                docker.extend(tools.iter().map(|t| t.to_string()));
+                docker.push("--".into());
+            }
+        }
+        debug!("docker {} {:?} {:?}", docker.join(" "), program, args);
+        let docker = docker
+            .into_iter()
+            .map(OsString::from)
+            .chain([program])
+            .chain(args)
+            .collect_vec();
+        self.exec(
+            "docker",
+            docker,
+            BTreeMap::<OsString, OsString>::new(),
+            false,
+        )
+    }
+
+    /// `--each`: runs the command with each tool arg in its own environment
+    fn exec_each(
+        &self,
+        mut config: Config,
+        tools: Vec<ToolArg>,
+        command: &Option<Vec<OsString>>,
+        out: &mut Output,
+    ) -> Result<()> {
+        let (program, args) = parse_command(&env::SHELL, command, &self.c);
+        let mut queue = vec![];
+        for tool in &self.tool {
+            let mut tools = tools.clone();
+            tools.push(tool.clone());
+            let ts = ToolsetBuilder::new()
+                .with_args(&tools)
+                .with_install_missing()
+                .build(&mut config)?;
+            self.check_strict(&config, &ts)?;
+            queue.push((tool.to_string(), ts.full_env(&config)));
+        }
+        let jobs = config.settings.jobs.max(1);
+        let capture = jobs > 1;
+        let out = Mutex::new(out);
+        let run = |name: &str, env: &BTreeMap<String, String>| -> Result<i32> {
+            let cmd = cmd::cmd(&program, &args).unchecked().full_env(env);
+            if !capture {
+                let mut out = out.lock().unwrap();
+                rtxstatusln!(out, "{}", style(name).cyan().for_stderr());
+                drop(out);
+                return Ok(cmd::exit_code(cmd.run()?.status));
+            }
+            let res = cmd.stderr_to_stdout().stdout_capture().run()?;
+            let mut out = out.lock().unwrap();
+            rtxstatusln!(
+                out,
+                "{}\n{}",
+                style(name).cyan().for_stderr(),
+                String::from_utf8_lossy(&res.stdout).trim_end()
+            );
+            Ok(cmd::exit_code(res.status))
+        };
+
+        let total = queue.len();
+        let queue = Arc::new(Mutex::new(
+            queue.into_iter().enumerate().rev().collect_vec(),
+        ));
+        let results = Mutex::new(vec![]);
+        let (run, results_ref) = (&run, &results);
+        thread::scope(|s| {
+            (0..jobs)
+                .map(|_| {
+                    let queue = queue.clone();
+                    s.spawn(move || {
+                        let next_job = move || queue.lock().unwrap().pop();
+                        while let Some((i, (name, env))) = next_job() {
+                            let code = run(&name, &env)?;
+                            results_ref.lock().unwrap().push((i, name, code));
+                        }
+                        Ok(())
+                    })
+                })
+                .collect_vec()
+                .into_iter()
+                .map(|t| t.join().unwrap())
+                .collect::<Result<Vec<()>>>()
+        })?;
+        let out = out.into_inner().unwrap();
+
+        let results = results
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .sorted()
+            .collect_vec();
+        for (_, name, code) in &results {
+            match code {
+                0 => rtxstatusln!(out, "{} {}", name, style("✓").green().for_stderr()),
+                _ => rtxstatusln!(
+                    out,
+                    "{} exited with {}",
+                    name,
+                    style(code).red().for_stderr()
+                ),
+            }
+        }
+        let failed = results.iter().filter(|(_, _, code)| *code != 0).count();
+        if failed > 0 {
+            return Err(eyre!(
+                "command failed for {failed} of {total} tool versions"
+            ));
+        }
+        Ok(())
+    }
+
     #[cfg(not(test))]
-    fn exec<T, U, E>(&self, program: T, args: U, env: BTreeMap<E, E>) -> Result<()>
+    fn exec<T, U, E>(&self, program: T, args: U, env: BTreeMap<E, E>, clear: bool) -> Result<()>
     where
         T: IntoExecutablePath,
         U: IntoIterator,
         U::Item: Into<OsString>,
         E: AsRef<OsStr>,
     {
+        if clear {
+            for (k, _) in env::vars_os() {
+                env::remove_var(k);
+            }
+        }
         for (k, v) in env.iter() {
             env::set_var(k, v);
         }
         let args = args.into_iter().map(Into::into).collect::<Vec<_>>();
         let program = program.to_executable();
-        if let Some(cd) = &self.cd {
-            env::set_current_dir(cd)?;
-        }
         let err = exec::Command::new(program.clone()).args(&args).exec();
         Err(eyre!("{:?} {}", program.to_string_lossy(), err.to_string()))
     }
 
     #[cfg(test)]
-    fn exec<T, U, E>(&self, program: T, args: U, env: BTreeMap<E, E>) -> Result<()>
+    fn exec<T, U, E>(&self, program: T, args: U, env: BTreeMap<E, E>, clear: bool) -> Result<()>
     where
         T: IntoExecutablePath,
         U: IntoIterator,
@@ -93,11 +364,12 @@ impl Exec {
         E: AsRef<OsStr>,
     {
         let mut cmd = cmd::cmd(program, args);
-        if let Some(cd) = &self.cd {
-            cmd = cmd.dir(cd);
-        }
-        for (k, v) in env.iter() {
-            cmd = cmd.env(k, v);
+        if clear {
+            cmd = cmd.full_env(env.iter());
+        } else {
+            for (k, v) in env.iter() {
+                cmd = cmd.env(k, v);
+            }
         }
         let res = cmd.unchecked().run()?;
         match res.status.code().unwrap_or(1) {
@@ -107,6 +379,116 @@ impl Exec {
     }
 }
 
+/// the "rtx:" header comment of a script which has rtx as its interpreter
+#[derive(Debug, Default)]
+struct ScriptHeader {
+    tools: Vec<ToolArg>,
+    interpreter: Option<String>,
+    /// the command after "--" in a shebang which runs rtx, None if the shebang does not run rtx
+    shebang_command: Option<Vec<String>>,
+    reinvokes_rtx: bool,
+}
+
+impl ScriptHeader {
+    /// finds the header of the script being run through its shebang
+    ///
+    /// `#!/usr/bin/env -S rtx x --` runs the script as the program and
+    /// `#!/usr/bin/env -S rtx x -- node` passes it as the first argument to node.
+    /// Headers of files which are only arguments, like `rtx x -- cat script.sh`, are ignored.
+    fn find(command: &[OsString]) -> Option<Self> {
+        command.iter().take(2).enumerate().find_map(|(i, arg)| {
+            let path = Path::new(arg);
+            if !path.is_file() {
+                return None;
+            }
+            let file = File::open(path).ok()?;
+            let head = BufReader::new(file)
+                .lines()
+                .take(20)
+                .map_while(|l| l.ok())
+                .collect::<Vec<_>>();
+            let mut header = Self::parse(&head)?;
+            let shebang_command = header.shebang_command.as_ref()?;
+            if shebang_command.len() != i
+                || !shebang_command
+                    .iter()
+                    .zip(command)
+                    .all(|(a, b)| b == a.as_str())
+            {
+                return None;
+            }
+            header.reinvokes_rtx = i == 0;
+            Some(header)
+        })
+    }
+
+    fn parse(lines: &[String]) -> Option<Self> {
+        let mut header = Self::default();
+        let mut found = false;
+        let mut lines = lines.iter().peekable();
+        if let Some(shebang) = lines.next_if(|l| l.starts_with("#!")) {
+            let mut words = shebang.split_whitespace();
+            if words.any(|w| w == "rtx" || w.ends_with("/rtx")) {
+                let command = words.skip_while(|w| *w != "--").skip(1);
+                header.shebang_command = Some(command.map(String::from).collect());
+            }
+        }
+        for line in lines {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some(comment) = ["#", "//", "--", ";"]
+                .iter()
+                .find_map(|prefix| line.strip_prefix(prefix))
+            else {
+                break;
+            };
+            let Some(rest) = comment.trim().strip_prefix("rtx:") else {
+                continue;
+            };
+            found = true;
+            match rest.trim().split_once(':') {
+                Some(("tools", tools)) => header.tools.extend(
+                    tools
+                        .split(|c: char| c == ',' || c.is_whitespace())
+                        .filter(|t| !t.is_empty())
+                        .map(ToolArg::parse),
+                ),
+                Some(("interpreter", interpreter)) => {
+                    header.interpreter = Some(interpreter.trim().to_string())
+                }
+                _ => warn!("unknown rtx header: {}", line),
+            }
+        }
+        (found && header.shebang_command.is_some()).then_some(header)
+    }
+}
+
+/// the `docker run` arguments before the image
+fn docker_run_args(tools: DockerTools, tty: bool) -> Vec<String> {
+    let cwd = dirs::CURRENT.to_string_lossy();
+    let mut args = vec!["run".into(), "--rm".into(), "-i".into()];
+    if tty {
+        args.push("-t".into());
+    }
+    args.extend([
+        "-v".into(),
+        format!("{cwd}:{cwd}"),
+        "-w".into(),
+        cwd.to_string(),
+    ]);
+    if tools == DockerTools::Install {
+        args.extend([
+            "-v".into(),
+            "rtx-docker:/rtx".into(),
+            "-e".into(),
+            "RTX_DATA_DIR=/rtx".into(),
+        ]);
+    }
+    args
+}
+
 fn parse_command(
     shell: &str,
     command: &Option<Vec<OsString>>,
@@ -130,16 +512,39 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
   # Specify command as a string:
   $ <bold>rtx exec node@20 python@3.11 --command "node -v && python -V"</bold>
 
+  # Use rtx as a script interpreter:
+  $ <bold>cat ./script.js</bold>
+  #!/usr/bin/env -S rtx x -- node
+  // rtx: tools: node@20
+  console.log(process.version)
+
   # Run a command in a different directory:
   $ <bold>rtx x -C /path/to/project node@20 -- node ./app.js</bold>
+
+  # Run the tests once for each node version:
+  $ <bold>rtx x node@18 node@20 --each -- npm test</bold>
+
+  # Reproduce an environment saved with `rtx env snapshot`:
+  $ <bold>rtx x --snapshot env.json -- npm test</bold>
+
+  # Run the tests in a clean container with the project's tools:
+  $ <bold>rtx x --docker ubuntu:22.04 -- npm test</bold>
+  $ <bold>rtx x --docker debian:12 --docker-tools=install node@20 -- node -v</bold>
+
+  # Fail instead of using whatever node is on PATH if node@20 isn't installed:
+  $ <bold>rtx x --strict node@20 -- node -v</bold>
 "#
 );
 
 #[cfg(test)]
 mod tests {
+    use itertools::Itertools;
+
     use crate::assert_cli;
     use crate::cli::tests::cli_run;
 
+    use super::{docker_run_args, DockerTools, ScriptHeader};
+
     #[test]
     fn test_exec_ok() {
         assert_cli!("exec", "--", "echo");
@@ -156,6 +561,59 @@ mod tests {
         .unwrap_err();
     }
 
+    #[test]
+    fn test_script_header() {
+        let lines = [
+            "#!/usr/bin/env -S rtx x --",
+            "# rtx: tools: node@20, jq@1.7",
+            "# rtx: interpreter: bash",
+            "echo hi",
+            "# rtx: tools: python@3.11",
+        ]
+        .map(String::from);
+        let header = ScriptHeader::parse(&lines).unwrap();
+        let tools = header.tools.iter().map(|t| t.plugin.as_str()).collect_vec();
+        assert_eq!(tools, vec!["node", "jq"]);
+        assert_eq!(header.interpreter.as_deref(), Some("bash"));
+        assert_eq!(header.shebang_command, Some(vec![]));
+
+        let lines = ["#!/usr/bin/env -S rtx x -- node", "// rtx: tools: node@20"].map(String::from);
+        let header = ScriptHeader::parse(&lines).unwrap();
+        assert_eq!(header.shebang_command, Some(vec!["node".to_string()]));
+
+        let lines = ["#!/bin/sh", "# rtx: tools: node@20", "echo hi"].map(String::from);
+        assert!(ScriptHeader::parse(&lines).is_none());
+    }
+
+    #[test]
+    fn test_exec_strict() {
+        assert_cli!("exec", "--strict", "tiny@2", "--", "echo");
+    }
+
+    #[test]
+    fn test_exec_each() {
+        assert_cli!("exec", "tiny@2", "tiny@3", "--each", "--", "echo");
+        let _ = cli_run(
+            &vec!["rtx", "exec", "tiny@3", "--each", "--", "exit", "1"]
+                .into_iter()
+                .map(String::from)
+                .collect::<Vec<String>>(),
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn test_docker_run_args() {
+        let args = docker_run_args(DockerTools::Install, false);
+        assert_eq!(args[..3], ["run", "--rm", "-i"]);
+        assert!(!args.contains(&"-t".to_string()));
+        assert!(args.contains(&"RTX_DATA_DIR=/rtx".to_string()));
+
+        let args = docker_run_args(DockerTools::Mount, true);
+        assert!(args.contains(&"-t".to_string()));
+        assert!(!args.contains(&"rtx-docker:/rtx".to_string()));
+    }
+
     #[test]
     fn test_exec_cd() {
         assert_cli!("exec", "-C", "/tmp", "--", "pwd");
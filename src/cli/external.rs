@@ -1,3 +1,4 @@
+use clap::parser::ValueSource;
 use clap::{ArgMatches, Command};
 use color_eyre::eyre::Result;
 use itertools::Itertools;
@@ -36,11 +37,32 @@ pub fn execute(
     {
         if let Some((subcommand, matches)) = args.subcommand() {
             let plugin = config.tools.get(&plugin.to_string()).unwrap();
-            let args: Vec<String> = matches
-                .get_raw("args")
-                .unwrap_or_default()
-                .map(|s| s.to_string_lossy().to_string())
-                .collect();
+            let mut args = vec![];
+            // flags declared in the script header are parsed by clap so pass them back along
+            if let Some(sub) = cmd.find_subcommand(subcommand) {
+                for arg in sub.get_arguments().filter(|a| a.get_long().is_some()) {
+                    let id = arg.get_id().as_str();
+                    if matches.value_source(id) != Some(ValueSource::CommandLine) {
+                        continue;
+                    }
+                    let flag = format!("--{}", arg.get_long().unwrap());
+                    match matches.get_raw(id) {
+                        Some(values) if arg.get_action().takes_values() => {
+                            for v in values {
+                                args.push(flag.clone());
+                                args.push(v.to_string_lossy().to_string());
+                            }
+                        }
+                        _ => args.push(flag),
+                    }
+                }
+            }
+            args.extend(
+                matches
+                    .get_raw("args")
+                    .unwrap_or_default()
+                    .map(|s| s.to_string_lossy().to_string()),
+            );
             plugin.execute_external_command(config, subcommand, args)?;
         } else {
             cmd.print_help().unwrap();
@@ -1,7 +1,11 @@
 use std::cmp::max;
+use std::collections::BTreeSet;
 use std::env::join_paths;
+use std::fs::OpenOptions;
 use std::ops::Deref;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::process::Stdio;
 
 use color_eyre::eyre::Result;
 use console::truncate_str;
@@ -9,15 +13,16 @@ use itertools::Itertools;
 use terminal_size::{terminal_size, Width};
 
 use crate::cli::command::Command;
-use crate::config::Config;
-use crate::config::MissingRuntimeBehavior::{Prompt, Warn};
+use crate::config::MissingRuntimeBehavior::{Ignore, Prompt, Warn};
+use crate::config::{ActivateInstallMissing, ActivateStatus, Config};
 use crate::direnv::DirenvDiff;
 use crate::env::__RTX_DIFF;
 use crate::env_diff::{EnvDiff, EnvDiffOperation};
+use crate::lock_file::LockFile;
 use crate::output::Output;
 use crate::shell::{get_shell, ShellType};
 use crate::toolset::{Toolset, ToolsetBuilder};
-use crate::{env, hook_env};
+use crate::{dirs, env, file, hook_env, http, last_used};
 
 /// [internal] called by activate hook to update env vars directory change
 #[derive(Debug, clap::Args)]
@@ -28,18 +33,44 @@ pub struct HookEnv {
     shell: Option<ShellType>,
 
     /// Show "rtx: <PLUGIN>@<VERSION>" message when changing directories
-    #[clap(long)]
+    /// same as setting activate_status to "verbose"
+    #[clap(long, verbatim_doc_comment)]
     status: bool,
 }
 
 impl Command for HookEnv {
     fn run(self, mut config: Config, out: &mut Output) -> Result<()> {
-        if config.settings.missing_runtime_behavior == Prompt {
-            config.settings.missing_runtime_behavior = Warn;
+        // remote data is only read from caches here, see `rtx doctor`
+        http::disable_network();
+        match config.settings.activate_install_missing {
+            ActivateInstallMissing::Prompt => {
+                config.settings.missing_runtime_behavior = Prompt;
+            }
+            // missing versions are handled below by `rtx install` in a separate process
+            ActivateInstallMissing::Background => {
+                config.settings.missing_runtime_behavior = Ignore;
+            }
+            ActivateInstallMissing::Off => {
+                if config.settings.missing_runtime_behavior == Prompt {
+                    config.settings.missing_runtime_behavior = Warn;
+                }
+            }
+        }
+        let status = match self.status {
+            true => ActivateStatus::Verbose,
+            false => config.settings.activate_status,
+        };
+        if status >= ActivateStatus::Missing && config.settings.missing_runtime_behavior == Warn {
+            // replaced by the one-line notice in display_notices
+            config.settings.missing_runtime_behavior = Ignore;
         }
         let ts = ToolsetBuilder::new()
             .with_install_missing()
             .build(&mut config)?;
+        last_used::touch_toolset(&config, &ts);
+        if config.settings.activate_install_missing == ActivateInstallMissing::Background {
+            self.install_missing_in_background(&config, &ts, out)?;
+        }
         let shell = get_shell(self.shell).expect("no shell provided, use `--shell=zsh`");
         out.stdout.write(hook_env::clear_old_env(&*shell));
         let env = ts.env(&config);
@@ -56,30 +87,93 @@ impl Command for HookEnv {
 
         let output = hook_env::build_env_commands(&*shell, &patches);
         out.stdout.write(output);
-        if self.status {
-            self.display_status(&config, &ts, out);
-        }
+        self.display_notices(&config, &ts, &diff, status, out);
 
         Ok(())
     }
 }
 
 impl HookEnv {
+    /// runs `rtx install` detached from the shell so the prompt is not blocked
+    /// the versions will be picked up by the next hook-env run after they finish installing
+    /// only one background install runs at a time, later prompts skip starting another one
+    fn install_missing_in_background(
+        &self,
+        config: &Config,
+        ts: &Toolset,
+        out: &mut Output,
+    ) -> Result<()> {
+        let missing = ts.list_missing_versions(config);
+        if missing.is_empty() {
+            return Ok(());
+        }
+        let pid_file = dirs::CACHE.join("background-install.pid");
+        let _lock = LockFile::new(&pid_file).lock()?;
+        if background_install_running(&pid_file) {
+            return Ok(());
+        }
+        file::create_dir_all(&*dirs::CACHE)?;
+        let log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dirs::CACHE.join("background-install.log"))?;
+        let child = process::Command::new(&*env::RTX_EXE)
+            .arg("install")
+            .env("RTX_ACTIVATE_INSTALL_MISSING", "off")
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log)
+            .spawn()?;
+        file::write(&pid_file, child.id().to_string())?;
+        rtxstatusln!(
+            out,
+            "installing {} in the background",
+            missing.iter().map(|tv| tv.to_string()).join(" ")
+        );
+        Ok(())
+    }
+
+    /// one-line notices printed to stderr based on the activate_status setting
+    fn display_notices(
+        &self,
+        config: &Config,
+        ts: &Toolset,
+        diff: &EnvDiff,
+        status: ActivateStatus,
+        out: &mut Output,
+    ) {
+        // background installs already announce the missing versions
+        if status >= ActivateStatus::Missing
+            && config.settings.activate_install_missing != ActivateInstallMissing::Background
+        {
+            let missing = ts.list_missing_versions(config);
+            if !missing.is_empty() {
+                let missing = missing.iter().map(|tv| tv.to_string()).join(" ");
+                let notice = format!("{missing} not installed, run `rtx install`");
+                rtxstatusln!(out, "{}", truncate_to_terminal(&notice));
+            }
+        }
+        if status >= ActivateStatus::Changes {
+            let changes = env_changes(&__RTX_DIFF, diff);
+            if !changes.is_empty() {
+                let notice = format!("env {}", changes.join(" "));
+                rtxstatusln!(out, "{}", truncate_to_terminal(&notice));
+            }
+        }
+        if status >= ActivateStatus::Verbose {
+            self.display_status(config, ts, out);
+        }
+    }
+
     fn display_status(&self, config: &Config, ts: &Toolset, out: &mut Output) {
         let installed_versions = ts
             .list_current_installed_versions(config)
             .into_iter()
-            .rev()
             .map(|(_, v)| v.to_string())
             .collect_vec();
         if !installed_versions.is_empty() {
-            let w = match terminal_size() {
-                Some((Width(w), _)) => w,
-                None => 80,
-            } as usize;
-            let w = max(w, 40);
-            let status = installed_versions.into_iter().rev().join(" ");
-            rtxstatusln!(out, "{}", truncate_str(&status, w - 4, "..."));
+            let status = installed_versions.join(" ");
+            rtxstatusln!(out, "{}", truncate_to_terminal(&status));
         }
     }
 
@@ -89,7 +183,8 @@ impl HookEnv {
         installs: &Vec<PathBuf>,
         to_remove: &Vec<PathBuf>,
     ) -> Result<Vec<EnvDiffOperation>> {
-        let new_path = join_paths([installs.clone(), env::PATH.clone()].concat())?
+        let pristine_path = without_leftover_installs(&env::PATH, &dirs::INSTALLS);
+        let new_path = join_paths([installs.clone(), pristine_path].concat())?
             .to_string_lossy()
             .to_string();
         let mut ops = vec![EnvDiffOperation::Add("PATH".into(), new_path)];
@@ -154,12 +249,95 @@ impl HookEnv {
     }
 }
 
+/// env vars set by rtx that differ from the previous hook-env run
+/// "+" added, "~" changed, "-" removed
+fn env_changes(old: &EnvDiff, new: &EnvDiff) -> Vec<String> {
+    let keys: BTreeSet<_> = old.new.keys().chain(new.new.keys()).collect();
+    keys.into_iter()
+        .filter_map(|k| match (old.new.get(k), new.new.get(k)) {
+            (None, Some(_)) => Some(format!("+{k}")),
+            (Some(_), None) => Some(format!("-{k}")),
+            (Some(a), Some(b)) if a != b => Some(format!("~{k}")),
+            _ => None,
+        })
+        .collect()
+}
+
+/// tool install dirs only get on PATH through rtx, any still there after __RTX_DIFF was reversed
+/// come from an activation whose __RTX_DIFF was not passed on (e.g.: a parent shell started with
+/// `env -i`, `sudo`, or an `eval "$(rtx env)"` in a profile) and would otherwise be duplicated
+fn without_leftover_installs(path: &[PathBuf], installs: &Path) -> Vec<PathBuf> {
+    path.iter()
+        .filter(|p| !p.starts_with(installs))
+        .cloned()
+        .collect()
+}
+
+fn truncate_to_terminal(s: &str) -> String {
+    let w = match terminal_size() {
+        Some((Width(w), _)) => w,
+        None => 80,
+    } as usize;
+    let w = max(w, 40);
+    truncate_str(s, w - 4, "...").to_string()
+}
+
+/// whether the process in the pid file of a background install is still running
+fn background_install_running(pid_file: &Path) -> bool {
+    let pid = match file::read_to_string(pid_file) {
+        Ok(pid) => pid.trim().parse::<libc::pid_t>().unwrap_or_default(),
+        Err(_) => return false,
+    };
+    // signal 0 only checks that the process exists
+    pid > 0 && unsafe { libc::kill(pid, 0) } == 0
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use crate::assert_cli;
 
+    use super::*;
+
     #[test]
     fn test_hook_env() {
         assert_cli!("hook-env", "--status", "-s", "fish");
     }
+
+    #[test]
+    fn test_env_changes() {
+        let old = EnvDiff::new(
+            &HashMap::new(),
+            vec![
+                ("FOO".to_string(), "1".to_string()),
+                ("BAR".to_string(), "1".to_string()),
+                ("BAZ".to_string(), "1".to_string()),
+            ],
+        );
+        let new = EnvDiff::new(
+            &HashMap::new(),
+            vec![
+                ("FOO".to_string(), "1".to_string()),
+                ("BAR".to_string(), "2".to_string()),
+                ("QUX".to_string(), "1".to_string()),
+            ],
+        );
+        assert_eq!(env_changes(&old, &new), vec!["~BAR", "-BAZ", "+QUX"]);
+    }
+
+    #[test]
+    fn test_without_leftover_installs() {
+        let installs = Path::new("/rtx/installs");
+        let path = vec![
+            PathBuf::from("/rtx/installs/node/20.0.0/bin"),
+            PathBuf::from("/rtx/shims"),
+            PathBuf::from("/usr/bin"),
+            PathBuf::from("/rtx/installs/node/18.0.0/bin"),
+        ];
+        assert_eq!(
+            without_leftover_installs(&path, installs),
+            vec![PathBuf::from("/rtx/shims"), PathBuf::from("/usr/bin")]
+        );
+    }
 }
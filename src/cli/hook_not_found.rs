@@ -0,0 +1,96 @@
+use std::ffi::OsString;
+use std::process::exit;
+
+use color_eyre::eyre::Result;
+
+use crate::bin_providers;
+use crate::cli::args::tool::ToolArg;
+use crate::cli::command::Command;
+use crate::cli::exec::Exec;
+use crate::cli::r#use::Use;
+use crate::config::Config;
+use crate::output::Output;
+use crate::toolset::ToolsetBuilder;
+use crate::ui::prompt;
+
+/// [internal] called by the shell's command-not-found handler
+///
+/// If an installed tool version provides the missing command, offers to run it with that
+/// tool set. Otherwise, if a plugin is known to provide it, offers to install it with
+/// `rtx use <plugin>@latest` and then runs it. Exits with 127 if nothing provides it so the shell
+/// can fall back to its default.
+#[derive(Debug, clap::Args)]
+#[clap(hide = true, verbatim_doc_comment)]
+pub struct HookNotFound {
+    /// The command that was not found and its arguments
+    #[clap(last = true, required = true)]
+    command: Vec<OsString>,
+}
+
+impl Command for HookNotFound {
+    fn run(self, mut config: Config, out: &mut Output) -> Result<()> {
+        let bin = self.command[0].to_string_lossy().to_string();
+        let ts = ToolsetBuilder::new().build(&mut config)?;
+        let provider = ts
+            .list_installed_versions(&config)?
+            .into_iter()
+            .rev()
+            .find(|(t, tv)| matches!(t.which(&config, tv, &bin), Ok(Some(_))));
+        let Some((_, tv)) = provider else {
+            return self.install_provider(config, &bin, out);
+        };
+        rtxstatusln!(out, "{bin} is provided by {tv}");
+        let run = config.settings.yes || prompt::confirm(&format!("Run {bin} with {tv}?"))?;
+        if !run {
+            rtxstatusln!(out, "to use it in this directory run: rtx use {tv}");
+            exit(127);
+        }
+        self.exec(config, vec![ToolArg::parse(&tv.to_string())], out)
+    }
+}
+
+impl HookNotFound {
+    /// no installed version provides `bin`, offers to install a plugin known to provide it
+    fn install_provider(self, config: Config, bin: &str, out: &mut Output) -> Result<()> {
+        let Some(plugin) = bin_providers::providers(bin).into_iter().next() else {
+            exit(127);
+        };
+        let tool = format!("{plugin}@latest");
+        let install = config.settings.yes
+            || prompt::confirm(&format!(
+                "{bin} is provided by {plugin}, which is not installed. Run rtx use {tool}?"
+            ))?;
+        if !install {
+            if let Some(hint) = bin_providers::hint(bin) {
+                rtxstatusln!(out, "{hint}");
+            }
+            exit(127);
+        }
+        let r#use = Use {
+            tool: vec![ToolArg::parse(&tool)],
+            pin: false,
+            fuzzy: false,
+            remove: None,
+            global: false,
+            path: None,
+        };
+        r#use.run(config, out)?;
+        // the tool is now in the config file of this directory
+        self.exec(Config::load()?, vec![], out)
+    }
+
+    fn exec(self, config: Config, tool: Vec<ToolArg>, out: &mut Output) -> Result<()> {
+        let exec = Exec {
+            tool,
+            c: None,
+            command: Some(self.command),
+            emit_env_file: None,
+            each: false,
+            snapshot: None,
+            docker: None,
+            docker_tools: None,
+            strict: false,
+        };
+        exec.run(config, out)
+    }
+}
@@ -6,6 +6,7 @@ use crate::config::Config;
 use crate::file::remove_all;
 use crate::output::Output;
 use crate::ui::prompt;
+use crate::ui::prompt::Destructive;
 use crate::{dirs, env, file};
 
 /// Removes rtx CLI and all related data
@@ -25,7 +26,11 @@ pub struct Implode {
 
 impl Command for Implode {
     fn run(self, config: Config, out: &mut Output) -> Result<()> {
-        let mut files = vec![&*dirs::ROOT, &*dirs::CACHE, &*env::RTX_EXE];
+        let mut files = vec![&*dirs::ROOT, &*dirs::CACHE, &*dirs::STATE, &*env::RTX_EXE];
+        // RTX_SHIMS_DIR is normally inside RTX_DATA_DIR
+        if !dirs::SHIMS.starts_with(&*dirs::ROOT) {
+            files.push(&*dirs::SHIMS);
+        }
         if self.config {
             files.push(&*dirs::CONFIG);
         }
@@ -51,10 +56,12 @@ impl Implode {
     fn confirm_remove(&self, config: &Config, f: &Path) -> Result<bool> {
         if self.dry_run {
             Ok(false)
-        } else if config.settings.yes {
-            Ok(true)
         } else {
-            let r = prompt::confirm(&format!("remove {} ?", f.display()))?;
+            let r = prompt::confirm_destructive(
+                &config.settings,
+                Destructive::Implode,
+                &format!("remove {} ?", f.display()),
+            )?;
             Ok(r)
         }
     }
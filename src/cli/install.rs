@@ -5,6 +5,7 @@ use crate::cli::command::Command;
 use crate::config::Config;
 
 use crate::output::Output;
+use crate::platform;
 
 use crate::toolset::{
     ToolVersion, ToolVersionOptions, ToolVersionRequest, Toolset, ToolsetBuilder,
@@ -19,6 +20,8 @@ use crate::ui::multi_progress_report::MultiProgressReport;
 /// Or you can call a tool version explicitly with `rtx exec <TOOL>@<VERSION> -- <COMMAND>`.
 ///
 /// Tools will be installed in parallel. To disable, set `--jobs=1` or `RTX_JOBS=1`
+/// Versions of the same tool are installed one at a time. If one tool fails to install
+/// the others are still installed and every failure is reported at the end.
 #[derive(Debug, clap::Args)]
 #[clap(visible_alias = "i", verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
 pub struct Install {
@@ -28,18 +31,37 @@ pub struct Install {
     tool: Option<Vec<ToolArg>>,
 
     /// Force reinstall even if already installed
-    #[clap(long, short, requires = "tool")]
+    /// the version is uninstalled first, its downloads and caches are removed
+    /// and the plugin's download/install scripts are run again
+    /// without TOOL@VERSION, every version in the current config is reinstalled
+    #[clap(long, short, verbatim_doc_comment)]
     force: bool,
 
     /// Show installation output
     #[clap(long, short, action = clap::ArgAction::Count)]
     verbose: u8,
+
+    /// Install builds for this OS instead of the current one
+    /// e.g.: linux or macos
+    #[clap(long, verbatim_doc_comment)]
+    target_os: Option<String>,
+
+    /// Install builds for this architecture instead of the current one
+    /// e.g.: x64 to run under Rosetta on Apple Silicon
+    #[clap(long, verbatim_doc_comment)]
+    target_arch: Option<String>,
 }
 
 impl Command for Install {
-    fn run(self, config: Config, _out: &mut Output) -> Result<()> {
+    fn run(self, mut config: Config, _out: &mut Output) -> Result<()> {
+        if self.target_os.is_some() || self.target_arch.is_some() {
+            config.settings.target_os = self.target_os.clone().or(config.settings.target_os);
+            config.settings.target_arch = self.target_arch.clone().or(config.settings.target_arch);
+            platform::set_target(&config.settings);
+        }
         match &self.tool {
             Some(runtime) => self.install_runtimes(config, runtime)?,
+            None if self.force => self.reinstall_current_runtimes(config)?,
             None => self.install_missing_runtimes(config)?,
         }
 
@@ -125,6 +147,28 @@ impl Install {
         ts.install_versions(&mut config, versions, &mpr, self.force)?;
         Ok(())
     }
+
+    fn reinstall_current_runtimes(&self, mut config: Config) -> Result<()> {
+        let mut ts = ToolsetBuilder::new().build(&mut config)?;
+        let versions = ts
+            .list_current_versions(&config)
+            .into_iter()
+            .map(|(_, tv)| tv)
+            // system and path: versions are not managed by rtx
+            .filter(|tv| {
+                !matches!(
+                    tv.request,
+                    ToolVersionRequest::System(_) | ToolVersionRequest::Path(_, _)
+                )
+            })
+            .collect::<Vec<_>>();
+        if versions.is_empty() {
+            info!("no runtimes to reinstall");
+            return Ok(());
+        }
+        let mpr = MultiProgressReport::new(config.show_progress_bars());
+        ts.install_versions(&mut config, versions, &mpr, true)
+    }
 }
 
 static AFTER_LONG_HELP: &str = color_print::cstr!(
@@ -133,6 +177,9 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
   $ <bold>rtx install node@20</bold>      # install fuzzy node version
   $ <bold>rtx install node</bold>         # install version specified in .tool-versions or .rtx.toml
   $ <bold>rtx install</bold>                # installs everything specified in .tool-versions or .rtx.toml
+  $ <bold>rtx install --force node@20</bold>  # uninstall and rebuild node@20 from scratch
+  $ <bold>rtx install --force</bold>        # rebuild everything specified in .tool-versions or .rtx.toml
+  $ <bold>rtx install --target-arch x64 node@20</bold>  # install the x64 build on Apple Silicon
 "#
 );
 
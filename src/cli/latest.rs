@@ -45,16 +45,32 @@ impl Command for Latest {
                     .for_stderr()
             )
         })?;
-        if let Some(v) = prefix {
-            prefix = Some(config.resolve_alias(&plugin.name, &v)?);
+        let hops = match &prefix {
+            Some(v) => config.trace_alias(&plugin.name, v)?,
+            None => vec![],
+        };
+        if let Some(hop) = hops.last() {
+            prefix = Some(hop.to.clone());
         }
 
         let latest_version = if self.installed {
-            plugin.latest_installed_version(prefix)?
+            plugin.latest_installed_version(prefix.clone())?
         } else {
-            plugin.latest_version(&config.settings, prefix)?
+            plugin.latest_version(&config.settings, prefix.clone())?
         };
         if let Some(version) = latest_version {
+            if config.settings.verbose {
+                // e.g.: "lts -> 20 (node core plugin)" then "20 -> 20.11.1 (latest available)"
+                for hop in &hops {
+                    rtxstatusln!(out, "{hop}");
+                }
+                let source = match self.installed {
+                    true => "latest installed",
+                    false => "latest available",
+                };
+                let from = prefix.as_deref().unwrap_or("latest");
+                rtxstatusln!(out, "{from} -> {version} ({source})");
+            }
             rtxprintln!(out, "{}", version);
         }
         Ok(())
@@ -68,6 +84,11 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 
   $ <bold>rtx latest node</bold>     # get the latest stable version of node
   20.0.0
+
+  $ <bold>rtx latest node@lts --verbose</bold>  # show how aliases were resolved
+  rtx lts -> 20 (node core plugin)
+  rtx 20 -> 20.11.1 (latest available)
+  20.11.1
 "#
 );
 
@@ -0,0 +1,103 @@
+use color_eyre::eyre::Result;
+use indexmap::IndexMap;
+
+use crate::cli::command::Command;
+use crate::config::config_file::ConfigFileType;
+use crate::config::Config;
+use crate::file::display_path;
+use crate::lockfile::{self, Lockfile};
+use crate::output::Output;
+use crate::toolset::ToolsetBuilder;
+
+/// Pins the versions that fuzzy requests resolve to in .rtx.lock files
+///
+/// A lockfile is written next to each local config file (.rtx.toml, .tool-versions) with the
+/// exact version each request like `node = "20"` resolved to. Config files in the same
+/// directory share one lockfile. While the lockfile exists those versions are used instead of
+/// the newest match, so `rtx install` in CI or on another machine installs the same versions.
+/// Commit it along with the config file.
+///
+/// Requests which are already locked keep their version, new requests are resolved and added.
+/// Use `rtx upgrade` to move locked versions to newer ones.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Lock {}
+
+impl Command for Lock {
+    fn run(self, mut config: Config, out: &mut Output) -> Result<()> {
+        // installs missing plugins, versions can't be resolved without them
+        ToolsetBuilder::new().build(&mut config)?;
+        // .rtx.toml and .tool-versions in the same directory share a lockfile
+        let mut lockfiles: IndexMap<_, Lockfile> = IndexMap::new();
+        for (path, cf) in &config.config_files {
+            if cf.is_global()
+                || !matches!(
+                    cf.get_type(),
+                    ConfigFileType::RtxToml | ConfigFileType::ToolVersions
+                )
+            {
+                continue;
+            }
+            let lockfile = lockfiles.entry(lockfile::path_for(path)).or_default();
+            for tvl in cf.to_toolset().versions.values() {
+                let mut tvl = tvl.clone();
+                tvl.resolve(&config, false);
+                for tv in &tvl.versions {
+                    lockfile.add(tv);
+                }
+            }
+        }
+        for (lockfile_path, lockfile) in lockfiles {
+            if lockfile.is_empty() && !lockfile_path.exists() {
+                continue;
+            }
+            lockfile.save(&lockfile_path)?;
+            rtxprintln!(out, "{}", display_path(&lockfile_path));
+        }
+        Ok(())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  $ <bold>cat .rtx.toml</bold>
+  [tools]
+  node = "20"
+
+  $ <bold>rtx lock</bold>
+  ~/myproject/.rtx.lock
+
+  $ <bold>cat .rtx.lock</bold>
+  # generated by `rtx lock`, commit this file along with the config file
+  [[tools.node]]
+  request = "20"
+  version = "20.3.0"
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_cli, dirs, file, lockfile};
+
+    #[test]
+    fn test_lock() {
+        let path = lockfile::path_for(&dirs::CURRENT.join(".test-tool-versions"));
+        let stdout = assert_cli!("lock");
+        assert!(stdout.contains(".rtx.lock"));
+        // "tiny 3" in .test-tool-versions
+        let lockfile = file::read_to_string(&path).unwrap();
+        assert!(lockfile.contains("request = \"3\""));
+
+        // .test.rtx.toml in the same directory shares the lockfile
+        let cf_path = dirs::CURRENT.join(".test.rtx.toml");
+        file::write(&cf_path, "[tools]\ntiny = \"2\"\n").unwrap();
+        assert_cli!("lock");
+        let lockfile = file::read_to_string(&path).unwrap();
+        file::remove_file(&cf_path).unwrap();
+        assert!(lockfile.contains("request = \"2\""));
+        assert!(lockfile.contains("request = \"3\""));
+
+        file::remove_all(&path).unwrap();
+        file::remove_all(lockfile::path_for(&dirs::HOME.join(".test-tool-versions"))).unwrap();
+    }
+}
@@ -11,9 +11,11 @@ use itertools::Itertools;
 use serde_derive::Serialize;
 use versions::Versioning;
 
+use crate::cli::args::version_list::VersionListArgs;
 use crate::cli::command::Command;
 use crate::config::Config;
 use crate::errors::Error::PluginNotInstalled;
+use crate::file::display_path;
 use crate::output::Output;
 use crate::plugins::{unalias_plugin, PluginName};
 use crate::tool::Tool;
@@ -58,6 +60,24 @@ pub struct Ls {
     /// Display versions matching this prefix
     #[clap(long)]
     prefix: Option<String>,
+
+    /// Show installed versions that share a major version with another installed version
+    /// and which of them are still used by a tracked config file
+    #[clap(long, verbatim_doc_comment, conflicts_with_all = ["json", "parseable", "missing"])]
+    duplicates: bool,
+
+    /// Show which installed versions are requested by a tracked config file
+    /// and which were only installed at some point and would be removed by `rtx prune`
+    #[clap(long, verbatim_doc_comment, conflicts_with_all = ["json", "parseable", "missing", "duplicates"])]
+    requested: bool,
+
+    /// Show how requested versions were resolved through aliases
+    /// e.g.: lts -> 20 (node core plugin) then 20 -> 20.11.1
+    #[clap(long, verbatim_doc_comment, conflicts_with_all = ["json", "parseable"])]
+    resolve: bool,
+
+    #[clap(flatten)]
+    list: VersionListArgs,
 }
 
 impl Command for Ls {
@@ -68,6 +88,12 @@ impl Command for Ls {
             .or(self.plugin_arg.clone())
             .map(|p| PluginName::from(unalias_plugin(&p)));
         self.verify_plugin(&config)?;
+        if self.duplicates {
+            return self.display_duplicates(&mut config, out);
+        }
+        if self.requested {
+            return self.display_requested(&mut config, out);
+        }
 
         let mut runtimes = self.get_runtime_list(&mut config)?;
         if self.current || self.global {
@@ -87,6 +113,7 @@ impl Command for Ls {
             }
             runtimes.retain(|(_, tv, _)| tv.version.starts_with(prefix));
         }
+        let runtimes = self.apply_version_list(runtimes);
         if self.json {
             self.display_json(runtimes, out)
         } else if self.parseable {
@@ -125,6 +152,23 @@ impl Ls {
         Ok(())
     }
 
+    /// applies --limit/--reverse/--latest-per-minor to each plugin's versions
+    fn apply_version_list(&self, runtimes: Vec<RuntimeRow>) -> Vec<RuntimeRow> {
+        let groups = runtimes.into_iter().group_by(|(p, _, _)| p.name.clone());
+        groups
+            .into_iter()
+            .flat_map(|(_, rows)| {
+                let mut rows: IndexMap<String, RuntimeRow> =
+                    rows.map(|row| (row.1.version.clone(), row)).collect();
+                self.list
+                    .apply(rows.keys().cloned().collect())
+                    .into_iter()
+                    .filter_map(|v| rows.remove(&v))
+                    .collect_vec()
+            })
+            .collect()
+    }
+
     fn display_json(&self, runtimes: Vec<RuntimeRow>, out: &mut Output) -> Result<()> {
         if let Some(plugin) = &self.plugin {
             // only runtimes for 1 plugin
@@ -176,6 +220,10 @@ impl Ls {
         let output = runtimes
             .into_iter()
             .map(|(p, tv, source)| {
+                let resolution = match self.resolve && source.is_some() {
+                    true => resolution_chain(config, &tv)?,
+                    false => vec![],
+                };
                 let plugin = p.name.to_string();
                 let version = if let Some(symlink_path) = p.symlink_path(&tv) {
                     VersionStatus::Symlink(tv.version, symlink_path, source.is_some())
@@ -187,12 +235,12 @@ impl Ls {
                     VersionStatus::Inactive(tv.version)
                 };
                 let request = source.map(|source| (source.to_string(), tv.request.version()));
-                (plugin, version, request)
+                Ok((plugin, version, request, resolution))
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>>>()?;
         let (max_plugin_len, max_version_len, max_source_len) = output.iter().fold(
             (0, 0, 0),
-            |(max_plugin, max_version, max_source), (plugin, version, request)| {
+            |(max_plugin, max_version, max_source), (plugin, version, request, _)| {
                 let plugin = max_plugin.max(plugin.len());
                 let version = max_version.max(version.to_plain_string().len());
                 let source = match request {
@@ -202,7 +250,7 @@ impl Ls {
                 (plugin.min(10), version.min(15), source.min(30))
             },
         );
-        for (plugin, version, request) in output {
+        for (plugin, version, request, resolution) in output {
             let pad = |s, len| console::pad_str(s, len, Left, None);
             let plugin_extra =
                 ((plugin.len() as i8 - max_plugin_len as i8).max(0) as usize).min(max_version_len);
@@ -223,10 +271,109 @@ impl Ls {
                 }
             };
             rtxprintln!(out, "{}", line.trim_end());
+            for hop in resolution {
+                rtxprintln!(out, "  {}", style(hop).dim());
+            }
         }
         Ok(())
     }
 
+    fn display_duplicates(&self, config: &mut Config, out: &mut Output) -> Result<()> {
+        let installed = self.list_installed(config)?;
+        let used = requested_versions(config)?;
+        let groups = installed
+            .into_iter()
+            .filter_map(|(p, tv)| {
+                let key = duplicate_key(&tv.version)?;
+                Some(((p.name.clone(), key), tv))
+            })
+            .into_group_map();
+        let mut found = false;
+        for ((plugin, key), tvs) in groups.into_iter().sorted_by(|a, b| a.0.cmp(&b.0)) {
+            if tvs.len() < 2 {
+                continue;
+            }
+            found = true;
+            let tvs = tvs
+                .into_iter()
+                .sorted_by_cached_key(|tv| Versioning::new(&tv.version))
+                .collect_vec();
+            let versions = tvs.iter().map(|tv| tv.version.as_str()).join(" ");
+            let in_use = tvs
+                .iter()
+                .filter(|tv| used.contains_key(&tv.to_string()))
+                .map(|tv| tv.version.as_str())
+                .join(" ");
+            let in_use = if in_use.is_empty() { "none" } else { &in_use };
+            rtxprintln!(
+                out,
+                "{} {} (in use: {})",
+                style(format!("{plugin}@{key}")).cyan(),
+                versions,
+                in_use
+            );
+            let unused = tvs
+                .iter()
+                .filter(|tv| !used.contains_key(&tv.to_string()))
+                .collect_vec();
+            if unused.is_empty() {
+                let latest = &tvs.last().unwrap().version;
+                rtxprintln!(
+                    out,
+                    "  consolidate on {latest} by updating the configs using the older versions"
+                );
+            } else {
+                let cmd = unused.iter().map(|tv| tv.to_string()).join(" ");
+                rtxprintln!(out, "  remove unused: rtx uninstall {cmd}");
+            }
+        }
+        if !found {
+            rtxprintln!(out, "no duplicate versions found");
+        } else {
+            rtxprintln!(
+                out,
+                "\nrun `rtx prune` to remove every version not used by a tracked config file"
+            );
+        }
+        Ok(())
+    }
+
+    fn display_requested(&self, config: &mut Config, out: &mut Output) -> Result<()> {
+        let installed = self.list_installed(config)?;
+        let requested = requested_versions(config)?;
+        let rows = installed
+            .into_iter()
+            .sorted_by_cached_key(|(p, tv)| (p.name.clone(), Versioning::new(&tv.version)))
+            .map(|(p, tv)| {
+                let sources = match requested.get(&tv.to_string()) {
+                    Some(paths) => paths.iter().map(|p| display_path(p)).join(", "),
+                    None => style("not requested").dim().to_string(),
+                };
+                (p.name.clone(), tv.version, sources)
+            })
+            .collect_vec();
+        let plugin_len = rows.iter().map(|(p, _, _)| p.len()).max().unwrap_or(0);
+        let version_len = rows.iter().map(|(_, v, _)| v.len()).max().unwrap_or(0);
+        for (plugin, version, sources) in rows {
+            let plugin = style(format!("{plugin:plugin_len$}")).cyan();
+            rtxprintln!(out, "{} {:version_len$} {}", plugin, version, sources);
+        }
+        Ok(())
+    }
+
+    fn list_installed(&self, config: &mut Config) -> Result<Vec<(Arc<Tool>, ToolVersion)>> {
+        let ts = ToolsetBuilder::new().build(config)?;
+        Ok(ts
+            .list_installed_versions(config)?
+            .into_iter()
+            .filter(|(p, _)| {
+                self.plugin
+                    .as_ref()
+                    .map_or(true, |plugin| plugin == &p.name)
+            })
+            .collect_vec())
+    }
+
     fn get_runtime_list(&self, config: &mut Config) -> Result<Vec<RuntimeRow>> {
         let mut tsb = ToolsetBuilder::new().with_global_only(self.global);
 
@@ -269,6 +416,46 @@ impl Ls {
     }
 }
 
+/// the config files requesting each version, keyed by "plugin@version", this is
+/// what `rtx prune` keeps
+fn requested_versions(config: &mut Config) -> Result<HashMap<String, Vec<PathBuf>>> {
+    let mut requested: HashMap<String, Vec<PathBuf>> = HashMap::new();
+    for (path, cf) in config.get_tracked_config_files()? {
+        let mut ts = cf.to_toolset().clone();
+        ts.resolve(config);
+        for (_, tv) in ts.list_current_versions(config) {
+            requested
+                .entry(tv.to_string())
+                .or_default()
+                .push(path.clone());
+        }
+    }
+    Ok(requested)
+}
+
+/// versions are considered duplicates if they share this, e.g.: "20" for 20.10.0 or "0.3" for 0.3.1
+fn duplicate_key(version: &str) -> Option<String> {
+    let v = Versioning::new(version)?;
+    let major = v.nth(0)?;
+    match major {
+        0 => Some(format!("0.{}", v.nth(1)?)),
+        major => Some(major.to_string()),
+    }
+}
+
+/// each step from the requested version to the resolved one, e.g.:
+/// ["lts -> 20 (node core plugin)", "20 -> 20.11.1 (matching version)"]
+fn resolution_chain(config: &Config, tv: &ToolVersion) -> Result<Vec<String>> {
+    let requested = tv.request.version();
+    let hops = config.trace_alias(&tv.plugin_name, &requested)?;
+    let resolved = hops.last().map_or(requested, |hop| hop.to.clone());
+    let mut chain = hops.iter().map(|hop| hop.to_string()).collect_vec();
+    if resolved != tv.version {
+        chain.push(format!("{} -> {} (matching version)", resolved, tv.version));
+    }
+    Ok(chain)
+}
+
 type RuntimeRow = (Arc<Tool>, ToolVersion, Option<ToolSource>);
 
 impl From<RuntimeRow> for JSONToolVersion {
@@ -361,6 +548,20 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
   node    20.0.0 ~/src/myapp/.tool-versions 20
   python  3.11.0 ~/.tool-versions           3.11.0
 
+  $ <bold>rtx ls --requested</bold>
+  node   18.0.0 not requested
+  node   20.0.0 ~/src/myapp/.tool-versions, ~/.tool-versions
+  python 3.11.0 ~/.tool-versions
+
+  $ <bold>rtx ls --current --resolve</bold>
+  node    20.11.1 ~/src/myapp/.tool-versions lts
+    lts -> 20 (node core plugin)
+    20 -> 20.11.1 (matching version)
+
+  $ <bold>rtx ls --duplicates</bold>
+  node@20 20.10.0 20.11.1 (in use: 20.11.1)
+    remove unused: rtx uninstall node@20.10.0
+
   $ <bold>rtx ls --json</bold>
   {
     "node": [
@@ -382,6 +583,7 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 mod tests {
     use pretty_assertions::assert_str_eq;
 
+    use crate::cli::tests::grep;
     use crate::file::remove_all;
     use crate::{assert_cli, assert_cli_err, assert_cli_snapshot, dirs};
 
@@ -442,4 +644,35 @@ mod tests {
         assert_cli!("install");
         assert_cli_snapshot!("ls", "--plugin=tiny", "--prefix=3");
     }
+
+    #[test]
+    fn test_ls_duplicates() {
+        assert_cli!("install", "tiny@3.0.1", "tiny@3.1.0");
+        let stdout = assert_cli!("ls", "--plugin=tiny", "--duplicates");
+        assert!(stdout.contains("tiny@3 3.0.1 3.1.0"));
+        assert!(stdout.contains("rtx uninstall tiny@3.0.1"));
+        assert_cli!("uninstall", "tiny@3.0.1");
+    }
+
+    #[test]
+    fn test_ls_requested() {
+        assert_cli!("install", "tiny@3.0.1", "tiny@3.1.0");
+        let stdout = assert_cli!("ls", "--plugin=tiny", "--requested");
+        assert!(grep(stdout, "3.0.1").contains("not requested"));
+        assert_cli!("uninstall", "tiny@3.0.1");
+    }
+
+    #[test]
+    fn test_ls_resolve() {
+        assert_cli!("install");
+        let stdout = assert_cli!("ls", "--plugin=tiny", "--resolve");
+        assert!(stdout.contains("3 -> 3.1.0 (matching version)"));
+    }
+
+    #[test]
+    fn test_duplicate_key() {
+        assert_str_eq!(super::duplicate_key("20.10.0").unwrap(), "20");
+        assert_str_eq!(super::duplicate_key("0.3.1").unwrap(), "0.3");
+        assert!(super::duplicate_key("ref:main").is_none());
+    }
 }
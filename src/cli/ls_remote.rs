@@ -2,8 +2,10 @@ use std::sync::Arc;
 
 use color_eyre::eyre::Result;
 
+use crate::cache::CacheManager;
 use crate::cli::args::tool::ToolArg;
 use crate::cli::args::tool::ToolArgParser;
+use crate::cli::args::version_list::VersionListArgs;
 use crate::cli::command::Command;
 use crate::config::Config;
 use crate::output::Output;
@@ -25,6 +27,19 @@ pub struct LsRemote {
     /// same as the first argument after the "@"
     #[clap(verbatim_doc_comment)]
     prefix: Option<String>,
+
+    #[clap(flatten)]
+    list: VersionListArgs,
+
+    /// Show the release date next to each version
+    /// Only plugins with the json-output capability can provide them
+    #[clap(long, verbatim_doc_comment)]
+    dates: bool,
+
+    /// Fetch the versions again and only show those published since they were last cached
+    /// Prints nothing the first time when there is no cached list to compare with
+    #[clap(long, verbatim_doc_comment)]
+    since: bool,
 }
 
 impl Command for LsRemote {
@@ -36,7 +51,22 @@ impl Command for LsRemote {
             _ => self.prefix.as_ref(),
         };
 
+        let previous = match self.since {
+            true => Some(take_cached_versions(&plugin)?),
+            false => None,
+        };
         let versions = plugin.list_remote_versions(&config.settings)?;
+        let versions = match previous {
+            Some(Some(previous)) => versions
+                .into_iter()
+                .filter(|v| !previous.contains(v))
+                .collect(),
+            Some(None) => {
+                debug!("no cached versions of {} to compare with", plugin.name);
+                vec![]
+            }
+            None => versions,
+        };
         let versions = match prefix {
             Some(prefix) => versions
                 .into_iter()
@@ -45,8 +75,15 @@ impl Command for LsRemote {
             None => versions,
         };
 
-        for version in versions {
-            rtxprintln!(out, "{}", version);
+        let dates = match self.dates {
+            true => plugin.list_remote_version_dates(&config.settings)?,
+            false => Default::default(),
+        };
+        for version in self.list.apply(versions) {
+            match dates.get(&version) {
+                Some(date) => rtxprintln!(out, "{:<20} {}", version, date),
+                None => rtxprintln!(out, "{}", version),
+            }
         }
 
         Ok(())
@@ -62,6 +99,22 @@ impl LsRemote {
     }
 }
 
+/// the versions in the plugin's remote versions cache, which is cleared so they are fetched again
+fn take_cached_versions(plugin: &Tool) -> Result<Option<Vec<String>>> {
+    let Some(status) = plugin
+        .plugin
+        .cache_status()
+        .into_iter()
+        .find(|s| s.path.ends_with("remote_versions.msgpack.z"))
+    else {
+        return Ok(None);
+    };
+    let cache = CacheManager::<Vec<String>>::new(status.path);
+    let versions = cache.peek();
+    cache.clear()?;
+    Ok(versions)
+}
+
 static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
   $ <bold>rtx ls-remote node</bold>
@@ -75,21 +128,50 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
   $ <bold>rtx ls-remote node 20</bold>
   20.0.0
   20.1.0
+
+  $ <bold>rtx ls-remote node --latest-per-minor --reverse --limit 2</bold>
+  20.1.0
+  20.0.1
+
+  # e.g.: in a daily cron job, shows nothing until a new version is published
+  $ <bold>rtx ls-remote node --since</bold>
+  21.4.0
 "#
 );
 
 #[cfg(test)]
 mod tests {
-    use crate::assert_cli_snapshot;
+    use crate::{assert_cli, assert_cli_snapshot};
 
     #[test]
     fn test_list_remote() {
         assert_cli_snapshot!("list-remote", "dummy");
     }
 
+    #[test]
+    fn test_ls_remote_dates() {
+        // dummy has no release dates so the output is the same as without --dates
+        assert_eq!(
+            assert_cli!("ls-remote", "dummy", "--dates"),
+            assert_cli!("ls-remote", "dummy")
+        );
+    }
+
     #[test]
     fn test_ls_remote_prefix() {
         assert_cli_snapshot!("list-remote", "dummy", "1");
         assert_cli_snapshot!("list-remote", "dummy@2");
     }
+
+    #[test]
+    fn test_ls_remote_since() {
+        assert_cli!("ls-remote", "dummy");
+        // dummy always lists the same versions so none are new
+        assert_eq!(assert_cli!("ls-remote", "dummy", "--since"), "");
+    }
+
+    #[test]
+    fn test_ls_remote_limit_reverse() {
+        assert_cli_snapshot!("list-remote", "dummy", "--reverse", "--limit", "2");
+    }
 }
@@ -3,17 +3,23 @@ use color_eyre::Result;
 use indoc::indoc;
 use log::LevelFilter;
 
+use crate::cli::args::progress_format::ProgressFormat;
 use crate::cli::command::Command;
-use crate::config::Config;
 use crate::config::MissingRuntimeBehavior::AutoInstall;
+use crate::config::{Config, ConfirmPolicy};
 use crate::output::Output;
+use crate::ui::progress_events;
 
 mod activate;
 mod alias;
+mod api;
 pub mod args;
 mod asdf;
+mod backfill;
 mod bin_paths;
 mod cache;
+mod checksum;
+mod clean;
 pub mod command;
 mod completion;
 mod current;
@@ -26,29 +32,37 @@ pub mod exec;
 mod external;
 mod global;
 mod hook_env;
+mod hook_not_found;
 mod implode;
 mod install;
 mod latest;
 mod link;
 mod local;
+mod lock;
 mod ls;
 mod ls_remote;
 mod outdated;
 mod plugins;
 mod prune;
+mod relocate;
 #[cfg(debug_assertions)]
 mod render_help;
 mod reshim;
+pub mod run;
+mod search;
 #[cfg(feature = "self_update")]
 mod self_update;
 mod settings;
 mod shell;
 mod sync;
+mod tasks;
+mod tool_stub;
 mod trust;
 mod uninstall;
 mod upgrade;
 mod r#use;
 pub mod version;
+mod watch;
 mod r#where;
 mod r#which;
 
@@ -61,9 +75,13 @@ pub struct Cli {
 pub enum Commands {
     Activate(activate::Activate),
     Alias(alias::Alias),
+    Api(api::Api),
     Asdf(asdf::Asdf),
+    Backfill(backfill::Backfill),
     BinPaths(bin_paths::BinPaths),
     Cache(cache::Cache),
+    Checksum(checksum::Checksum),
+    Clean(clean::Clean),
     Completion(completion::Completion),
     Current(current::Current),
     Deactivate(deactivate::Deactivate),
@@ -74,27 +92,35 @@ pub enum Commands {
     Exec(exec::Exec),
     Global(global::Global),
     HookEnv(hook_env::HookEnv),
+    HookNotFound(hook_not_found::HookNotFound),
     Implode(implode::Implode),
     Install(install::Install),
     Latest(latest::Latest),
     Link(link::Link),
     Local(local::Local),
+    Lock(lock::Lock),
     Ls(ls::Ls),
     LsRemote(ls_remote::LsRemote),
     Outdated(outdated::Outdated),
     Plugins(plugins::Plugins),
     Prune(prune::Prune),
+    Relocate(relocate::Relocate),
     Reshim(reshim::Reshim),
+    Run(run::Run),
+    Search(search::Search),
     #[cfg(feature = "self_update")]
     SelfUpdate(self_update::SelfUpdate),
     Settings(settings::Settings),
     Shell(shell::Shell),
     Sync(sync::Sync),
+    Tasks(tasks::Tasks),
+    ToolStub(tool_stub::ToolStub),
     Trust(trust::Trust),
     Uninstall(uninstall::Uninstall),
     Upgrade(upgrade::Upgrade),
     Use(r#use::Use),
     Version(version::Version),
+    Watch(watch::Watch),
     Where(r#where::Where),
     Which(which::Which),
 
@@ -107,9 +133,13 @@ impl Commands {
         match self {
             Self::Activate(cmd) => cmd.run(config, out),
             Self::Alias(cmd) => cmd.run(config, out),
+            Self::Api(cmd) => cmd.run(config, out),
             Self::Asdf(cmd) => cmd.run(config, out),
+            Self::Backfill(cmd) => cmd.run(config, out),
             Self::BinPaths(cmd) => cmd.run(config, out),
             Self::Cache(cmd) => cmd.run(config, out),
+            Self::Checksum(cmd) => cmd.run(config, out),
+            Self::Clean(cmd) => cmd.run(config, out),
             Self::Completion(cmd) => cmd.run(config, out),
             Self::Current(cmd) => cmd.run(config, out),
             Self::Deactivate(cmd) => cmd.run(config, out),
@@ -120,27 +150,35 @@ impl Commands {
             Self::Exec(cmd) => cmd.run(config, out),
             Self::Global(cmd) => cmd.run(config, out),
             Self::HookEnv(cmd) => cmd.run(config, out),
+            Self::HookNotFound(cmd) => cmd.run(config, out),
             Self::Implode(cmd) => cmd.run(config, out),
             Self::Install(cmd) => cmd.run(config, out),
             Self::Latest(cmd) => cmd.run(config, out),
             Self::Link(cmd) => cmd.run(config, out),
             Self::Local(cmd) => cmd.run(config, out),
+            Self::Lock(cmd) => cmd.run(config, out),
             Self::Ls(cmd) => cmd.run(config, out),
             Self::LsRemote(cmd) => cmd.run(config, out),
             Self::Outdated(cmd) => cmd.run(config, out),
             Self::Plugins(cmd) => cmd.run(config, out),
             Self::Prune(cmd) => cmd.run(config, out),
+            Self::Relocate(cmd) => cmd.run(config, out),
             Self::Reshim(cmd) => cmd.run(config, out),
+            Self::Run(cmd) => cmd.run(config, out),
+            Self::Search(cmd) => cmd.run(config, out),
             #[cfg(feature = "self_update")]
             Self::SelfUpdate(cmd) => cmd.run(config, out),
             Self::Settings(cmd) => cmd.run(config, out),
             Self::Shell(cmd) => cmd.run(config, out),
             Self::Sync(cmd) => cmd.run(config, out),
+            Self::Tasks(cmd) => cmd.run(config, out),
+            Self::ToolStub(cmd) => cmd.run(config, out),
             Self::Trust(cmd) => cmd.run(config, out),
             Self::Uninstall(cmd) => cmd.run(config, out),
             Self::Upgrade(cmd) => cmd.run(config, out),
             Self::Use(cmd) => cmd.run(config, out),
             Self::Version(cmd) => cmd.run(config, out),
+            Self::Watch(cmd) => cmd.run(config, out),
             Self::Where(cmd) => cmd.run(config, out),
             Self::Which(cmd) => cmd.run(config, out),
 
@@ -176,10 +214,13 @@ impl Cli {
                 .arg_required_else_help(true)
                 .subcommand_required(true)
                 .after_long_help(AFTER_LONG_HELP)
+                .arg(args::cd::Cd::arg())
                 .arg(args::log_level::Debug::arg())
                 .arg(args::install_missing::InstallMissing::arg())
                 .arg(args::jobs::Jobs::arg())
                 .arg(args::log_level::LogLevel::arg())
+                .arg(args::no::No::arg())
+                .arg(args::progress_format::ProgressFormat::arg())
                 .arg(args::raw::Raw::arg())
                 .arg(args::yes::Yes::arg())
                 .arg(args::log_level::Trace::arg())
@@ -206,17 +247,25 @@ impl Cli {
         if let Some(jobs) = matches.get_one::<usize>("jobs") {
             config.settings.jobs = *jobs;
         }
+        if let Some(ProgressFormat::Json) = matches.get_one::<ProgressFormat>("progress-format") {
+            progress_events::enable(*crate::env::RTX_PROGRESS_FD)?;
+        }
         if let Some(raw) = matches.get_one::<bool>("raw") {
             config.settings.raw = *raw;
         }
         if let Some(true) = matches.get_one::<bool>("yes") {
             config.settings.yes = true;
+            config.settings.confirm_policy = ConfirmPolicy::Yes;
+        }
+        if let Some(true) = matches.get_one::<bool>("no") {
+            config.settings.confirm_policy = ConfirmPolicy::No;
         }
         if let Some(true) = matches.get_one::<bool>("install-missing") {
             config.settings.missing_runtime_behavior = AutoInstall;
         }
         if *matches.get_one::<u8>("verbose").unwrap() > 0 {
             config.settings.verbose = true;
+            crate::cache::enable_verbose();
         }
         if config.settings.raw {
             config.settings.jobs = 1;
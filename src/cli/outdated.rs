@@ -1,12 +1,14 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::sync::Arc;
 
 use color_eyre::eyre::Result;
 use console::{pad_str, style, Alignment};
+use serde_derive::Serialize;
 
 use crate::cli::args::tool::{ToolArg, ToolArgParser};
 use crate::cli::command::Command;
 use crate::config::Config;
+use crate::eol;
 use crate::output::Output;
 use crate::tool::Tool;
 use crate::toolset::{ToolVersion, ToolsetBuilder};
@@ -20,6 +22,18 @@ pub struct Outdated {
     /// If not specified, all tools in global and local configs will be shown
     #[clap(value_name="TOOL@VERSION", value_parser = ToolArgParser, verbatim_doc_comment)]
     pub tool: Vec<ToolArg>,
+
+    /// Output in json format
+    #[clap(long, visible_short_alias = 'J')]
+    pub json: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct OutdatedRow {
+    requested: String,
+    /// None if the requested version is not installed
+    current: Option<String>,
+    latest: String,
 }
 
 impl Command for Outdated {
@@ -34,8 +48,13 @@ impl Command for Outdated {
             .collect::<HashSet<_>>();
         ts.versions
             .retain(|_, tvl| tool_set.is_empty() || tool_set.contains(&tvl.plugin_name));
+        for (_, tv) in ts.list_current_versions(&config) {
+            eol::check(&config.settings, &tv)?;
+        }
         let outdated = ts.list_outdated_versions(&config);
-        if outdated.is_empty() {
+        if self.json {
+            self.display_json(outdated, out)?;
+        } else if outdated.is_empty() {
             info!("All tools are up to date");
         } else {
             self.display(outdated, out);
@@ -48,6 +67,22 @@ impl Command for Outdated {
 type OutputVec = Vec<(Arc<Tool>, ToolVersion, String)>;
 
 impl Outdated {
+    fn display_json(&self, outdated: OutputVec, out: &mut Output) -> Result<()> {
+        let rows: BTreeMap<_, _> = outdated
+            .into_iter()
+            .map(|(t, tv, latest)| {
+                let row = OutdatedRow {
+                    requested: tv.request.version(),
+                    current: t.is_version_installed(&tv).then(|| tv.version.clone()),
+                    latest,
+                };
+                (t.name.clone(), row)
+            })
+            .collect();
+        out.stdout.writeln(serde_json::to_string_pretty(&rows)?);
+        Ok(())
+    }
+
     fn display(&self, outdated: OutputVec, out: &mut Output) {
         // TODO: make a generic table printer in src/ui/table
         let plugins = outdated
@@ -127,6 +162,15 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
   $ <bold>rtx outdated node</bold>
   Plugin  Requested  Current  Latest
   node    20         20.0.0   20.1.0
+
+  $ <bold>rtx outdated --json</bold>
+  {
+    "node": {
+      "requested": "20",
+      "current": "20.0.0",
+      "latest": "20.1.0"
+    }
+  }
 "#
 );
 
@@ -134,7 +178,7 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 mod tests {
     use std::env;
 
-    use crate::assert_cli_snapshot;
+    use crate::{assert_cli, assert_cli_snapshot};
 
     #[test]
     fn test_current() {
@@ -145,4 +189,11 @@ mod tests {
     fn test_current_with_runtimes() {
         assert_cli_snapshot!("outdated", "tiny");
     }
+
+    #[test]
+    fn test_outdated_json() {
+        let stdout = assert_cli!("outdated", "tiny", "--json");
+        let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        assert!(json.is_object());
+    }
 }
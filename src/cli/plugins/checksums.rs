@@ -0,0 +1,119 @@
+use std::path::PathBuf;
+
+use color_eyre::eyre::{eyre, Result};
+use console::style;
+
+use crate::checksums::{checksum_dir, Checksums};
+use crate::cli::command::Command;
+use crate::config::Config;
+use crate::output::Output;
+use crate::plugins::unalias_plugin;
+use crate::toolset::ToolVersionRequest;
+use crate::ui::multi_progress_report::MultiProgressReport;
+use crate::{dirs, file};
+
+/// Generate a checksum manifest for a plugin's downloads
+///
+/// Runs the plugin's bin/download script for each version in a sandbox
+/// directory (nothing is installed) and prints the sha256 of every file it
+/// downloaded. Plugin authors can ship the result as checksums.toml in the
+/// root of the plugin to verify downloads for tools that do not publish
+/// checksums upstream.
+///
+/// Checksums are for the current platform only.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct PluginsChecksums {
+    /// Plugin to generate checksums for
+    #[clap()]
+    plugin: String,
+
+    /// Version(s) to download
+    #[clap(required = true)]
+    versions: Vec<String>,
+
+    /// Write the manifest to this file instead of stdout
+    #[clap(long, short, value_hint = clap::ValueHint::FilePath)]
+    output: Option<PathBuf>,
+}
+
+impl Command for PluginsChecksums {
+    fn run(self, config: Config, out: &mut Output) -> Result<()> {
+        let name = unalias_plugin(&self.plugin);
+        let tool = config
+            .tools
+            .get(name)
+            .ok_or_else(|| eyre!("plugin {} not found", style(name).cyan().for_stderr()))?;
+        let mpr = MultiProgressReport::new(config.show_progress_bars());
+        let mut manifest = Checksums::new();
+        for v in &self.versions {
+            let tv = ToolVersionRequest::new(tool.name.clone(), v).resolve(
+                &config,
+                tool,
+                Default::default(),
+                false,
+            )?;
+            let sandbox = dirs::CACHE.join("checksums").join(tv.to_string());
+            file::remove_all(&sandbox)?;
+            let mut pr = mpr.add();
+            tool.decorate_progress_bar(&mut pr, Some(&tv));
+            pr.set_message("downloading");
+            let result = tool
+                .plugin
+                .download_to(&config, &tv, &sandbox, &pr)
+                .and_then(|_| checksum_dir(&sandbox.join("download")));
+            file::remove_all(&sandbox)?;
+            match result {
+                Ok(checksums) => {
+                    pr.finish_with_message(format!("{} files", checksums.len()));
+                    manifest.insert(tv.version, checksums);
+                }
+                Err(err) => {
+                    pr.error(err.to_string());
+                    return Err(err.wrap_err(format!("failed to download {tv}")));
+                }
+            }
+        }
+        let manifest = format!(
+            "# generated by rtx plugins checksums for {}-{}\n{}",
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            toml::to_string_pretty(&manifest)?
+        );
+        match &self.output {
+            Some(path) => file::write(path, manifest)?,
+            None => rtxprint!(out, "{}", manifest),
+        }
+        Ok(())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  $ <bold>rtx plugins checksums tiny 3.1.0</bold>
+  # generated by rtx plugins checksums for linux-x86_64
+  ["3.1.0"]
+  "tiny-3.1.0.tar.gz" = "sha256:2c26b46b68ffc68ff99b453c1d30413413422d706483bfa0f98a5e886266e7ae"
+
+  $ <bold>rtx plugins checksums node 20.0.0 20.1.0 -o checksums.toml</bold>
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum_dir() {
+        let dir = dirs::CACHE.join("test-checksum-dir");
+        file::remove_all(&dir).unwrap();
+        file::create_dir_all(dir.join("sub")).unwrap();
+        file::write(dir.join("sub/foo"), "foo").unwrap();
+        let checksums = checksum_dir(&dir).unwrap();
+        assert_eq!(
+            checksums.get("sub/foo").unwrap(),
+            "sha256:2c26b46b68ffc68ff99b453c1d30413413422d706483bfa0f98a5e886266e7ae"
+        );
+        file::remove_all(&dir).unwrap();
+    }
+}
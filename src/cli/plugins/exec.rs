@@ -0,0 +1,108 @@
+use std::process::exit;
+
+use color_eyre::eyre::{bail, eyre, Result};
+use console::style;
+use itertools::Itertools;
+
+use crate::cli::args::tool::{ToolArg, ToolArgParser};
+use crate::cli::command::Command;
+use crate::config::Config;
+use crate::file::display_path;
+use crate::output::Output;
+use crate::plugins::{unalias_plugin, Script};
+use crate::toolset::ToolsetBuilder;
+use crate::{cmd, env};
+
+/// Run one of a plugin's scripts with the env rtx would give it
+///
+/// Prints the env vars rtx sets and the command line before running the script,
+/// so plugin bugs can be reproduced outside of rtx and included in bug reports.
+/// With a version, or a version set in the config, the version specific vars
+/// (e.g.: ASDF_INSTALL_PATH) and tool options are set too.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct PluginsExec {
+    /// Plugin to run the script of, e.g.: node or node@20
+    #[clap(value_name = "PLUGIN[@VERSION]", value_parser = ToolArgParser)]
+    tool: ToolArg,
+
+    /// Script in the plugin's bin/ directory, e.g.: list-all or exec-env
+    script: String,
+
+    /// Arguments to pass to the script
+    #[clap(allow_hyphen_values = true, trailing_var_arg = true)]
+    args: Vec<String>,
+
+    /// Only print the env and command line, do not run the script
+    #[clap(long, short = 'n')]
+    dry_run: bool,
+}
+
+impl Command for PluginsExec {
+    fn run(self, mut config: Config, out: &mut Output) -> Result<()> {
+        let name = unalias_plugin(&self.tool.plugin).to_string();
+        let tool = config
+            .tools
+            .get(&name)
+            .cloned()
+            .ok_or_else(|| eyre!("plugin {} not found", style(&name).cyan().for_stderr()))?;
+        let mut builder = ToolsetBuilder::new();
+        if self.tool.tvr.is_some() {
+            builder = builder.with_args(std::slice::from_ref(&self.tool));
+        }
+        let ts = builder.build(&mut config)?;
+        let tv = ts.versions.get(&name).and_then(|tvl| tvl.versions.first());
+        let sm = tool
+            .plugin
+            .script_manager(&config, tv)
+            .ok_or_else(|| eyre!("{name} is a core plugin and does not have scripts"))?;
+        let script = Script::Custom(self.script.clone(), self.args.clone());
+        let script_path = sm.get_script_path(&script);
+        if !script_path.is_file() {
+            bail!("{} does not exist", display_path(&script_path));
+        }
+
+        // only what differs from the current shell, everything else is passed through
+        let script_env = sm.script_env(&config.settings, &script);
+        for (k, v) in script_env.iter().sorted() {
+            let (k, v) = (k.to_string_lossy(), v.to_string_lossy());
+            if env::PRISTINE_ENV.get(k.as_ref()) != Some(&v.to_string()) {
+                rtxprintln!(out, "{}={}", k, shell_words::quote(&v));
+            }
+        }
+        let cmdline = [script_path.to_string_lossy().to_string()]
+            .into_iter()
+            .chain(self.args.iter().cloned())
+            .collect_vec();
+        rtxprintln!(out, "{}", shell_words::join(cmdline));
+        if self.dry_run {
+            return Ok(());
+        }
+        let code = cmd::run_foreground(sm.cmd(&config.settings, &script))?;
+        exit(code);
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  $ <bold>rtx plugins exec node@20 exec-env</bold>
+  ASDF_INSTALL_PATH=~/.local/share/rtx/installs/node/20.9.0
+  ASDF_INSTALL_VERSION=20.9.0
+  ...
+  ~/.local/share/rtx/plugins/node/bin/exec-env
+
+  $ <bold>rtx plugins exec --dry-run python list-all</bold>
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_cli;
+
+    #[test]
+    fn test_plugins_exec() {
+        let stdout = assert_cli!("plugins", "exec", "--dry-run", "tiny@3", "exec-env");
+        assert!(stdout.contains("RTX_INSTALL_VERSION=3."));
+        assert!(stdout.contains("plugins/tiny/bin/exec-env"));
+    }
+}
@@ -0,0 +1,189 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use color_eyre::eyre::{eyre, Result};
+use console::style;
+use toml_edit::Document;
+
+use crate::cli::command::Command;
+use crate::config::Config;
+use crate::file;
+use crate::file::{display_path, modified_duration};
+use crate::output::Output;
+use crate::plugins::rtx_plugin_toml::RtxPluginToml;
+use crate::plugins::{unalias_plugin, PluginType};
+use crate::tool::Tool;
+
+/// Show details about a plugin
+///
+/// Includes where it came from, which asdf scripts it implements, how fresh its
+/// caches are, and any rtx.plugin.toml config it has
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct PluginsInfo {
+    /// Plugin to show info for
+    #[clap()]
+    plugin: String,
+}
+
+/// scripts a plugin may implement, see https://asdf-vm.com/plugins/create.html
+const KNOWN_SCRIPTS: &[&str] = &[
+    "list-all",
+    "download",
+    "install",
+    "uninstall",
+    "latest-stable",
+    "list-bin-paths",
+    "exec-env",
+    "exec-path",
+    "list-legacy-filenames",
+    "parse-legacy-file",
+    "help.overview",
+    "help.deps",
+    "help.config",
+    "help.links",
+    "post-plugin-add",
+    "post-plugin-update",
+    "pre-plugin-remove",
+];
+
+impl Command for PluginsInfo {
+    fn run(self, config: Config, out: &mut Output) -> Result<()> {
+        let name = unalias_plugin(&self.plugin);
+        let tool = config
+            .tools
+            .get(name)
+            .ok_or_else(|| eyre!("plugin {} not found", style(name).cyan().for_stderr()))?;
+        rtxprintln!(out, "plugin:  {}", tool.name);
+        let plugin_type = tool.plugin.get_type();
+        rtxprintln!(out, "type:    {}", plugin_type.as_str());
+        match plugin_type {
+            PluginType::External => {}
+            PluginType::Core => return Ok(()),
+            // backends without a plugin repo, e.g.: "gh:cli/cli"
+            _ => {
+                if let Some(url) = tool.get_remote_url() {
+                    rtxprintln!(out, "url:     {}", url);
+                }
+                return Ok(());
+            }
+        }
+        rtxprintln!(out, "path:    {}", display_path(&tool.plugin_path));
+        if let Some(url) = tool.get_remote_url() {
+            rtxprintln!(out, "url:     {}", url);
+        }
+        if let (Ok(aref), Ok(sha)) = (tool.current_abbrev_ref(), tool.current_sha_short()) {
+            rtxprintln!(out, "ref:     {} {}", aref, sha);
+        }
+
+        let bin = tool.plugin_path.join("bin");
+        let (scripts, missing): (Vec<_>, Vec<_>) = KNOWN_SCRIPTS
+            .iter()
+            .copied()
+            .partition(|s| bin.join(s).exists());
+        rtxprintln!(out, "scripts: {}", join(&scripts));
+        rtxprintln!(out, "missing: {}", join(&missing));
+        let depends = tool.plugin.depends();
+        if !depends.is_empty() {
+            rtxprintln!(out, "depends: {}", depends.join(", "));
+        }
+
+        self.render_toml(tool, out)?;
+        self.render_cache(tool, out)?;
+        Ok(())
+    }
+}
+
+impl PluginsInfo {
+    fn render_toml(&self, tool: &Tool, out: &mut Output) -> Result<()> {
+        let path = tool.plugin_path.join("rtx.plugin.toml");
+        if !path.exists() {
+            return Ok(());
+        }
+        let doc: Document = file::read_to_string(&path)?.parse()?;
+        let keys = doc.iter().map(|(k, _)| k.to_string()).collect::<Vec<_>>();
+        rtxprintln!(out, "rtx.plugin.toml: {}", keys.join(", "));
+        let toml = RtxPluginToml::from_file(&path)?;
+        if toml.protocol > 0 {
+            let caps = toml.capabilities;
+            let capabilities = [
+                ("json-output", caps.json_output),
+                ("declarative-env", caps.declarative_env),
+                ("checksums", caps.checksums),
+            ]
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>();
+            rtxprintln!(out, "protocol: {}", toml.protocol);
+            rtxprintln!(out, "capabilities: {}", join(&capabilities));
+        }
+        Ok(())
+    }
+
+    fn render_cache(&self, tool: &Tool, out: &mut Output) -> Result<()> {
+        let mut files = vec![];
+        list_files(&tool.cache_path, 2, &mut files)?;
+        if files.is_empty() {
+            return Ok(());
+        }
+        files.sort();
+        rtxprintln!(out, "cache:");
+        for f in files {
+            let age = modified_duration(&f)?;
+            let age = humantime::format_duration(Duration::from_secs(age.as_secs()));
+            let rel = f.strip_prefix(&tool.cache_path).unwrap_or(&f);
+            rtxprintln!(out, "  {} (updated {} ago)", rel.display(), age);
+        }
+        Ok(())
+    }
+}
+
+fn join(scripts: &[&str]) -> String {
+    match scripts.is_empty() {
+        true => "none".into(),
+        false => scripts.join(", "),
+    }
+}
+
+fn list_files(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) -> Result<()> {
+    if depth == 0 || !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in dir.read_dir()? {
+        let path = entry?.path();
+        if path.is_dir() {
+            list_files(&path, depth - 1, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  $ <bold>rtx plugins info node</bold>
+  plugin:  node
+  type:    external
+  path:    ~/.local/share/rtx/plugins/node
+  url:     https://github.com/asdf-vm/asdf-nodejs.git
+  ref:     master 1234abc
+  scripts: list-all, download, install, ...
+  missing: exec-path, help.links, ...
+  cache:
+    remote_versions.msgpack.z (updated 2h 10m 3s ago)
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_cli;
+
+    #[test]
+    fn test_plugin_info() {
+        let stdout = assert_cli!("plugins", "info", "dummy");
+        assert!(stdout.contains("plugin:  dummy"));
+        assert!(stdout.contains("list-all, download, install"));
+    }
+}
@@ -2,6 +2,7 @@ use std::collections::BTreeSet;
 use std::sync::Arc;
 
 use color_eyre::eyre::Result;
+use serde_derive::Serialize;
 
 use crate::cli::command::Command;
 use crate::config::Config;
@@ -32,8 +33,44 @@ pub struct PluginsLs {
 
     /// Show the git refs for each plugin
     /// e.g.: main 1234abc
+    /// Use with --urls to show a table with all the details of each plugin
     #[clap(long, verbatim_doc_comment)]
     pub refs: bool,
+
+    /// Output in JSON format with all the details of each plugin
+    #[clap(long, visible_short_alias = 'J', conflicts_with_all = ["urls", "refs"])]
+    pub json: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct PluginRow {
+    name: String,
+    #[serde(rename = "type")]
+    plugin_type: &'static str,
+    url: Option<String>,
+    #[serde(rename = "ref")]
+    aref: Option<String>,
+    sha: Option<String>,
+    updated: Option<String>,
+}
+
+impl PluginRow {
+    fn new(tool: &Tool) -> Self {
+        // git details are only available once the plugin is cloned, e.g.: not with --all
+        let installed = tool.is_installed();
+        let git = |f: fn(&Tool) -> Result<String>| match installed {
+            true => f(tool).ok().filter(|s| !s.is_empty()),
+            false => None,
+        };
+        Self {
+            name: tool.name.clone(),
+            plugin_type: tool.plugin.get_type().as_str(),
+            url: tool.get_remote_url(),
+            aref: git(Tool::current_abbrev_ref),
+            sha: git(Tool::current_sha_short),
+            updated: git(Tool::current_commit_date),
+        }
+    }
 }
 
 impl Command for PluginsLs {
@@ -53,7 +90,13 @@ impl Command for PluginsLs {
             tools.retain(|p| matches!(p.plugin.get_type(), PluginType::External));
         }
 
-        if self.urls || self.refs {
+        if self.json {
+            let rows = tools.iter().map(|t| PluginRow::new(t)).collect::<Vec<_>>();
+            rtxprintln!(out, "{}", serde_json::to_string_pretty(&rows)?);
+        } else if self.urls && self.refs {
+            let rows = tools.iter().map(|t| PluginRow::new(t)).collect::<Vec<_>>();
+            display_table(rows, out);
+        } else if self.urls || self.refs {
             for tool in tools {
                 rtxprint!(out, "{:29}", tool.name);
                 if self.urls {
@@ -80,6 +123,39 @@ impl Command for PluginsLs {
     }
 }
 
+/// one row per plugin with each column padded to its widest value
+fn display_table(rows: Vec<PluginRow>, out: &mut Output) {
+    let rows = rows
+        .into_iter()
+        .map(|r| {
+            let col = |s: Option<String>| s.unwrap_or_else(|| "-".into());
+            [
+                r.name,
+                r.plugin_type.to_string(),
+                col(r.aref),
+                col(r.sha),
+                col(r.url),
+                col(r.updated),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let mut widths = [0; 6];
+    for row in &rows {
+        for (w, col) in widths.iter_mut().zip(row) {
+            *w = (*w).max(col.len());
+        }
+    }
+    for row in rows {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(col, w)| format!("{col:w$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        rtxprintln!(out, "{}", line.trim_end());
+    }
+}
+
 static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
   $ <bold>rtx plugins ls</bold>
@@ -89,6 +165,10 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
   $ <bold>rtx plugins ls --urls</bold>
   node                        https://github.com/asdf-vm/asdf-node.git
   ruby                          https://github.com/asdf-vm/asdf-ruby.git
+
+  $ <bold>rtx plugins ls --urls --refs</bold>
+  node  external  master  3e8b5b4  https://github.com/asdf-vm/asdf-node.git  2023-11-02
+  ruby  external  master  b1a1e4c  https://github.com/asdf-vm/asdf-ruby.git  2023-10-28
 "#
 );
 
@@ -124,4 +204,25 @@ mod tests {
         let stdout = assert_cli!("plugin", "list", "--refs");
         assert!(stdout.contains("dummy"))
     }
+
+    #[test]
+    fn test_plugin_list_urls_refs() {
+        let stdout = assert_cli!("plugin", "list", "--urls", "--refs");
+        let line = grep(stdout, "dummy");
+        let cols = line.split_whitespace().collect::<Vec<_>>();
+        assert_eq!(cols[..2], ["dummy", "external"]);
+    }
+
+    #[test]
+    fn test_plugin_list_json() {
+        let stdout = assert_cli!("plugin", "list", "--json");
+        let rows: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        let dummy = rows
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|r| r["name"] == "dummy")
+            .unwrap();
+        assert_eq!(dummy["type"], "external");
+    }
 }
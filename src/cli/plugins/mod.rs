@@ -5,11 +5,16 @@ use crate::cli::command::Command;
 use crate::config::Config;
 use crate::output::Output;
 
+mod checksums;
+mod exec;
+mod info;
 mod install;
 mod link;
 mod ls;
 mod ls_remote;
+mod pin;
 mod uninstall;
+mod unpin;
 mod update;
 
 #[derive(Debug, clap::Args)]
@@ -40,26 +45,40 @@ pub struct Plugins {
     /// e.g.: main 1234abc
     #[clap(long)]
     pub refs: bool,
+
+    /// output in JSON format with all the details of each plugin
+    #[clap(long, visible_short_alias = 'J', conflicts_with_all = ["urls", "refs"])]
+    pub json: bool,
 }
 
 #[derive(Debug, Subcommand)]
 enum Commands {
+    Checksums(checksums::PluginsChecksums),
+    Exec(exec::PluginsExec),
+    Info(info::PluginsInfo),
     Install(install::PluginsInstall),
     Link(link::PluginsLink),
     Ls(ls::PluginsLs),
     LsRemote(ls_remote::PluginsLsRemote),
+    Pin(pin::PluginsPin),
     Uninstall(uninstall::PluginsUninstall),
+    Unpin(unpin::PluginsUnpin),
     Update(update::Update),
 }
 
 impl Commands {
     pub fn run(self, config: Config, out: &mut Output) -> Result<()> {
         match self {
+            Self::Checksums(cmd) => cmd.run(config, out),
+            Self::Exec(cmd) => cmd.run(config, out),
+            Self::Info(cmd) => cmd.run(config, out),
             Self::Install(cmd) => cmd.run(config, out),
             Self::Link(cmd) => cmd.run(config, out),
             Self::Ls(cmd) => cmd.run(config, out),
             Self::LsRemote(cmd) => cmd.run(config, out),
+            Self::Pin(cmd) => cmd.run(config, out),
             Self::Uninstall(cmd) => cmd.run(config, out),
+            Self::Unpin(cmd) => cmd.run(config, out),
             Self::Update(cmd) => cmd.run(config, out),
         }
     }
@@ -72,6 +91,7 @@ impl Command for Plugins {
             core: self.core,
             urls: self.urls,
             refs: self.refs,
+            json: self.json,
         }));
 
         cmd.run(config, out)
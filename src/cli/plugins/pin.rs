@@ -0,0 +1,77 @@
+use color_eyre::eyre::{eyre, Result};
+use console::style;
+
+use crate::cli::command::Command;
+use crate::config::config_file::ConfigFile;
+use crate::config::Config;
+use crate::git::Git;
+use crate::output::Output;
+use crate::plugins::{unalias_plugin, PluginName, PluginType};
+
+/// Pin plugins at their current commit
+///
+/// This records the commit in [plugins] of ~/.config/rtx/config.toml, e.g.:
+///
+///     [plugins]
+///     node = "https://github.com/rtx-plugins/rtx-nodejs#1a2b3c4..."
+///
+/// `rtx plugins update` skips pinned plugins and reinstalling a pinned plugin checks out
+/// the commit, so it only changes when it is unpinned with `rtx plugins unpin`.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct PluginsPin {
+    /// Plugin(s) to pin
+    #[clap(required = true)]
+    plugin: Vec<PluginName>,
+}
+
+impl Command for PluginsPin {
+    fn run(self, mut config: Config, out: &mut Output) -> Result<()> {
+        for name in &self.plugin {
+            let name = unalias_plugin(name);
+            let tool = config
+                .tools
+                .get(name)
+                .filter(|t| matches!(t.plugin.get_type(), PluginType::External))
+                .filter(|t| t.is_installed())
+                .cloned()
+                .ok_or_else(|| {
+                    eyre!(
+                        "plugin {} is not installed",
+                        style(name).cyan().for_stderr()
+                    )
+                })?;
+            let git = Git::new(tool.plugin_path.clone());
+            let url = git
+                .get_remote_url()
+                .ok_or_else(|| eyre!("plugin {name} is not a git repository, cannot pin it"))?;
+            let sha = git.current_sha()?;
+            config
+                .global_config
+                .set_plugin(name, &format!("{url}#{sha}"));
+            rtxprintln!(out, "pinned {name} at {}", git.current_sha_short()?);
+        }
+        config.global_config.save()
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  $ <bold>rtx plugins pin node</bold>
+  pinned node at 1a2b3c4
+  $ <bold>rtx plugins update node</bold>
+  updating plugin node
+    pinned at 1a2b3c4, unpin it to update with: rtx plugins unpin node
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_cli_err;
+
+    #[test]
+    fn test_plugins_pin_not_installed() {
+        let err = assert_cli_err!("plugins", "pin", "not-a-plugin");
+        assert_eq!(err.to_string(), "plugin not-a-plugin is not installed");
+    }
+}
@@ -43,7 +43,7 @@ impl PluginsUninstall {
             Some(plugin) if plugin.is_installed() => {
                 let mut pr = mpr.add();
                 plugin.decorate_progress_bar(&mut pr, None);
-                plugin.uninstall(&pr)?;
+                plugin.uninstall(config, &pr)?;
                 if self.purge {
                     plugin.purge(&pr)?;
                 }
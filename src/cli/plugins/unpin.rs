@@ -0,0 +1,64 @@
+use color_eyre::eyre::Result;
+use console::style;
+
+use crate::cli::command::Command;
+use crate::config::config_file::ConfigFile;
+use crate::config::Config;
+use crate::git::Git;
+use crate::output::Output;
+use crate::plugins::{unalias_plugin, PluginName};
+
+/// Unpin plugins pinned with `rtx plugins pin`
+///
+/// This removes the commit from [plugins] of ~/.config/rtx/config.toml so
+/// `rtx plugins update` updates the plugins again. The plugins are not updated.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct PluginsUnpin {
+    /// Plugin(s) to unpin
+    #[clap(required = true)]
+    plugin: Vec<PluginName>,
+}
+
+impl Command for PluginsUnpin {
+    fn run(self, mut config: Config, out: &mut Output) -> Result<()> {
+        let mut changed = false;
+        for name in &self.plugin {
+            let name = unalias_plugin(name);
+            let url = config.global_config.plugins().get(name).cloned();
+            match url.map(|url| Git::split_url_and_ref(&url)) {
+                Some((url, Some(_))) => {
+                    config.global_config.set_plugin(name, &url);
+                    rtxprintln!(out, "unpinned {name}");
+                    changed = true;
+                }
+                _ => warn!(
+                    "{} is not pinned in the global config",
+                    style(name).cyan().for_stderr()
+                ),
+            }
+        }
+        if changed {
+            config.global_config.save()?;
+        }
+        Ok(())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  $ <bold>rtx plugins unpin node</bold>
+  unpinned node
+  $ <bold>rtx plugins update node</bold>
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_cli;
+
+    #[test]
+    fn test_plugins_unpin_not_pinned() {
+        assert_eq!(assert_cli!("plugins", "unpin", "tiny"), "");
+    }
+}
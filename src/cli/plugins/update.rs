@@ -3,12 +3,19 @@ use console::style;
 
 use crate::cli::command::Command;
 use crate::config::Config;
+use crate::git::Git;
 use crate::output::Output;
 use crate::plugins::{unalias_plugin, PluginName};
+use crate::ui::progress_events;
 
 /// Updates a plugin to the latest version
 ///
 /// note: this updates the plugin itself, not the runtime versions
+///
+/// After updating, the new commits and any changed bin/ scripts are listed
+/// so it is clear whether the plugin's behavior may have changed.
+///
+/// Plugins pinned with `rtx plugins pin` are not updated.
 #[derive(Debug, clap::Args)]
 #[clap(verbatim_doc_comment, alias = "upgrade", after_long_help = AFTER_LONG_HELP)]
 pub struct Update {
@@ -47,12 +54,91 @@ impl Command for Update {
 
         for (plugin, ref_) in plugins {
             rtxprintln!(out, "updating plugin {}", plugin.name);
-            plugin.update(ref_)?;
+            if let Some(pinned) = pinned_ref(&config, &plugin.name) {
+                rtxprintln!(
+                    out,
+                    "  pinned at {}, unpin it to update with: rtx plugins unpin {}",
+                    pinned.chars().take(7).collect::<String>(),
+                    plugin.name
+                );
+                continue;
+            }
+            progress_events::emit("start", &plugin.name, "updating plugin", None);
+            let git = Git::new(plugin.plugin_path.clone());
+            let prev_sha = if git.is_repo() && !plugin.plugin_path.is_symlink() {
+                git.current_sha().ok()
+            } else {
+                None
+            };
+            if let Err(err) = plugin.update(&config, ref_) {
+                progress_events::emit("error", &plugin.name, &format!("{err:#}"), None);
+                return Err(err);
+            }
+            if let Some(prev_sha) = prev_sha {
+                print_changes(out, &git, &prev_sha)?;
+            }
+            progress_events::emit("finish", &plugin.name, "", Some(100));
         }
         Ok(())
     }
 }
 
+/// the ref a plugin is pinned at by a "url#ref" in [plugins], see `rtx plugins pin`
+fn pinned_ref(config: &Config, name: &PluginName) -> Option<String> {
+    config
+        .get_repo_url(name)
+        .and_then(|url| Git::split_url_and_ref(&url).1)
+}
+
+fn print_changes(out: &mut Output, git: &Git, prev_sha: &str) -> Result<()> {
+    let sha = git.current_sha()?;
+    if sha == prev_sha {
+        rtxprintln!(out, "  already up to date");
+        return Ok(());
+    }
+    let short = |sha: &str| sha.chars().take(7).collect::<String>();
+    let commits = git.log_oneline(prev_sha, &sha).unwrap_or_default();
+    rtxprintln!(
+        out,
+        "  {}..{} ({} commit{})",
+        short(prev_sha),
+        short(&sha),
+        commits.len(),
+        if commits.len() == 1 { "" } else { "s" }
+    );
+    for commit in commits.iter().take(10) {
+        rtxprintln!(out, "    {}", commit);
+    }
+    if commits.len() > 10 {
+        rtxprintln!(out, "    ...");
+    }
+    let scripts = git
+        .changed_files(prev_sha, &sha)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|f| f.starts_with("bin/"))
+        .collect::<Vec<_>>();
+    if !scripts.is_empty() {
+        rtxprintln!(out, "  changed scripts: {}", scripts.join(", "));
+    }
+    if scripts
+        .iter()
+        .any(|f| f == "bin/list-all" || f == "bin/latest-stable")
+    {
+        rtxprintln!(out, "  cached remote versions will be refreshed");
+    }
+    if scripts
+        .iter()
+        .any(|f| f == "bin/install" || f == "bin/download")
+    {
+        rtxprintln!(
+            out,
+            "  installs may behave differently, existing installs are unchanged"
+        );
+    }
+    Ok(())
+}
+
 static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
   $ <bold>rtx plugins update</bold>            # update all plugins
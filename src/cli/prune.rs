@@ -1,8 +1,10 @@
 use std::collections::BTreeMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use color_eyre::eyre::Result;
 use console::style;
+use itertools::Itertools;
 
 use crate::cli::command::Command;
 use crate::config::Config;
@@ -12,13 +14,19 @@ use crate::tool::Tool;
 use crate::toolset::{ToolVersion, ToolsetBuilder};
 use crate::ui::multi_progress_report::MultiProgressReport;
 use crate::ui::prompt;
+use crate::ui::prompt::Destructive;
+use crate::{last_used, runtime_symlinks, shims};
 
 /// Delete unused versions of tools
 ///
-/// rtx tracks which config files have been used in ~/.local/share/rtx/tracked_config_files
+/// rtx tracks which config files have been used in ~/.local/state/rtx/tracked_config_files
 /// Versions which are no longer the latest specified in any of those configs are deleted.
 /// Versions installed only with environment variables (`RTX_<PLUGIN>_VERSION`) will be deleted,
 /// as will versions only referenced on the command line (`rtx exec <PLUGIN>@<VERSION>`).
+/// Use --older-than to keep those if they were used recently from a shim, `rtx exec` or an
+/// activated shell.
+///
+/// Shims and runtime symlinks which are left pointing at nothing are removed as well.
 #[derive(Debug, clap::Args)]
 #[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
 pub struct Prune {
@@ -29,6 +37,10 @@ pub struct Prune {
     /// Do not actually delete anything
     #[clap(long, short_alias = 'n')]
     pub dry_run: bool,
+
+    /// Only delete versions which were not used for longer than this, e.g.: 30d
+    #[clap(long, value_parser = humantime::parse_duration, value_name = "DURATION")]
+    pub older_than: Option<Duration>,
 }
 
 impl Command for Prune {
@@ -43,6 +55,11 @@ impl Command for Prune {
         if let Some(plugins) = &self.plugins {
             to_delete.retain(|_, (_, tv)| plugins.contains(&tv.plugin_name));
         }
+        if let Some(older_than) = self.older_than {
+            to_delete.retain(|_, (_, tv)| {
+                last_used::unused_for(tv).map_or(true, |unused| unused > older_than)
+            });
+        }
 
         for cf in config.get_tracked_config_files()?.values() {
             let mut ts = cf.to_toolset().clone();
@@ -52,7 +69,20 @@ impl Command for Prune {
             }
         }
 
-        self.delete(&config, to_delete.into_values().collect())
+        let tools = to_delete
+            .values()
+            .map(|(p, _)| p.name.clone())
+            .unique()
+            .collect_vec();
+        self.delete(&config, to_delete.into_values().collect())?;
+
+        if !self.dry_run && !tools.is_empty() {
+            let ts = ToolsetBuilder::new().build(&mut config)?;
+            shims::reshim_tools(&config, &ts, Some(&tools))?;
+            runtime_symlinks::rebuild_tools(&config, Some(&tools))?;
+            config.remove_orphaned_shims_and_runtime_symlinks(&ts)?;
+        }
+        Ok(())
     }
 }
 
@@ -64,7 +94,12 @@ impl Prune {
             if self.dry_run {
                 pr.set_prefix(format!("{} {} ", pr.prefix(), style("[dryrun]").bold()));
             }
-            if self.dry_run || config.settings.yes || prompt::confirm(&format!("remove {} ?", &tv))?
+            if self.dry_run
+                || prompt::confirm_destructive(
+                    &config.settings,
+                    Destructive::Prune,
+                    &format!("remove {} ?", &tv),
+                )?
             {
                 p.decorate_progress_bar(&mut pr, Some(&tv));
                 p.uninstall_version(config, &tv, &pr, self.dry_run)?;
@@ -80,6 +115,8 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
   $ <bold>rtx prune --dry-run</bold>
   rm -rf ~/.local/share/rtx/versions/node/20.0.0
   rm -rf ~/.local/share/rtx/versions/node/20.0.1
+
+  $ <bold>rtx prune --older-than 30d</bold>
 "#
 );
 
@@ -90,6 +127,7 @@ mod tests {
     #[test]
     fn test_prune() {
         assert_cli!("prune", "--dry-run");
+        assert_cli!("prune", "--dry-run", "--older-than", "30d");
         assert_cli!("prune", "tiny");
         assert_cli!("prune");
         assert_cli!("install");
@@ -0,0 +1,93 @@
+use std::path::PathBuf;
+
+use clap::ValueHint;
+use color_eyre::eyre::{eyre, Result};
+use console::style;
+use itertools::Itertools;
+
+use crate::cli::command::Command;
+use crate::config::Config;
+use crate::file::display_path;
+use crate::output::Output;
+use crate::{dirs, relocate};
+
+/// Fix up installed tools after moving RTX_DATA_DIR
+///
+/// rtx records the directory tools were installed in. After RTX_DATA_DIR is moved,
+/// e.g.: copied to another machine or user, this rewrites scripts whose shebangs point
+/// into the old location. Executables and libraries with the old path compiled in
+/// (e.g.: an RPATH) can't be fixed and are listed so they can be reinstalled.
+/// Changes are logged to RTX_STATE_DIR/relocations.log
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Relocate {
+    /// The installs directory tools were installed in
+    /// defaults to the one rtx recorded
+    #[clap(long, value_hint = ValueHint::DirPath, verbatim_doc_comment)]
+    from: Option<PathBuf>,
+
+    /// Only show what would be changed
+    #[clap(long, short = 'n')]
+    dry_run: bool,
+}
+
+impl Command for Relocate {
+    fn run(self, _config: Config, out: &mut Output) -> Result<()> {
+        let from = self
+            .from
+            .or_else(relocate::previous_path)
+            .ok_or_else(|| eyre!("installs have not moved, nothing to relocate"))?;
+        let relocation = relocate::relocate(&from, self.dry_run)?;
+        let dry_run = match self.dry_run {
+            true => format!("{} ", style("[dryrun]").bold()),
+            false => String::new(),
+        };
+        for path in &relocation.fixed {
+            rtxprintln!(out, "{dry_run}fixed {}", display_path(path));
+        }
+        // e.g.: ~/.local/share/rtx/installs/node/20.0.0/bin/node -> node@20.0.0
+        let tools = relocation
+            .unfixable
+            .iter()
+            .filter_map(|path| {
+                let mut components = path.strip_prefix(&*dirs::INSTALLS).ok()?.iter();
+                let plugin = components.next()?.to_string_lossy();
+                let version = components.next()?.to_string_lossy();
+                Some(format!("{plugin}@{version}"))
+            })
+            .unique()
+            .collect_vec();
+        if !tools.is_empty() {
+            warn!(
+                "these tools have the old path compiled in and need to be reinstalled: {}",
+                tools.join(" ")
+            );
+            warn!("run `rtx install --force {}`", tools.join(" "));
+        }
+        Ok(())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  $ <bold>mv ~/.local/share/rtx /opt/rtx</bold>
+  $ <bold>export RTX_DATA_DIR=/opt/rtx</bold>
+  $ <bold>rtx relocate</bold>
+  fixed /opt/rtx/installs/node/20.9.0/bin/npm
+  fixed /opt/rtx/installs/node/20.9.0/bin/npx
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_cli_err;
+
+    #[test]
+    fn test_relocate_not_moved() {
+        let err = assert_cli_err!("relocate", "--dry-run");
+        assert_eq!(
+            err.to_string(),
+            "installs have not moved, nothing to relocate"
+        );
+    }
+}
@@ -2,13 +2,14 @@ use color_eyre::eyre::Result;
 
 use crate::cli::command::Command;
 use crate::config::Config;
+use crate::file::display_path;
 use crate::output::Output;
 use crate::shims;
 use crate::toolset::ToolsetBuilder;
 
 /// rebuilds the shim farm
 ///
-/// This creates new shims in ~/.local/share/rtx/shims for CLIs that have been added.
+/// This creates new shims in RTX_SHIMS_DIR (default: ~/.local/share/rtx/shims) for CLIs that have been added.
 /// rtx will try to do this automatically for commands like `npm i -g` but there are
 /// other ways to install things (like using yarn or pnpm for node) that rtx does
 /// not know about and so it will be necessary to call this explicitly.
@@ -21,27 +22,65 @@ use crate::toolset::ToolsetBuilder;
 ///   command npm "$@"
 ///   rtx reshim
 /// }
+///
+/// Pass a tool name to only rebuild the shims of that tool.
+///
+/// Use --prune to also remove the shims and runtime symlinks (e.g.: installs/node/20) of
+/// any tool which point at nothing, these can be left behind by uninstalling versions.
 #[derive(Debug, clap::Args)]
 #[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
 pub struct Reshim {
-    #[clap(hide = true)]
+    /// Only rebuild the shims for this tool
     pub plugin: Option<String>,
     #[clap(hide = true)]
     pub version: Option<String>,
+
+    /// Remove shims and runtime symlinks of every tool which point at nothing
+    #[clap(long)]
+    pub prune: bool,
 }
 
 impl Command for Reshim {
-    fn run(self, mut config: Config, _out: &mut Output) -> Result<()> {
+    fn run(self, mut config: Config, out: &mut Output) -> Result<()> {
         let ts = ToolsetBuilder::new().build(&mut config)?;
 
-        shims::reshim(&config, &ts)
+        match self.plugin {
+            Some(plugin) => shims::reshim_tools(&config, &ts, Some(&[plugin]))?,
+            None => shims::reshim(&config, &ts)?,
+        }
+        if self.prune {
+            for path in config.remove_orphaned_shims_and_runtime_symlinks(&ts)? {
+                rtxprintln!(out, "removed {}", display_path(&path));
+            }
+        }
+        Ok(())
     }
 }
 
 static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
   $ <bold>rtx reshim</bold>
+  $ <bold>rtx reshim node</bold>
+  $ <bold>rtx reshim --prune</bold>
+  removed ~/.local/share/rtx/shims/yarn
   $ <bold>~/.local/share/rtx/shims/node -v</bold>
   v20.0.0
 "#
 );
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_cli;
+    use crate::dirs;
+    use crate::file::make_symlink;
+
+    #[test]
+    fn test_reshim_prune() {
+        let shim = dirs::SHIMS.join("orphaned-shim");
+        make_symlink(&dirs::SHIMS.join("does-not-exist"), &shim).unwrap();
+        assert_cli!("reshim");
+        assert!(shim.symlink_metadata().is_ok());
+        assert_cli!("reshim", "--prune");
+        assert!(shim.symlink_metadata().is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::BTreeMap;
+use std::process::exit;
+use std::time::Duration;
+
+use color_eyre::eyre::{bail, Result};
+use console::style;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::Select;
+
+use crate::cli::command::Command;
+use crate::cli::watch::watch_task;
+use crate::cmd;
+use crate::config::Config;
+use crate::output::Output;
+use crate::task::{self, Task};
+use crate::toolset::ToolsetBuilder;
+
+/// Run a task from `[tasks]` in .rtx.toml
+///
+/// The task's dependencies run first, each task runs once. Scripts run with `sh -c` in the
+/// directory of the config file which defines them, with the env and PATH of the current
+/// tools like `rtx exec`. Missing tools are installed first.
+///
+/// Arguments after the task name are passed to the script of the task as "$@".
+/// The dependencies of the task do not get them.
+///
+/// Without a task name the tasks are listed to pick one from.
+#[derive(Debug, clap::Args)]
+#[clap(visible_alias = "r", verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Run {
+    /// Task to run, see `rtx tasks ls`
+    #[clap()]
+    task: Option<String>,
+
+    /// Arguments to pass to the task's script
+    #[clap(allow_hyphen_values = true, trailing_var_arg = true)]
+    args: Vec<String>,
+
+    /// Pick the task to run from a list of the tasks with their descriptions
+    /// This is the default when no task is given and the terminal is interactive
+    #[clap(short, long, conflicts_with = "task", verbatim_doc_comment)]
+    interactive: bool,
+
+    /// Run the task again each time its sources change, like `rtx watch`
+    #[clap(short, long)]
+    watch: bool,
+
+    /// How long to wait for more changes before running the task
+    /// [default: RTX_WATCH_DEBOUNCE or 200ms]
+    #[clap(long, value_parser = humantime::parse_duration, value_name = "DURATION", requires = "watch", verbatim_doc_comment)]
+    debounce: Option<Duration>,
+}
+
+impl Command for Run {
+    fn run(self, mut config: Config, out: &mut Output) -> Result<()> {
+        let tasks = task::load(&config);
+        let name = match self.task {
+            Some(name) => name,
+            None if self.interactive || console::user_attended_stderr() => pick_task(&tasks)?,
+            None => bail!("no task given, see `rtx tasks ls`"),
+        };
+        if self.watch {
+            return watch_task(&mut config, &name, &self.args, self.debounce, out);
+        }
+        let order = task::run_order(&tasks, &name)?;
+        let ts = ToolsetBuilder::new()
+            .with_install_missing()
+            .build(&mut config)?;
+        let env = ts.full_env(&config);
+        let code = run_tasks(&order, &env, &name, &self.args, out)?;
+        if code != 0 {
+            exit(code);
+        }
+        Ok(())
+    }
+}
+
+/// prompts for one of `tasks`, shown with their descriptions
+fn pick_task(tasks: &BTreeMap<String, Task>) -> Result<String> {
+    if tasks.is_empty() {
+        bail!("no tasks are defined, add them to [tasks] in .rtx.toml");
+    }
+    let width = tasks.keys().map(|n| n.len()).max().unwrap_or_default();
+    let items = tasks
+        .values()
+        .map(|t| {
+            let description = t.description.as_deref().unwrap_or_default();
+            format!("{:width$}  {}", t.name, style(description).dim())
+        })
+        .collect::<Vec<_>>();
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Select a task to run")
+        .items(&items)
+        .default(0)
+        .interact_opt()?;
+    match selection {
+        Some(i) => Ok(tasks.keys().nth(i).unwrap().clone()),
+        None => bail!("no task selected"),
+    }
+}
+
+/// runs the tasks in order until one fails and returns its exit code, `args` are passed to the
+/// script of the task named `name`
+pub fn run_tasks(
+    order: &[&Task],
+    env: &BTreeMap<String, String>,
+    name: &str,
+    args: &[String],
+    out: &mut Output,
+) -> Result<i32> {
+    for task in order {
+        if task.run.is_empty() {
+            continue;
+        }
+        let mut script = task.run.clone();
+        let mut extra_args = vec![];
+        if task.name == name && !args.is_empty() {
+            script.push_str(r#" "$@""#);
+            extra_args = args.to_vec();
+        }
+        rtxstatusln!(
+            out,
+            "{} {}",
+            style(format!("[{}]", task.name)).cyan().for_stderr(),
+            style(&script).dim().for_stderr()
+        );
+        // $0 is the task name so it shows in error messages from sh
+        let args = [String::from("-c"), script, task.name.clone()]
+            .into_iter()
+            .chain(extra_args);
+        let code = cmd::run_foreground(cmd::cmd("sh", args).full_env(env).dir(task.dir()))?;
+        if code != 0 {
+            warn!("task {} failed with exit code {code}", task.name);
+            return Ok(code);
+        }
+    }
+    Ok(0)
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  # .rtx.toml
+  [tasks]
+  lint = "eslint src"
+  [tasks.test]
+  run = "jest"
+  description = "Run the test suite"
+  depends = ["lint"]
+
+  $ <bold>rtx run test -- --watch</bold>
+  rtx [lint] eslint src
+  rtx [test] jest "$@"
+
+  $ <bold>rtx run --watch test</bold>  # like `rtx watch test`
+  $ <bold>rtx run</bold>               # pick a task from a list
+  ? Select a task to run ›
+  ❯ lint
+    test  Run the test suite
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_cli_err;
+
+    #[test]
+    fn test_run_missing_task() {
+        let err = assert_cli_err!("run", "nope");
+        assert_eq!(
+            err.to_string(),
+            "task nope is not defined, see `rtx tasks ls`"
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use color_eyre::eyre::Result;
+use console::{measure_text_width, pad_str, style, Alignment};
+use itertools::Itertools;
+
+use crate::cli::command::Command;
+use crate::config::Config;
+use crate::output::Output;
+use crate::plugins::core::{CORE_PLUGINS, EXPERIMENTAL_CORE_PLUGINS};
+use crate::plugins::list_plugin_aliases;
+
+/// Search for tools by name
+///
+/// Looks through the core plugins, the shorthand plugin registry, installed
+/// plugins, plugin aliases (e.g.: nodejs -> node) and the aliases in your
+/// config, and shows how to install each match. Use this when you don't know
+/// which plugin provides a tool.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Search {
+    /// Part of the tool name to search for, e.g.: terraform
+    query: String,
+}
+
+#[derive(Debug)]
+struct Match {
+    name: String,
+    source: String,
+    install: String,
+}
+
+impl Command for Search {
+    fn run(self, config: Config, out: &mut Output) -> Result<()> {
+        let query = self.query.to_lowercase();
+        let is_match = |name: &str| name.to_lowercase().contains(&query);
+        let use_latest = |name: &str| format!("rtx use -g {name}@latest");
+        let mut matches = vec![];
+        for name in CORE_PLUGINS.keys().filter(|n| is_match(n)) {
+            matches.push(Match {
+                name: name.clone(),
+                source: "core".into(),
+                install: use_latest(name),
+            });
+        }
+        for name in EXPERIMENTAL_CORE_PLUGINS.keys().filter(|n| is_match(n)) {
+            matches.push(Match {
+                name: name.clone(),
+                source: "core (experimental)".into(),
+                install: format!("RTX_EXPERIMENTAL=1 {}", use_latest(name)),
+            });
+        }
+        for (name, repo) in config.get_shorthands().iter() {
+            if is_match(name) && !CORE_PLUGINS.contains_key(name.as_str()) {
+                matches.push(Match {
+                    name: name.clone(),
+                    source: format!("plugin {repo}"),
+                    install: use_latest(name),
+                });
+            }
+        }
+        for tool in config.tools.values() {
+            let known = matches.iter().any(|m| m.name == tool.name);
+            if !known && is_match(&tool.name) && tool.is_installed() {
+                matches.push(Match {
+                    name: tool.name.clone(),
+                    source: "installed plugin".into(),
+                    install: use_latest(&tool.name),
+                });
+            }
+        }
+        for (from, to) in list_plugin_aliases() {
+            if is_match(&from) {
+                matches.push(Match {
+                    install: use_latest(&from),
+                    source: format!("alias for {to}"),
+                    name: from,
+                });
+            }
+        }
+        for (plugin, aliases) in &config.aliases {
+            for (alias, version) in aliases {
+                if is_match(plugin) || is_match(alias) {
+                    matches.push(Match {
+                        name: format!("{plugin}@{alias}"),
+                        source: format!("alias for {plugin}@{version}"),
+                        install: format!("rtx use {plugin}@{alias}"),
+                    });
+                }
+            }
+        }
+
+        if matches.is_empty() {
+            rtxstatusln!(out, "no tools found matching {}", style(&self.query).cyan());
+            return Ok(());
+        }
+        // exact matches first, e.g.: "go" before "golang" and "gohugo"
+        let matches = matches
+            .into_iter()
+            .sorted_by_cached_key(|m| (m.name.to_lowercase() != query, m.name.clone()))
+            .collect_vec();
+        let name_len = matches.iter().map(|m| measure_text_width(&m.name)).max();
+        let source_len = matches.iter().map(|m| measure_text_width(&m.source)).max();
+        for m in matches {
+            let name = pad_str(&m.name, name_len.unwrap(), Alignment::Left, None);
+            let source = pad_str(&m.source, source_len.unwrap(), Alignment::Left, None);
+            rtxprintln!(
+                out,
+                "{} {} {}",
+                style(name).cyan(),
+                source,
+                style(m.install).dim()
+            );
+        }
+        Ok(())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  $ <bold>rtx search terraform</bold>
+  terraform    plugin https://github.com/asdf-community/asdf-hashicorp.git rtx use -g terraform@latest
+  terraform-ls plugin https://github.com/asdf-community/asdf-hashicorp.git rtx use -g terraform-ls@latest
+
+  $ <bold>rtx search node</bold>
+  node   core           rtx use -g node@latest
+  nodejs alias for node rtx use -g nodejs@latest
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_cli;
+
+    #[test]
+    fn test_search() {
+        let stdout = assert_cli!("search", "nodejs");
+        assert!(stdout.contains("alias for node"));
+        assert!(stdout.contains("rtx use -g nodejs@latest"));
+    }
+
+    #[test]
+    fn test_search_config_alias() {
+        let stdout = assert_cli!("search", "my/alias");
+        assert!(stdout.contains("tiny@my/alias alias for tiny@3.0"));
+    }
+
+    #[test]
+    fn test_search_no_match() {
+        let stdout = assert_cli!("search", "this-tool-does-not-exist");
+        assert_eq!(stdout, "");
+    }
+}
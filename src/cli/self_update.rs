@@ -1,3 +1,4 @@
+use color_eyre::eyre::bail;
 use color_eyre::Result;
 use console::style;
 
@@ -10,6 +11,7 @@ use crate::cli::version::{ARCH, OS};
 use crate::config::Config;
 use crate::env;
 use crate::output::Output;
+use crate::ui::prompt::{preset_answer, Destructive};
 
 /// Updates rtx itself
 ///
@@ -51,6 +53,11 @@ impl SelfUpdate {
     }
 
     fn do_update(&self, config: &Config, latest: &str) -> Result<Status> {
+        let no_confirm = match preset_answer(&config.settings, Destructive::SelfUpdate) {
+            Some(true) => true,
+            Some(false) => bail!("not updating rtx, confirm_policy is \"no\""),
+            None => false,
+        };
         let current_version =
             env::var("RTX_SELF_UPDATE_VERSION").unwrap_or(cargo_crate_version!().to_string());
         let target = format!("{}-{}", *OS, *ARCH);
@@ -68,7 +75,7 @@ impl SelfUpdate {
             .target(&target)
             .bin_path_in_archive("rtx/bin/rtx")
             .identifier(&format!("rtx-v{latest}-{target}.tar.gz"))
-            .no_confirm(config.settings.yes)
+            .no_confirm(no_confirm)
             .build()?
             .update()?;
         Ok(status)
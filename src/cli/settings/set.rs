@@ -32,6 +32,16 @@ impl Command for SettingsSet {
             "shorthands_file" => self.value.into(),
             "disable_default_shorthands" => parse_bool(&self.value)?,
             "raw" => parse_bool(&self.value)?,
+            "activate_install_missing" => self.value.into(),
+            "prerelease_regex" => self.value.into(),
+            "eol_error" => parse_bool(&self.value)?,
+            "env_clear" => parse_bool(&self.value)?,
+            "exec_strict" => parse_bool(&self.value)?,
+            "activate_status" => self.value.into(),
+            "target_os" => self.value.into(),
+            "target_arch" => self.value.into(),
+            "verify_installs" => self.value.into(),
+            "confirm_policy" => self.value.into(),
             _ => return Err(eyre!("Unknown setting: {}", self.key)),
         };
 
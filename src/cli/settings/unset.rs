@@ -43,21 +43,36 @@ mod tests {
 
         let stdout = assert_cli!("settings");
         assert_snapshot!(stdout, @r###"
+        activate_install_missing = off
+        activate_status = off
         always_keep_download = true
+        always_keep_download_tools = []
         always_keep_install = true
         asdf_compat = false
+        confirm_policy = default
         disable_default_shorthands = false
         disable_tools = []
+        env_clear = false
+        eol_error = false
+        exec_strict = false
         experimental = true
+        hooks = {}
         jobs = 2
         legacy_version_file = true
         legacy_version_file_disable_tools = []
+        list_all_regexes = {}
+        list_all_urls = {}
         log_level = INFO
         missing_runtime_behavior = autoinstall
+        never_keep_download_tools = []
+        plugin_aliases = {}
         plugin_autoupdate_last_check_duration = 20
+        plugin_env_passthrough = []
         raw = false
+        shim_bypass = []
         trusted_config_paths = []
         verbose = true
+        verify_installs = off
         yes = true
         "###);
 
@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+
+use color_eyre::eyre::Result;
+use itertools::Itertools;
+
+use crate::cli::command::Command;
+use crate::config::Config;
+use crate::output::Output;
+use crate::task::{self, Task};
+
+/// Show the dependencies of tasks
+///
+/// Prints each task with the tasks it depends on indented below it.
+/// Without task names, the tasks which no other task depends on are shown.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct TasksDeps {
+    /// Tasks to show the dependencies of
+    #[clap()]
+    tasks: Vec<String>,
+
+    /// Output the graph in DOT format for graphviz
+    #[clap(long)]
+    dot: bool,
+}
+
+impl Command for TasksDeps {
+    fn run(self, config: Config, out: &mut Output) -> Result<()> {
+        let tasks = task::load(&config);
+        let roots = match self.tasks.is_empty() {
+            true => roots(&tasks),
+            false => self.tasks,
+        };
+        for name in &roots {
+            // errors on undefined tasks and cycles
+            task::run_order(&tasks, name)?;
+        }
+        let output = match self.dot {
+            true => dot(&tasks, &roots),
+            false => tree(&tasks, &roots),
+        };
+        rtxprint!(out, "{output}");
+        Ok(())
+    }
+}
+
+/// the tasks which no other task depends on
+fn roots(tasks: &BTreeMap<String, Task>) -> Vec<String> {
+    tasks
+        .keys()
+        .filter(|name| !tasks.values().any(|t| t.depends.contains(name)))
+        .cloned()
+        .collect()
+}
+
+fn tree(tasks: &BTreeMap<String, Task>, roots: &[String]) -> String {
+    fn visit(tasks: &BTreeMap<String, Task>, name: &str, depth: usize, lines: &mut Vec<String>) {
+        lines.push(format!("{}{name}", "  ".repeat(depth)));
+        for dep in &tasks[name].depends {
+            visit(tasks, dep, depth + 1, lines);
+        }
+    }
+    let mut lines = vec![];
+    for name in roots {
+        visit(tasks, name, 0, &mut lines);
+    }
+    lines.into_iter().map(|l| l + "\n").collect()
+}
+
+fn dot(tasks: &BTreeMap<String, Task>, roots: &[String]) -> String {
+    let mut nodes = vec![];
+    for name in roots {
+        for task in task::run_order(tasks, name).unwrap_or_default() {
+            if !nodes.contains(&task) {
+                nodes.push(task);
+            }
+        }
+    }
+    let mut lines = vec!["digraph tasks {".to_string()];
+    for task in nodes.iter().sorted_by_key(|t| &t.name) {
+        match task.depends.is_empty() {
+            true => lines.push(format!("  {:?};", task.name)),
+            false => lines.extend(
+                task.depends
+                    .iter()
+                    .map(|dep| format!("  {:?} -> {dep:?};", task.name)),
+            ),
+        }
+    }
+    lines.push("}".to_string());
+    lines.into_iter().map(|l| l + "\n").collect()
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  $ <bold>rtx tasks deps</bold>
+  test
+    build
+      codegen
+    lint
+
+  $ <bold>rtx tasks deps --dot test | dot -Tsvg > tasks.svg</bold>
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn tasks() -> BTreeMap<String, Task> {
+        [
+            ("test", vec!["build", "lint"]),
+            ("build", vec!["codegen"]),
+            ("lint", vec![]),
+            ("codegen", vec![]),
+        ]
+        .into_iter()
+        .map(|(name, depends)| {
+            let task = Task {
+                name: name.to_string(),
+                depends: depends.into_iter().map(String::from).collect(),
+                ..Default::default()
+            };
+            (name.to_string(), task)
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_tree() {
+        let tasks = tasks();
+        let roots = roots(&tasks);
+        assert_eq!(roots, vec!["test"]);
+        assert_eq!(tree(&tasks, &roots), "test\n  build\n    codegen\n  lint\n");
+    }
+
+    #[test]
+    fn test_dot() {
+        let tasks = tasks();
+        assert_eq!(
+            dot(&tasks, &["build".to_string()]),
+            "digraph tasks {\n  \"build\" -> \"codegen\";\n  \"codegen\";\n}\n"
+        );
+    }
+}
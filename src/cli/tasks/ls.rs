@@ -0,0 +1,48 @@
+use color_eyre::eyre::Result;
+use console::style;
+
+use crate::cli::command::Command;
+use crate::config::Config;
+use crate::file::display_path;
+use crate::output::Output;
+use crate::task;
+
+/// List the tasks which can be run with `rtx run`
+///
+/// Shows each task with its description and the config file which defines it.
+/// A task in a config file closer to the current directory replaces one with the same name
+/// from a parent directory or the global config.
+#[derive(Debug, clap::Args)]
+#[clap(visible_alias = "list", verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct TasksLs {}
+
+impl Command for TasksLs {
+    fn run(self, config: Config, out: &mut Output) -> Result<()> {
+        let tasks = task::load(&config);
+        let name_width = tasks.keys().map(|n| n.len()).max().unwrap_or_default();
+        let desc_width = tasks
+            .values()
+            .filter_map(|t| t.description.as_ref().map(|d| d.len()))
+            .max()
+            .unwrap_or_default();
+        for task in tasks.values() {
+            let description = task.description.as_deref().unwrap_or_default();
+            rtxprintln!(
+                out,
+                "{:name_width$}  {:desc_width$}  {}",
+                task.name,
+                description,
+                style(display_path(&task.config_path)).dim()
+            );
+        }
+        Ok(())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  $ <bold>rtx tasks ls</bold>
+  lint  Check for lint errors  ~/src/myproj/.rtx.toml
+  test  Run the test suite     ~/src/myproj/.rtx.toml
+"#
+);
@@ -0,0 +1,41 @@
+use clap::Subcommand;
+use color_eyre::eyre::Result;
+
+use crate::cli::command::Command;
+use crate::config::Config;
+use crate::output::Output;
+
+mod deps;
+mod ls;
+
+/// Manage tasks defined in `[tasks]` of .rtx.toml
+///
+/// Run `rtx tasks` with no args to list the tasks.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct Tasks {
+    #[clap(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    Deps(deps::TasksDeps),
+    Ls(ls::TasksLs),
+}
+
+impl Commands {
+    pub fn run(self, config: Config, out: &mut Output) -> Result<()> {
+        match self {
+            Self::Deps(cmd) => cmd.run(config, out),
+            Self::Ls(cmd) => cmd.run(config, out),
+        }
+    }
+}
+
+impl Command for Tasks {
+    fn run(self, config: Config, out: &mut Output) -> Result<()> {
+        let cmd = self.command.unwrap_or(Commands::Ls(ls::TasksLs {}));
+        cmd.run(config, out)
+    }
+}
@@ -0,0 +1,165 @@
+use std::path::PathBuf;
+
+use clap::ValueHint;
+use color_eyre::eyre::{bail, eyre, Result};
+use itertools::Itertools;
+
+use crate::cli::args::tool::{ToolArg, ToolArgParser};
+use crate::cli::command::Command;
+use crate::config::Config;
+use crate::file;
+use crate::file::display_path;
+use crate::output::Output;
+use crate::toolset::ToolsetBuilder;
+
+/// Write executable stubs into a project which run a pinned tool version
+///
+/// The stubs call `rtx x <TOOL>@<VERSION> -- <BIN>` so scripts and CI can run
+/// the project's tools without activating rtx, similar to the gradle wrapper.
+/// They only need rtx to be on PATH and can be committed to the project.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct ToolStub {
+    /// Tool to write stubs for
+    /// e.g.: node@20
+    /// without a version, the version currently set in the config is pinned
+    #[clap(value_name = "TOOL@VERSION", value_parser = ToolArgParser, verbatim_doc_comment)]
+    tool: ToolArg,
+
+    /// Executable to write a stub for, can be used multiple times
+    /// defaults to every executable the installed version provides,
+    /// or the tool name if it is not installed
+    #[clap(long, short, verbatim_doc_comment)]
+    bin: Vec<String>,
+
+    /// Directory to write the stubs to
+    #[clap(long, short, value_hint = ValueHint::DirPath, default_value = "bin")]
+    dir: PathBuf,
+
+    /// Overwrite existing files
+    #[clap(long, short)]
+    force: bool,
+}
+
+impl Command for ToolStub {
+    fn run(self, mut config: Config, out: &mut Output) -> Result<()> {
+        let plugin = &self.tool.plugin;
+        let ts = ToolsetBuilder::new()
+            .with_args(std::slice::from_ref(&self.tool))
+            .build(&mut config)?;
+        let tv = ts
+            .versions
+            .get(plugin)
+            .and_then(|tvl| tvl.versions.first())
+            .ok_or_else(|| eyre!("no version of {plugin} is set, use {plugin}@<VERSION>"))?;
+        // pin what was asked for, e.g.: "node@20", otherwise the exact version in use
+        let version = match &self.tool.tvr {
+            Some(tvr) => tvr.version(),
+            None => tv.version.clone(),
+        };
+        let bins = match self.bin.is_empty() {
+            true => {
+                let tool = config.get_or_create_tool(plugin);
+                let bins = match tool.is_version_installed(tv) {
+                    true => list_executables(&tool.list_bin_paths(&config, tv)?),
+                    false => vec![],
+                };
+                match bins.is_empty() {
+                    true => vec![plugin.to_string()],
+                    false => bins,
+                }
+            }
+            false => self.bin.clone(),
+        };
+
+        file::create_dir_all(&self.dir)?;
+        for bin in bins {
+            let path = self.dir.join(&bin);
+            if path.exists() && !self.force {
+                bail!(
+                    "{} already exists, use --force to overwrite",
+                    display_path(&path)
+                );
+            }
+            file::write(&path, stub(plugin, &version, &bin))?;
+            file::make_executable(&path)?;
+            rtxprintln!(out, "{}", display_path(&path));
+        }
+        Ok(())
+    }
+}
+
+fn list_executables(bin_paths: &[PathBuf]) -> Vec<String> {
+    bin_paths
+        .iter()
+        .flat_map(|dir| {
+            file::dir_files(dir)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|f| file::is_executable(&dir.join(f)))
+        })
+        .unique()
+        .sorted()
+        .collect()
+}
+
+fn stub(plugin: &str, version: &str, bin: &str) -> String {
+    format!(
+        r#"#!/bin/sh
+# runs {bin} from {plugin}@{version} with rtx
+# generated by `rtx tool-stub`, rtx must be on PATH: {url}
+set -eu
+if ! command -v rtx >/dev/null 2>&1; then
+  echo "{bin}: rtx is required to run this, see {url}" >&2
+  exit 127
+fi
+exec rtx x {plugin}@{version} -- {bin} "$@"
+"#,
+        url = "https://github.com/jdx/rtx#installation",
+    )
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  $ <bold>rtx tool-stub node@20</bold>
+  bin/node
+  bin/npm
+  bin/npx
+  $ <bold>./bin/node -v</bold>
+  v20.9.0
+
+  $ <bold>rtx tool-stub python --bin python3 --dir scripts</bold>
+  scripts/python3
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use crate::{assert_cli, dirs, file};
+
+    #[test]
+    fn test_tool_stub() {
+        let dir = dirs::CACHE.join("test-tool-stub");
+        file::remove_all(&dir).unwrap();
+        let d = dir.to_string_lossy().to_string();
+        assert_cli!("tool-stub", "tiny@2", "--bin", "rtx-tiny", "--dir", &d);
+        assert_snapshot!(file::read_to_string(dir.join("rtx-tiny")).unwrap());
+        assert!(file::is_executable(&dir.join("rtx-tiny")));
+
+        // pins the version from .test-tool-versions
+        assert_cli!(
+            "tool-stub",
+            "tiny",
+            "--bin",
+            "rtx-tiny",
+            "--dir",
+            &d,
+            "--force"
+        );
+        let stub = file::read_to_string(dir.join("rtx-tiny")).unwrap();
+        assert!(stub.contains("exec rtx x tiny@3."));
+        file::remove_all(&dir).unwrap();
+    }
+}
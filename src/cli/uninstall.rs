@@ -1,5 +1,6 @@
 use color_eyre::eyre::{eyre, Result};
 use console::style;
+use itertools::Itertools;
 
 use crate::cli::args::tool::{ToolArg, ToolArgParser};
 use crate::cli::command::Command;
@@ -7,6 +8,8 @@ use crate::config::Config;
 use crate::output::Output;
 use crate::toolset::{ToolVersion, ToolVersionRequest, ToolsetBuilder};
 use crate::ui::multi_progress_report::MultiProgressReport;
+use crate::ui::prompt;
+use crate::ui::prompt::Destructive;
 use crate::{runtime_symlinks, shims};
 
 /// Removes runtime versions
@@ -79,6 +82,18 @@ impl Command for Uninstall {
                 .collect::<Vec<_>>();
         }
 
+        if self.all && !self.dry_run && !tool_versions.is_empty() {
+            let msg = format!("uninstall {} versions?", tool_versions.len());
+            if !prompt::confirm_destructive(&config.settings, Destructive::UninstallAll, &msg)? {
+                return Ok(());
+            }
+        }
+
+        let tools = tool_versions
+            .iter()
+            .map(|(t, _)| t.name.clone())
+            .unique()
+            .collect_vec();
         let mpr = MultiProgressReport::new(config.show_progress_bars());
         for (plugin, tv) in tool_versions {
             if !plugin.is_version_installed(&tv) {
@@ -96,8 +111,12 @@ impl Command for Uninstall {
         }
 
         let ts = ToolsetBuilder::new().build(&mut config)?;
-        shims::reshim(&config, &ts).map_err(|err| eyre!("failed to reshim: {}", err))?;
-        runtime_symlinks::rebuild(&config)?;
+        shims::reshim_tools(&config, &ts, Some(&tools))
+            .map_err(|err| eyre!("failed to reshim: {}", err))?;
+        runtime_symlinks::rebuild_tools(&config, Some(&tools))?;
+        if !self.dry_run {
+            config.remove_orphaned_shims_and_runtime_symlinks(&ts)?;
+        }
 
         Ok(())
     }
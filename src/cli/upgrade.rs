@@ -1,31 +1,54 @@
 use std::collections::HashSet;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use color_eyre::eyre::{eyre, Result};
+use indexmap::map::Entry;
+use indexmap::IndexMap;
 use itertools::Itertools;
 use rayon::prelude::*;
 use rayon::ThreadPoolBuilder;
 
 use crate::cli::args::tool::{ToolArg, ToolArgParser};
 use crate::cli::command::Command;
+use crate::config::config_file::{self, ConfigFile};
 use crate::config::Config;
+use crate::file::display_path;
+use crate::lockfile;
 use crate::output::Output;
 use crate::runtime_symlinks;
 use crate::shims;
 use crate::tool::Tool;
-use crate::toolset::{ToolVersion, ToolsetBuilder};
+use crate::toolset::{ToolSource, ToolVersion, ToolVersionRequest, ToolsetBuilder};
 use crate::ui::multi_progress_report::MultiProgressReport;
 use crate::ui::progress_report::ProgressReport;
 
 /// Upgrades outdated tool versions
+///
+/// Installs the newest version matching the request in the config file (or the one passed
+/// as an argument), rewrites config files which pinned the old version, updates .rtx.lock,
+/// uninstalls the old version and reshims.
+/// Requests which still match the new version, like "20" for node@20.3.0, are not rewritten.
+/// Exact versions like "20.1.0" match only themselves, pass a prefix to upgrade them,
+/// e.g.: `rtx upgrade node@20` rewrites node 20.1.0 to 20.3.0.
+/// Upgrades stay within the major version of the request unless --bump is passed,
+/// e.g.: node "18" is only rewritten to "20" with `rtx upgrade --bump node@20`.
 #[derive(Debug, clap::Args)]
-#[clap(verbatim_doc_comment)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
 pub struct Upgrade {
     /// Tool(s) to upgrade
     /// e.g.: node@20 python@3.10
     /// If not specified, all current tools will be upgraded
     #[clap(value_name="TOOL@VERSION", value_parser = ToolArgParser, verbatim_doc_comment)]
     pub tool: Vec<ToolArg>,
+
+    /// Do not uninstall the versions that were upgraded
+    #[clap(long)]
+    pub keep_old: bool,
+
+    /// Allow upgrading to a new major version, rewriting the major version in config files
+    #[clap(long)]
+    pub bump: bool,
 }
 
 impl Command for Upgrade {
@@ -40,7 +63,10 @@ impl Command for Upgrade {
             .collect::<HashSet<_>>();
         ts.versions
             .retain(|_, tvl| tool_set.is_empty() || tool_set.contains(&tvl.plugin_name));
-        let outdated = ts.list_outdated_versions(&config);
+        let mut outdated = ts.list_outdated_versions(&config);
+        if !self.bump {
+            outdated = skip_major_upgrades(&mut config, outdated)?;
+        }
         if outdated.is_empty() {
             info!("All tools are up to date");
         } else {
@@ -52,43 +78,59 @@ impl Command for Upgrade {
 }
 
 type OutputVec = Vec<(Arc<Tool>, ToolVersion, String)>;
-type GroupedToolVersions = Vec<(Arc<Tool>, Vec<(ToolVersion, String)>)>;
+type GroupedToolVersions = Vec<(Arc<Tool>, Vec<ToolVersion>)>;
 
 impl Upgrade {
     fn upgrade(&self, config: &mut Config, outdated: OutputVec) -> Result<()> {
         let mpr = MultiProgressReport::new(config.show_progress_bars());
-        ThreadPoolBuilder::new()
-            .num_threads(config.settings.jobs)
-            .build()?
-            .install(|| -> Result<()> {
-                self.install_new_versions(config, &mpr, outdated)?;
-
-                let ts = ToolsetBuilder::new().with_args(&self.tool).build(config)?;
-                shims::reshim(config, &ts).map_err(|err| eyre!("failed to reshim: {}", err))?;
-                runtime_symlinks::rebuild(config)?;
-
-                Ok(())
+        let tools = outdated
+            .iter()
+            .map(|(t, _, _)| t.name.clone())
+            .unique()
+            .collect_vec();
+        // installing through the toolset applies the same policy and eol checks as `rtx install`
+        let new_versions = outdated
+            .iter()
+            .map(|(tool, tv, latest)| {
+                let request = ToolVersionRequest::new(tool.name.clone(), latest);
+                ToolVersion::new(tool, request, tv.opts.clone(), latest.clone())
             })
+            .collect_vec();
+        let mut ts = ToolsetBuilder::new().with_args(&self.tool).build(config)?;
+        ts.install_versions(config, new_versions, &mpr, false)?;
+        self.bump_config_files(config, &outdated)?;
+        // only once nothing refers to them anymore
+        if !self.keep_old {
+            ThreadPoolBuilder::new()
+                .num_threads(config.settings.jobs)
+                .build()?
+                .install(|| self.uninstall_old_versions(config, &mpr, &outdated))?;
+        }
+
+        let ts = ToolsetBuilder::new().with_args(&self.tool).build(config)?;
+        shims::reshim_tools(config, &ts, Some(&tools))
+            .map_err(|err| eyre!("failed to reshim: {}", err))?;
+        runtime_symlinks::rebuild_tools(config, Some(&tools))?;
+        Ok(())
     }
 
-    fn install_new_versions(
+    fn uninstall_old_versions(
         &self,
         config: &Config,
         mpr: &MultiProgressReport,
-        outdated: OutputVec,
+        outdated: &OutputVec,
     ) -> Result<()> {
         let grouped_tool_versions: GroupedToolVersions = outdated
-            .into_iter()
+            .iter()
             .group_by(|(t, _, _)| t.clone())
             .into_iter()
-            .map(|(t, tvs)| (t, tvs.map(|(_, tv, latest)| (tv, latest)).collect()))
+            .map(|(t, tvs)| (t, tvs.map(|(_, tv, _)| tv.clone()).collect()))
             .collect();
         grouped_tool_versions
             .into_par_iter()
             .map(|(tool, versions)| {
-                for (tv, latest) in versions {
+                for tv in versions {
                     let mut pr = mpr.add();
-                    self.install_new_version(config, &tool, &tv, latest, &mut pr)?;
                     self.uninstall_old_version(config, &tool, &tv, &mut pr)?;
                 }
                 Ok(())
@@ -97,24 +139,47 @@ impl Upgrade {
         Ok(())
     }
 
-    fn install_new_version(
-        &self,
-        config: &Config,
-        tool: &Tool,
-        tv: &ToolVersion,
-        latest: String,
-        pr: &mut ProgressReport,
-    ) -> Result<()> {
-        let mut tv = tv.clone();
-        tv.version = latest;
-        tool.decorate_progress_bar(pr, Some(&tv));
-        match tool.install_version(config, &tv, pr, false) {
-            Ok(_) => Ok(()),
-            Err(err) => {
-                pr.error(err.to_string());
-                Err(err.wrap_err(format!("failed to install {}", tv)))
+    /// rewrites the versions in config files (and their .rtx.lock) which resolved to an
+    /// upgraded version
+    fn bump_config_files(&self, config: &mut Config, outdated: &OutputVec) -> Result<()> {
+        let ts = ToolsetBuilder::new().build(config)?;
+        let mut config_files: IndexMap<PathBuf, Box<dyn ConfigFile>> = IndexMap::new();
+        for (tool, old, latest) in outdated {
+            let Some(tvl) = ts.versions.get(&tool.name) else {
+                continue;
+            };
+            let path = match &tvl.source {
+                ToolSource::RtxToml(path) | ToolSource::ToolVersions(path) => path,
+                _ => continue,
+            };
+            for tv in tvl.versions.iter().filter(|tv| tv.version == old.version) {
+                let request = tv.request.version();
+                let Some(bumped) = bump_request(&request, latest) else {
+                    // still matches, but .rtx.lock would keep it on the old version
+                    lockfile::upgrade(path, &tool.name, &request, &request, latest)?;
+                    continue;
+                };
+                lockfile::upgrade(path, &tool.name, &request, &bumped, latest)?;
+                let cf = match config_files.entry(path.clone()) {
+                    Entry::Occupied(e) => e.into_mut(),
+                    Entry::Vacant(e) => {
+                        let is_trusted = config_file::is_trusted(&config.settings, path);
+                        e.insert(config_file::parse(path, is_trusted)?)
+                    }
+                };
+                if cf.upgrade_version(&tool.name, &request, &bumped) {
+                    info!(
+                        "{}: {} {request} -> {bumped}",
+                        display_path(path),
+                        tool.name
+                    );
+                }
             }
         }
+        for cf in config_files.values() {
+            cf.save()?;
+        }
+        Ok(())
     }
 
     fn uninstall_old_version(
@@ -137,3 +202,88 @@ impl Upgrade {
         }
     }
 }
+
+/// drops upgrades which would move the request, or a config file request which resolved to the
+/// same version, to a new major version, e.g.: "18" to 20.3.0
+fn skip_major_upgrades(config: &mut Config, outdated: OutputVec) -> Result<OutputVec> {
+    let ts = ToolsetBuilder::new().build(config)?;
+    let outdated = outdated
+        .into_iter()
+        .filter(|(tool, tv, latest)| {
+            let config_requests = ts
+                .versions
+                .get(&tool.name)
+                .into_iter()
+                .flat_map(|tvl| &tvl.versions)
+                .filter(|ctv| ctv.version == tv.version)
+                .map(|ctv| ctv.request.version());
+            let crossing = std::iter::once(tv.request.version())
+                .chain(config_requests)
+                .find(|request| crosses_major(request, latest));
+            match crossing {
+                Some(request) => {
+                    info!(
+                        "{} {request}: {latest} is a new major version, use --bump to upgrade to it",
+                        tool.name
+                    );
+                    false
+                }
+                None => true,
+            }
+        })
+        .collect();
+    Ok(outdated)
+}
+
+/// true if `version` has a different major version than the version number `request`
+fn crosses_major(request: &str, version: &str) -> bool {
+    request.starts_with(|c: char| c.is_ascii_digit())
+        && request.split('.').next() != version.split('.').next()
+}
+
+/// the version to write in place of `request` after upgrading to `version`, keeps the precision
+/// of the request, e.g.: "20.1" becomes "20.3" for 20.3.0
+/// None if the request still matches `version` or is not a version number (aliases, refs, etc.)
+fn bump_request(request: &str, version: &str) -> Option<String> {
+    if request == version
+        || version.starts_with(&format!("{request}."))
+        || !request.starts_with(|c: char| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let precision = request.split('.').count();
+    Some(version.split('.').take(precision).join("."))
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  $ <bold>rtx upgrade</bold>
+  $ <bold>rtx upgrade node@20 --keep-old</bold>
+  $ <bold>rtx upgrade --bump node@20</bold>  # also rewrites node "18" to "20"
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bump_request() {
+        assert_eq!(bump_request("20", "20.3.0"), None);
+        assert_eq!(bump_request("20.3.0", "20.3.0"), None);
+        assert_eq!(bump_request("lts", "20.3.0"), None);
+        assert_eq!(bump_request("20.1.0", "20.3.0"), Some("20.3.0".into()));
+        assert_eq!(bump_request("20.1", "20.3.0"), Some("20.3".into()));
+        assert_eq!(bump_request("18", "20.3.0"), Some("20".into()));
+    }
+
+    #[test]
+    fn test_crosses_major() {
+        assert!(crosses_major("18", "20.3.0"));
+        assert!(crosses_major("18.1.0", "20.3.0"));
+        assert!(!crosses_major("20", "20.3.0"));
+        assert!(!crosses_major("20.1", "20.3.0"));
+        assert!(!crosses_major("lts", "20.3.0"));
+        assert!(!crosses_major("20", "20"));
+    }
+}
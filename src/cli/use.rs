@@ -24,30 +24,30 @@ pub struct Use {
     /// e.g.: node@20
     /// If no version is specified, it will default to @latest
     #[clap(value_name="TOOL@VERSION", value_parser = ToolArgParser, verbatim_doc_comment, required_unless_present = "remove")]
-    tool: Vec<ToolArg>,
+    pub tool: Vec<ToolArg>,
 
     /// Save exact version to config file
     /// e.g.: `rtx use --pin node@20` will save `node 20.0.0` to ~/.tool-versions
     #[clap(long, verbatim_doc_comment, overrides_with = "fuzzy")]
-    pin: bool,
+    pub pin: bool,
 
     /// Save fuzzy version to config file
     /// e.g.: `rtx use --fuzzy node@20` will save `node 20` to ~/.tool-versions
     /// this is the default behavior unless RTX_ASDF_COMPAT=1
     #[clap(long, verbatim_doc_comment, overrides_with = "pin")]
-    fuzzy: bool,
+    pub fuzzy: bool,
 
     /// Remove the tool(s) from config file
     #[clap(long, value_name = "TOOL", aliases = ["rm", "unset"])]
-    remove: Option<Vec<PluginName>>,
+    pub remove: Option<Vec<PluginName>>,
 
     /// Use the global config file (~/.config/rtx/config.toml) instead of the local one
     #[clap(short, long, overrides_with = "path")]
-    global: bool,
+    pub global: bool,
 
     /// Specify a path to a config file or directory
     #[clap(short, long, overrides_with = "global", value_hint = clap::ValueHint::FilePath)]
-    path: Option<PathBuf>,
+    pub path: Option<PathBuf>,
 }
 
 impl Command for Use {
@@ -0,0 +1,120 @@
+use std::process::exit;
+use std::time::Duration;
+
+use color_eyre::eyre::{bail, Result};
+
+use crate::cli::command::Command;
+use crate::cli::run::run_tasks;
+use crate::config::Config;
+use crate::env;
+use crate::file::display_path;
+use crate::output::Output;
+use crate::task;
+use crate::toolset::ToolsetBuilder;
+use crate::watch::{self, Sources};
+
+/// Run a task each time its sources change
+///
+/// Watches the files matching `sources` of the task and of its dependencies and runs
+/// the task like `rtx run` when they change. The task runs once when starting.
+/// Globs are relative to the directory of the config file which defines the task,
+/// `*` does not match `/` while `**` matches any number of directories:
+///
+///     [tasks.build]
+///     run = "tsc"
+///     sources = ["src/**/*.ts", "tsconfig.json"]
+///
+/// A failing task does not stop watching. Press Ctrl-C to stop.
+#[derive(Debug, clap::Args)]
+#[clap(visible_alias = "w", verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub struct Watch {
+    /// Task to run, see `rtx tasks ls`
+    #[clap()]
+    task: String,
+
+    /// Arguments to pass to the task's script
+    #[clap(allow_hyphen_values = true, trailing_var_arg = true)]
+    args: Vec<String>,
+
+    /// How long to wait for more changes before running the task
+    /// [default: RTX_WATCH_DEBOUNCE or 200ms]
+    #[clap(long, value_parser = humantime::parse_duration, value_name = "DURATION", verbatim_doc_comment)]
+    debounce: Option<Duration>,
+}
+
+impl Command for Watch {
+    fn run(self, mut config: Config, out: &mut Output) -> Result<()> {
+        watch_task(&mut config, &self.task, &self.args, self.debounce, out)
+    }
+}
+
+/// runs the task `name` like `rtx run` and again each time the sources of it or of its
+/// dependencies change, until the task is stopped with Ctrl-C
+pub fn watch_task(
+    config: &mut Config,
+    name: &str,
+    args: &[String],
+    debounce: Option<Duration>,
+    out: &mut Output,
+) -> Result<()> {
+    let tasks = task::load(config);
+    let order = task::run_order(&tasks, name)?;
+    let sources = Sources::new(
+        order
+            .iter()
+            .flat_map(|t| t.sources.iter().map(move |s| (t.dir(), s.as_str()))),
+    )?;
+    if sources.is_empty() {
+        bail!(
+            r#"task {name} has no sources to watch, add them to .rtx.toml, e.g.: [tasks.{name}] sources = ["src/**/*"]"#,
+        );
+    }
+    let ts = ToolsetBuilder::new().with_install_missing().build(config)?;
+    let env = ts.full_env(config);
+    let debounce = debounce.unwrap_or(*env::RTX_WATCH_DEBOUNCE);
+
+    let run = |out: &mut Output| -> Result<()> {
+        let code = run_tasks(&order, &env, name, args, out)?;
+        // the task was stopped with Ctrl-C, which is how watching is stopped too
+        if code == 130 {
+            exit(code);
+        }
+        Ok(())
+    };
+    run(out)?;
+    watch::watch(&sources, debounce, |changed| {
+        if let Some(path) = changed.first() {
+            let more = match changed.len() {
+                1 => String::new(),
+                n => format!(" and {} more", n - 1),
+            };
+            rtxstatusln!(out, "{} changed{more}", display_path(path));
+        }
+        run(out)
+    })
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+  $ <bold>rtx watch build</bold>
+  rtx [build] tsc
+  rtx ~/src/myproj/src/index.ts changed
+  rtx [build] tsc
+
+  $ <bold>RTX_WATCH_DEBOUNCE=1s rtx watch test -- --bail</bold>
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_cli_err;
+
+    #[test]
+    fn test_watch_missing_task() {
+        let err = assert_cli_err!("watch", "nope");
+        assert_eq!(
+            err.to_string(),
+            "task nope is not defined, see `rtx tasks ls`"
+        );
+    }
+}
@@ -5,11 +5,11 @@ use crate::cli::command::Command;
 use crate::config::Config;
 use crate::errors::Error::{PluginNotInstalled, VersionNotInstalled};
 use crate::output::Output;
-use crate::toolset::ToolsetBuilder;
+use crate::toolset::{ToolVersion, ToolsetBuilder};
 
 /// Display the installation path for a runtime
 ///
-/// Must be installed.
+/// Must be installed unless --resolve is used.
 #[derive(Debug, clap::Args)]
 #[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
 pub struct Where {
@@ -26,6 +26,16 @@ pub struct Where {
     /// used for asdf compatibility
     #[clap(hide = true, verbatim_doc_comment)]
     asdf_version: Option<String>,
+
+    /// Template for the output
+    /// e.g.: "{install_path}/bin"
+    /// Available fields: {plugin}, {version}, {install_path}, {cache_path}, {download_path}
+    #[clap(long, verbatim_doc_comment)]
+    format: Option<String>,
+
+    /// Print the path even if the version is not installed
+    #[clap(long)]
+    resolve: bool,
 }
 
 impl Command for Where {
@@ -58,8 +68,12 @@ impl Command for Where {
             .as_ref()
             .map(|tvr| tvr.resolve(&config, plugin, Default::default(), false))
         {
-            Some(Ok(tv)) if plugin.is_version_installed(&tv) => {
-                rtxprintln!(out, "{}", tv.install_path().to_string_lossy());
+            Some(Ok(tv)) if self.resolve || plugin.is_version_installed(&tv) => {
+                let output = match &self.format {
+                    Some(format) => render_format(format, &tv),
+                    None => tv.install_path().to_string_lossy().to_string(),
+                };
+                rtxprintln!(out, "{}", output);
                 Ok(())
             }
             _ => Err(VersionNotInstalled(
@@ -70,6 +84,15 @@ impl Command for Where {
     }
 }
 
+fn render_format(format: &str, tv: &ToolVersion) -> String {
+    format
+        .replace("{plugin}", &tv.plugin_name)
+        .replace("{version}", &tv.version)
+        .replace("{install_path}", &tv.install_path().to_string_lossy())
+        .replace("{cache_path}", &tv.cache_path().to_string_lossy())
+        .replace("{download_path}", &tv.download_path().to_string_lossy())
+}
+
 static AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
   # Show the latest installed version of node
@@ -81,6 +104,10 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
   # Errors if node is not referenced in any .tool-version file
   $ <bold>rtx where node</bold>
   /home/jdx/.local/share/rtx/installs/node/20.0.0
+
+  # Show the bin directory of a version that may not be installed yet
+  $ <bold>rtx where node@20.0.0 --resolve --format "{install_path}/bin"</bold>
+  /home/jdx/.local/share/rtx/installs/node/20.0.0/bin
 "#
 );
 
@@ -120,6 +147,33 @@ mod tests {
         assert_cli!("uninstall", "tiny@my/alias");
     }
 
+    #[test]
+    fn test_where_format() {
+        assert_cli!("install", "tiny@3.1.0");
+        let stdout = assert_cli!(
+            "where",
+            "tiny@3.1.0",
+            "--format",
+            "{plugin}-{version}:{install_path}/bin"
+        );
+        assert_str_eq!(
+            stdout.trim(),
+            format!(
+                "tiny-3.1.0:{}/bin",
+                dirs::ROOT.join("installs/tiny/3.1.0").to_string_lossy()
+            )
+        );
+    }
+
+    #[test]
+    fn test_where_resolve() {
+        let stdout = assert_cli!("where", "tiny@1111", "--resolve");
+        assert_str_eq!(
+            stdout.trim(),
+            dirs::ROOT.join("installs/tiny/1111").to_string_lossy()
+        );
+    }
+
     #[test]
     fn test_where_not_found() {
         let err = assert_cli_err!("where", "tiny@1111");
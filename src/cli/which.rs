@@ -5,6 +5,7 @@ use crate::cli::command::Command;
 use crate::config::Config;
 use crate::output::Output;
 use crate::toolset::{Toolset, ToolsetBuilder};
+use crate::{bin_providers, dirs, file};
 
 /// Shows the path that a bin name points to
 #[derive(Debug, clap::Args)]
@@ -22,6 +23,15 @@ pub struct Which {
     #[clap(long, conflicts_with = "plugin")]
     pub version: bool,
 
+    /// Show every matching executable on PATH in order, rtx-managed or not
+    /// the one marked with "*" is the one that actually executes
+    #[clap(long, short, conflicts_with_all = ["plugin", "version"], verbatim_doc_comment)]
+    pub all: bool,
+
+    /// Show the plugins known to provide this bin, even if none are installed
+    #[clap(long, conflicts_with_all = ["plugin", "version", "all"])]
+    pub provider: bool,
+
     /// Use a specific tool@version
     /// e.g.: `rtx which npm --tool=node@20`
     #[clap(short, long, value_name = "TOOL@VERSION", value_parser=ToolArgParser, verbatim_doc_comment)]
@@ -30,7 +40,13 @@ pub struct Which {
 
 impl Command for Which {
     fn run(self, mut config: Config, out: &mut Output) -> Result<()> {
+        if self.provider {
+            return self.show_providers(out);
+        }
         let ts = self.get_toolset(&mut config)?;
+        if self.all {
+            return self.show_all(&config, &ts, out);
+        }
 
         match ts.which(&config, &self.bin_name) {
             Some((p, tv)) => {
@@ -44,7 +60,10 @@ impl Command for Which {
                 }
                 Ok(())
             }
-            None => Err(eyre!("{} not found", self.bin_name)),
+            None => match bin_providers::hint(&self.bin_name) {
+                Some(hint) => Err(eyre!("{} not found\n{}", self.bin_name, hint)),
+                None => Err(eyre!("{} not found", self.bin_name)),
+            },
         }
     }
 }
@@ -58,6 +77,43 @@ impl Which {
         let ts = tsb.build(config)?;
         Ok(ts)
     }
+
+    fn show_providers(&self, out: &mut Output) -> Result<()> {
+        let providers = bin_providers::providers(&self.bin_name);
+        if providers.is_empty() {
+            return Err(eyre!("no known provider for {}", self.bin_name));
+        }
+        for p in providers {
+            rtxprintln!(out, "{}", p);
+        }
+        Ok(())
+    }
+
+    fn show_all(&self, config: &Config, ts: &Toolset, out: &mut Output) -> Result<()> {
+        let rtx_bin = match ts.which(config, &self.bin_name) {
+            Some((p, tv)) => p.which(config, &tv, &self.bin_name)?.map(|path| (path, tv)),
+            None => None,
+        };
+        let on_path = file::which_all(&self.bin_name);
+        if on_path.is_empty() && rtx_bin.is_none() {
+            return Err(eyre!("{} not found", self.bin_name));
+        }
+        for (i, path) in on_path.iter().enumerate() {
+            let marker = if i == 0 { "*" } else { " " };
+            let note = match &rtx_bin {
+                _ if path.starts_with(&*dirs::SHIMS) => " (rtx shim)".to_string(),
+                Some((bin, tv)) if bin == path => format!(" (rtx {})", tv),
+                _ => "".to_string(),
+            };
+            rtxprintln!(out, "{} {}{}", marker, path.display(), note);
+        }
+        if let Some((bin, tv)) = &rtx_bin {
+            if !on_path.contains(bin) {
+                rtxprintln!(out, "  {} (rtx {}, not on PATH)", bin.display(), tv);
+            }
+        }
+        Ok(())
+    }
 }
 
 static AFTER_LONG_HELP: &str = color_print::cstr!(
@@ -68,6 +124,12 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
   node
   $ <bold>rtx which node --version</bold>
   20.0.0
+  $ <bold>rtx which npx --provider</bold>
+  node
+  $ <bold>rtx which node --all</bold>
+  * /home/username/.local/share/rtx/shims/node (rtx shim)
+    /usr/local/bin/node
+    /home/username/.local/share/rtx/installs/node/20.0.0/bin/node (rtx node@20.0.0, not on PATH)
 "#
 );
 
@@ -99,6 +161,20 @@ mod tests {
         assert_cli!("uninstall", "dummy@1.0.0");
     }
 
+    #[test]
+    fn test_which_all() {
+        assert_cli!("install", "dummy@1.0.1");
+        let output = assert_cli!("which", "dummy", "--all", "--tool=dummy@1.0.1");
+        assert!(output.contains("installs/dummy/1.0.1/bin/dummy (rtx dummy@1.0.1"));
+    }
+
+    #[test]
+    fn test_which_provider() {
+        assert_cli!("install", "dummy@1.0.1");
+        assert_cli!("reshim");
+        assert_cli_snapshot!("which", "dummy", "--provider");
+    }
+
     #[test]
     fn test_which_tool() {
         assert_cli!("install", "dummy@1.0.1");
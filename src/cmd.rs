@@ -1,7 +1,10 @@
 use color_eyre::Result;
 use std::ffi::{OsStr, OsString};
 use std::io::{BufRead, BufReader, Write};
+use std::os::unix::process::ExitStatusExt;
+use std::path::Path;
 use std::process::{Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::channel;
 use std::thread;
 
@@ -82,6 +85,33 @@ where
     duct::cmd(program, args)
 }
 
+static FOREGROUND_CHILD: AtomicBool = AtomicBool::new(false);
+
+/// true while a command started with [`run_foreground`] owns the terminal
+pub fn is_foreground_child_running() -> bool {
+    FOREGROUND_CHILD.load(Ordering::Relaxed)
+}
+
+/// Runs a command attached to the terminal and returns the exit code rtx should exit with.
+///
+/// The terminal sends ctrl-c/ctrl-z to the whole foreground process group so the child
+/// already receives them, rtx only has to stay alive until the child exits.
+pub fn run_foreground(cmd: Expression) -> Result<i32> {
+    FOREGROUND_CHILD.store(true, Ordering::Relaxed);
+    let res = cmd.unchecked().run();
+    FOREGROUND_CHILD.store(false, Ordering::Relaxed);
+    Ok(exit_code(res?.status))
+}
+
+/// like a shell, a child killed by a signal exits with 128+signal
+pub fn exit_code(status: ExitStatus) -> i32 {
+    match (status.code(), status.signal()) {
+        (Some(code), _) => code,
+        (None, Some(signal)) => 128 + signal,
+        (None, None) => 1,
+    }
+}
+
 pub struct CmdLineRunner<'a> {
     cmd: Command,
     settings: &'a Settings,
@@ -126,6 +156,11 @@ impl<'a> CmdLineRunner<'a> {
         self
     }
 
+    pub fn current_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.cmd.current_dir(dir);
+        self
+    }
+
     pub fn arg<S: AsRef<OsStr>>(mut self, arg: S) -> Self {
         self.cmd.arg(arg.as_ref());
         self
@@ -293,4 +328,12 @@ mod tests {
         let output = cmd!("echo", "foo", "bar").read().unwrap();
         assert_eq!("foo bar", output);
     }
+
+    #[test]
+    fn test_run_foreground_signal_exit_code() {
+        let code = cmd::run_foreground(cmd!("sh", "-c", "kill -TERM $$")).unwrap();
+        assert_eq!(code, 128 + 15);
+        let code = cmd::run_foreground(cmd!("sh", "-c", "exit 3")).unwrap();
+        assert_eq!(code, 3);
+    }
 }
@@ -66,6 +66,10 @@ impl ConfigFile for LegacyVersionFile {
         unimplemented!()
     }
 
+    fn upgrade_version(&mut self, _plugin_name: &PluginName, _old: &str, _new: &str) -> bool {
+        unimplemented!()
+    }
+
     fn save(&self) -> Result<()> {
         unimplemented!()
     }
@@ -15,6 +15,7 @@ use crate::file::{display_path, replace_path};
 use crate::hash::hash_to_str;
 use crate::output::Output;
 use crate::plugins::PluginName;
+use crate::task::Task;
 use crate::toolset::{ToolVersion, ToolVersionList, Toolset};
 use crate::ui::multi_progress_report::MultiProgressReport;
 use crate::{dirs, env, file};
@@ -41,12 +42,18 @@ pub trait ConfigFile: Debug + Display + Send + Sync {
     fn path_dirs(&self) -> Vec<PathBuf>;
     fn remove_plugin(&mut self, plugin_name: &PluginName);
     fn replace_versions(&mut self, plugin_name: &PluginName, versions: &[String]);
+    /// replaces a single version of a tool, keeping the other versions and its options
+    /// returns false if `old` is not in the file
+    fn upgrade_version(&mut self, plugin_name: &PluginName, old: &str, new: &str) -> bool;
     fn save(&self) -> Result<()>;
     fn dump(&self) -> String;
     fn to_toolset(&self) -> &Toolset;
     fn settings(&self) -> SettingsBuilder;
     fn aliases(&self) -> AliasMap;
     fn watch_files(&self) -> Vec<PathBuf>;
+    fn tasks(&self) -> Vec<Task> {
+        vec![]
+    }
 
     fn is_global(&self) -> bool {
         false
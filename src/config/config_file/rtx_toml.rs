@@ -15,7 +15,9 @@ use crate::config::settings::SettingsBuilder;
 use crate::config::{config_file, global_config_files, AliasMap, MissingRuntimeBehavior};
 use crate::errors::Error::UntrustedConfig;
 use crate::file::create_dir_all;
+use crate::plugins::core::{CORE_PLUGINS, EXPERIMENTAL_CORE_PLUGINS};
 use crate::plugins::{unalias_plugin, PluginName};
+use crate::task::Task;
 use crate::tera::{get_tera, BASE_CONTEXT};
 use crate::toolset::{
     ToolSource, ToolVersionList, ToolVersionOptions, ToolVersionRequest, Toolset,
@@ -36,6 +38,7 @@ pub struct RtxToml {
     alias: AliasMap,
     doc: Document,
     plugins: HashMap<String, String>,
+    tasks: Vec<Task>,
     is_trusted: bool,
 }
 
@@ -75,6 +78,7 @@ impl RtxToml {
                 "tools" => self.toolset = self.parse_toolset(k, v)?,
                 "settings" => self.settings = self.parse_settings(k, v)?,
                 "plugins" => self.plugins = self.parse_plugins(k, v)?,
+                "tasks" => self.tasks = self.parse_tasks(k, v)?,
                 _ => Err(eyre!("unknown key: {}", k))?,
             }
         }
@@ -202,6 +206,52 @@ impl RtxToml {
         self.parse_hashmap(key, v)
     }
 
+    /// `name = "script"` or `[tasks.name]` with run, description, depends and sources
+    fn parse_tasks(&mut self, key: &str, v: &Item) -> Result<Vec<Task>> {
+        self.trust_check()?;
+        let mut tasks = vec![];
+        match v.as_table_like() {
+            Some(table) => {
+                for (name, v) in table.iter() {
+                    let key = format!("{key}.{name}");
+                    let mut task = Task {
+                        name: name.to_string(),
+                        config_path: self.path.clone(),
+                        ..Default::default()
+                    };
+                    if let Some(run) = v.as_str() {
+                        task.run = run.to_string();
+                    } else if let Some(table) = v.as_table_like() {
+                        for (k, v) in table.iter() {
+                            let key = format!("{key}.{k}");
+                            match (k, v.as_str()) {
+                                ("run", Some(run)) => task.run = run.to_string(),
+                                ("description", Some(d)) => task.description = Some(d.to_string()),
+                                ("depends", _) => {
+                                    task.depends = self.parse_string_array(&key, v)?
+                                }
+                                ("sources", _) => {
+                                    task.sources = self.parse_string_array(&key, v)?
+                                }
+                                ("run" | "description", None) => parse_error!(key, v, "string")?,
+                                _ => parse_error!(
+                                    key,
+                                    v,
+                                    "one of: run, description, depends, sources"
+                                )?,
+                            }
+                        }
+                    } else {
+                        parse_error!(key, v, "string or table")?;
+                    }
+                    tasks.push(task);
+                }
+            }
+            _ => parse_error!(key, v, "table")?,
+        }
+        Ok(tasks)
+    }
+
     fn parse_hashmap(&mut self, key: &str, v: &Item) -> Result<HashMap<String, String>> {
         match v.as_table_like() {
             Some(table) => {
@@ -420,6 +470,66 @@ impl RtxToml {
                         "log_level" => settings.log_level = Some(self.parse_log_level(&k, v)?),
                         "raw" => settings.raw = Some(self.parse_bool(&k, v)?),
                         "yes" => settings.yes = Some(self.parse_bool(&k, v)?),
+                        "activate_install_missing" => {
+                            self.process_setting_check()?;
+                            settings.activate_install_missing =
+                                Some(self.parse_string(&k, v)?.parse()?)
+                        }
+                        "prerelease_regex" => {
+                            settings.prerelease_regex = Some(self.parse_string(&k, v)?)
+                        }
+                        "eol_error" => settings.eol_error = Some(self.parse_bool(&k, v)?),
+                        "env_clear" => settings.env_clear = Some(self.parse_bool(&k, v)?),
+                        "exec_strict" => settings.exec_strict = Some(self.parse_bool(&k, v)?),
+                        "plugin_env_passthrough" => {
+                            self.process_setting_check()?;
+                            settings.plugin_env_passthrough =
+                                self.parse_string_array(&k, v)?.into_iter().collect()
+                        }
+                        "activate_status" => {
+                            settings.activate_status = Some(self.parse_string(&k, v)?.parse()?)
+                        }
+                        "shim_bypass" => {
+                            settings.shim_bypass =
+                                self.parse_string_array(&k, v)?.into_iter().collect()
+                        }
+                        "plugin_aliases" => {
+                            settings.plugin_aliases =
+                                self.parse_hashmap(&k, v)?.into_iter().collect()
+                        }
+                        "list_all_urls" => {
+                            self.process_setting_check()?;
+                            let urls = self.parse_hashmap(&k, v)?;
+                            warn_core_plugins(&k, urls.keys());
+                            settings.list_all_urls = urls.into_iter().collect()
+                        }
+                        "list_all_regexes" => {
+                            self.process_setting_check()?;
+                            let regexes = self.parse_hashmap(&k, v)?;
+                            warn_core_plugins(&k, regexes.keys());
+                            settings.list_all_regexes = regexes.into_iter().collect()
+                        }
+                        "hooks" => {
+                            self.process_setting_check()?;
+                            settings.hooks = self.parse_hashmap(&k, v)?.into_iter().collect()
+                        }
+                        "target_os" => settings.target_os = Some(self.parse_string(&k, v)?),
+                        "target_arch" => settings.target_arch = Some(self.parse_string(&k, v)?),
+                        "always_keep_download_tools" => {
+                            settings.always_keep_download_tools =
+                                self.parse_string_array(&k, v)?.into_iter().collect()
+                        }
+                        "never_keep_download_tools" => {
+                            settings.never_keep_download_tools =
+                                self.parse_string_array(&k, v)?.into_iter().collect()
+                        }
+                        "verify_installs" => {
+                            self.process_setting_check()?;
+                            settings.verify_installs = Some(self.parse_string(&k, v)?.parse()?)
+                        }
+                        "confirm_policy" => {
+                            settings.confirm_policy = Some(self.parse_string(&k, v)?.parse()?)
+                        }
                         _ => Err(eyre!("Unknown config setting: {}", k))?,
                     };
                 }
@@ -447,6 +557,17 @@ impl RtxToml {
             .insert(from, value(to));
     }
 
+    /// sets the repo url of a plugin in [plugins], "url#ref" pins it at the ref
+    pub fn set_plugin(&mut self, name: &str, url: &str) {
+        self.plugins.insert(name.into(), url.into());
+        self.doc
+            .entry("plugins")
+            .or_insert_with(table)
+            .as_table_like_mut()
+            .unwrap()
+            .insert(name, value(url));
+    }
+
     pub fn remove_alias(&mut self, plugin: &str, from: &str) {
         if let Some(aliases) = self.doc.get_mut("alias").and_then(|v| v.as_table_mut()) {
             if let Some(plugin_aliases) = aliases.get_mut(plugin).and_then(|v| v.as_table_mut()) {
@@ -636,6 +757,16 @@ impl RtxToml {
         Ok(output)
     }
 
+    /// settings which make rtx start processes on its own, e.g.: when entering a directory, or
+    /// which change what it checks, downloads or hands to plugins, are only read from the global config and
+    /// trusted config files, like [env]
+    fn process_setting_check(&mut self) -> Result<()> {
+        if self.is_global() {
+            return Ok(());
+        }
+        self.trust_check()
+    }
+
     fn trust_check(&mut self) -> Result<()> {
         let default_cmd = String::new();
         let cmd = env::ARGS.get(1).unwrap_or(&default_cmd).as_str();
@@ -730,6 +861,31 @@ impl ConfigFile for RtxToml {
         }
     }
 
+    fn upgrade_version(&mut self, plugin_name: &PluginName, old: &str, new: &str) -> bool {
+        if let Some(tvl) = self.toolset.versions.get_mut(plugin_name) {
+            for (tvr, _) in tvl
+                .requests
+                .iter_mut()
+                .filter(|(tvr, _)| tvr.version() == old)
+            {
+                *tvr = ToolVersionRequest::new(plugin_name.clone(), new);
+            }
+        }
+        let Some(tools) = self
+            .doc
+            .get_mut("tools")
+            .and_then(|t| t.as_table_like_mut())
+        else {
+            return false;
+        };
+        tools
+            .iter_mut()
+            .filter(|(k, _)| unalias_plugin(k.get()) == plugin_name)
+            .fold(false, |upgraded, (_, v)| {
+                upgrade_version_item(v, old, new) || upgraded
+            })
+    }
+
     fn save(&self) -> Result<()> {
         let contents = self.dump();
         if let Some(parent) = self.path.parent() {
@@ -761,11 +917,62 @@ impl ConfigFile for RtxToml {
         }
     }
 
+    fn tasks(&self) -> Vec<Task> {
+        self.tasks.clone()
+    }
+
     fn is_global(&self) -> bool {
         global_config_files().iter().any(|p| p == &self.path)
     }
 }
 
+/// core plugins list their versions themselves, only asdf plugins use list_all_urls/regexes
+fn warn_core_plugins<'a>(key: &str, plugins: impl Iterator<Item = &'a String>) {
+    for plugin in plugins {
+        if CORE_PLUGINS.contains_key(plugin) || EXPERIMENTAL_CORE_PLUGINS.contains_key(plugin) {
+            warn!("{key}: {plugin} is a core plugin, it is ignored");
+        }
+    }
+}
+
+/// replaces `old` in a tool's version(s), keeping options, formatting and comments
+fn upgrade_version_item(item: &mut Item, old: &str, new: &str) -> bool {
+    match item {
+        Item::Value(v) => upgrade_version_value(v, old, new),
+        Item::Table(t) => t
+            .get_mut("version")
+            .map_or(false, |v| upgrade_version_item(v, old, new)),
+        // not `any`, every matching entry is replaced
+        Item::ArrayOfTables(tables) => tables
+            .iter_mut()
+            .map(|t| {
+                t.get_mut("version")
+                    .map_or(false, |v| upgrade_version_item(v, old, new))
+            })
+            .fold(false, |a, b| a | b),
+        Item::None => false,
+    }
+}
+
+fn upgrade_version_value(v: &mut Value, old: &str, new: &str) -> bool {
+    match v {
+        Value::String(s) if s.value() == old => {
+            let decor = s.decor().clone();
+            *v = Value::from(new);
+            *v.decor_mut() = decor;
+            true
+        }
+        Value::Array(arr) => arr
+            .iter_mut()
+            .map(|v| upgrade_version_value(v, old, new))
+            .fold(false, |a, b| a | b),
+        Value::InlineTable(t) => t
+            .get_mut("version")
+            .map_or(false, |v| upgrade_version_value(v, old, new)),
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use indoc::formatdoc;
@@ -807,6 +1014,48 @@ mod tests {
         assert_display_snapshot!(cf);
     }
 
+    #[test]
+    fn test_tasks() {
+        let mut cf = RtxToml::init(PathBuf::from("/tmp/.rtx.toml").as_path(), true);
+        cf.parse(&formatdoc! {r#"
+        [tasks]
+        lint = "cargo clippy"
+        [tasks.test]
+        description = "Run the tests"
+        run = "cargo test"
+        depends = ["lint"]
+        sources = ["src/**/*.rs"]
+        "#})
+            .unwrap();
+
+        assert_debug_snapshot!(cf.tasks(), @r###"
+        [
+            Task {
+                name: "lint",
+                description: None,
+                run: "cargo clippy",
+                depends: [],
+                sources: [],
+                config_path: "/tmp/.rtx.toml",
+            },
+            Task {
+                name: "test",
+                description: Some(
+                    "Run the tests",
+                ),
+                run: "cargo test",
+                depends: [
+                    "lint",
+                ],
+                sources: [
+                    "src/**/*.rs",
+                ],
+                config_path: "/tmp/.rtx.toml",
+            },
+        ]
+        "###);
+    }
+
     #[test]
     fn test_path_dirs() {
         let p = dirs::HOME.join("fixtures/.rtx.toml");
@@ -881,6 +1130,26 @@ mod tests {
         assert_display_snapshot!(cf);
     }
 
+    #[test]
+    fn test_upgrade_version() {
+        let mut cf = RtxToml::init(PathBuf::from("/tmp/.rtx.toml").as_path(), true);
+        cf.parse(&formatdoc! {r#"
+        [tools]
+        node = ["16.0.0", "18.0.0"] # lts versions
+        python = {{version = "3.11.0", virtualenv = ".venv"}}
+        "#})
+            .unwrap();
+        assert!(cf.upgrade_version(&PluginName::from("node"), "18.0.0", "18.1.0"));
+        assert!(cf.upgrade_version(&PluginName::from("python"), "3.11.0", "3.11.5"));
+        assert!(!cf.upgrade_version(&PluginName::from("python"), "3.10.0", "3.10.1"));
+
+        assert_snapshot!(cf.dump(), @r###"
+        [tools]
+        node = ["16.0.0", "18.1.0"] # lts versions
+        python = {version = "3.11.5", virtualenv = ".venv"}
+        "###);
+    }
+
     #[test]
     fn test_remove_plugin() {
         let mut cf = RtxToml::init(PathBuf::from("/tmp/.rtx.toml").as_path(), true);
@@ -15,11 +15,12 @@ use crate::file;
 use crate::file::display_path;
 use crate::plugins::{unalias_plugin, PluginName};
 use crate::tera::{get_tera, BASE_CONTEXT};
-use crate::toolset::{ToolSource, ToolVersionRequest, Toolset};
+use crate::toolset::{ToolSource, ToolVersionOptions, ToolVersionRequest, Toolset};
 
 // python 3.11.0 3.10.0
 // shellcheck 0.9.0
 // shfmt 3.6.0
+// nodejs 20.11.1 # rtx: pin
 
 /// represents asdf's .tool-versions file
 #[derive(Debug, Default)]
@@ -129,14 +130,46 @@ impl ToolVersions {
 
     fn populate_toolset(&mut self) {
         for (plugin, tvp) in &self.plugins {
+            let mut opts = ToolVersionOptions::new();
+            let mut ignore = false;
+            for directive in parse_directives(&tvp.post) {
+                match directive {
+                    // never resolve this line to a newer version in `rtx upgrade`/`rtx outdated`
+                    "pin" => {
+                        opts.insert("pin".into(), "true".into());
+                    }
+                    // leave this line to asdf
+                    "ignore" => ignore = true,
+                    _ => warn!(
+                        "unknown directive in {}: rtx: {}",
+                        display_path(&self.path),
+                        directive
+                    ),
+                }
+            }
+            if ignore {
+                continue;
+            }
             for version in &tvp.versions {
                 let tvr = ToolVersionRequest::new(plugin.clone(), version);
-                self.toolset.add_version(tvr, Default::default())
+                self.toolset.add_version(tvr, opts.clone())
             }
         }
     }
 }
 
+/// directives in a trailing "# rtx: pin, ignore" comment
+fn parse_directives(post: &str) -> Vec<&str> {
+    match post.split_once("rtx:") {
+        Some((_, directives)) => directives
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .take_while(|d| !d.starts_with('#'))
+            .filter(|d| !d.is_empty())
+            .collect(),
+        None => vec![],
+    }
+}
+
 impl Display for ToolVersions {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let plugins = &self
@@ -185,6 +218,18 @@ impl ConfigFile for ToolVersions {
         }
     }
 
+    fn upgrade_version(&mut self, plugin_name: &PluginName, old: &str, new: &str) -> bool {
+        let Some(tvp) = self.plugins.get_mut(plugin_name) else {
+            return false;
+        };
+        let mut upgraded = false;
+        for version in tvp.versions.iter_mut().filter(|v| *v == old) {
+            *version = new.to_string();
+            upgraded = true;
+        }
+        upgraded
+    }
+
     fn save(&self) -> Result<()> {
         let s = self.dump();
         file::write(&self.path, s)
@@ -286,6 +331,25 @@ pub(crate) mod tests {
         "###);
     }
 
+    #[test]
+    fn test_upgrade_version() {
+        let orig = indoc! {"
+        python 3.11.0 3.10.0 # some comment
+        shfmt  3.6.0
+        "};
+        let path = dirs::CURRENT.join(".test-tool-versions");
+        let mut tv = ToolVersions::parse_str(orig, path, false).unwrap();
+        assert!(tv.upgrade_version(&PluginName::from("python"), "3.10.0", "3.10.13"));
+        assert!(!tv.upgrade_version(&PluginName::from("shfmt"), "3.5.0", "3.7.0"));
+        assert_eq!(
+            tv.dump(),
+            indoc! {"
+            python 3.11.0 3.10.13 # some comment
+            shfmt  3.6.0
+            "}
+        );
+    }
+
     #[test]
     fn test_from_toolset() {
         let orig = indoc! {"
@@ -295,4 +359,23 @@ pub(crate) mod tests {
         let tv = ToolVersions::parse_str(orig, path, true).unwrap();
         assert_display_snapshot!(tv.to_toolset(), @"ruby@3.0.5 ruby@3.1");
     }
+
+    #[test]
+    fn test_parse_directives() {
+        let orig = indoc! {"
+        nodejs 20.11.1 # rtx: pin
+        ruby   3.0.5 # rtx: ignore
+        python 3.11.0 # some comment
+        "};
+        let path = dirs::CURRENT.join(".test-tool-versions");
+        let tv = ToolVersions::parse_str(orig, path, false).unwrap();
+        assert_eq!(tv.dump(), orig);
+        assert_display_snapshot!(tv.to_toolset(), @"node@20.11.1, python@3.11.0");
+        let node = &tv.to_toolset().versions["node"].requests[0];
+        assert_eq!(node.1.get("pin").unwrap(), "true");
+        assert_eq!(
+            parse_directives(" # rtx: pin, ignore # why\n"),
+            vec!["pin", "ignore"]
+        );
+    }
 }
@@ -1,6 +1,6 @@
 use std::collections::{BTreeMap, HashMap};
 use std::fmt::{Display, Formatter};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::thread;
 
@@ -10,18 +10,31 @@ use itertools::Itertools;
 use once_cell::sync::OnceCell;
 use rayon::prelude::*;
 
-pub use settings::{MissingRuntimeBehavior, Settings};
+pub use settings::{
+    ActivateInstallMissing, ActivateStatus, ConfirmPolicy, MissingRuntimeBehavior, Settings,
+    VerifyInstalls,
+};
 
 use crate::config::config_file::legacy_version::LegacyVersionFile;
 use crate::config::config_file::rtx_toml::RtxToml;
 use crate::config::config_file::{ConfigFile, ConfigFileType};
 use crate::config::tracking::Tracker;
 use crate::file::display_path;
+use crate::plugins::cargo_plugin::PREFIX as CARGO_PREFIX;
 use crate::plugins::core::{CORE_PLUGINS, EXPERIMENTAL_CORE_PLUGINS};
-use crate::plugins::{ExternalPlugin, Plugin, PluginName, PluginType};
+use crate::plugins::github_release_plugin::PREFIX as GITHUB_RELEASE_PREFIX;
+use crate::plugins::go_install_plugin::PREFIX as GO_INSTALL_PREFIX;
+use crate::plugins::http_plugin::PREFIX as HTTP_PREFIX;
+use crate::plugins::npm_plugin::PREFIX as NPM_PREFIX;
+use crate::plugins::pipx_plugin::PREFIXES as PIPX_PREFIXES;
+use crate::plugins::{
+    CargoPlugin, ExternalPlugin, GithubReleasePlugin, GoInstallPlugin, HttpPlugin, NpmPlugin,
+    PipxPlugin, Plugin, PluginName, PluginType,
+};
 use crate::shorthands::{get_shorthands, Shorthands};
 use crate::tool::Tool;
-use crate::{dirs, env, file, hook_env};
+use crate::toolset::Toolset;
+use crate::{dirs, env, file, hook_env, platform, plugins};
 
 pub mod config_file;
 mod settings;
@@ -31,6 +44,37 @@ type AliasMap = BTreeMap<PluginName, BTreeMap<String, String>>;
 type ConfigMap = IndexMap<PathBuf, Box<dyn ConfigFile>>;
 type ToolMap = BTreeMap<PluginName, Arc<Tool>>;
 
+/// one step of resolving an alias, e.g.: `lts -> 20`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AliasHop {
+    pub from: String,
+    pub to: String,
+    pub source: AliasSource,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AliasSource {
+    /// an `[alias]` section in a config file
+    ConfigFile(PathBuf),
+    /// the plugin itself, e.g.: "bin/list-aliases" or "rtx.plugin.toml"
+    Plugin(String),
+}
+
+impl Display for AliasSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AliasSource::ConfigFile(path) => write!(f, "{}", display_path(path)),
+            AliasSource::Plugin(source) => write!(f, "{}", source),
+        }
+    }
+}
+
+impl Display for AliasHop {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> {} ({})", self.from, self.to, self.source)
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct Config {
     pub settings: Settings,
@@ -49,10 +93,17 @@ pub struct Config {
 
 impl Config {
     pub fn load() -> Result<Self> {
+        Self::load_for_dir(&dirs::CURRENT)
+    }
+
+    /// like `load` but local config files are found from `dir` instead of the current directory
+    pub fn load_for_dir(dir: &Path) -> Result<Self> {
         let global_config = load_rtxrc()?;
         let mut settings_b = global_config.settings();
         let settings = settings_b.build();
-        let config_filenames = load_config_filenames(&settings, &BTreeMap::new());
+        plugins::add_plugin_aliases(&settings.plugin_aliases);
+        platform::set_target(&settings);
+        let config_filenames = load_config_filenames(dir, &settings, &BTreeMap::new());
         let tools = load_tools(&settings)?;
         let config_files = load_all_config_files(
             &settings_b.build(),
@@ -66,9 +117,15 @@ impl Config {
         }
         let settings = settings_b.build();
         trace!("Settings: {:#?}", settings);
+        platform::set_target(&settings);
+        // config files parsed before a project config set plugin_aliases need to be parsed again
+        let config_files = match plugins::add_plugin_aliases(&settings.plugin_aliases) {
+            true => ConfigMap::new(),
+            false => config_files,
+        };
 
         let legacy_files = load_legacy_files(&settings, &tools);
-        let config_filenames = load_config_filenames(&settings, &legacy_files);
+        let config_filenames = load_config_filenames(dir, &settings, &legacy_files);
         let config_track = track_config_files(&config_filenames);
 
         let config_files = load_all_config_files(
@@ -139,17 +196,43 @@ impl Config {
     }
 
     pub fn resolve_alias(&self, plugin_name: &PluginName, v: &str) -> Result<String> {
-        if let Some(plugin_aliases) = self.aliases.get(plugin_name) {
-            if let Some(alias) = plugin_aliases.get(v) {
-                return Ok(alias.clone());
-            }
+        let hops = self.trace_alias(plugin_name, v)?;
+        Ok(hops
+            .last()
+            .map_or_else(|| v.to_string(), |hop| hop.to.clone()))
+    }
+
+    /// each alias `v` goes through and where it came from, e.g.: `lts -> 20` from the plugin
+    /// empty if `v` is not an alias
+    pub fn trace_alias(&self, plugin_name: &PluginName, v: &str) -> Result<Vec<AliasHop>> {
+        let mut hops = vec![];
+        let mut v = v.to_string();
+        // config files closer to the current directory win, the same as in load_aliases
+        let config_alias = self.config_files.values().find_map(|cf| {
+            Some((
+                cf.get_path(),
+                cf.aliases().get(plugin_name)?.get(&v)?.clone(),
+            ))
+        });
+        if let Some((path, alias)) = config_alias {
+            hops.push(AliasHop {
+                from: v,
+                to: alias.clone(),
+                source: AliasSource::ConfigFile(path.to_path_buf()),
+            });
+            // config aliases may point to a plugin alias, e.g.: `work = "lts"`
+            v = alias;
         }
         if let Some(plugin) = self.tools.get(plugin_name) {
-            if let Some(alias) = plugin.get_aliases(&self.settings)?.get(v) {
-                return Ok(alias.clone());
+            if let Some(alias) = plugin.get_aliases(&self.settings)?.get(&v) {
+                hops.push(AliasHop {
+                    from: v,
+                    to: alias.clone(),
+                    source: AliasSource::Plugin(plugin.get_aliases_source()),
+                });
             }
         }
-        Ok(v.to_string())
+        Ok(hops)
     }
 
     pub fn external_plugins(&self) -> Vec<(&PluginName, Arc<Tool>)> {
@@ -164,8 +247,22 @@ impl Config {
         self.tools
             .entry(plugin_name.clone())
             .or_insert_with(|| {
-                let plugin = ExternalPlugin::new(plugin_name.clone());
-                build_tool(plugin_name.clone(), Box::new(plugin))
+                let plugin: Box<dyn Plugin> = if plugin_name.starts_with(GITHUB_RELEASE_PREFIX) {
+                    Box::new(GithubReleasePlugin::new(plugin_name.clone()))
+                } else if plugin_name.starts_with(CARGO_PREFIX) {
+                    Box::new(CargoPlugin::new(plugin_name.clone()))
+                } else if plugin_name.starts_with(GO_INSTALL_PREFIX) {
+                    Box::new(GoInstallPlugin::new(plugin_name.clone()))
+                } else if plugin_name.starts_with(HTTP_PREFIX) {
+                    Box::new(HttpPlugin::new(plugin_name.clone()))
+                } else if plugin_name.starts_with(NPM_PREFIX) {
+                    Box::new(NpmPlugin::new(plugin_name.clone()))
+                } else if PIPX_PREFIXES.iter().any(|p| plugin_name.starts_with(p)) {
+                    Box::new(PipxPlugin::new(plugin_name.clone()))
+                } else {
+                    Box::new(ExternalPlugin::new(plugin_name.clone()))
+                };
+                build_tool(plugin_name.clone(), plugin)
             })
             .clone()
     }
@@ -235,6 +332,17 @@ impl Config {
         crate::runtime_symlinks::rebuild(self)?;
         Ok(())
     }
+
+    /// removes the shims and runtime symlinks of every tool which point at nothing,
+    /// e.g.: after uninstalling versions. Returns the removed paths
+    pub fn remove_orphaned_shims_and_runtime_symlinks(&self, ts: &Toolset) -> Result<Vec<PathBuf>> {
+        let mut removed = crate::shims::remove_orphans(self, ts)?;
+        removed.extend(crate::runtime_symlinks::remove_orphans()?);
+        for path in &removed {
+            debug!("removed orphaned {}", display_path(path));
+        }
+        Ok(removed)
+    }
 }
 
 fn get_project_root(config_files: &ConfigMap) -> Option<PathBuf> {
@@ -335,6 +443,7 @@ fn load_legacy_files(settings: &Settings, tools: &ToolMap) -> BTreeMap<String, V
 }
 
 fn load_config_filenames(
+    dir: &Path,
     settings: &Settings,
     legacy_filenames: &BTreeMap<String, Vec<PluginName>>,
 ) -> Vec<PathBuf> {
@@ -349,7 +458,7 @@ fn load_config_filenames(
         }
     }
 
-    let mut config_files = file::FindUp::new(&dirs::CURRENT, &filenames).collect::<Vec<_>>();
+    let mut config_files = file::FindUp::new(dir, &filenames).collect::<Vec<_>>();
 
     for cf in global_config_files() {
         config_files.push(cf);
@@ -452,10 +561,11 @@ fn load_path_dirs(config_files: &ConfigMap) -> Vec<PathBuf> {
     path_dirs
 }
 
+/// aliases from config files closer to the current directory override global ones
 fn load_aliases(config_files: &ConfigMap) -> AliasMap {
     let mut aliases: AliasMap = AliasMap::new();
 
-    for config_file in config_files.values() {
+    for config_file in config_files.values().rev() {
         for (plugin, plugin_aliases) in config_file.aliases() {
             for (from, to) in plugin_aliases {
                 aliases.entry(plugin.clone()).or_default().insert(from, to);
@@ -511,4 +621,39 @@ mod tests {
         let config = Config::load().unwrap();
         assert_display_snapshot!(config);
     }
+
+    #[test]
+    fn test_load_aliases() {
+        let dir = dirs::CACHE.join("test-load-aliases");
+        file::create_dir_all(&dir).unwrap();
+        let project = dir.join(".rtx.toml");
+        let global = dir.join("config.toml");
+        file::write(&project, "[alias.tiny]\nwork = '3.1'\n").unwrap();
+        file::write(&global, "[alias.tiny]\nwork = '2'\nother = '1'\n").unwrap();
+        let mut config_files = ConfigMap::new();
+        for path in [&project, &global] {
+            let cf = RtxToml::from_file(path, true).unwrap();
+            config_files.insert(path.clone(), Box::new(cf));
+        }
+        let aliases = load_aliases(&config_files);
+        assert_eq!(aliases["tiny"]["work"], "3.1");
+        assert_eq!(aliases["tiny"]["other"], "1");
+        file::remove_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_trace_alias() {
+        let config = Config::load().unwrap();
+        let tiny = PluginName::from("tiny");
+        let hops = config.trace_alias(&tiny, "my/alias").unwrap();
+        assert_eq!(hops.len(), 1);
+        assert_eq!(hops[0].to, "3.0");
+        assert_eq!(
+            hops[0].source,
+            AliasSource::ConfigFile(dirs::CONFIG.join("config.toml"))
+        );
+        let hops = config.trace_alias(&tiny, "lts").unwrap();
+        assert_eq!(hops[0].to_string(), "lts -> 3.1.0 (tiny bin/list-aliases)");
+        assert!(config.trace_alias(&tiny, "3.1.0").unwrap().is_empty());
+    }
 }
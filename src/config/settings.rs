@@ -1,8 +1,10 @@
 use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{Debug, Display, Formatter};
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::time::Duration;
 
+use color_eyre::eyre::eyre;
 use log::LevelFilter;
 
 use crate::env::*;
@@ -27,6 +29,24 @@ pub struct Settings {
     pub log_level: LevelFilter,
     pub raw: bool,
     pub yes: bool,
+    pub activate_install_missing: ActivateInstallMissing,
+    pub prerelease_regex: Option<String>,
+    pub eol_error: bool,
+    pub env_clear: bool,
+    pub exec_strict: bool,
+    pub plugin_env_passthrough: BTreeSet<String>,
+    pub activate_status: ActivateStatus,
+    pub shim_bypass: BTreeSet<String>,
+    pub plugin_aliases: BTreeMap<String, String>,
+    pub list_all_urls: BTreeMap<String, String>,
+    pub list_all_regexes: BTreeMap<String, String>,
+    pub hooks: BTreeMap<String, String>,
+    pub target_os: Option<String>,
+    pub target_arch: Option<String>,
+    pub always_keep_download_tools: BTreeSet<String>,
+    pub never_keep_download_tools: BTreeSet<String>,
+    pub verify_installs: VerifyInstalls,
+    pub confirm_policy: ConfirmPolicy,
 }
 
 impl Default for Settings {
@@ -49,6 +69,36 @@ impl Default for Settings {
             log_level: *RTX_LOG_LEVEL,
             raw: *RTX_RAW,
             yes: *RTX_YES,
+            activate_install_missing: RTX_ACTIVATE_INSTALL_MISSING
+                .as_deref()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default(),
+            prerelease_regex: RTX_PRERELEASE_REGEX.clone(),
+            eol_error: *RTX_EOL_ERROR,
+            env_clear: *RTX_ENV_CLEAR,
+            exec_strict: *RTX_EXEC_STRICT,
+            plugin_env_passthrough: RTX_PLUGIN_ENV_PASSTHROUGH.clone(),
+            activate_status: RTX_ACTIVATE_STATUS
+                .as_deref()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default(),
+            shim_bypass: RTX_SHIM_BYPASS.clone(),
+            plugin_aliases: RTX_PLUGIN_ALIASES.clone(),
+            list_all_urls: BTreeMap::new(),
+            list_all_regexes: BTreeMap::new(),
+            hooks: BTreeMap::new(),
+            target_os: RTX_TARGET_OS.clone(),
+            target_arch: RTX_TARGET_ARCH.clone(),
+            always_keep_download_tools: RTX_ALWAYS_KEEP_DOWNLOAD_TOOLS.clone(),
+            never_keep_download_tools: RTX_NEVER_KEEP_DOWNLOAD_TOOLS.clone(),
+            verify_installs: RTX_VERIFY_INSTALLS
+                .as_deref()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default(),
+            confirm_policy: RTX_CONFIRM_POLICY
+                .as_deref()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default(),
         }
     }
 }
@@ -110,6 +160,60 @@ impl Settings {
         map.insert("log_level".into(), self.log_level.to_string());
         map.insert("raw".into(), self.raw.to_string());
         map.insert("yes".into(), self.yes.to_string());
+        map.insert(
+            "activate_install_missing".into(),
+            self.activate_install_missing.to_string(),
+        );
+        if let Some(prerelease_regex) = &self.prerelease_regex {
+            map.insert("prerelease_regex".into(), prerelease_regex.clone());
+        }
+        map.insert("eol_error".into(), self.eol_error.to_string());
+        map.insert("env_clear".into(), self.env_clear.to_string());
+        map.insert("exec_strict".into(), self.exec_strict.to_string());
+        map.insert(
+            "plugin_env_passthrough".into(),
+            format!(
+                "{:?}",
+                self.plugin_env_passthrough.iter().collect::<Vec<_>>()
+            ),
+        );
+        map.insert("activate_status".into(), self.activate_status.to_string());
+        map.insert(
+            "shim_bypass".into(),
+            format!("{:?}", self.shim_bypass.iter().collect::<Vec<_>>()),
+        );
+        map.insert(
+            "plugin_aliases".into(),
+            format!("{:?}", self.plugin_aliases),
+        );
+        map.insert("list_all_urls".into(), format!("{:?}", self.list_all_urls));
+        map.insert(
+            "list_all_regexes".into(),
+            format!("{:?}", self.list_all_regexes),
+        );
+        map.insert("hooks".into(), format!("{:?}", self.hooks));
+        if let Some(target_os) = &self.target_os {
+            map.insert("target_os".into(), target_os.clone());
+        }
+        if let Some(target_arch) = &self.target_arch {
+            map.insert("target_arch".into(), target_arch.clone());
+        }
+        map.insert(
+            "always_keep_download_tools".into(),
+            format!(
+                "{:?}",
+                self.always_keep_download_tools.iter().collect::<Vec<_>>()
+            ),
+        );
+        map.insert(
+            "never_keep_download_tools".into(),
+            format!(
+                "{:?}",
+                self.never_keep_download_tools.iter().collect::<Vec<_>>()
+            ),
+        );
+        map.insert("verify_installs".into(), self.verify_installs.to_string());
+        map.insert("confirm_policy".into(), self.confirm_policy.to_string());
         map
     }
 }
@@ -133,6 +237,24 @@ pub struct SettingsBuilder {
     pub log_level: Option<LevelFilter>,
     pub raw: Option<bool>,
     pub yes: Option<bool>,
+    pub activate_install_missing: Option<ActivateInstallMissing>,
+    pub prerelease_regex: Option<String>,
+    pub eol_error: Option<bool>,
+    pub env_clear: Option<bool>,
+    pub exec_strict: Option<bool>,
+    pub plugin_env_passthrough: BTreeSet<String>,
+    pub activate_status: Option<ActivateStatus>,
+    pub shim_bypass: BTreeSet<String>,
+    pub plugin_aliases: BTreeMap<String, String>,
+    pub list_all_urls: BTreeMap<String, String>,
+    pub list_all_regexes: BTreeMap<String, String>,
+    pub hooks: BTreeMap<String, String>,
+    pub target_os: Option<String>,
+    pub target_arch: Option<String>,
+    pub always_keep_download_tools: BTreeSet<String>,
+    pub never_keep_download_tools: BTreeSet<String>,
+    pub verify_installs: Option<VerifyInstalls>,
+    pub confirm_policy: Option<ConfirmPolicy>,
 }
 
 impl SettingsBuilder {
@@ -190,6 +312,47 @@ impl SettingsBuilder {
         if other.yes.is_some() {
             self.yes = other.yes;
         }
+        if other.activate_install_missing.is_some() {
+            self.activate_install_missing = other.activate_install_missing;
+        }
+        if other.prerelease_regex.is_some() {
+            self.prerelease_regex = other.prerelease_regex;
+        }
+        if other.eol_error.is_some() {
+            self.eol_error = other.eol_error;
+        }
+        if other.env_clear.is_some() {
+            self.env_clear = other.env_clear;
+        }
+        if other.exec_strict.is_some() {
+            self.exec_strict = other.exec_strict;
+        }
+        self.plugin_env_passthrough
+            .extend(other.plugin_env_passthrough);
+        if other.activate_status.is_some() {
+            self.activate_status = other.activate_status;
+        }
+        self.shim_bypass.extend(other.shim_bypass);
+        self.plugin_aliases.extend(other.plugin_aliases);
+        self.list_all_urls.extend(other.list_all_urls);
+        self.list_all_regexes.extend(other.list_all_regexes);
+        self.hooks.extend(other.hooks);
+        if other.target_os.is_some() {
+            self.target_os = other.target_os;
+        }
+        if other.target_arch.is_some() {
+            self.target_arch = other.target_arch;
+        }
+        self.always_keep_download_tools
+            .extend(other.always_keep_download_tools);
+        self.never_keep_download_tools
+            .extend(other.never_keep_download_tools);
+        if other.verify_installs.is_some() {
+            self.verify_installs = other.verify_installs;
+        }
+        if other.confirm_policy.is_some() {
+            self.confirm_policy = other.confirm_policy;
+        }
         self
     }
 
@@ -239,6 +402,38 @@ impl SettingsBuilder {
         settings.log_level = self.log_level.unwrap_or(settings.log_level);
         settings.raw = self.raw.unwrap_or(settings.raw);
         settings.yes = self.yes.unwrap_or(settings.yes);
+        settings.activate_install_missing = self
+            .activate_install_missing
+            .clone()
+            .unwrap_or(settings.activate_install_missing);
+        settings.prerelease_regex = self.prerelease_regex.clone().or(settings.prerelease_regex);
+        settings.eol_error = self.eol_error.unwrap_or(settings.eol_error);
+        settings.env_clear = self.env_clear.unwrap_or(settings.env_clear);
+        settings.exec_strict = self.exec_strict.unwrap_or(settings.exec_strict);
+        settings
+            .plugin_env_passthrough
+            .extend(self.plugin_env_passthrough.clone());
+        settings.activate_status = self.activate_status.unwrap_or(settings.activate_status);
+        settings.shim_bypass.extend(self.shim_bypass.clone());
+        settings.plugin_aliases.extend(self.plugin_aliases.clone());
+        settings.list_all_urls.extend(self.list_all_urls.clone());
+        settings
+            .list_all_regexes
+            .extend(self.list_all_regexes.clone());
+        settings.hooks.extend(self.hooks.clone());
+        settings.target_os = self.target_os.clone().or(settings.target_os);
+        settings.target_arch = self.target_arch.clone().or(settings.target_arch);
+        settings
+            .always_keep_download_tools
+            .extend(self.always_keep_download_tools.clone());
+        settings
+            .never_keep_download_tools
+            .extend(self.never_keep_download_tools.clone());
+        settings.verify_installs = self
+            .verify_installs
+            .clone()
+            .unwrap_or(settings.verify_installs);
+        settings.confirm_policy = self.confirm_policy.unwrap_or(settings.confirm_policy);
 
         if settings.raw {
             settings.verbose = true;
@@ -274,6 +469,165 @@ impl Display for MissingRuntimeBehavior {
     }
 }
 
+/// what `rtx activate` does when entering a directory with missing tool versions
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub enum ActivateInstallMissing {
+    /// only warn that versions are not installed
+    #[default]
+    Off,
+    /// ask which versions to install
+    Prompt,
+    /// install missing versions in a background process
+    Background,
+}
+
+impl Display for ActivateInstallMissing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActivateInstallMissing::Off => write!(f, "off"),
+            ActivateInstallMissing::Prompt => write!(f, "prompt"),
+            ActivateInstallMissing::Background => write!(f, "background"),
+        }
+    }
+}
+
+impl FromStr for ActivateInstallMissing {
+    type Err = color_eyre::eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" | "false" => Ok(ActivateInstallMissing::Off),
+            "prompt" => Ok(ActivateInstallMissing::Prompt),
+            "background" => Ok(ActivateInstallMissing::Background),
+            _ => Err(eyre!(
+                "expected activate_install_missing to be one of: 'off', 'prompt', 'background'. Got: {s}"
+            )),
+        }
+    }
+}
+
+/// how much `rtx activate` reports when entering a directory
+/// each level includes the notices of the levels before it
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum ActivateStatus {
+    /// print nothing besides the usual warnings
+    #[default]
+    Off,
+    /// a one-line notice listing tool versions that are not installed
+    Missing,
+    /// also list env vars that were added, changed or removed
+    Changes,
+    /// also show the active tool versions
+    Verbose,
+}
+
+impl Display for ActivateStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActivateStatus::Off => write!(f, "off"),
+            ActivateStatus::Missing => write!(f, "missing"),
+            ActivateStatus::Changes => write!(f, "changes"),
+            ActivateStatus::Verbose => write!(f, "verbose"),
+        }
+    }
+}
+
+impl FromStr for ActivateStatus {
+    type Err = color_eyre::eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" | "false" | "0" => Ok(ActivateStatus::Off),
+            "missing" => Ok(ActivateStatus::Missing),
+            "changes" | "true" | "1" => Ok(ActivateStatus::Changes),
+            "verbose" => Ok(ActivateStatus::Verbose),
+            _ => Err(eyre!(
+                "expected activate_status to be one of: 'off', 'missing', 'changes', 'verbose'. Got: {s}"
+            )),
+        }
+    }
+}
+
+/// whether executables are checked against the manifest recorded when they were installed
+/// before a shim runs them
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub enum VerifyInstalls {
+    /// do not record or check manifests
+    #[default]
+    Off,
+    /// warn if an executable was modified but still run it
+    Warn,
+    /// refuse to run executables of a modified install
+    Error,
+}
+
+impl Display for VerifyInstalls {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerifyInstalls::Off => write!(f, "off"),
+            VerifyInstalls::Warn => write!(f, "warn"),
+            VerifyInstalls::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl FromStr for VerifyInstalls {
+    type Err = color_eyre::eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" | "false" => Ok(VerifyInstalls::Off),
+            "warn" => Ok(VerifyInstalls::Warn),
+            "error" => Ok(VerifyInstalls::Error),
+            _ => Err(eyre!(
+                "expected verify_installs to be one of: 'off', 'warn', 'error'. Got: {s}"
+            )),
+        }
+    }
+}
+
+/// which operations that are hard to undo ask for confirmation first,
+/// e.g.: `rtx prune`, `rtx uninstall --all` or `rtx self-update`
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum ConfirmPolicy {
+    /// prompt for the operations which always did, unless `yes` is set
+    #[default]
+    Default,
+    /// prompt for every destructive operation, even when `yes`, RTX_YES or CI is set
+    Paranoid,
+    /// never prompt, answer yes
+    Yes,
+    /// never prompt, answer no
+    No,
+}
+
+impl Display for ConfirmPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfirmPolicy::Default => write!(f, "default"),
+            ConfirmPolicy::Paranoid => write!(f, "paranoid"),
+            ConfirmPolicy::Yes => write!(f, "yes"),
+            ConfirmPolicy::No => write!(f, "no"),
+        }
+    }
+}
+
+impl FromStr for ConfirmPolicy {
+    type Err = color_eyre::eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "default" => Ok(ConfirmPolicy::Default),
+            "paranoid" => Ok(ConfirmPolicy::Paranoid),
+            "yes" => Ok(ConfirmPolicy::Yes),
+            "no" => Ok(ConfirmPolicy::No),
+            _ => Err(eyre!(
+                "expected confirm_policy to be one of: 'default', 'paranoid', 'yes', 'no'. Got: {s}"
+            )),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -298,4 +652,31 @@ mod tests {
         assert_eq!(Warn.to_string(), "warn");
         assert_eq!(Ignore.to_string(), "ignore");
     }
+
+    #[test]
+    fn test_activate_install_missing_parse() {
+        assert_eq!(
+            "background".parse::<ActivateInstallMissing>().unwrap(),
+            ActivateInstallMissing::Background
+        );
+        assert_eq!(
+            "Prompt".parse::<ActivateInstallMissing>().unwrap(),
+            ActivateInstallMissing::Prompt
+        );
+        assert!("always".parse::<ActivateInstallMissing>().is_err());
+    }
+
+    #[test]
+    fn test_activate_status_parse() {
+        assert_eq!(
+            "Missing".parse::<ActivateStatus>().unwrap(),
+            ActivateStatus::Missing
+        );
+        assert_eq!(
+            "true".parse::<ActivateStatus>().unwrap(),
+            ActivateStatus::Changes
+        );
+        assert!(ActivateStatus::Verbose > ActivateStatus::Changes);
+        assert!("loud".parse::<ActivateStatus>().is_err());
+    }
 }
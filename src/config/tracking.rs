@@ -18,12 +18,15 @@ pub struct Tracker {
 impl Tracker {
     pub fn new() -> Self {
         Self {
-            tracking_dir: dirs::ROOT.join("tracked_config_files"),
+            tracking_dir: dirs::STATE.join("tracked_config_files"),
             ..Default::default()
         }
     }
 
     pub fn track(&mut self, path: &Path) -> Result<()> {
+        if *dirs::STATE_READ_ONLY {
+            return Ok(());
+        }
         if !self.config_files.contains(path) {
             let tracking_path = self.tracking_dir.join(hash_to_str(&path));
             if !tracking_path.exists() {
@@ -1,15 +1,49 @@
 use std::path::PathBuf;
 
+use color_eyre::eyre::{bail, Result};
 use once_cell::sync::Lazy;
 
-use crate::env;
+use crate::{env, file};
 
 pub static CURRENT: Lazy<PathBuf> = Lazy::new(|| env::PWD.clone());
 pub static HOME: Lazy<PathBuf> = Lazy::new(|| env::HOME.clone());
 pub static ROOT: Lazy<PathBuf> = Lazy::new(|| env::RTX_DATA_DIR.clone());
-pub static CACHE: Lazy<PathBuf> = Lazy::new(|| env::RTX_CACHE_DIR.clone());
+pub static CACHE: Lazy<PathBuf> = Lazy::new(|| match &*env::RTX_SHARED_CACHE_DIR {
+    Some(shared) if file::is_writable(shared) => shared.clone(),
+    Some(shared) => {
+        debug!(
+            "{} is not writable, using {}",
+            file::display_path(shared),
+            file::display_path(&USER_CACHE)
+        );
+        USER_CACHE.clone()
+    }
+    None => USER_CACHE.clone(),
+});
+/// the cache of the current user, CACHE is this unless RTX_SHARED_CACHE_DIR is used
+pub static USER_CACHE: Lazy<PathBuf> = Lazy::new(|| env::RTX_CACHE_DIR.clone());
 pub static CONFIG: Lazy<PathBuf> = Lazy::new(|| env::RTX_CONFIG_DIR.clone());
+pub static STATE: Lazy<PathBuf> = Lazy::new(|| env::RTX_STATE_DIR.clone());
 pub static PLUGINS: Lazy<PathBuf> = Lazy::new(|| env::RTX_DATA_DIR.join("plugins"));
 pub static DOWNLOADS: Lazy<PathBuf> = Lazy::new(|| env::RTX_DATA_DIR.join("downloads"));
 pub static INSTALLS: Lazy<PathBuf> = Lazy::new(|| env::RTX_DATA_DIR.join("installs"));
-pub static SHIMS: Lazy<PathBuf> = Lazy::new(|| env::RTX_DATA_DIR.join("shims"));
+pub static SHIMS: Lazy<PathBuf> = Lazy::new(|| env::RTX_SHIMS_DIR.clone());
+
+/// e.g.: RTX_DATA_DIR was baked into a container image. Installed tools can still be used but
+/// anything which writes to it is skipped or fails with an explanation.
+pub static ROOT_READ_ONLY: Lazy<bool> = Lazy::new(|| !file::is_writable(&ROOT));
+pub static CACHE_READ_ONLY: Lazy<bool> = Lazy::new(|| !file::is_writable(&CACHE));
+pub static STATE_READ_ONLY: Lazy<bool> = Lazy::new(|| !file::is_writable(&STATE));
+pub static SHIMS_READ_ONLY: Lazy<bool> = Lazy::new(|| !file::is_writable(&SHIMS));
+
+/// fails if RTX_DATA_DIR is read-only, `what` is the operation which needs to write to it
+pub fn ensure_root_writable(what: &str) -> Result<()> {
+    if *ROOT_READ_ONLY {
+        bail!(
+            "{what} needs to write to RTX_DATA_DIR ({}) which is read-only\n\
+            mount a writable overlay on it or set RTX_DATA_DIR to a writable directory",
+            file::display_path(&ROOT)
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use color_eyre::eyre::Result;
+use indexmap::IndexMap;
+use serde_derive::Serialize;
+
+use crate::config::Config;
+use crate::toolset::ToolsetBuilder;
+use crate::ui::multi_progress_report::MultiProgressReport;
+
+/// The public API for using rtx as a library, e.g.: from an editor server or infra tooling that
+/// needs the active tools or their env without running `rtx env --json` and parsing the output.
+/// Everything else in the `rtx` crate is internal to the CLI and may change in any release.
+///
+/// Config is loaded the same way as the CLI does it: from the directory the process was started
+/// in, the global config and RTX_* env vars.
+///
+///     let mut project = rtx::embed::Project::load()?;
+///     for tool in project.tools()? {
+///         println!("{} {} installed: {}", tool.plugin, tool.version, tool.installed);
+///     }
+///     let path = project.env()?.remove("PATH");
+pub struct Project {
+    config: Config,
+}
+
+/// a tool version requested by a config file, env var or argument and what it resolved to
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedTool {
+    pub plugin: String,
+    pub version: String,
+    /// as it was requested, e.g.: "20" or "lts" for node 20.3.0
+    pub requested_version: String,
+    pub installed: bool,
+    pub install_path: PathBuf,
+    /// where it was requested, e.g.: {"type": ".rtx.toml", "path": "/project/.rtx.toml"}
+    pub source: IndexMap<String, String>,
+}
+
+impl Project {
+    pub fn load() -> Result<Self> {
+        Ok(Self {
+            config: Config::load()?,
+        })
+    }
+
+    /// the config files which were loaded, closest to the current directory first
+    pub fn config_files(&self) -> Vec<PathBuf> {
+        self.config.config_files.keys().cloned().collect()
+    }
+
+    /// the active tool versions, same as `rtx current`
+    pub fn tools(&mut self) -> Result<Vec<ResolvedTool>> {
+        resolve_tools(&mut self.config)
+    }
+
+    /// env vars including PATH for the active tools, same as `rtx env --json`
+    pub fn env(&mut self) -> Result<BTreeMap<String, String>> {
+        resolve_env(&mut self.config)
+    }
+
+    /// installs the active tool versions which are not installed yet, same as `rtx install`
+    pub fn install_missing(&mut self) -> Result<()> {
+        let mut ts = ToolsetBuilder::new().build(&mut self.config)?;
+        ts.install_missing(&mut self.config, MultiProgressReport::new(false))
+    }
+}
+
+pub(crate) fn resolve_tools(config: &mut Config) -> Result<Vec<ResolvedTool>> {
+    let ts = ToolsetBuilder::new().build(config)?;
+    let tools = ts
+        .versions
+        .values()
+        .flat_map(|tvl| {
+            tvl.versions.iter().map(|tv| ResolvedTool {
+                plugin: tv.plugin_name.clone(),
+                version: tv.version.clone(),
+                requested_version: tv.request.version(),
+                installed: config
+                    .tools
+                    .get(&tv.plugin_name)
+                    .map_or(false, |t| t.is_version_installed(tv)),
+                install_path: tv.install_path(),
+                source: tvl.source.as_json(),
+            })
+        })
+        .collect();
+    Ok(tools)
+}
+
+pub(crate) fn resolve_env(config: &mut Config) -> Result<BTreeMap<String, String>> {
+    let ts = ToolsetBuilder::new().build(config)?;
+    Ok(ts.env_with_path(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dirs;
+
+    use super::*;
+
+    #[test]
+    fn test_project() {
+        let mut project = Project::load().unwrap();
+        let tiny = project
+            .tools()
+            .unwrap()
+            .into_iter()
+            .find(|t| t.plugin == "tiny")
+            .unwrap();
+        assert_eq!(
+            tiny.install_path,
+            dirs::INSTALLS.join("tiny").join(&tiny.version)
+        );
+        assert!(project.env().unwrap().contains_key("PATH"));
+    }
+}
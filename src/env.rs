@@ -1,4 +1,4 @@
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 pub use std::env::*;
 use std::path::PathBuf;
 use std::time::Duration;
@@ -18,18 +18,30 @@ pub static SHELL: Lazy<String> = Lazy::new(|| var("SHELL").unwrap_or_else(|_| "s
 pub static HOME: Lazy<PathBuf> =
     Lazy::new(|| dirs_next::home_dir().unwrap_or_else(|| PathBuf::from("/")));
 pub static PWD: Lazy<PathBuf> = Lazy::new(|| current_dir().unwrap_or_else(|_| PathBuf::new()));
-pub static XDG_CACHE_HOME: Lazy<PathBuf> =
-    Lazy::new(|| dirs_next::cache_dir().unwrap_or_else(|| HOME.join(".cache")));
+pub static XDG_CACHE_HOME: Lazy<PathBuf> = Lazy::new(|| {
+    var_path("XDG_CACHE_HOME")
+        .or_else(dirs_next::cache_dir)
+        .unwrap_or_else(|| HOME.join(".cache"))
+});
 pub static XDG_DATA_HOME: Lazy<PathBuf> =
     Lazy::new(|| var_path("XDG_DATA_HOME").unwrap_or_else(|| HOME.join(".local/share")));
 pub static XDG_CONFIG_HOME: Lazy<PathBuf> =
     Lazy::new(|| var_path("XDG_CONFIG_HOME").unwrap_or_else(|| HOME.join(".config")));
+pub static XDG_STATE_HOME: Lazy<PathBuf> =
+    Lazy::new(|| var_path("XDG_STATE_HOME").unwrap_or_else(|| HOME.join(".local/state")));
 pub static RTX_CACHE_DIR: Lazy<PathBuf> =
     Lazy::new(|| var_path("RTX_CACHE_DIR").unwrap_or_else(|| XDG_CACHE_HOME.join("rtx")));
+/// e.g.: /var/cache/rtx, a cache used by every user in a group instead of RTX_CACHE_DIR
+pub static RTX_SHARED_CACHE_DIR: Lazy<Option<PathBuf>> =
+    Lazy::new(|| var_path("RTX_SHARED_CACHE_DIR"));
 pub static RTX_CONFIG_DIR: Lazy<PathBuf> =
     Lazy::new(|| var_path("RTX_CONFIG_DIR").unwrap_or_else(|| XDG_CONFIG_HOME.join("rtx")));
 pub static RTX_DATA_DIR: Lazy<PathBuf> =
     Lazy::new(|| var_path("RTX_DATA_DIR").unwrap_or_else(|| XDG_DATA_HOME.join("rtx")));
+pub static RTX_STATE_DIR: Lazy<PathBuf> =
+    Lazy::new(|| var_path("RTX_STATE_DIR").unwrap_or_else(|| XDG_STATE_HOME.join("rtx")));
+pub static RTX_SHIMS_DIR: Lazy<PathBuf> =
+    Lazy::new(|| var_path("RTX_SHIMS_DIR").unwrap_or_else(|| RTX_DATA_DIR.join("shims")));
 pub static RTX_TMP_DIR: Lazy<PathBuf> = Lazy::new(|| temp_dir().join("rtx"));
 
 pub static RTX_DEFAULT_TOOL_VERSIONS_FILENAME: Lazy<String> = Lazy::new(|| {
@@ -39,6 +51,9 @@ pub static RTX_DEFAULT_CONFIG_FILENAME: Lazy<String> =
     Lazy::new(|| var("RTX_DEFAULT_CONFIG_FILENAME").unwrap_or_else(|_| ".rtx.toml".into()));
 pub static RTX_ENV: Lazy<Option<String>> = Lazy::new(|| var("RTX_ENV").ok());
 pub static RTX_CONFIG_FILE: Lazy<Option<PathBuf>> = Lazy::new(|| var_path("RTX_CONFIG_FILE"));
+/// the tools and versions an organization allows, see `Policy`
+pub static RTX_POLICY_FILE: Lazy<PathBuf> =
+    Lazy::new(|| var_path("RTX_POLICY_FILE").unwrap_or_else(|| "/etc/rtx/policy.toml".into()));
 pub static RTX_USE_TOML: Lazy<bool> = Lazy::new(|| var_is_true("RTX_USE_TOML"));
 pub static RTX_EXE: Lazy<PathBuf> = Lazy::new(|| current_exe().unwrap_or_else(|_| "rtx".into()));
 pub static RTX_LOG_LEVEL: Lazy<LevelFilter> = Lazy::new(log_level);
@@ -47,12 +62,53 @@ pub static RTX_MISSING_RUNTIME_BEHAVIOR: Lazy<Option<String>> =
     Lazy::new(|| var("RTX_MISSING_RUNTIME_BEHAVIOR").ok());
 pub static RTX_VERBOSE: Lazy<bool> =
     Lazy::new(|| *RTX_LOG_LEVEL > LevelFilter::Info || var_is_true("RTX_VERBOSE"));
+pub static RTX_EOL_ERROR: Lazy<bool> = Lazy::new(|| var_is_true("RTX_EOL_ERROR"));
+pub static RTX_ENV_CLEAR: Lazy<bool> = Lazy::new(|| var_is_true("RTX_ENV_CLEAR"));
+pub static RTX_EXEC_STRICT: Lazy<bool> = Lazy::new(|| var_is_true("RTX_EXEC_STRICT"));
+pub static RTX_PRERELEASE_REGEX: Lazy<Option<String>> =
+    Lazy::new(|| var("RTX_PRERELEASE_REGEX").ok());
+pub static RTX_TARGET_OS: Lazy<Option<String>> = Lazy::new(|| var("RTX_TARGET_OS").ok());
+pub static RTX_TARGET_ARCH: Lazy<Option<String>> = Lazy::new(|| var("RTX_TARGET_ARCH").ok());
+pub static RTX_PROGRESS_FORMAT: Lazy<String> =
+    Lazy::new(|| var("RTX_PROGRESS_FORMAT").unwrap_or_else(|_| "text".into()));
+pub static RTX_PROGRESS_FD: Lazy<i32> = Lazy::new(|| {
+    var("RTX_PROGRESS_FD")
+        .ok()
+        .and_then(|v| v.parse::<i32>().ok())
+        .unwrap_or(2)
+});
 pub static RTX_JOBS: Lazy<usize> = Lazy::new(|| {
     var("RTX_JOBS")
         .ok()
         .and_then(|v| v.parse::<usize>().ok())
         .unwrap_or(4)
 });
+/// bytes/sec shared by all downloads, e.g.: "500K" or "2MB"
+pub static RTX_DOWNLOAD_RATE_LIMIT: Lazy<Option<u64>> = Lazy::new(|| {
+    let v = var("RTX_DOWNLOAD_RATE_LIMIT").ok()?;
+    match parse_bytes(&v).filter(|n| *n > 0) {
+        Some(n) => Some(n),
+        None => {
+            warn!("invalid RTX_DOWNLOAD_RATE_LIMIT: {v}, expected e.g.: 500K or 2M");
+            None
+        }
+    }
+});
+/// compare content hashes of a cache's fresh files instead of their mtimes
+pub static RTX_CACHE_CONTENT_HASH: Lazy<bool> = Lazy::new(|| var_is_true("RTX_CACHE_CONTENT_HASH"));
+pub static RTX_SKIP_DEPENDENCY_CHECK: Lazy<bool> =
+    Lazy::new(|| var_is_true("RTX_SKIP_DEPENDENCY_CHECK"));
+/// how long `rtx watch` waits for more changes before running the task
+pub static RTX_WATCH_DEBOUNCE: Lazy<Duration> =
+    Lazy::new(|| var_duration("RTX_WATCH_DEBOUNCE").unwrap_or(Duration::from_millis(200)));
+/// number of parallel range requests for large downloads, 1 (the default) disables segmenting
+pub static RTX_DOWNLOAD_SEGMENTS: Lazy<u64> = Lazy::new(|| {
+    var("RTX_DOWNLOAD_SEGMENTS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(1)
+});
 pub static RTX_FETCH_REMOTE_VERSIONS_TIMEOUT: Lazy<Duration> = Lazy::new(|| {
     var_duration("RTX_FETCH_REMOTE_VERSIONS_TIMEOUT").unwrap_or(Duration::from_secs(10))
 });
@@ -74,6 +130,9 @@ pub static RTX_FETCH_REMOTE_VERSIONS_CACHE: Lazy<Option<Duration>> = Lazy::new(|
 /// used to prevent infinite loops
 pub static __RTX_SCRIPT: Lazy<bool> = Lazy::new(|| var_is_true("__RTX_SCRIPT"));
 pub static __RTX_DIFF: Lazy<EnvDiff> = Lazy::new(get_env_diff);
+/// pid of the shell which ran `rtx activate`, exported by the activate script
+/// nested shells and tmux panes inherit it, so it differs from their pid until they activate too
+pub static __RTX_SESSION: Lazy<Option<String>> = Lazy::new(|| var("__RTX_SESSION").ok());
 pub static CI: Lazy<bool> = Lazy::new(|| var_is_true("CI"));
 pub static PREFER_STALE: Lazy<bool> = Lazy::new(|| prefer_stale(&ARGS));
 /// essentially, this is whether we show spinners or build output on runtime install
@@ -103,6 +162,40 @@ pub static RTX_DISABLE_TOOLS: Lazy<BTreeSet<String>> = Lazy::new(|| {
         .map(|v| v.split(',').map(|s| s.to_string()).collect())
         .unwrap_or_default()
 });
+pub static RTX_PLUGIN_ENV_PASSTHROUGH: Lazy<BTreeSet<String>> = Lazy::new(|| {
+    var("RTX_PLUGIN_ENV_PASSTHROUGH")
+        .map(|v| v.split(',').map(|s| s.to_string()).collect())
+        .unwrap_or_default()
+});
+pub static RTX_ALWAYS_KEEP_DOWNLOAD_TOOLS: Lazy<BTreeSet<String>> = Lazy::new(|| {
+    var("RTX_ALWAYS_KEEP_DOWNLOAD_TOOLS")
+        .map(|v| v.split(',').map(|s| s.to_string()).collect())
+        .unwrap_or_default()
+});
+pub static RTX_NEVER_KEEP_DOWNLOAD_TOOLS: Lazy<BTreeSet<String>> = Lazy::new(|| {
+    var("RTX_NEVER_KEEP_DOWNLOAD_TOOLS")
+        .map(|v| v.split(',').map(|s| s.to_string()).collect())
+        .unwrap_or_default()
+});
+pub static RTX_CONFIRM_POLICY: Lazy<Option<String>> = Lazy::new(|| var("RTX_CONFIRM_POLICY").ok());
+pub static RTX_VERIFY_INSTALLS: Lazy<Option<String>> =
+    Lazy::new(|| var("RTX_VERIFY_INSTALLS").ok());
+pub static RTX_SHIM_BYPASS: Lazy<BTreeSet<String>> = Lazy::new(|| {
+    var("RTX_SHIM_BYPASS")
+        .map(|v| v.split(',').map(|s| s.to_string()).collect())
+        .unwrap_or_default()
+});
+/// e.g.: "nodejs=node,golang=go"
+pub static RTX_PLUGIN_ALIASES: Lazy<BTreeMap<String, String>> = Lazy::new(|| {
+    var("RTX_PLUGIN_ALIASES")
+        .map(|v| {
+            v.split(',')
+                .filter_map(|s| s.split_once('='))
+                .map(|(from, to)| (from.trim().to_string(), to.trim().to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+});
 pub static RTX_RAW: Lazy<bool> = Lazy::new(|| var_is_true("RTX_RAW"));
 pub static RTX_YES: Lazy<bool> = Lazy::new(|| *CI || var_is_true("RTX_YES"));
 pub static RTX_TRUSTED_CONFIG_PATHS: Lazy<BTreeSet<PathBuf>> = Lazy::new(|| {
@@ -110,6 +203,10 @@ pub static RTX_TRUSTED_CONFIG_PATHS: Lazy<BTreeSet<PathBuf>> = Lazy::new(|| {
         .map(|v| split_paths(&v).collect())
         .unwrap_or_default()
 });
+pub static RTX_ACTIVATE_INSTALL_MISSING: Lazy<Option<String>> =
+    Lazy::new(|| var("RTX_ACTIVATE_INSTALL_MISSING").ok());
+pub static RTX_ACTIVATE_STATUS: Lazy<Option<String>> =
+    Lazy::new(|| var("RTX_ACTIVATE_STATUS").ok());
 pub static RTX_ALWAYS_KEEP_DOWNLOAD: Lazy<bool> =
     Lazy::new(|| var_is_true("RTX_ALWAYS_KEEP_DOWNLOAD"));
 pub static RTX_ALWAYS_KEEP_INSTALL: Lazy<bool> =
@@ -130,6 +227,8 @@ pub static RTX_PYTHON_DEFAULT_PACKAGES_FILE: Lazy<PathBuf> = Lazy::new(|| {
     var_path("RTX_PYTHON_DEFAULT_PACKAGES_FILE")
         .unwrap_or_else(|| HOME.join(".default-python-packages"))
 });
+/// install prebuilt python-build-standalone builds instead of compiling python with python-build
+pub static RTX_PYTHON_PRECOMPILED: Lazy<bool> = Lazy::new(|| var_is_true("RTX_PYTHON_PRECOMPILED"));
 pub static PYENV_ROOT: Lazy<PathBuf> =
     Lazy::new(|| var_path("PYENV_ROOT").unwrap_or_else(|| HOME.join(".pyenv")));
 
@@ -137,6 +236,15 @@ pub static PYENV_ROOT: Lazy<PathBuf> =
 pub static RTX_NODE_BUILD_REPO: Lazy<String> = Lazy::new(|| {
     var("RTX_NODE_BUILD_REPO").unwrap_or_else(|_| "https://github.com/nodenv/node-build.git".into())
 });
+pub static RTX_NODE_MIRROR_URL: Lazy<String> = Lazy::new(|| {
+    let url = var("RTX_NODE_MIRROR_URL")
+        .or_else(|_| var("NODEJS_ORG_MIRROR"))
+        .unwrap_or_else(|_| "https://nodejs.org/dist/".into());
+    match url.ends_with('/') {
+        true => url,
+        false => format!("{url}/"),
+    }
+});
 pub static RTX_NODE_CONCURRENCY: Lazy<usize> = Lazy::new(|| {
     var("RTX_NODE_CONCURRENCY")
         .ok()
@@ -173,6 +281,17 @@ pub static RTX_RUBY_INSTALL_REPO: Lazy<String> = Lazy::new(|| {
     var("RTX_RUBY_INSTALL_REPO")
         .unwrap_or_else(|_| "https://github.com/postmodern/ruby-install.git".into())
 });
+/// the ruby-build release to use, e.g.: "20231114", the latest release is used if not set
+pub static RTX_RUBY_BUILD_VERSION: Lazy<Option<String>> = Lazy::new(|| {
+    var("RTX_RUBY_BUILD_VERSION")
+        .ok()
+        .map(|v| v.trim_start_matches('v').to_string())
+});
+/// install prebuilt rubies from ruby/ruby-builder when there is one for this platform
+pub static RTX_RUBY_PRECOMPILED: Lazy<bool> = Lazy::new(|| var_is_true("RTX_RUBY_PRECOMPILED"));
+/// the platform of the prebuilt rubies to install, e.g.: "ubuntu-22.04" or "macos-latest"
+pub static RTX_RUBY_PRECOMPILED_PLATFORM: Lazy<Option<String>> =
+    Lazy::new(|| var("RTX_RUBY_PRECOMPILED_PLATFORM").ok());
 pub static RTX_RUBY_INSTALL: Lazy<bool> = Lazy::new(|| var_is_true("RTX_RUBY_INSTALL"));
 pub static RTX_RUBY_APPLY_PATCHES: Lazy<Option<String>> =
     Lazy::new(|| var("RTX_RUBY_APPLY_PATCHES").ok());
@@ -200,6 +319,22 @@ pub static RTX_GO_SET_GOROOT: Lazy<Option<bool>> =
     Lazy::new(|| var_option_bool("RTX_GO_SET_GOROOT"));
 pub static RTX_GO_SET_GOPATH: Lazy<Option<bool>> =
     Lazy::new(|| var_option_bool("RTX_GO_SET_GOPATH"));
+/// the first go module proxy in GOPROXY, e.g.: "https://proxy.golang.org,direct"
+pub static GOPROXY: Lazy<String> = Lazy::new(|| {
+    var("GOPROXY")
+        .unwrap_or_default()
+        .split([',', '|'])
+        .find(|p| p.starts_with("http"))
+        .unwrap_or("https://proxy.golang.org")
+        .trim_end_matches('/')
+        .to_string()
+});
+
+// swift
+pub static RTX_SWIFT_PLATFORM: Lazy<Option<String>> = Lazy::new(|| var("RTX_SWIFT_PLATFORM").ok());
+
+// cargo
+pub static RTX_CARGO_BINSTALL: Lazy<bool> = Lazy::new(|| !var_is_false("RTX_CARGO_BINSTALL"));
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Confirm {
@@ -265,6 +400,22 @@ fn var_confirm(key: &str) -> Confirm {
     }
 }
 
+/// "1024", "500K", "2M" or "1G" (powers of 1024), a trailing "B" or "iB" is allowed: "2MB", "2MiB"
+fn parse_bytes(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let s = s
+        .strip_suffix("iB")
+        .or_else(|| s.strip_suffix(['B', 'b']))
+        .unwrap_or(s);
+    let (num, multiplier) = match s.chars().last()?.to_ascii_uppercase() {
+        'K' => (&s[..s.len() - 1], 1024),
+        'M' => (&s[..s.len() - 1], 1024 * 1024),
+        'G' => (&s[..s.len() - 1], 1024 * 1024 * 1024),
+        _ => (s, 1),
+    };
+    num.trim().parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
 fn var_duration(key: &str) -> Option<Duration> {
     var(key)
         .ok()
@@ -321,12 +472,46 @@ fn apply_patches(
     new_env
 }
 
+/// returns the directory passed with `-C/--cd` if rtx was called directly (not as a shim)
+/// this is read before config is loaded so config discovery happens from that directory
+pub fn cd_arg(args: &[String]) -> Option<PathBuf> {
+    let (_, bin_name) = args.first()?.rsplit_once('/').unwrap_or(("", &args[0]));
+    if bin_name != "rtx" {
+        return None;
+    }
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--" => break,
+            "-C" | "--cd" => return args.next().map(PathBuf::from),
+            _ => {
+                // "--cd=/tmp", "-C/tmp" or "-C=/tmp"
+                let dir = arg.strip_prefix("--cd=").or_else(|| {
+                    let dir = arg.strip_prefix("-C")?;
+                    Some(dir.strip_prefix('=').unwrap_or(dir))
+                });
+                if let Some(dir) = dir {
+                    return Some(PathBuf::from(dir));
+                }
+            }
+        }
+    }
+    None
+}
+
 /// returns true if new runtime versions should not be fetched
 fn prefer_stale(args: &[String]) -> bool {
     let binding = String::new();
+    let mut skip_next = false;
     let c = args
         .iter()
-        .filter(|a| !a.starts_with('-'))
+        .filter(|a| {
+            if std::mem::take(&mut skip_next) {
+                return false;
+            }
+            skip_next = *a == "-C" || *a == "--cd";
+            !a.starts_with('-')
+        })
         .nth(1)
         .unwrap_or(&binding);
     return [
@@ -416,6 +601,18 @@ mod tests {
         assert_eq!(new_env.get("baz").unwrap(), "qux");
     }
 
+    #[test]
+    fn test_parse_bytes() {
+        assert_eq!(parse_bytes("1024"), Some(1024));
+        assert_eq!(parse_bytes("500k"), Some(500 * 1024));
+        assert_eq!(parse_bytes("2M"), Some(2 * 1024 * 1024));
+        assert_eq!(parse_bytes("2MB"), Some(2 * 1024 * 1024));
+        assert_eq!(parse_bytes("1GiB"), Some(1024 * 1024 * 1024));
+        assert_eq!(parse_bytes("100B"), Some(100));
+        assert_eq!(parse_bytes("fast"), None);
+        assert_eq!(parse_bytes("18446744073709551615G"), None);
+    }
+
     #[test]
     fn test_var_path() {
         set_var("RTX_TEST_PATH", "/foo/bar");
@@ -426,6 +623,28 @@ mod tests {
         remove_var("RTX_TEST_PATH");
     }
 
+    #[test]
+    fn test_cd_arg() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            cd_arg(&args(&["rtx", "-C", "/tmp", "ls"])),
+            Some(PathBuf::from("/tmp"))
+        );
+        assert_eq!(
+            cd_arg(&args(&["/usr/bin/rtx", "ls", "--cd=/tmp"])),
+            Some(PathBuf::from("/tmp"))
+        );
+        assert_eq!(
+            cd_arg(&args(&["rtx", "-C/tmp", "ls"])),
+            Some(PathBuf::from("/tmp"))
+        );
+        assert_eq!(
+            cd_arg(&args(&["rtx", "x", "--", "git", "-C", "/tmp"])),
+            None
+        );
+        assert_eq!(cd_arg(&args(&["node", "-C", "/tmp"])), None);
+    }
+
     #[test]
     fn test_var_confirm() {
         set_var("RTX_TEST_CONFIRM", "true");
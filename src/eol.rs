@@ -0,0 +1,67 @@
+use chrono::{Local, NaiveDate};
+use color_eyre::eyre::{eyre, Result};
+
+use crate::config::Settings;
+use crate::toolset::ToolVersion;
+
+/// end-of-life dates of runtime release lines, after which they no longer get security fixes
+/// (plugin, version prefix, eol date)
+#[rustfmt::skip]
+const EOL_DATES: &[(&str, &str, &str)] = &[
+    ("node", "10", "2021-04-30"),
+    ("node", "12", "2022-04-30"),
+    ("node", "14", "2023-04-30"),
+    ("node", "16", "2023-09-11"),
+    ("node", "18", "2025-04-30"),
+    ("node", "20", "2026-04-30"),
+    ("python", "2.7", "2020-01-01"),
+    ("python", "3.6", "2021-12-23"),
+    ("python", "3.7", "2023-06-27"),
+    ("python", "3.8", "2024-10-07"),
+    ("python", "3.9", "2025-10-31"),
+    ("python", "3.10", "2026-10-31"),
+    ("ruby", "2.6", "2022-04-12"),
+    ("ruby", "2.7", "2023-03-31"),
+    ("ruby", "3.0", "2024-04-23"),
+    ("ruby", "3.1", "2025-03-26"),
+];
+
+/// the eol date of the release line `version` belongs to, if known
+pub fn eol_date(plugin: &str, version: &str) -> Option<NaiveDate> {
+    EOL_DATES
+        .iter()
+        .find(|(p, prefix, _)| {
+            *p == plugin && (version == *prefix || version.starts_with(&format!("{prefix}.")))
+        })
+        .and_then(|(_, _, date)| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+}
+
+/// warns, or fails with the `eol_error` setting, if the version is past its end-of-life
+pub fn check(settings: &Settings, tv: &ToolVersion) -> Result<()> {
+    let Some(date) = eol_date(&tv.plugin_name, &tv.version) else {
+        return Ok(());
+    };
+    if date > Local::now().date_naive() {
+        return Ok(());
+    }
+    let msg = format!("{tv} reached end-of-life on {date} and no longer gets security fixes");
+    if settings.eol_error {
+        return Err(eyre!("{msg}\nset eol_error = false to allow it"));
+    }
+    warn!("{msg}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eol_date() {
+        let date = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok();
+        assert_eq!(eol_date("node", "16.20.2"), date("2023-09-11"));
+        assert_eq!(eol_date("python", "3.7"), date("2023-06-27"));
+        assert_eq!(eol_date("python", "3.70.0"), None);
+        assert_eq!(eol_date("node", "21.0.0"), None);
+    }
+}
@@ -1,5 +1,6 @@
 use std::fs;
-use std::fs::File;
+use std::fs::{File, Permissions};
+use std::io::Read;
 use std::os::unix::fs::symlink;
 use std::os::unix::prelude::*;
 use std::path::{Path, PathBuf};
@@ -10,11 +11,12 @@ use filetime::{set_file_times, FileTime};
 use flate2::read::GzDecoder;
 use tar::Archive;
 
+use crate::ui::progress_report::ProgressReport;
 use crate::{cmd, dirs, env};
 
 pub fn remove_all<P: AsRef<Path>>(path: P) -> Result<()> {
     let path = path.as_ref();
-    match path.metadata().map(|m| m.file_type()) {
+    match path.symlink_metadata().map(|m| m.file_type()) {
         Ok(x) if x.is_symlink() || x.is_file() => {
             remove_file(path)?;
         }
@@ -52,13 +54,17 @@ pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> Result<()> {
     let to = to.as_ref();
     trace!("mv {} {}", from.display(), to.display());
     fs::rename(from, to)
-        .with_context(|| format!("failed rename: {} -> {}", from.display(), to.display()))
+        .with_context(|| format!("failed rename: {} -> {}", from.display(), to.display()))?;
+    share(to);
+    Ok(())
 }
 
 pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> Result<()> {
     let path = path.as_ref();
     trace!("write {}", path.display());
-    fs::write(path, contents).with_context(|| format!("failed write: {}", path.display()))
+    fs::write(path, contents).with_context(|| format!("failed write: {}", path.display()))?;
+    share(path);
+    Ok(())
 }
 
 pub fn read_to_string<P: AsRef<Path>>(path: P) -> Result<String> {
@@ -70,7 +76,43 @@ pub fn read_to_string<P: AsRef<Path>>(path: P) -> Result<String> {
 pub fn create_dir_all<P: AsRef<Path>>(path: P) -> Result<()> {
     let path = path.as_ref();
     trace!("mkdir -p {}", path.display());
-    fs::create_dir_all(path).with_context(|| format!("failed create_dir_all: {}", path.display()))
+    fs::create_dir_all(path)
+        .with_context(|| format!("failed create_dir_all: {}", path.display()))?;
+    share(path);
+    Ok(())
+}
+
+/// files and directories in RTX_SHARED_CACHE_DIR are made group-writable so every user in the
+/// group can update them. This is done for `path` and its parents up to RTX_SHARED_CACHE_DIR.
+/// Files owned by other users are left alone, they were shared when those users created them.
+pub fn share(path: &Path) {
+    let Some(shared) = &*env::RTX_SHARED_CACHE_DIR else {
+        return;
+    };
+    for p in path.ancestors().take_while(|p| p.starts_with(shared)) {
+        let Ok(metadata) = p.symlink_metadata() else {
+            continue;
+        };
+        if metadata.file_type().is_symlink() {
+            continue;
+        }
+        let mode = metadata.permissions().mode() & 0o7777;
+        let new_mode = shared_mode(mode, metadata.is_dir());
+        if mode != new_mode {
+            trace!("chmod {:o} {}", new_mode, p.display());
+            let _ = fs::set_permissions(p, Permissions::from_mode(new_mode));
+        }
+    }
+}
+
+/// gives the group the same permissions as the owner, what others get is left as the umask set it.
+/// Directories also get setgid so files created in them keep the directory's group.
+fn shared_mode(mode: u32, is_dir: bool) -> u32 {
+    let mode = mode | ((mode & 0o700) >> 3);
+    match is_dir {
+        true => mode | 0o2000,
+        false => mode,
+    }
 }
 
 pub fn basename(path: &Path) -> Option<String> {
@@ -141,6 +183,21 @@ pub fn dir_subdirs(dir: &Path) -> Result<Vec<String>> {
     Ok(output)
 }
 
+/// total size of the files in `dir` in bytes, symlinks are not followed
+pub fn dir_size(dir: &Path) -> Result<u64> {
+    let mut size = 0;
+    for entry in dir.read_dir()? {
+        let entry = entry?;
+        let ft = entry.file_type()?;
+        if ft.is_dir() {
+            size += dir_size(&entry.path())?;
+        } else if ft.is_file() {
+            size += entry.metadata()?.len();
+        }
+    }
+    Ok(size)
+}
+
 pub fn dir_files(dir: &Path) -> Result<Vec<String>> {
     let mut output = vec![];
 
@@ -184,6 +241,25 @@ pub fn remove_symlinks_with_target_prefix(symlink_dir: &Path, target_prefix: &Pa
     Ok(())
 }
 
+/// true if files can be created in `dir`, which is created if it does not exist
+/// checks by writing a file since permissions do not show read-only mounts
+pub fn is_writable(dir: &Path) -> bool {
+    if create_dir_all(dir).is_err() {
+        return false;
+    }
+    let probe = dir.join(format!(".rtx-write-test-{}", std::process::id()));
+    match File::create(&probe) {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            true
+        }
+        Err(err) => {
+            debug!("{} is not writable: {}", display_path(dir), err);
+            false
+        }
+    }
+}
+
 pub fn is_executable(path: &Path) -> bool {
     if let Ok(metadata) = path.metadata() {
         return metadata.permissions().mode() & 0o111 != 0;
@@ -246,12 +322,56 @@ pub fn which(name: &str) -> Option<PathBuf> {
     None
 }
 
-pub fn untar(archive: &Path, dest: &Path) -> Result<()> {
+/// returns every executable named `name` on the current PATH in the order they would be found
+pub fn which_all(name: &str) -> Vec<PathBuf> {
+    let path = env::var_os("PATH").unwrap_or_default();
+    env::split_paths(&path)
+        .map(|p| p.join(name))
+        .filter(|bin| bin.is_file() && is_executable(bin))
+        .collect()
+}
+
+/// with pr the progress is how much of the archive has been read since the extracted size isn't
+/// known upfront
+pub fn untar(archive: &Path, dest: &Path, pr: Option<&ProgressReport>) -> Result<()> {
     debug!("tar -xzf {} -C {}", archive.display(), dest.display());
     let f = File::open(archive)?;
-    let tar = GzDecoder::new(f);
+    if let Some(pr) = pr {
+        pr.set_length(f.metadata()?.len());
+    }
+    let tar = GzDecoder::new(ProgressReader {
+        inner: f,
+        pr,
+        pos: 0,
+    });
     let mut archive = Archive::new(tar);
     archive.unpack(dest)?;
+    if let Some(pr) = pr {
+        pr.reset_length();
+    }
+    Ok(())
+}
+
+struct ProgressReader<'a, R> {
+    inner: R,
+    pr: Option<&'a ProgressReport>,
+    pos: u64,
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.pos += n as u64;
+        if let Some(pr) = self.pr {
+            pr.set_position(self.pos);
+        }
+        Ok(n)
+    }
+}
+
+/// flate2 does not do xz so this uses the system tar
+pub fn untar_xz(archive: &Path, dest: &Path) -> Result<()> {
+    cmd!("tar", "-xJf", archive, "-C", dest).run()?;
     Ok(())
 }
 
@@ -287,6 +407,47 @@ mod tests {
         assert_eq!(find_up.next(), Some(dirs::HOME.join(".test-tool-versions")));
     }
 
+    #[test]
+    fn test_is_writable() {
+        assert!(is_writable(&dirs::CACHE));
+        assert!(!is_writable(Path::new("/dev/null/rtx")));
+    }
+
+    #[test]
+    fn test_untar() {
+        let dir = dirs::CACHE.join("test-untar");
+        remove_all(&dir).unwrap();
+        create_dir_all(&dir).unwrap();
+        let archive = dir.join("archive.tar.gz");
+        let gz = flate2::write::GzEncoder::new(
+            File::create(&archive).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut tar = tar::Builder::new(gz);
+        let data = b"hello";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append_data(&mut header, "hello.txt", &data[..])
+            .unwrap();
+        tar.into_inner().unwrap().finish().unwrap();
+
+        let pr = ProgressReport::new(false);
+        untar(&archive, &dir.join("out"), Some(&pr)).unwrap();
+        assert!(pr.pb.as_ref().unwrap().position() > 0);
+        assert_eq!(read_to_string(dir.join("out/hello.txt")).unwrap(), "hello");
+        remove_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_shared_mode() {
+        assert_eq!(shared_mode(0o644, false), 0o664);
+        assert_eq!(shared_mode(0o755, false), 0o775);
+        assert_eq!(shared_mode(0o700, true), 0o2770);
+        assert_eq!(shared_mode(0o2775, true), 0o2775);
+    }
+
     #[test]
     fn test_find_up_2() {
         let path = &dirs::HOME.join("fixtures");
@@ -4,8 +4,8 @@ use std::path::PathBuf;
 use color_eyre::eyre::{eyre, Result};
 use duct::Expression;
 
-use crate::cmd;
 use crate::file::touch_dir;
+use crate::{cmd, http};
 
 pub struct Git {
     pub dir: PathBuf,
@@ -30,6 +30,7 @@ impl Git {
     }
 
     pub fn update(&self, gitref: Option<String>) -> Result<(String, String)> {
+        http::ensure_network(&format!("git fetch in {}", self.dir.display()))?;
         let gitref = gitref.map_or_else(|| self.current_branch(), Ok)?;
         debug!("updating {} to {}", self.dir.display(), gitref);
         let exec = |cmd: Expression| match cmd.stderr_to_stdout().stdout_capture().unchecked().run()
@@ -72,6 +73,7 @@ impl Git {
     }
 
     pub fn clone(&self, url: &str) -> Result<()> {
+        http::ensure_network(&format!("git clone of {url}"))?;
         debug!("cloning {} to {}", url, self.dir.display());
         if let Some(parent) = self.dir.parent() {
             create_dir_all(parent)?;
@@ -87,6 +89,19 @@ impl Git {
         Ok(())
     }
 
+    /// `git log --oneline` of the commits in `from..to`
+    pub fn log_oneline(&self, from: &str, to: &str) -> Result<Vec<String>> {
+        let range = format!("{from}..{to}");
+        let log = git_cmd!(&self.dir, "log", "--oneline", "--no-decorate", &range).read()?;
+        Ok(log.lines().map(|l| l.to_string()).collect())
+    }
+
+    /// files that differ between `from` and `to`
+    pub fn changed_files(&self, from: &str, to: &str) -> Result<Vec<String>> {
+        let files = git_cmd!(&self.dir, "diff", "--name-only", from, to).read()?;
+        Ok(files.lines().map(|l| l.to_string()).collect())
+    }
+
     pub fn current_branch(&self) -> Result<String> {
         let branch = git_cmd!(&self.dir, "branch", "--show-current").read()?;
         debug!("current branch for {}: {}", self.dir.display(), &branch);
@@ -110,6 +125,13 @@ impl Git {
         Ok(aref)
     }
 
+    /// committer date of HEAD, e.g.: 2023-11-05
+    pub fn current_commit_date(&self) -> Result<String> {
+        let date = git_cmd!(&self.dir, "log", "-1", "--format=%cs", "HEAD").read()?;
+        debug!("current commit date for {}: {}", self.dir.display(), &date);
+        Ok(date)
+    }
+
     pub fn get_remote_url(&self) -> Option<String> {
         if !self.dir.exists() {
             return None;
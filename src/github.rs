@@ -1,11 +1,28 @@
+use serde::Deserializer;
 use serde_derive::Deserialize;
 
 #[derive(Debug, Deserialize)]
 pub struct GithubRelease {
     pub tag_name: String,
+    #[serde(deserialize_with = "null_as_empty")]
     pub name: String,
+    #[serde(deserialize_with = "null_as_empty")]
     pub body: String,
     pub prerelease: bool,
     pub created_at: String,
     pub published_at: String,
+    #[serde(default)]
+    pub assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GithubAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+/// releases created from a tag without editing them have no name or body
+fn null_as_empty<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    let s: Option<String> = serde::Deserialize::deserialize(d)?;
+    Ok(s.unwrap_or_default())
 }
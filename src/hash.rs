@@ -34,6 +34,16 @@ pub fn ensure_checksum_sha256(path: &Path, checksum: &str) -> Result<()> {
     Ok(())
 }
 
+/// SHASUMS256.txt and sha256sum files have lines of "<sha256>  <filename>", the filename may be
+/// prefixed with "*" for binary mode
+pub fn checksum_from_shasums(shasums: &str, filename: &str) -> Option<String> {
+    shasums.lines().find_map(|line| {
+        let (checksum, name) = line.split_once(char::is_whitespace)?;
+        let name = name.trim().trim_start_matches('*');
+        (name == filename).then(|| checksum.to_string())
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use insta::assert_snapshot;
@@ -45,6 +55,28 @@ mod tests {
         assert_eq!(hash_to_str(&"foo"), "3e8b8c44c3ca73b7");
     }
 
+    #[test]
+    fn test_checksum_from_shasums() {
+        let shasums = "\
+abc123  node-v20.9.0-darwin-arm64.tar.gz
+def456  node-v20.9.0-linux-x64.tar.gz
+789abc  node-v20.9.0-linux-x64.tar.xz
+123def *bun-linux-x64.zip
+";
+        assert_eq!(
+            checksum_from_shasums(shasums, "node-v20.9.0-linux-x64.tar.gz"),
+            Some("def456".into())
+        );
+        assert_eq!(
+            checksum_from_shasums(shasums, "node-v20.9.0-win-x64.zip"),
+            None
+        );
+        assert_eq!(
+            checksum_from_shasums(shasums, "bun-linux-x64.zip"),
+            Some("123def".into())
+        );
+    }
+
     #[test]
     fn test_hash_sha256() {
         let path = Path::new(".test-tool-versions");
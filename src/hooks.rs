@@ -0,0 +1,90 @@
+use std::fmt::{Display, Formatter};
+
+use color_eyre::eyre::{Result, WrapErr};
+
+use crate::cmd;
+use crate::config::Settings;
+
+/// lifecycle events which run the shell commands set in [settings.hooks], e.g.:
+///
+/// ```toml
+/// [settings.hooks]
+/// post_rtx_install_node = "corepack enable"
+/// pre_rtx_plugin_update = "echo updating $RTX_PLUGIN_NAME"
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    PreInstall,
+    PostInstall,
+    PreUninstall,
+    PostUninstall,
+    PrePluginAdd,
+    PostPluginAdd,
+    PrePluginUpdate,
+    PostPluginUpdate,
+    PrePluginRemove,
+    PostPluginRemove,
+}
+
+impl Display for Hook {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Hook::PreInstall => "pre_rtx_install",
+            Hook::PostInstall => "post_rtx_install",
+            Hook::PreUninstall => "pre_rtx_uninstall",
+            Hook::PostUninstall => "post_rtx_uninstall",
+            Hook::PrePluginAdd => "pre_rtx_plugin_add",
+            Hook::PostPluginAdd => "post_rtx_plugin_add",
+            Hook::PrePluginUpdate => "pre_rtx_plugin_update",
+            Hook::PostPluginUpdate => "post_rtx_plugin_update",
+            Hook::PrePluginRemove => "pre_rtx_plugin_remove",
+            Hook::PostPluginRemove => "post_rtx_plugin_remove",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// runs the hook for every plugin then the one for this plugin, e.g.: "post_rtx_install" then
+/// "post_rtx_install_node"
+/// the commands get RTX_HOOK, RTX_PLUGIN_NAME and the vars in env describing the event
+pub fn run(settings: &Settings, hook: Hook, plugin: &str, env: &[(&str, String)]) -> Result<()> {
+    for name in [hook.to_string(), format!("{hook}_{plugin}")] {
+        let Some(script) = settings.hooks.get(&name) else {
+            continue;
+        };
+        debug!("running hook {name}: {script}");
+        let mut cmd = cmd!("sh", "-c", script)
+            .env("RTX_HOOK", &name)
+            .env("RTX_PLUGIN_NAME", plugin);
+        for (k, v) in env {
+            cmd = cmd.env(k, v);
+        }
+        // stdout may be eval'd by the shell, e.g.: `rtx install` in hook-env
+        cmd.stdout_to_stderr()
+            .run()
+            .wrap_err_with(|| format!("hook {name} failed: {script}"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        let mut settings = Settings::default();
+        assert!(run(&settings, Hook::PreInstall, "tiny", &[]).is_ok());
+        settings.hooks.insert(
+            "pre_rtx_install".into(),
+            "test \"$RTX_HOOK-$RTX_INSTALL_VERSION\" = pre_rtx_install-1.0.0".into(),
+        );
+        let env = [("RTX_INSTALL_VERSION", "1.0.0".to_string())];
+        assert!(run(&settings, Hook::PreInstall, "tiny", &env).is_ok());
+        settings
+            .hooks
+            .insert("pre_rtx_install_tiny".into(), "exit 1".into());
+        assert!(run(&settings, Hook::PreInstall, "tiny", &env).is_err());
+        assert!(run(&settings, Hook::PreInstall, "dummy", &env).is_ok());
+    }
+}
@@ -1,8 +1,57 @@
-use color_eyre::eyre::{eyre, Result};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{bail, eyre, Result};
+use indicatif::HumanBytes;
+use once_cell::sync::{Lazy, OnceCell};
 use reqwest::blocking::{RequestBuilder, Response};
+use reqwest::header::{ACCEPT_ENCODING, ACCEPT_RANGES, CONTENT_LENGTH, RANGE};
 use reqwest::IntoUrl;
-use std::fs::File;
-use std::path::Path;
+
+use crate::env;
+use crate::file;
+use crate::ui::progress_report::ProgressReport;
+
+/// set by the hot path (hook-env and shims) which runs on every prompt/command
+/// so it must never wait on the network, even if caches are stale
+static NETWORK_DISABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn disable_network() {
+    NETWORK_DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// re-enables network access for explicit installs, e.g.: when the user accepted
+/// a prompt to install missing versions
+pub fn enable_network() {
+    NETWORK_DISABLED.store(false, Ordering::Relaxed);
+}
+
+pub fn is_network_disabled() -> bool {
+    NETWORK_DISABLED.load(Ordering::Relaxed)
+}
+
+/// errors if network access has been disabled by `disable_network`
+pub fn ensure_network(what: &str) -> Result<()> {
+    if is_network_disabled() {
+        bail!("{what} {NETWORK_DISABLED_MSG}, run `rtx install` to refresh");
+    }
+    Ok(())
+}
+
+pub const NETWORK_DISABLED_MSG: &str =
+    "needs network access which is disabled in hook-env and shims";
+
+/// all clients share one connection pool
+static REQWEST: OnceCell<reqwest::blocking::Client> = OnceCell::new();
+
+/// with RTX_DOWNLOAD_SEGMENTS set, files at least this big are downloaded in parallel segments
+/// if the server supports ranges
+const SEGMENT_MIN_SIZE: u64 = 8 * 1024 * 1024;
 
 pub struct Client {
     reqwest: reqwest::blocking::Client,
@@ -10,11 +59,16 @@ pub struct Client {
 
 impl Client {
     pub fn new() -> Result<Self> {
-        let reqwest = reqwest::blocking::ClientBuilder::new()
-            .user_agent(format!("rtx/{}", env!("CARGO_PKG_VERSION")))
-            .gzip(true)
-            .build()?;
-        Ok(Self { reqwest })
+        ensure_network("http request")?;
+        let reqwest = REQWEST.get_or_try_init(|| {
+            reqwest::blocking::ClientBuilder::new()
+                .user_agent(format!("rtx/{}", env!("CARGO_PKG_VERSION")))
+                .gzip(true)
+                .build()
+        })?;
+        Ok(Self {
+            reqwest: reqwest.clone(),
+        })
     }
 
     pub fn get<U: IntoUrl>(&self, url: U) -> RequestBuilder {
@@ -32,13 +86,91 @@ impl Client {
         Ok(text)
     }
 
-    pub fn download_file<U: IntoUrl>(&self, url: U, path: &Path) -> Result<()> {
+    /// downloads `url` to `path` reporting progress on `pr`
+    /// large files are fetched in parallel range requests if RTX_DOWNLOAD_SEGMENTS is set and
+    /// all downloads share the RTX_DOWNLOAD_RATE_LIMIT bandwidth cap
+    pub fn download_file<U: IntoUrl>(
+        &self,
+        url: U,
+        path: &Path,
+        pr: Option<&ProgressReport>,
+    ) -> Result<()> {
         let url = url.into_url()?;
         debug!("Downloading {} to {}", &url, path.display());
-        let mut resp = self.get(url).send()?;
+        let filename = path.file_name().unwrap_or_default().to_string_lossy();
+        let progress = Progress::new(pr, format!("downloading {filename}"));
+        let result = match self.segmented_size(url.clone()) {
+            Some(size) => self.download_segments(url, path, size, &progress),
+            None => self.download_stream(url, path, &progress),
+        };
+        if result.is_err() {
+            let _ = file::remove_file(path);
+        }
+        result
+    }
+
+    /// the size of the file if it should be downloaded in segments
+    fn segmented_size(&self, url: reqwest::Url) -> Option<u64> {
+        if *env::RTX_DOWNLOAD_SEGMENTS < 2 {
+            return None;
+        }
+        let resp = self.reqwest.head(url).send().ok()?;
+        let headers = resp.headers();
+        let ranges = headers.get(ACCEPT_RANGES)?.to_str().ok()?;
+        let size = headers.get(CONTENT_LENGTH)?.to_str().ok()?.parse().ok()?;
+        (resp.status().is_success() && ranges == "bytes" && size >= SEGMENT_MIN_SIZE)
+            .then_some(size)
+    }
+
+    fn download_stream(&self, url: reqwest::Url, path: &Path, progress: &Progress) -> Result<()> {
+        let resp = self.get(url).send()?;
         self.ensure_success(&resp)?;
+        progress.set_total(resp.content_length());
         let mut file = File::create(path)?;
-        resp.copy_to(&mut file)?;
+        copy_limited(resp, &mut file, progress)
+    }
+
+    fn download_segments(
+        &self,
+        url: reqwest::Url,
+        path: &Path,
+        size: u64,
+        progress: &Progress,
+    ) -> Result<()> {
+        File::create(path)?.set_len(size)?;
+        progress.set_total(Some(size));
+        let segments = *env::RTX_DOWNLOAD_SEGMENTS;
+        let segment_size = (size + segments - 1) / segments;
+        thread::scope(|s| {
+            (0..segments)
+                .map(|i| {
+                    let url = url.clone();
+                    s.spawn(move || {
+                        let start = i * segment_size;
+                        if start >= size {
+                            return Ok(());
+                        }
+                        let end = (start + segment_size).min(size) - 1;
+                        // the offsets must be of the file itself, not of a compressed response
+                        let resp = self
+                            .get(url)
+                            .header(RANGE, format!("bytes={start}-{end}"))
+                            .header(ACCEPT_ENCODING, "identity")
+                            .send()?;
+                        self.ensure_success(&resp)?;
+                        if resp.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+                            bail!("server ignored range request for {}", resp.url());
+                        }
+                        let mut file = OpenOptions::new().write(true).open(path)?;
+                        file.seek(SeekFrom::Start(start))?;
+                        copy_limited(resp, &mut file, progress)
+                    })
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|t| t.join().unwrap())
+                .collect::<Result<Vec<()>>>()
+        })?;
         Ok(())
     }
 
@@ -50,3 +182,120 @@ impl Client {
         }
     }
 }
+
+fn copy_limited(mut resp: Response, file: &mut File, progress: &Progress) -> Result<()> {
+    let mut buf = [0; 64 * 1024];
+    loop {
+        let n = resp.read(&mut buf)?;
+        if n == 0 {
+            return Ok(());
+        }
+        file.write_all(&buf[..n])?;
+        if let Some(limiter) = &*RATE_LIMITER {
+            limiter.throttle(n as u64);
+        }
+        progress.add(n as u64);
+    }
+}
+
+/// byte count shown in the progress report message, shared by all segments of a download
+struct Progress<'a> {
+    pr: Option<&'a ProgressReport>,
+    message: String,
+    total: AtomicU64,
+    done: AtomicU64,
+    last_update: Mutex<Instant>,
+}
+
+impl<'a> Progress<'a> {
+    fn new(pr: Option<&'a ProgressReport>, message: String) -> Self {
+        if let Some(pr) = pr {
+            pr.set_message(&message);
+        }
+        Self {
+            pr,
+            message,
+            total: AtomicU64::new(0),
+            done: AtomicU64::new(0),
+            last_update: Mutex::new(Instant::now()),
+        }
+    }
+
+    fn set_total(&self, total: Option<u64>) {
+        let total = total.unwrap_or_default();
+        self.total.store(total, Ordering::Relaxed);
+        match self.pr {
+            Some(pr) if total > 0 => pr.set_length(total),
+            _ => (),
+        }
+    }
+
+    fn add(&self, n: u64) {
+        let done = self.done.fetch_add(n, Ordering::Relaxed) + n;
+        let Some(pr) = self.pr else {
+            return;
+        };
+        let mut last_update = self.last_update.lock().unwrap();
+        if last_update.elapsed() < Duration::from_millis(100) {
+            return;
+        }
+        *last_update = Instant::now();
+        match self.total.load(Ordering::Relaxed) {
+            // without a size there is no rate or ETA to show
+            0 => pr.set_message(format!("{} {}", self.message, HumanBytes(done))),
+            _ => pr.set_position(done),
+        }
+    }
+}
+
+impl Drop for Progress<'_> {
+    fn drop(&mut self) {
+        match self.pr {
+            Some(pr) if *self.total.get_mut() > 0 => pr.reset_length(),
+            _ => (),
+        }
+    }
+}
+
+static RATE_LIMITER: Lazy<Option<RateLimiter>> =
+    Lazy::new(|| env::RTX_DOWNLOAD_RATE_LIMIT.map(RateLimiter::new));
+
+/// caps the combined throughput of all downloads in the process to `rate` bytes/sec
+struct RateLimiter {
+    rate: u64,
+    state: Mutex<(Instant, u64)>,
+}
+
+impl RateLimiter {
+    fn new(rate: u64) -> Self {
+        Self {
+            rate,
+            state: Mutex::new((Instant::now(), 0)),
+        }
+    }
+
+    fn throttle(&self, n: u64) {
+        let wait = {
+            let mut state = self.state.lock().unwrap();
+            state.1 += n;
+            let expected = Duration::from_secs_f64(state.1 as f64 / self.rate as f64);
+            expected.saturating_sub(state.0.elapsed())
+        };
+        if !wait.is_zero() {
+            thread::sleep(wait);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter() {
+        let limiter = RateLimiter::new(1024 * 1024);
+        let start = Instant::now();
+        limiter.throttle(100 * 1024);
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{bail, Result};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::config::{Settings, VerifyInstalls};
+use crate::file::display_path;
+use crate::toolset::ToolVersion;
+use crate::{dirs, file, hash};
+
+/// the executables an install provides, recorded after installing when verify_installs is set
+/// so files changed afterwards (tampering or a corrupted install) can be detected before running
+/// them. Kept in RTX_STATE_DIR rather than in the install dir so it can't be changed with it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    files: BTreeMap<PathBuf, Entry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Entry {
+    size: u64,
+    sha256: String,
+}
+
+fn manifest_path(tv: &ToolVersion) -> PathBuf {
+    dirs::STATE
+        .join("install-manifests")
+        .join(&tv.plugin_name)
+        .join(format!("{}.json", tv.tv_pathname()))
+}
+
+/// records every executable in `bin_paths`, symlinks are followed
+pub fn record(tv: &ToolVersion, bin_paths: &[PathBuf]) -> Result<()> {
+    let mut manifest = Manifest::default();
+    for bin_path in bin_paths {
+        for name in file::dir_files(bin_path).unwrap_or_default() {
+            let path = bin_path.join(name);
+            if file::is_executable(&path) {
+                let entry = entry(&path)?;
+                manifest.files.insert(path, entry);
+            }
+        }
+    }
+    let path = manifest_path(tv);
+    file::create_dir_all(path.parent().unwrap())?;
+    file::write(path, serde_json::to_string_pretty(&manifest)?)
+}
+
+pub fn remove(tv: &ToolVersion) -> Result<()> {
+    file::remove_all(manifest_path(tv))
+}
+
+/// executables which were changed or removed since they were installed
+/// empty if there is no manifest, e.g.: it was installed before verify_installs was set
+pub fn modified_files(tv: &ToolVersion) -> Result<Vec<PathBuf>> {
+    let path = manifest_path(tv);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let manifest: Manifest = serde_json::from_str(&file::read_to_string(&path)?)?;
+    let modified = manifest
+        .files
+        .into_iter()
+        .filter(|(path, expected)| !matches(path, expected))
+        .map(|(path, _)| path)
+        .collect();
+    Ok(modified)
+}
+
+/// run before executing one of the tool's executables from a shim
+pub fn check(settings: &Settings, tv: &ToolVersion) -> Result<()> {
+    if settings.verify_installs == VerifyInstalls::Off {
+        return Ok(());
+    }
+    let modified = modified_files(tv)?;
+    if modified.is_empty() {
+        return Ok(());
+    }
+    let files = modified.iter().map(|p| display_path(p)).collect::<Vec<_>>();
+    let msg = format!(
+        "{tv} was modified after it was installed: {}\nreinstall it with `rtx install --force {tv}`",
+        files.join(", ")
+    );
+    match settings.verify_installs {
+        VerifyInstalls::Error => bail!(msg),
+        _ => {
+            warn!("{msg}");
+            Ok(())
+        }
+    }
+}
+
+fn matches(path: &Path, expected: &Entry) -> bool {
+    let Ok(metadata) = path.metadata() else {
+        return false;
+    };
+    if metadata.len() != expected.size {
+        return false;
+    }
+    // always hashed, the size and mtime can be kept the same by whoever changed the file
+    hash::file_hash_sha256(path).map_or(false, |sha| sha == expected.sha256)
+}
+
+fn entry(path: &Path) -> Result<Entry> {
+    Ok(Entry {
+        size: path.metadata()?.len(),
+        sha256: hash::file_hash_sha256(path)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        let dir = dirs::CACHE.join("test-install-manifest");
+        file::create_dir_all(&dir).unwrap();
+        let bin = dir.join("tool");
+        file::write(&bin, "#!/bin/sh\necho hi\n").unwrap();
+        let expected = entry(&bin).unwrap();
+        assert!(matches(&bin, &expected));
+
+        // same size and mtime, different content
+        let mtime = filetime::FileTime::from_last_modification_time(&bin.metadata().unwrap());
+        file::write(&bin, "#!/bin/sh\necho no\n").unwrap();
+        filetime::set_file_mtime(&bin, mtime).unwrap();
+        assert!(!matches(&bin, &expected));
+
+        file::remove_all(&dir).unwrap();
+        assert!(!matches(&bin, &expected));
+    }
+}
@@ -0,0 +1,53 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use color_eyre::eyre::Result;
+
+use crate::config::Config;
+use crate::toolset::{ToolVersion, Toolset};
+use crate::{dirs, file};
+
+/// when each installed version was last used from a shim, `rtx exec` or activated by hook-env,
+/// so `rtx prune --older-than` keeps versions which are in use but not in a tracked config file.
+/// Recorded as the modified time of an empty file in RTX_STATE_DIR since atime is often disabled.
+fn marker_path(tv: &ToolVersion) -> PathBuf {
+    dirs::STATE
+        .join("last-used")
+        .join(&tv.plugin_name)
+        .join(tv.tv_pathname())
+}
+
+/// markers are only rewritten this often so shims don't write a file every time they run
+const RESOLUTION: Duration = Duration::from_secs(60 * 60);
+
+pub fn touch(tv: &ToolVersion) {
+    if *dirs::STATE_READ_ONLY {
+        return;
+    }
+    let path = marker_path(tv);
+    if file::modified_duration(&path).map_or(false, |d| d < RESOLUTION) {
+        return;
+    }
+    let result = file::create_dir_all(path.parent().unwrap()).and_then(|_| file::write(&path, ""));
+    if let Err(err) = result {
+        debug!("failed to record use of {tv}: {err:#}");
+    }
+}
+
+pub fn touch_toolset(config: &Config, ts: &Toolset) {
+    for (_, tv) in ts.list_current_installed_versions(config) {
+        touch(&tv);
+    }
+}
+
+/// how long ago the version was last used, versions used before this was recorded count from
+/// when they were installed
+pub fn unused_for(tv: &ToolVersion) -> Option<Duration> {
+    file::modified_duration(&marker_path(tv))
+        .or_else(|_| file::modified_duration(&tv.install_path()))
+        .ok()
+}
+
+pub fn remove(tv: &ToolVersion) -> Result<()> {
+    file::remove_all(marker_path(tv))
+}
@@ -10,17 +10,22 @@ mod regex;
 #[macro_use]
 pub mod cli;
 
+mod bin_providers;
+mod build_deps;
 mod build_time;
 mod cache;
+mod checksums;
 pub mod cmd;
 mod config;
 mod default_shorthands;
 mod direnv;
 mod dirs;
 mod duration;
+pub mod embed;
 #[allow(dead_code)]
 mod env;
 mod env_diff;
+mod eol;
 mod errors;
 mod fake_asdf;
 mod file;
@@ -28,14 +33,22 @@ mod git;
 pub mod github;
 mod hash;
 mod hook_env;
+mod hooks;
 mod http;
+mod install_manifest;
+mod last_used;
 mod lock_file;
+mod lockfile;
+mod platform;
 mod plugins;
+mod policy;
 mod rand;
+mod relocate;
 mod runtime_symlinks;
 mod shell;
 mod shims;
 mod shorthands;
+mod task;
 mod tera;
 #[cfg(test)]
 mod test;
@@ -44,3 +57,4 @@ mod toml;
 mod tool;
 mod toolset;
 mod ui;
+mod watch;
@@ -1,7 +1,8 @@
 use crate::dirs;
-use crate::file::create_dir_all;
+use crate::file::{create_dir_all, display_path, share};
 use crate::hash::hash_to_str;
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{bail, Result};
+use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
 
 pub type OnLockedFn = Box<dyn Fn(&Path)>;
@@ -32,7 +33,24 @@ impl LockFile {
         if let Some(parent) = self.path.parent() {
             create_dir_all(parent)?;
         }
-        let mut lock = fslock::LockFile::open(&self.path)?;
+        let mut lock = match fslock::LockFile::open(&self.path) {
+            Ok(lock) => lock,
+            // a lockfile of its own would not keep out the users of the shared cache
+            Err(err)
+                if err.kind() == ErrorKind::PermissionDenied
+                    && *dirs::CACHE != *dirs::USER_CACHE =>
+            {
+                bail!(
+                    "cannot open lockfile {} in RTX_SHARED_CACHE_DIR: {err}\n\
+                    it was likely created by another user without group write access, \
+                    run `chmod g+w {}` as its owner",
+                    display_path(&self.path),
+                    display_path(&self.path)
+                );
+            }
+            Err(err) => return Err(err.into()),
+        };
+        share(&self.path);
         if !lock.try_lock()? {
             if let Some(f) = self.on_locked {
                 f(&self.path)
@@ -0,0 +1,191 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use color_eyre::eyre::Result;
+use once_cell::sync::Lazy;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::file;
+use crate::file::display_path;
+use crate::plugins::PluginName;
+use crate::toolset::{ToolSource, ToolVersion, ToolVersionRequest};
+
+/// `.rtx.lock`, the exact versions that fuzzy requests in the config file next to it resolved to
+/// e.g.: `node = "20"` resolved to 20.3.0, so other machines and CI use 20.3.0 as well.
+/// Not to be confused with [`crate::lock_file`] which locks files while rtx changes them.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Lockfile {
+    #[serde(default)]
+    tools: BTreeMap<PluginName, Vec<LockedVersion>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LockedVersion {
+    request: String,
+    version: String,
+}
+
+pub const FILENAME: &str = ".rtx.lock";
+
+/// lockfiles are read many times while resolving, this keeps each one parsed once per process
+static LOCKFILES: Lazy<Mutex<HashMap<PathBuf, Option<Arc<Lockfile>>>>> =
+    Lazy::new(Default::default);
+
+/// the lockfile for the config file at `config_path`
+pub fn path_for(config_path: &Path) -> PathBuf {
+    config_path.with_file_name(FILENAME)
+}
+
+/// the version a request from a config file was locked to, if it has a lockfile
+pub fn locked_version(source: &ToolSource, plugin_name: &str, request: &str) -> Option<String> {
+    let path = match source {
+        ToolSource::RtxToml(path) | ToolSource::ToolVersions(path) => path_for(path),
+        _ => return None,
+    };
+    let mut lockfiles = LOCKFILES.lock().unwrap();
+    let lockfile = lockfiles
+        .entry(path.clone())
+        .or_insert_with(|| match Lockfile::read(&path) {
+            Ok(lockfile) => lockfile.map(Arc::new),
+            Err(err) => {
+                warn!("failed to read {}: {:#}", display_path(&path), err);
+                None
+            }
+        });
+    lockfile.as_ref()?.get(plugin_name, request)
+}
+
+/// after `rtx upgrade` changed a request in the config file at `config_path`, or resolved it
+/// to a newer version, only updates lockfiles which already exist
+pub fn upgrade(
+    config_path: &Path,
+    plugin_name: &str,
+    old_request: &str,
+    new_request: &str,
+    version: &str,
+) -> Result<()> {
+    let path = path_for(config_path);
+    let Some(mut lockfile) = Lockfile::read(&path)? else {
+        return Ok(());
+    };
+    lockfile.remove(plugin_name, old_request);
+    lockfile.insert(plugin_name, new_request, version);
+    lockfile.save(&path)
+}
+
+impl Lockfile {
+    /// None if the file does not exist
+    pub fn read(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let lockfile = toml::from_str(&file::read_to_string(path)?)?;
+        Ok(Some(lockfile))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents = format!(
+            "# generated by `rtx lock`, commit this file along with the config file\n{}",
+            toml::to_string_pretty(self)?
+        );
+        file::write(path, contents)?;
+        LOCKFILES.lock().unwrap().remove(path);
+        Ok(())
+    }
+
+    /// records the version a request resolved to, requests for exact versions, refs, paths and
+    /// "system" are not recorded as they always resolve to themselves
+    pub fn add(&mut self, tv: &ToolVersion) {
+        match &tv.request {
+            ToolVersionRequest::Version(..)
+            | ToolVersionRequest::Prefix(..)
+            | ToolVersionRequest::Sub { .. } => {}
+            _ => return,
+        }
+        self.insert(&tv.plugin_name, &tv.request.version(), &tv.version);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+
+    fn get(&self, plugin_name: &str, request: &str) -> Option<String> {
+        self.tools
+            .get(plugin_name)?
+            .iter()
+            .find(|lv| lv.request == request)
+            .map(|lv| lv.version.clone())
+    }
+
+    fn insert(&mut self, plugin_name: &str, request: &str, version: &str) {
+        if request == version {
+            return;
+        }
+        self.remove(plugin_name, request);
+        self.tools
+            .entry(plugin_name.to_string())
+            .or_default()
+            .push(LockedVersion {
+                request: request.to_string(),
+                version: version.to_string(),
+            });
+    }
+
+    fn remove(&mut self, plugin_name: &str, request: &str) {
+        if let Some(versions) = self.tools.get_mut(plugin_name) {
+            versions.retain(|lv| lv.request != request);
+            if versions.is_empty() {
+                self.tools.remove(plugin_name);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::dirs;
+
+    use super::*;
+
+    #[test]
+    fn test_lockfile() {
+        let dir = dirs::CACHE.join("test-lockfile");
+        let config_path = dir.join(".rtx.toml");
+        let path = path_for(&config_path);
+        let source = ToolSource::RtxToml(config_path.clone());
+        file::create_dir_all(&dir).unwrap();
+
+        let mut lockfile = Lockfile::default();
+        lockfile.insert("node", "20", "20.3.0");
+        lockfile.insert("node", "18.0.0", "18.0.0");
+        lockfile.insert("python", "3.11", "3.11.5");
+        lockfile.save(&path).unwrap();
+        assert_eq!(
+            locked_version(&source, "node", "20"),
+            Some("20.3.0".to_string())
+        );
+        assert_eq!(locked_version(&source, "node", "18.0.0"), None);
+
+        upgrade(&config_path, "node", "20", "20", "20.4.0").unwrap();
+        assert_eq!(
+            locked_version(&source, "node", "20"),
+            Some("20.4.0".to_string())
+        );
+        upgrade(&config_path, "python", "3.11", "3.12.0", "3.12.0").unwrap();
+        assert_eq!(locked_version(&source, "python", "3.11"), None);
+        assert_eq!(
+            Lockfile::read(&path)
+                .unwrap()
+                .unwrap()
+                .tools
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["node"]
+        );
+
+        file::remove_all(&dir).unwrap();
+    }
+}
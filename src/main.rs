@@ -4,7 +4,7 @@ extern crate log;
 
 use std::process::exit;
 
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{Result, WrapErr};
 use color_eyre::{Help, Report, SectionExt};
 use console::{style, Term};
 
@@ -19,8 +19,11 @@ mod output;
 #[macro_use]
 mod regex;
 
+mod bin_providers;
+mod build_deps;
 pub mod build_time;
 mod cache;
+mod checksums;
 mod cli;
 mod cmd;
 mod config;
@@ -28,8 +31,12 @@ mod default_shorthands;
 mod direnv;
 mod dirs;
 pub mod duration;
+// only used by other crates which depend on the library
+#[allow(dead_code)]
+mod embed;
 mod env;
 mod env_diff;
+mod eol;
 mod errors;
 mod fake_asdf;
 mod file;
@@ -37,16 +44,24 @@ mod git;
 pub mod github;
 mod hash;
 mod hook_env;
+mod hooks;
 mod http;
+mod install_manifest;
+mod last_used;
 mod lock_file;
+mod lockfile;
 mod logger;
 mod migrate;
+mod platform;
 mod plugins;
+mod policy;
 mod rand;
+mod relocate;
 mod runtime_symlinks;
 mod shell;
 mod shims;
 mod shorthands;
+mod task;
 pub mod tera;
 #[cfg(test)]
 mod test;
@@ -55,6 +70,7 @@ mod toml;
 mod tool;
 mod toolset;
 mod ui;
+mod watch;
 
 fn main() -> Result<()> {
     color_eyre::install()?;
@@ -77,6 +93,10 @@ fn run(args: &Vec<String>) -> Result<()> {
 
     // show version before loading config in case of error
     cli::version::print_version_if_requested(&env::ARGS, out);
+    if let Some(cd) = env::cd_arg(args) {
+        env::set_current_dir(&cd)
+            .wrap_err_with(|| format!("failed to change directory to {}", cd.display()))?;
+    }
     if let Err(err) = migrate::run() {
         warn!("Error migrating: {}", err);
     }
@@ -92,6 +112,10 @@ fn run(args: &Vec<String>) -> Result<()> {
 
 fn handle_ctrlc() {
     let _ = ctrlc::set_handler(move || {
+        if cmd::is_foreground_child_running() {
+            // the child got the same SIGINT, its exit status decides ours
+            return;
+        }
         let _ = Term::stderr().show_cursor();
         debug!("Ctrl-C pressed, exiting...");
         exit(1);
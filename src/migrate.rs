@@ -10,6 +10,7 @@ pub fn run() -> Result<()> {
     move_subdirs(&dirs::PLUGINS.join("nodejs"), &dirs::PLUGINS.join("node"))?;
     move_subdirs(&dirs::PLUGINS.join("golang"), &dirs::PLUGINS.join("go"))?;
     move_trusted_configs()?;
+    move_tracked_configs()?;
 
     Ok(())
 }
@@ -33,6 +34,17 @@ fn move_subdirs(from: &Path, to: &Path) -> Result<()> {
     Ok(())
 }
 
+fn move_tracked_configs() -> Result<()> {
+    let from = dirs::ROOT.join("tracked_config_files");
+    let to = dirs::STATE.join("tracked_config_files");
+    if from.exists() && !to.exists() {
+        info!("migrating {} to {}", from.display(), to.display());
+        file::create_dir_all(to.parent().unwrap())?;
+        file::rename(from, to)?;
+    }
+    Ok(())
+}
+
 fn move_trusted_configs() -> Result<()> {
     let from = dirs::CACHE.join("trusted-configs");
     let to = dirs::CONFIG.join("trusted-configs");
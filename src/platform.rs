@@ -0,0 +1,152 @@
+use std::path::Path;
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use versions::Versioning;
+
+use crate::config::Settings;
+use crate::{cmd, file};
+
+/// the platform tools are installed for, normally the one rtx is running on
+/// the target_os/target_arch settings override it, e.g.: to install x64 builds on Apple Silicon to
+/// run under Rosetta, or to prepare installs for a container
+static TARGET: Lazy<RwLock<(Option<String>, Option<String>)>> =
+    Lazy::new(|| RwLock::new((None, None)));
+
+pub fn set_target(settings: &Settings) {
+    *TARGET.write().unwrap() = (settings.target_os.clone(), settings.target_arch.clone());
+}
+
+/// "linux", "macos", etc.
+pub fn os() -> String {
+    let target = TARGET.read().unwrap();
+    normalize_os(target.0.as_deref().unwrap_or(std::env::consts::OS)).to_string()
+}
+
+/// "x64", "arm64", etc. the same names `rtx version` uses
+pub fn arch() -> String {
+    let target = TARGET.read().unwrap();
+    normalize_arch(target.1.as_deref().unwrap_or(std::env::consts::ARCH)).to_string()
+}
+
+/// the C library prebuilt binaries are linked against on linux
+#[derive(Debug, Clone, PartialEq)]
+pub enum Libc {
+    /// the version is None if it could not be determined
+    Glibc(Option<Versioning>),
+    Musl,
+}
+
+static LIBC: Lazy<Option<Libc>> = Lazy::new(detect_libc);
+
+/// None if not installing for linux or if the libc could not be detected
+/// when target_os is set to linux on another OS this assumes glibc
+pub fn libc() -> Option<Libc> {
+    if os() != "linux" {
+        return None;
+    }
+    match &*LIBC {
+        Some(libc) => Some(libc.clone()),
+        None if std::env::consts::OS != "linux" => Some(Libc::Glibc(None)),
+        None => None,
+    }
+}
+
+/// true on alpine and other musl distros where glibc binaries fail with loader errors
+pub fn is_musl() -> bool {
+    libc() == Some(Libc::Musl)
+}
+
+/// true if this is a glibc system with a version older than `min`, e.g.: centos 7 has glibc 2.17
+/// but node 18+ binaries need 2.28
+/// an unknown glibc version is assumed to be new enough
+pub fn glibc_older_than(min: &str) -> bool {
+    match (libc(), Versioning::new(min)) {
+        (Some(Libc::Glibc(Some(v))), Some(min)) => v < min,
+        _ => false,
+    }
+}
+
+fn detect_libc() -> Option<Libc> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    // the musl dynamic loader, e.g.: /lib/ld-musl-x86_64.so.1
+    let has_musl_loader = file::dir_files(Path::new("/lib"))
+        .map(|files| files.iter().any(|f| f.starts_with("ld-musl-")))
+        .unwrap_or(false);
+    if has_musl_loader {
+        return Some(Libc::Musl);
+    }
+    // musl's ldd prints its version to stderr and exits 1
+    let output = cmd!("ldd", "--version")
+        .stderr_to_stdout()
+        .unchecked()
+        .read()
+        .ok()?;
+    let libc = parse_ldd_version(&output);
+    debug!("detected libc: {:?}", libc);
+    libc
+}
+
+fn parse_ldd_version(output: &str) -> Option<Libc> {
+    let first_line = output.lines().next()?;
+    if first_line.contains("musl") {
+        return Some(Libc::Musl);
+    }
+    if !first_line.to_lowercase().contains("glibc") && !first_line.contains("GNU libc") {
+        return None;
+    }
+    // e.g.: "ldd (Ubuntu GLIBC 2.35-0ubuntu3.4) 2.35"
+    let version = first_line
+        .split_whitespace()
+        .last()
+        .and_then(Versioning::new);
+    Some(Libc::Glibc(version))
+}
+
+fn normalize_os(os: &str) -> &str {
+    match os {
+        "darwin" | "macosx" => "macos",
+        os => os,
+    }
+}
+
+fn normalize_arch(arch: &str) -> &str {
+    match arch {
+        "x86_64" | "amd64" => "x64",
+        "aarch64" => "arm64",
+        arch => arch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize_os("darwin"), "macos");
+        assert_eq!(normalize_os("linux"), "linux");
+        assert_eq!(normalize_arch("x86_64"), "x64");
+        assert_eq!(normalize_arch("aarch64"), "arm64");
+        assert_eq!(normalize_arch("arm64"), "arm64");
+    }
+
+    #[test]
+    fn test_parse_ldd_version() {
+        assert_eq!(
+            parse_ldd_version("ldd (Ubuntu GLIBC 2.35-0ubuntu3.4) 2.35\nCopyright"),
+            Some(Libc::Glibc(Versioning::new("2.35")))
+        );
+        assert_eq!(
+            parse_ldd_version("ldd (GNU libc) 2.17\n"),
+            Some(Libc::Glibc(Versioning::new("2.17")))
+        );
+        assert_eq!(
+            parse_ldd_version("musl libc (x86_64)\nVersion 1.2.4\n"),
+            Some(Libc::Musl)
+        );
+        assert_eq!(parse_ldd_version("ldd: not found"), None);
+    }
+}
@@ -0,0 +1,138 @@
+use std::iter::once;
+use std::path::PathBuf;
+
+use color_eyre::eyre::Result;
+use itertools::Itertools;
+use serde_derive::Deserialize;
+use versions::Versioning;
+
+use crate::cache::{CacheManager, CacheStatus};
+use crate::cmd::CmdLineRunner;
+use crate::config::{Config, Settings};
+use crate::plugins::{pathname, Plugin, PluginName, PluginType};
+use crate::toolset::ToolVersion;
+use crate::ui::progress_report::ProgressReport;
+use crate::{dirs, env, file, http};
+
+/// prefix of tools installed from crates.io, e.g.: "cargo:ripgrep"
+pub const PREFIX: &str = "cargo:";
+
+/// installs the binaries of a crate with `cargo install`, or with `cargo binstall` if it is
+/// available to download prebuilt binaries instead of compiling them
+#[derive(Debug)]
+pub struct CargoPlugin {
+    name: PluginName,
+    /// e.g.: "ripgrep"
+    crate_name: String,
+    remote_version_cache: CacheManager<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CratesIoVersions {
+    versions: Vec<CratesIoVersion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CratesIoVersion {
+    num: String,
+    yanked: bool,
+}
+
+impl CargoPlugin {
+    pub fn new(name: PluginName) -> Self {
+        let crate_name = name.trim_start_matches(PREFIX).to_string();
+        let cache_path = dirs::CACHE.join(pathname(&name));
+        Self {
+            remote_version_cache: CacheManager::new(cache_path.join("remote_versions.msgpack.z"))
+                .with_fresh_duration(*env::RTX_FETCH_REMOTE_VERSIONS_CACHE),
+            name,
+            crate_name,
+        }
+    }
+
+    fn fetch_remote_versions(&self) -> Result<Vec<String>> {
+        let http = http::Client::new()?;
+        let url = format!(
+            "https://crates.io/api/v1/crates/{}/versions",
+            self.crate_name
+        );
+        let resp = http.get(url).send()?;
+        http.ensure_success(&resp)?;
+        let body: CratesIoVersions = resp.json()?;
+        let versions = body
+            .versions
+            .into_iter()
+            .filter(|v| !v.yanked)
+            .map(|v| v.num)
+            .sorted_by_cached_key(|s| Versioning::new(s))
+            .collect();
+        Ok(versions)
+    }
+
+    /// cargo is found on the shims first so a rust installed by rtx is used
+    fn path(&self) -> Result<std::ffi::OsString> {
+        Ok(env::join_paths(
+            once(dirs::SHIMS.clone()).chain(env::PATH.iter().cloned()),
+        )?)
+    }
+}
+
+impl Plugin for CargoPlugin {
+    fn name(&self) -> &PluginName {
+        &self.name
+    }
+
+    fn get_type(&self) -> PluginType {
+        PluginType::Cargo
+    }
+
+    fn get_remote_url(&self) -> Option<String> {
+        Some(format!("https://crates.io/crates/{}", self.crate_name))
+    }
+
+    fn depends(&self) -> Vec<PluginName> {
+        vec!["rust".into()]
+    }
+
+    fn cache_status(&self) -> Vec<CacheStatus> {
+        vec![self.remote_version_cache.status()]
+    }
+
+    fn list_remote_versions(&self, _settings: &Settings) -> Result<Vec<String>> {
+        self.remote_version_cache
+            .get_or_try_init(|| self.fetch_remote_versions())
+            .cloned()
+    }
+
+    fn install_version(
+        &self,
+        config: &Config,
+        tv: &ToolVersion,
+        pr: &ProgressReport,
+    ) -> Result<()> {
+        let binstall = *env::RTX_CARGO_BINSTALL && file::which("cargo-binstall").is_some();
+        let mut cmd = CmdLineRunner::new(&config.settings, "cargo")
+            .with_pr(pr)
+            .env("PATH", self.path()?);
+        cmd = match binstall {
+            true => {
+                pr.set_message(format!("cargo binstall {}@{}", self.crate_name, tv.version));
+                cmd.arg("binstall").arg("--no-confirm")
+            }
+            false => {
+                pr.set_message(format!("cargo install {}@{}", self.crate_name, tv.version));
+                cmd.arg("install").arg("--locked")
+            }
+        };
+        cmd.arg("--version")
+            .arg(&tv.version)
+            .arg("--root")
+            .arg(tv.install_path())
+            .arg(&self.crate_name)
+            .execute()
+    }
+
+    fn list_bin_paths(&self, _config: &Config, tv: &ToolVersion) -> Result<Vec<PathBuf>> {
+        Ok(vec![tv.install_path().join("bin")])
+    }
+}
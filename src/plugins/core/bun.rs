@@ -1,10 +1,10 @@
 use std::path::{Path, PathBuf};
 
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{bail, eyre, Result};
 use itertools::Itertools;
 use versions::Versioning;
 
-use crate::cli::version::{ARCH, OS};
+use crate::cache::CacheStatus;
 use crate::cmd::CmdLineRunner;
 use crate::config::{Config, Settings};
 use crate::github::GithubRelease;
@@ -12,7 +12,7 @@ use crate::plugins::core::CorePlugin;
 use crate::plugins::{Plugin, PluginName};
 use crate::toolset::{ToolVersion, ToolVersionRequest};
 use crate::ui::progress_report::ProgressReport;
-use crate::{env, file, http};
+use crate::{env, file, hash, http, platform};
 
 #[derive(Debug)]
 pub struct BunPlugin {
@@ -67,8 +67,24 @@ impl BunPlugin {
         let filename = url.split('/').last().unwrap();
         let tarball_path = tv.download_path().join(filename);
 
-        pr.set_message(format!("downloading {}", &url));
-        http.download_file(&url, &tarball_path)?;
+        http.download_file(&url, &tarball_path, Some(pr))?;
+
+        pr.set_message(format!("verifying {filename}"));
+        let shasums_url = format!(
+            "https://github.com/oven-sh/bun/releases/download/bun-v{}/SHASUMS256.txt",
+            tv.version
+        );
+        let resp = http.get(&shasums_url).send()?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            // early releases have no checksums
+            pr.warn(format!("bun {} has no checksum to verify with", tv.version));
+            return Ok(tarball_path);
+        }
+        http.ensure_success(&resp)?;
+        let shasums = resp.text()?;
+        let checksum = hash::checksum_from_shasums(&shasums, filename)
+            .ok_or_else(|| eyre!("no checksum for {filename} in SHASUMS256.txt"))?;
+        hash::ensure_checksum_sha256(&tarball_path, &checksum)?;
 
         Ok(tarball_path)
     }
@@ -98,6 +114,10 @@ impl Plugin for BunPlugin {
         &self.core.name
     }
 
+    fn cache_status(&self) -> Vec<CacheStatus> {
+        vec![self.core.remote_version_cache.status()]
+    }
+
     fn list_remote_versions(&self, _settings: &Settings) -> Result<Vec<String>> {
         self.core
             .remote_version_cache
@@ -117,6 +137,9 @@ impl Plugin for BunPlugin {
     ) -> Result<()> {
         assert!(matches!(&tv.request, ToolVersionRequest::Version { .. }));
 
+        if platform::is_musl() {
+            bail!("bun only publishes glibc builds, they will not run with musl libc");
+        }
         let tarball_path = self.download(tv, pr)?;
         self.install(tv, pr, &tarball_path)?;
         self.verify(config, tv, pr)?;
@@ -125,22 +148,16 @@ impl Plugin for BunPlugin {
     }
 }
 
-fn os() -> &'static str {
-    if cfg!(target_os = "macos") {
-        "darwin"
-    } else if cfg!(target_os = "linux") {
-        "linux"
-    } else {
-        &OS
+fn os() -> String {
+    match platform::os().as_str() {
+        "macos" => "darwin".into(),
+        os => os.into(),
     }
 }
 
-fn arch() -> &'static str {
-    if cfg!(target_arch = "x86_64") || cfg!(target_arch = "amd64") {
-        "x64"
-    } else if cfg!(target_arch = "aarch64") || cfg!(target_arch = "arm64") {
-        "aarch64"
-    } else {
-        &ARCH
+fn arch() -> String {
+    match platform::arch().as_str() {
+        "arm64" => "aarch64".into(),
+        arch => arch.into(),
     }
 }
@@ -2,11 +2,11 @@ use std::collections::HashMap;
 
 use std::path::{Path, PathBuf};
 
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{bail, eyre, Result};
 use itertools::Itertools;
 use versions::Versioning;
 
-use crate::cli::version::{ARCH, OS};
+use crate::cache::CacheStatus;
 use crate::cmd::CmdLineRunner;
 use crate::config::{Config, Settings};
 use crate::github::GithubRelease;
@@ -14,7 +14,7 @@ use crate::plugins::core::CorePlugin;
 use crate::plugins::{Plugin, PluginName};
 use crate::toolset::{ToolVersion, ToolVersionRequest};
 use crate::ui::progress_report::ProgressReport;
-use crate::{env, file, http};
+use crate::{env, file, hash, http, platform};
 
 #[derive(Debug)]
 pub struct DenoPlugin {
@@ -71,10 +71,23 @@ impl DenoPlugin {
         let filename = url.split('/').last().unwrap();
         let tarball_path = tv.download_path().join(filename);
 
-        pr.set_message(format!("downloading {}", &url));
-        http.download_file(&url, &tarball_path)?;
-
-        // TODO: hash::ensure_checksum_sha256(&tarball_path, &m.sha256)?;
+        http.download_file(&url, &tarball_path, Some(pr))?;
+
+        pr.set_message(format!("verifying {filename}"));
+        let resp = http.get(format!("{url}.sha256sum")).send()?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            // older releases have no checksums
+            pr.warn(format!(
+                "deno {} has no checksum to verify with",
+                tv.version
+            ));
+            return Ok(tarball_path);
+        }
+        http.ensure_success(&resp)?;
+        let sha256sum = resp.text()?;
+        let checksum = checksum_from_sha256sum(&sha256sum, filename)
+            .ok_or_else(|| eyre!("no checksum for {filename} in {url}.sha256sum"))?;
+        hash::ensure_checksum_sha256(&tarball_path, &checksum)?;
 
         Ok(tarball_path)
     }
@@ -99,6 +112,10 @@ impl Plugin for DenoPlugin {
         &self.core.name
     }
 
+    fn cache_status(&self) -> Vec<CacheStatus> {
+        vec![self.core.remote_version_cache.status()]
+    }
+
     fn list_remote_versions(&self, _settings: &Settings) -> Result<Vec<String>> {
         self.core
             .remote_version_cache
@@ -118,6 +135,9 @@ impl Plugin for DenoPlugin {
     ) -> Result<()> {
         assert!(matches!(&tv.request, ToolVersionRequest::Version { .. }));
 
+        if platform::is_musl() {
+            bail!("deno only publishes glibc builds, they will not run with musl libc");
+        }
         let tarball_path = self.download(tv, pr)?;
         self.install(tv, pr, &tarball_path)?;
         self.verify(config, tv, pr)?;
@@ -142,22 +162,47 @@ impl Plugin for DenoPlugin {
     }
 }
 
-fn os() -> &'static str {
-    if cfg!(target_os = "macos") {
-        "apple-darwin"
-    } else if cfg!(target_os = "linux") {
-        "unknown-linux-gnu"
-    } else {
-        &OS
+fn os() -> String {
+    match platform::os().as_str() {
+        "macos" => "apple-darwin".into(),
+        "linux" => "unknown-linux-gnu".into(),
+        os => os.into(),
     }
 }
 
-fn arch() -> &'static str {
-    if cfg!(target_arch = "x86_64") || cfg!(target_arch = "amd64") {
-        "x86_64"
-    } else if cfg!(target_arch = "aarch64") || cfg!(target_arch = "arm64") {
-        "aarch64"
-    } else {
-        &ARCH
+fn arch() -> String {
+    match platform::arch().as_str() {
+        "x64" => "x86_64".into(),
+        "arm64" => "aarch64".into(),
+        arch => arch.into(),
+    }
+}
+
+/// the .sha256sum files are "<sha256>  <filename>" or only the sha256
+fn checksum_from_sha256sum(sha256sum: &str, filename: &str) -> Option<String> {
+    hash::checksum_from_shasums(sha256sum, filename).or_else(|| {
+        let checksum = sha256sum.split_whitespace().next()?;
+        let is_sha256 = checksum.len() == 64 && checksum.chars().all(|c| c.is_ascii_hexdigit());
+        is_sha256.then(|| checksum.to_lowercase())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum_from_sha256sum() {
+        let sha256 = "0a7c4b3c9d1e8f2a6b5c4d3e2f1a0b9c8d7e6f5a4b3c2d1e0f9a8b7c6d5e4f3a";
+        let filename = "deno-x86_64-unknown-linux-gnu.zip";
+        assert_eq!(
+            checksum_from_sha256sum(&format!("{sha256}  {filename}\n"), filename),
+            Some(sha256.into())
+        );
+        assert_eq!(
+            checksum_from_sha256sum(&format!("{sha256}\n"), filename),
+            Some(sha256.into())
+        );
+        assert_eq!(checksum_from_sha256sum("Not Found", filename), None);
     }
 }
@@ -6,14 +6,14 @@ use color_eyre::eyre::Result;
 use itertools::Itertools;
 use versions::Versioning;
 
-use crate::cli::version::{ARCH, OS};
+use crate::cache::CacheStatus;
 use crate::cmd::CmdLineRunner;
 use crate::config::{Config, Settings};
 use crate::plugins::core::CorePlugin;
 use crate::plugins::{Plugin, PluginName};
 use crate::toolset::ToolVersion;
 use crate::ui::progress_report::ProgressReport;
-use crate::{cmd, env, file, hash, http};
+use crate::{cmd, env, file, hash, http, platform};
 
 #[derive(Debug)]
 pub struct GoPlugin {
@@ -97,12 +97,11 @@ impl GoPlugin {
 
     fn download(&self, tv: &ToolVersion, pr: &ProgressReport) -> Result<PathBuf> {
         let http = http::Client::new()?;
-        let filename = format!("go{}.{}-{}.tar.gz", tv.version, platform(), arch());
+        let filename = format!("go{}.{}-{}.tar.gz", tv.version, os(), arch());
         let tarball_url = format!("{}/{}", &*env::RTX_GO_DOWNLOAD_MIRROR, &filename);
         let tarball_path = tv.download_path().join(filename);
 
-        pr.set_message(format!("downloading {}", &tarball_url));
-        http.download_file(&tarball_url, &tarball_path)?;
+        http.download_file(&tarball_url, &tarball_path, Some(pr))?;
 
         self.verify_tarball_checksum(&tarball_url, &tarball_path)?;
 
@@ -124,7 +123,7 @@ impl GoPlugin {
             .unwrap_or_default()
             .to_string_lossy();
         pr.set_message(format!("installing {}", tarball));
-        file::untar(tarball_path, &tv.install_path())?;
+        file::untar(tarball_path, &tv.install_path(), Some(pr))?;
         Ok(())
     }
 
@@ -139,6 +138,10 @@ impl Plugin for GoPlugin {
         &self.core.name
     }
 
+    fn cache_status(&self) -> Vec<CacheStatus> {
+        vec![self.core.remote_version_cache.status()]
+    }
+
     fn list_remote_versions(&self, _settings: &Settings) -> Result<Vec<String>> {
         self.core
             .remote_version_cache
@@ -199,25 +202,18 @@ impl Plugin for GoPlugin {
     }
 }
 
-fn platform() -> &'static str {
-    if cfg!(target_os = "macos") {
-        "darwin"
-    } else {
-        &OS
+fn os() -> String {
+    match platform::os().as_str() {
+        "macos" => "darwin".into(),
+        os => os.into(),
     }
 }
 
-fn arch() -> &'static str {
-    if cfg!(target_arch = "x86_64") || cfg!(target_arch = "amd64") {
-        "amd64"
-    } else if cfg!(target_arch = "i686") || cfg!(target_arch = "i386") || cfg!(target_arch = "386")
-    {
-        "386"
-    } else if cfg!(target_arch = "armv6l") || cfg!(target_arch = "armv7l") {
-        "armv6l"
-    } else if cfg!(target_arch = "aarch64") || cfg!(target_arch = "arm64") {
-        "arm64"
-    } else {
-        &ARCH
+fn arch() -> String {
+    match platform::arch().as_str() {
+        "x64" => "amd64".into(),
+        "x86" => "386".into(),
+        "arm" => "armv6l".into(),
+        arch => arch.into(),
     }
 }
@@ -10,15 +10,14 @@ use once_cell::sync::Lazy;
 use serde_derive::{Deserialize, Serialize};
 use versions::Versioning;
 
-use crate::cache::CacheManager;
-use crate::cli::version::{ARCH, OS};
+use crate::cache::{CacheManager, CacheStatus};
 use crate::cmd::CmdLineRunner;
 use crate::config::{Config, Settings};
 use crate::plugins::core::CorePlugin;
 use crate::plugins::{Plugin, PluginName};
 use crate::toolset::{ToolVersion, ToolVersionRequest};
 use crate::ui::progress_report::ProgressReport;
-use crate::{env, file, hash, http};
+use crate::{env, file, hash, http, platform};
 
 #[derive(Debug)]
 pub struct JavaPlugin {
@@ -61,11 +60,16 @@ impl JavaPlugin {
                 for m in download_java_metadata(&release_type)?.into_iter() {
                     // add openjdk short versions like "java@17.0.0" which default to openjdk
                     if m.vendor == "openjdk" {
-                        if m.version.contains('.') {
-                            metadata.insert(m.version.to_string(), m.clone());
+                        let v = if m.version.contains('.') {
+                            m.version.to_string()
                         } else {
                             // rtx expects full versions like ".0.0"
-                            metadata.insert(format!("{}.0.0", m.version), m.clone());
+                            format!("{}.0.0", m.version)
+                        };
+                        // prefer the build matching the system libc, but fall back to the other
+                        let is_musl = m.features.iter().any(|f| f == "musl");
+                        if is_musl == platform::is_musl() || !metadata.contains_key(&v) {
+                            metadata.insert(v, m.clone());
                         }
                     }
                     metadata.insert(m.to_string(), m);
@@ -112,10 +116,15 @@ impl JavaPlugin {
         let filename = m.url.split('/').last().unwrap();
         let tarball_path = tv.download_path().join(filename);
 
-        pr.set_message(format!("downloading {}", &m.url));
-        http.download_file(&m.url, &tarball_path)?;
+        http.download_file(&m.url, &tarball_path, Some(pr))?;
 
-        hash::ensure_checksum_sha256(&tarball_path, &m.sha256)?;
+        match m.sha256.is_empty() {
+            true => match fetch_disco_checksum(&m.url)? {
+                Some(checksum) => hash::ensure_checksum_sha256(&tarball_path, &checksum)?,
+                None => pr.warn(format!("no checksum to verify {filename} with")),
+            },
+            false => hash::ensure_checksum_sha256(&tarball_path, &m.sha256)?,
+        }
 
         Ok(tarball_path)
     }
@@ -131,7 +140,7 @@ impl JavaPlugin {
         if m.file_type == "zip" {
             file::unzip(tarball_path, &tv.download_path())?;
         } else {
-            file::untar(tarball_path, &tv.download_path())?;
+            file::untar(tarball_path, &tv.download_path(), Some(pr))?;
         }
         self.move_to_install_path(tv, m)
     }
@@ -233,6 +242,14 @@ impl Plugin for JavaPlugin {
         &self.core.name
     }
 
+    fn cache_status(&self) -> Vec<CacheStatus> {
+        vec![
+            self.core.remote_version_cache.status(),
+            self.java_metadata_ga_cache.status(),
+            self.java_metadata_ea_cache.status(),
+        ]
+    }
+
     fn list_remote_versions(&self, _settings: &Settings) -> Result<Vec<String>> {
         self.core
             .remote_version_cache
@@ -290,23 +307,19 @@ impl Plugin for JavaPlugin {
     }
 }
 
-fn os() -> &'static str {
-    if cfg!(target_os = "macos") {
-        "macosx"
-    } else {
-        &OS
+fn os() -> String {
+    match platform::os().as_str() {
+        "macos" => "macosx".into(),
+        os => os.into(),
     }
 }
 
-fn arch() -> &'static str {
-    if cfg!(target_arch = "x86_64") || cfg!(target_arch = "amd64") {
-        "x86_64"
-    } else if cfg!(target_arch = "armv6l") || cfg!(target_arch = "armv7l") {
-        "arm32-vfp-hflt"
-    } else if cfg!(target_arch = "aarch64") || cfg!(target_arch = "arm64") {
-        "aarch64"
-    } else {
-        &ARCH
+fn arch() -> String {
+    match platform::arch().as_str() {
+        "x64" => "x86_64".into(),
+        "arm" => "arm32-vfp-hflt".into(),
+        "arm64" => "aarch64".into(),
+        arch => arch.into(),
     }
 }
 
@@ -360,7 +373,17 @@ static JAVA_FEATURES: Lazy<HashSet<String>> =
 static JAVA_FILE_TYPES: Lazy<HashSet<String>> =
     Lazy::new(|| HashSet::from(["tar.gz", "zip"].map(|s| s.to_string())));
 
+/// the metadata from java.rtx.pub, or from the foojay Disco API if it is unavailable
 fn download_java_metadata(release_type: &str) -> Result<Vec<JavaMetadata>> {
+    download_rtx_java_metadata(release_type).or_else(|err| {
+        warn!(
+            "failed to fetch java metadata from java.rtx.pub, using the foojay Disco API: {err:#}"
+        );
+        download_disco_java_metadata(release_type)
+    })
+}
+
+fn download_rtx_java_metadata(release_type: &str) -> Result<Vec<JavaMetadata>> {
     let http = http::Client::new()?;
     let url = format!(
         "https://java.rtx.pub/metadata/{}/{}/{}.json",
@@ -378,3 +401,141 @@ fn download_java_metadata(release_type: &str) -> Result<Vec<JavaMetadata>> {
         .collect();
     Ok(metadata)
 }
+
+const DISCO_API: &str = "https://api.foojay.io/disco/v3.0";
+
+#[derive(Debug, Deserialize)]
+struct DiscoResponse<T> {
+    result: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscoPackage {
+    archive_type: String,
+    distribution: String,
+    java_version: String,
+    release_status: String,
+    operating_system: String,
+    #[serde(default)]
+    lib_c_type: String,
+    architecture: String,
+    package_type: String,
+    #[serde(default)]
+    javafx_bundled: bool,
+    links: DiscoLinks,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscoLinks {
+    /// e.g.: https://api.foojay.io/disco/v3.0/ids/<id>/redirect
+    pkg_download_redirect: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscoPackageInfo {
+    #[serde(default)]
+    checksum: String,
+    #[serde(default)]
+    checksum_type: String,
+}
+
+/// builds of all the distributions foojay knows about, e.g.: temurin, zulu, graalvm_community
+fn download_disco_java_metadata(release_type: &str) -> Result<Vec<JavaMetadata>> {
+    let http = http::Client::new()?;
+    let url = format!(
+        "{DISCO_API}/packages?os={}&architecture={}&release_status={release_type}\
+        &archive_type=tar.gz&archive_type=zip&directly_downloadable=true",
+        platform::os(),
+        platform::arch(),
+    );
+    let resp = http.get(url).send()?;
+    http.ensure_success(&resp)?;
+    let metadata = resp
+        .json::<DiscoResponse<DiscoPackage>>()?
+        .result
+        .into_iter()
+        .map(JavaMetadata::from)
+        .filter(|m| JAVA_FILE_TYPES.contains(&m.file_type))
+        .collect();
+    Ok(metadata)
+}
+
+/// the Disco API has the checksums of packages in their details instead of in the package list
+fn fetch_disco_checksum(url: &str) -> Result<Option<String>> {
+    let Some(info_url) = url.strip_suffix("/redirect") else {
+        return Ok(None);
+    };
+    let http = http::Client::new()?;
+    let resp = http.get(info_url).send()?;
+    http.ensure_success(&resp)?;
+    let info = resp.json::<DiscoResponse<DiscoPackageInfo>>()?;
+    Ok(info
+        .result
+        .into_iter()
+        .find(|i| i.checksum_type == "sha256" && !i.checksum.is_empty())
+        .map(|i| i.checksum))
+}
+
+impl From<DiscoPackage> for JavaMetadata {
+    fn from(p: DiscoPackage) -> Self {
+        let mut features = vec![];
+        if p.lib_c_type == "musl" {
+            features.push("musl".to_string());
+        }
+        if p.javafx_bundled {
+            features.push("javafx".to_string());
+        }
+        let vendor = match p.distribution.as_str() {
+            "oracle_open_jdk" => "openjdk".to_string(),
+            d => d.replace('_', "-"),
+        };
+        let jvm_impl = match vendor.starts_with("semeru") {
+            true => "openj9",
+            false => "hotspot",
+        };
+        JavaMetadata {
+            vendor,
+            release_type: p.release_status,
+            version: p.java_version,
+            jvm_impl: jvm_impl.to_string(),
+            os: p.operating_system,
+            architecture: p.architecture,
+            file_type: p.archive_type,
+            image_type: p.package_type,
+            features,
+            url: p.links.pkg_download_redirect,
+            sha256: String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disco_package_to_metadata() {
+        let package: DiscoPackage = serde_json::from_str(
+            r#"{
+                "archive_type": "tar.gz",
+                "distribution": "graalvm_community",
+                "java_version": "21.0.1",
+                "release_status": "ga",
+                "operating_system": "linux",
+                "lib_c_type": "glibc",
+                "architecture": "x64",
+                "package_type": "jdk",
+                "javafx_bundled": false,
+                "links": {
+                    "pkg_info_uri": "https://api.foojay.io/disco/v3.0/ids/abc",
+                    "pkg_download_redirect": "https://api.foojay.io/disco/v3.0/ids/abc/redirect"
+                }
+            }"#,
+        )
+        .unwrap();
+        let m = JavaMetadata::from(package);
+        assert_eq!(m.to_string(), "graalvm-community-21.0.1");
+        assert_eq!(m.sha256, "");
+        assert_eq!(m.url, "https://api.foojay.io/disco/v3.0/ids/abc/redirect");
+    }
+}
@@ -0,0 +1,117 @@
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{bail, Result};
+use itertools::Itertools;
+use versions::Versioning;
+
+use crate::cache::CacheStatus;
+use crate::config::{Config, Settings};
+use crate::github::GithubRelease;
+use crate::plugins::core::CorePlugin;
+use crate::plugins::{Plugin, PluginName};
+use crate::toolset::ToolVersion;
+use crate::ui::progress_report::ProgressReport;
+use crate::{env, file, hash, http};
+
+#[derive(Debug)]
+pub struct KotlinPlugin {
+    core: CorePlugin,
+}
+
+impl KotlinPlugin {
+    pub fn new(name: PluginName) -> Self {
+        let core = CorePlugin::new(name);
+        Self { core }
+    }
+
+    fn fetch_remote_versions(&self) -> Result<Vec<String>> {
+        let http = http::Client::new()?;
+        let mut req =
+            http.get("https://api.github.com/repos/JetBrains/kotlin/releases?per_page=100");
+        if let Some(token) = &*env::GITHUB_API_TOKEN {
+            req = req.header("authorization", format!("token {}", token));
+        }
+        let resp = req.send()?;
+        http.ensure_success(&resp)?;
+        let releases: Vec<GithubRelease> = resp.json()?;
+        let versions = releases
+            .into_iter()
+            .filter(|r| !r.prerelease)
+            .filter_map(|r| r.tag_name.strip_prefix('v').map(|v| v.to_string()))
+            .unique()
+            .sorted_by_cached_key(|s| Versioning::new(s))
+            .collect();
+        Ok(versions)
+    }
+
+    fn kotlinc_bin(&self, tv: &ToolVersion) -> PathBuf {
+        tv.install_path().join("bin/kotlinc")
+    }
+
+    fn download(&self, tv: &ToolVersion, pr: &ProgressReport) -> Result<PathBuf> {
+        let http = http::Client::new()?;
+        let url = format!(
+            "https://github.com/JetBrains/kotlin/releases/download/v{0}/kotlin-compiler-{0}.zip",
+            tv.version
+        );
+        let filename = url.split('/').next_back().unwrap();
+        let zip_path = tv.download_path().join(filename);
+
+        http.download_file(&url, &zip_path, Some(pr))?;
+        // e.g.: "<sha256>  kotlin-compiler-1.9.21.zip"
+        let checksum = http.get_text(format!("{url}.sha256"))?;
+        let checksum = checksum.split_whitespace().next().unwrap_or_default();
+        hash::ensure_checksum_sha256(&zip_path, checksum)?;
+
+        Ok(zip_path)
+    }
+
+    fn install(&self, tv: &ToolVersion, pr: &ProgressReport, zip_path: &Path) -> Result<()> {
+        let filename = file::basename(zip_path).unwrap_or_default();
+        pr.set_message(format!("installing {}", filename));
+        let extracted = tv.download_path().join("kotlinc");
+        file::remove_all(&extracted)?;
+        file::unzip(zip_path, &tv.download_path())?;
+        file::remove_all(tv.install_path())?;
+        file::rename(extracted, tv.install_path())?;
+        Ok(())
+    }
+
+    /// kotlinc is not run since it needs a java runtime, which may not be installed yet
+    fn verify(&self, tv: &ToolVersion) -> Result<()> {
+        if !self.kotlinc_bin(tv).is_file() {
+            bail!("kotlinc was not found in kotlin {}", tv.version);
+        }
+        Ok(())
+    }
+}
+
+impl Plugin for KotlinPlugin {
+    fn name(&self) -> &PluginName {
+        &self.core.name
+    }
+
+    fn cache_status(&self) -> Vec<CacheStatus> {
+        vec![self.core.remote_version_cache.status()]
+    }
+
+    fn list_remote_versions(&self, _settings: &Settings) -> Result<Vec<String>> {
+        self.core
+            .remote_version_cache
+            .get_or_try_init(|| self.fetch_remote_versions())
+            .cloned()
+    }
+
+    fn install_version(
+        &self,
+        _config: &Config,
+        tv: &ToolVersion,
+        pr: &ProgressReport,
+    ) -> Result<()> {
+        let zip_path = self.download(tv, pr)?;
+        self.install(tv, pr, &zip_path)?;
+        self.verify(tv)?;
+
+        Ok(())
+    }
+}
@@ -13,8 +13,13 @@ use crate::plugins::core::bun::BunPlugin;
 use crate::plugins::core::deno::DenoPlugin;
 use crate::plugins::core::go::GoPlugin;
 use crate::plugins::core::java::JavaPlugin;
+use crate::plugins::core::kotlin::KotlinPlugin;
+use crate::plugins::core::nim::NimPlugin;
 use crate::plugins::core::node::NodePlugin;
 use crate::plugins::core::ruby::RubyPlugin;
+use crate::plugins::core::rust::RustPlugin;
+use crate::plugins::core::swift::SwiftPlugin;
+use crate::plugins::core::zig::ZigPlugin;
 use crate::plugins::{Plugin, PluginName};
 use crate::timeout::run_with_timeout;
 use crate::tool::Tool;
@@ -25,9 +30,14 @@ mod bun;
 mod deno;
 mod go;
 mod java;
+mod kotlin;
+mod nim;
 mod node;
 mod python;
 mod ruby;
+mod rust;
+mod swift;
+mod zig;
 
 type ToolMap = BTreeMap<PluginName, Arc<Tool>>;
 
@@ -45,6 +55,11 @@ pub static EXPERIMENTAL_CORE_PLUGINS: Lazy<ToolMap> = Lazy::new(|| {
     build_core_plugins(vec![
         Box::new(BunPlugin::new("bun".to_string())),
         Box::new(DenoPlugin::new("deno".to_string())),
+        Box::new(KotlinPlugin::new("kotlin".to_string())),
+        Box::new(NimPlugin::new("nim".to_string())),
+        Box::new(RustPlugin::new("rust".to_string())),
+        Box::new(SwiftPlugin::new("swift".to_string())),
+        Box::new(ZigPlugin::new("zig".to_string())),
     ])
 });
 
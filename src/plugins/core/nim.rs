@@ -0,0 +1,155 @@
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::Result;
+use itertools::Itertools;
+use versions::Versioning;
+
+use crate::cache::CacheStatus;
+use crate::cmd::CmdLineRunner;
+use crate::config::{Config, Settings};
+use crate::plugins::core::CorePlugin;
+use crate::plugins::{Plugin, PluginName};
+use crate::toolset::ToolVersion;
+use crate::ui::progress_report::ProgressReport;
+use crate::{cmd, file, hash, http, platform};
+
+#[derive(Debug)]
+pub struct NimPlugin {
+    core: CorePlugin,
+}
+
+impl NimPlugin {
+    pub fn new(name: PluginName) -> Self {
+        let core = CorePlugin::new(name);
+        Self { core }
+    }
+
+    fn fetch_remote_versions(&self) -> Result<Vec<String>> {
+        CorePlugin::run_fetch_task_with_timeout(move || {
+            let output = cmd!(
+                "git",
+                "ls-remote",
+                "--tags",
+                "https://github.com/nim-lang/Nim",
+                "v*"
+            )
+            .read()?;
+            let versions = output
+                .lines()
+                .filter_map(|s| s.split("refs/tags/v").last())
+                .filter(|s| regex!(r"^[0-9]+\.[0-9]+\.[0-9]+$").is_match(s))
+                .map(|s| s.to_string())
+                .unique()
+                .sorted_by_cached_key(|s| Versioning::new(s))
+                .collect();
+            Ok(versions)
+        })
+    }
+
+    fn nim_bin(&self, tv: &ToolVersion) -> PathBuf {
+        tv.install_path().join("bin/nim")
+    }
+
+    fn test_nim(&self, config: &Config, tv: &ToolVersion, pr: &ProgressReport) -> Result<()> {
+        pr.set_message("nim -v");
+        CmdLineRunner::new(&config.settings, self.nim_bin(tv))
+            .with_pr(pr)
+            .arg("-v")
+            .execute()
+    }
+
+    fn download(&self, tv: &ToolVersion, pr: &ProgressReport) -> Result<PathBuf> {
+        let http = http::Client::new()?;
+        let filename = match prebuilt_platform() {
+            Some(platform) => format!("nim-{}-{}.tar.xz", tv.version, platform),
+            None => format!("nim-{}.tar.xz", tv.version),
+        };
+        let tarball_url = format!("https://nim-lang.org/download/{}", filename);
+        let tarball_path = tv.download_path().join(filename);
+
+        http.download_file(&tarball_url, &tarball_path, Some(pr))?;
+
+        // the checksum file is in `sha256sum` format: "<hash>  <filename>"
+        let checksum = http.get_text(format!("{}.sha256", tarball_url))?;
+        let checksum = checksum.split_whitespace().next().unwrap_or_default();
+        hash::ensure_checksum_sha256(&tarball_path, checksum)?;
+
+        Ok(tarball_path)
+    }
+
+    fn install(&self, tv: &ToolVersion, pr: &ProgressReport, tarball_path: &Path) -> Result<()> {
+        let tarball = file::basename(tarball_path).unwrap_or_default();
+        pr.set_message(format!("installing {}", tarball));
+        file::untar_xz(tarball_path, &tv.download_path())?;
+        // both the source and binary tarballs extract to nim-<version>/
+        file::remove_all(tv.install_path())?;
+        file::rename(
+            tv.download_path().join(format!("nim-{}", tv.version)),
+            tv.install_path(),
+        )?;
+        Ok(())
+    }
+
+    /// the source tarball contains the generated C code for the compiler, so it only needs a C
+    /// compiler to build. See https://nim-lang.org/install_unix.html
+    fn build(&self, config: &Config, tv: &ToolVersion, pr: &ProgressReport) -> Result<()> {
+        let run = |program: &str, args: &[&str]| {
+            pr.set_message(format!("{} {}", program, args.join(" ")));
+            CmdLineRunner::new(&config.settings, program)
+                .with_pr(pr)
+                .current_dir(tv.install_path())
+                .args(args)
+                .execute()
+        };
+        run("sh", &["build.sh"])?;
+        run("bin/nim", &["c", "koch"])?;
+        run("./koch", &["boot", "-d:release"])?;
+        run("./koch", &["tools"])
+    }
+
+    fn verify(&self, config: &Config, tv: &ToolVersion, pr: &ProgressReport) -> Result<()> {
+        self.test_nim(config, tv, pr)
+    }
+}
+
+impl Plugin for NimPlugin {
+    fn name(&self) -> &PluginName {
+        &self.core.name
+    }
+
+    fn cache_status(&self) -> Vec<CacheStatus> {
+        vec![self.core.remote_version_cache.status()]
+    }
+
+    fn list_remote_versions(&self, _settings: &Settings) -> Result<Vec<String>> {
+        self.core
+            .remote_version_cache
+            .get_or_try_init(|| self.fetch_remote_versions())
+            .cloned()
+    }
+
+    fn install_version(
+        &self,
+        config: &Config,
+        tv: &ToolVersion,
+        pr: &ProgressReport,
+    ) -> Result<()> {
+        let tarball_path = self.download(tv, pr)?;
+        self.install(tv, pr, &tarball_path)?;
+        if prebuilt_platform().is_none() {
+            self.build(config, tv, pr)?;
+        }
+        self.verify(config, tv, pr)?;
+
+        Ok(())
+    }
+}
+
+/// nim-lang.org only publishes glibc binaries for linux x86, everything else builds from source
+fn prebuilt_platform() -> Option<&'static str> {
+    if platform::os() == "linux" && platform::arch() == "x64" && !platform::is_musl() {
+        Some("linux_x64")
+    } else {
+        None
+    }
+}
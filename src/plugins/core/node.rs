@@ -4,12 +4,16 @@ use std::path::{Path, PathBuf};
 use std::process::exit;
 
 use clap::Command;
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{eyre, Result};
+use itertools::Itertools;
+use serde_derive::Deserialize;
+use versions::Versioning;
 
+use crate::cache::CacheStatus;
 use crate::cmd::CmdLineRunner;
 use crate::config::{Config, Settings};
 use crate::duration::DAILY;
-use crate::env::{RTX_NODE_CONCURRENCY, RTX_NODE_FORCE_COMPILE};
+use crate::env::{RTX_NODE_CONCURRENCY, RTX_NODE_FORCE_COMPILE, RTX_NODE_MIRROR_URL};
 use crate::file::create_dir_all;
 use crate::git::Git;
 use crate::lock_file::LockFile;
@@ -17,7 +21,7 @@ use crate::plugins::core::CorePlugin;
 use crate::plugins::{Plugin, PluginName};
 use crate::toolset::{ToolVersion, ToolVersionRequest};
 use crate::ui::progress_report::ProgressReport;
-use crate::{cmd, env, file};
+use crate::{cmd, env, file, hash, http, platform};
 
 #[derive(Debug)]
 pub struct NodePlugin {
@@ -84,19 +88,77 @@ impl NodePlugin {
     }
 
     fn fetch_remote_versions(&self) -> Result<Vec<String>> {
-        self.install_or_update_node_build()?;
-        let node_build_bin = self.node_build_bin();
         CorePlugin::run_fetch_task_with_timeout(move || {
-            let output = cmd!(node_build_bin, "--definitions").read()?;
-            let versions = output
-                .split('\n')
-                .filter(|s| regex!(r"^[0-9].+$").is_match(s))
-                .map(|s| s.to_string())
+            let http = http::Client::new()?;
+            let resp = http
+                .get(format!("{}index.json", *RTX_NODE_MIRROR_URL))
+                .send()?;
+            http.ensure_success(&resp)?;
+            let releases: Vec<NodeRelease> = resp.json()?;
+            let versions = releases
+                .into_iter()
+                .map(|r| r.version.trim_start_matches('v').to_string())
+                .unique()
+                .sorted_by_cached_key(|s| Versioning::new(s))
                 .collect();
             Ok(versions)
         })
     }
 
+    fn install_precompiled(&self, tv: &ToolVersion, pr: &ProgressReport) -> Result<()> {
+        let http = http::Client::new()?;
+        let dirname = format!("node-v{}-{}-{}", tv.version, os(), arch());
+        let filename = format!("{dirname}.tar.gz");
+        let base_url = format!("{}v{}/", *RTX_NODE_MIRROR_URL, tv.version);
+        let tarball_path = tv.download_path().join(&filename);
+
+        pr.set_message("fetching SHASUMS256.txt");
+        let shasums = http.get_text(format!("{base_url}SHASUMS256.txt"))?;
+        let checksum = hash::checksum_from_shasums(&shasums, &filename)
+            .ok_or_else(|| eyre!("no checksum for {filename} in SHASUMS256.txt"))?;
+
+        http.download_file(format!("{base_url}{filename}"), &tarball_path, Some(pr))?;
+        pr.set_message(format!("verifying {filename}"));
+        hash::ensure_checksum_sha256(&tarball_path, &checksum)?;
+
+        pr.set_message(format!("extracting {filename}"));
+        file::untar(&tarball_path, &tv.download_path(), Some(pr))?;
+        file::remove_all(tv.install_path())?;
+        file::rename(tv.download_path().join(dirname), tv.install_path())?;
+        Ok(())
+    }
+
+    fn install_with_node_build(
+        &self,
+        config: &Config,
+        tv: &ToolVersion,
+        pr: &ProgressReport,
+        compile: bool,
+    ) -> Result<()> {
+        self.install_or_update_node_build()?;
+        pr.set_message("running node-build");
+        let mut cmd = CmdLineRunner::new(&config.settings, self.node_build_bin())
+            .with_pr(pr)
+            .arg(tv.version.as_str());
+        if compile {
+            let make_opts = String::from(" -j") + &RTX_NODE_CONCURRENCY.to_string();
+            cmd = cmd
+                .env(
+                    "MAKE_OPTS",
+                    env::var("MAKE_OPTS").unwrap_or_default() + &make_opts,
+                )
+                .env(
+                    "NODE_MAKE_OPTS",
+                    env::var("NODE_MAKE_OPTS").unwrap_or_default() + &make_opts,
+                )
+                .arg("--compile");
+        }
+        if self.verbose_install(&config.settings) {
+            cmd = cmd.arg("--verbose");
+        }
+        cmd.arg(tv.install_path()).execute()
+    }
+
     fn node_path(&self, tv: &ToolVersion) -> PathBuf {
         tv.install_path().join("bin/node")
     }
@@ -170,6 +232,10 @@ impl Plugin for NodePlugin {
         &self.core.name
     }
 
+    fn cache_status(&self) -> Vec<CacheStatus> {
+        vec![self.core.remote_version_cache.status()]
+    }
+
     fn list_remote_versions(&self, _settings: &Settings) -> Result<Vec<String>> {
         self.core
             .remote_version_cache
@@ -255,28 +321,17 @@ impl Plugin for NodePlugin {
         tv: &ToolVersion,
         pr: &ProgressReport,
     ) -> Result<()> {
-        self.install_node_build()?;
-        pr.set_message("running node-build");
-        let mut cmd = CmdLineRunner::new(&config.settings, self.node_build_bin())
-            .with_pr(pr)
-            .arg(tv.version.as_str());
-        if matches!(&tv.request, ToolVersionRequest::Ref { .. }) || *RTX_NODE_FORCE_COMPILE {
-            let make_opts = String::from(" -j") + &RTX_NODE_CONCURRENCY.to_string();
-            cmd = cmd
-                .env(
-                    "MAKE_OPTS",
-                    env::var("MAKE_OPTS").unwrap_or_default() + &make_opts,
-                )
-                .env(
-                    "NODE_MAKE_OPTS",
-                    env::var("NODE_MAKE_OPTS").unwrap_or_default() + &make_opts,
-                )
-                .arg("--compile");
-        }
-        if self.verbose_install(&config.settings) {
-            cmd = cmd.arg("--verbose");
+        if matches!(&tv.request, ToolVersionRequest::Ref { .. })
+            || *RTX_NODE_FORCE_COMPILE
+            || !has_compatible_binary(&tv.version)
+        {
+            self.install_with_node_build(config, tv, pr, true)?;
+        } else if !regex!(r"^[0-9]").is_match(&tv.version) {
+            // node-build variations, e.g.: chakracore-8.1.2, only node-build has definitions for them
+            self.install_with_node_build(config, tv, pr, false)?;
+        } else {
+            self.install_precompiled(tv, pr)?;
         }
-        cmd.arg(tv.install_path()).execute()?;
         self.test_node(config, tv, pr)?;
         self.install_npm_shim(tv)?;
         self.test_npm(config, tv, pr)?;
@@ -284,3 +339,47 @@ impl Plugin for NodePlugin {
         Ok(())
     }
 }
+
+#[derive(Debug, Deserialize)]
+struct NodeRelease {
+    version: String,
+}
+
+fn os() -> String {
+    match platform::os().as_str() {
+        "macos" => "darwin".into(),
+        os => os.into(),
+    }
+}
+
+fn arch() -> String {
+    match platform::arch().as_str() {
+        "arm" => "armv7l".into(),
+        arch => arch.into(),
+    }
+}
+
+/// nodejs.org only publishes glibc binaries and node 18+ needs glibc 2.28 or newer
+/// (e.g.: centos 7 has 2.17), otherwise node-build compiles from source
+fn has_compatible_binary(version: &str) -> bool {
+    let major = version
+        .split('.')
+        .next()
+        .and_then(|v| v.parse::<u32>().ok());
+    if !matches!(platform::os().as_str(), "linux" | "macos") {
+        debug!(
+            "no node binaries for {}, compiling {version} from source",
+            platform::os()
+        );
+        return false;
+    }
+    if platform::is_musl() {
+        debug!("no node binaries for musl libc, compiling {version} from source");
+        return false;
+    }
+    if major.map_or(false, |m| m >= 18) && platform::glibc_older_than("2.28") {
+        debug!("node {version} binaries need glibc 2.28, compiling from source");
+        return false;
+    }
+    true
+}
@@ -3,29 +3,42 @@ use std::path::{Path, PathBuf};
 
 use color_eyre::eyre::{eyre, Result};
 
+use crate::cache::{CacheManager, CacheStatus};
 use crate::cmd::CmdLineRunner;
 use crate::config::{Config, Settings};
 
 use crate::file::create_dir_all;
 use crate::git::Git;
+use crate::github::GithubRelease;
 use crate::plugins::core::CorePlugin;
 use crate::plugins::{Plugin, PluginName};
 use crate::toolset::{ToolVersion, ToolVersionRequest};
 use crate::ui::progress_report::ProgressReport;
-use crate::{cmd, env, file, http};
+use crate::{cmd, env, file, http, platform};
 
 #[derive(Debug)]
 pub struct PythonPlugin {
     core: CorePlugin,
+    /// (version, url) of the builds in the latest python-build-standalone release for this platform
+    precompiled_cache: CacheManager<Vec<(String, String)>>,
 }
 
 impl PythonPlugin {
     pub fn new(name: PluginName) -> Self {
+        let core = CorePlugin::new(name);
         Self {
-            core: CorePlugin::new(name),
+            precompiled_cache: CacheManager::new(core.cache_path.join("precompiled.msgpack.z"))
+                .with_fresh_duration(*env::RTX_FETCH_REMOTE_VERSIONS_CACHE),
+            core,
         }
     }
 
+    /// tarballs downloaded by python-build and precompiled builds, kept so reinstalling a version
+    /// does not download it again
+    fn downloads_path(&self) -> PathBuf {
+        self.core.cache_path.join("downloads")
+    }
+
     fn python_build_path(&self) -> PathBuf {
         self.core.cache_path.join("pyenv")
     }
@@ -71,6 +84,108 @@ impl PythonPlugin {
         })
     }
 
+    fn fetch_precompiled(&self) -> Result<Vec<(String, String)>> {
+        let Some(target) =
+            precompiled_target(&platform::os(), &platform::arch(), platform::is_musl())
+        else {
+            return Ok(vec![]);
+        };
+        let http = http::Client::new()?;
+        let mut req = http
+            .get("https://api.github.com/repos/indygreg/python-build-standalone/releases/latest");
+        if let Some(token) = &*env::GITHUB_API_TOKEN {
+            req = req.header("authorization", format!("token {}", token));
+        }
+        let resp = req.send()?;
+        http.ensure_success(&resp)?;
+        let release: GithubRelease = resp.json()?;
+        let builds = release
+            .assets
+            .into_iter()
+            .filter_map(|a| {
+                let version = precompiled_version(&a.name, target)?;
+                Some((version.to_string(), a.browser_download_url))
+            })
+            .collect();
+        Ok(builds)
+    }
+
+    /// the url of the precompiled build of this version, only the latest patch versions are built
+    fn precompiled_url(&self, tv: &ToolVersion) -> Result<Option<String>> {
+        let builds = self
+            .precompiled_cache
+            .get_or_try_init(|| self.fetch_precompiled())?;
+        Ok(builds
+            .iter()
+            .find(|(v, _)| v == &tv.version)
+            .map(|(_, url)| url.clone()))
+    }
+
+    fn install_precompiled(&self, tv: &ToolVersion, pr: &ProgressReport, url: &str) -> Result<()> {
+        let filename = url.rsplit('/').next().unwrap();
+        let tarball = self.downloads_path().join(filename);
+        if !tarball.exists() {
+            create_dir_all(self.downloads_path())?;
+            pr.set_message(format!("downloading {filename}"));
+            http::Client::new()?.download_file(url, &tarball, Some(pr))?;
+        }
+        pr.set_message(format!("installing {filename}"));
+        // the builds have everything in a "python" directory
+        let extracted = tv.download_path().join("python");
+        file::remove_all(&extracted)?;
+        file::create_dir_all(tv.download_path())?;
+        file::untar(&tarball, &tv.download_path(), Some(pr))?;
+        file::remove_all(tv.install_path())?;
+        file::rename(extracted, tv.install_path())?;
+        // the builds only have python3, python is expected to be there as well like with python-build
+        let python = self.python_path(tv);
+        if !python.exists() {
+            file::make_symlink(Path::new("python3"), &python)?;
+        }
+        Ok(())
+    }
+
+    fn install_compiled(
+        &self,
+        config: &Config,
+        tv: &ToolVersion,
+        pr: &ProgressReport,
+    ) -> Result<()> {
+        self.install_python_build()?;
+        pr.set_message("running python-build");
+        create_dir_all(self.downloads_path())?;
+        let mut cmd = CmdLineRunner::new(&config.settings, self.python_build_bin())
+            .with_pr(pr)
+            .arg(tv.version.as_str())
+            .arg(tv.install_path());
+        // python-build only caches the source tarballs if this directory exists
+        if env::var_os("PYTHON_BUILD_CACHE_PATH").is_none() {
+            cmd = cmd.env("PYTHON_BUILD_CACHE_PATH", self.downloads_path());
+        }
+        if config.settings.verbose {
+            cmd = cmd.arg("--verbose");
+        }
+        if let Some(patch_url) = &*env::RTX_PYTHON_PATCH_URL {
+            pr.set_message(format!("with patch file from: {patch_url}"));
+            let http = http::Client::new()?;
+            let resp = http.get(patch_url).send()?;
+            http.ensure_success(&resp)?;
+            let patch = resp.text()?;
+            cmd = cmd.arg("--patch").stdin_string(patch)
+        }
+        if let Some(patches_dir) = &*env::RTX_PYTHON_PATCHES_DIRECTORY {
+            let patch_file = patches_dir.join(format!("{}.patch", tv.version));
+            if patch_file.exists() {
+                pr.set_message(format!("with patch file: {}", patch_file.display()));
+                let contents = file::read_to_string(&patch_file)?;
+                cmd = cmd.arg("--patch").stdin_string(contents);
+            } else {
+                pr.warn(format!("patch file not found: {}", patch_file.display()));
+            }
+        }
+        cmd.execute()
+    }
+
     fn python_path(&self, tv: &ToolVersion) -> PathBuf {
         tv.install_path().join("bin/python")
     }
@@ -148,6 +263,13 @@ impl Plugin for PythonPlugin {
         &self.core.name
     }
 
+    fn cache_status(&self) -> Vec<CacheStatus> {
+        vec![
+            self.core.remote_version_cache.status(),
+            self.precompiled_cache.status(),
+        ]
+    }
+
     fn list_remote_versions(&self, _settings: &Settings) -> Result<Vec<String>> {
         self.core
             .remote_version_cache
@@ -165,37 +287,25 @@ impl Plugin for PythonPlugin {
         tv: &ToolVersion,
         pr: &ProgressReport,
     ) -> Result<()> {
-        self.install_python_build()?;
         if matches!(tv.request, ToolVersionRequest::Ref(..)) {
             return Err(eyre!("Ref versions not supported for python"));
         }
-        pr.set_message("running python-build");
-        let mut cmd = CmdLineRunner::new(&config.settings, self.python_build_bin())
-            .with_pr(pr)
-            .arg(tv.version.as_str())
-            .arg(tv.install_path());
-        if config.settings.verbose {
-            cmd = cmd.arg("--verbose");
-        }
-        if let Some(patch_url) = &*env::RTX_PYTHON_PATCH_URL {
-            pr.set_message(format!("with patch file from: {patch_url}"));
-            let http = http::Client::new()?;
-            let resp = http.get(patch_url).send()?;
-            http.ensure_success(&resp)?;
-            let patch = resp.text()?;
-            cmd = cmd.arg("--patch").stdin_string(patch)
-        }
-        if let Some(patches_dir) = &*env::RTX_PYTHON_PATCHES_DIRECTORY {
-            let patch_file = patches_dir.join(format!("{}.patch", tv.version));
-            if patch_file.exists() {
-                pr.set_message(format!("with patch file: {}", patch_file.display()));
-                let contents = file::read_to_string(&patch_file)?;
-                cmd = cmd.arg("--patch").stdin_string(contents);
-            } else {
-                pr.warn(format!("patch file not found: {}", patch_file.display()));
+        let precompiled_url = match *env::RTX_PYTHON_PRECOMPILED {
+            true => self.precompiled_url(tv)?,
+            false => None,
+        };
+        match precompiled_url {
+            Some(url) => self.install_precompiled(tv, pr, &url)?,
+            None => {
+                if *env::RTX_PYTHON_PRECOMPILED {
+                    pr.warn(format!(
+                        "no precompiled build of python {} for this platform, compiling it",
+                        tv.version
+                    ));
+                }
+                self.install_compiled(config, tv, pr)?;
             }
         }
-        cmd.execute()?;
         self.test_python(&config, tv, pr)?;
         self.get_virtualenv(config, tv, Some(pr))?;
         self.install_default_packages(&config.settings, tv, pr)?;
@@ -222,3 +332,60 @@ impl Plugin for PythonPlugin {
         }
     }
 }
+
+/// the target triple in the names of python-build-standalone builds, e.g.:
+/// cpython-3.11.6+20231002-x86_64-unknown-linux-gnu-install_only.tar.gz
+fn precompiled_target(os: &str, arch: &str, musl: bool) -> Option<&'static str> {
+    match (os, arch, musl) {
+        ("linux", "x64", false) => Some("x86_64-unknown-linux-gnu"),
+        ("linux", "x64", true) => Some("x86_64-unknown-linux-musl"),
+        ("linux", "arm64", false) => Some("aarch64-unknown-linux-gnu"),
+        ("macos", "x64", _) => Some("x86_64-apple-darwin"),
+        ("macos", "arm64", _) => Some("aarch64-apple-darwin"),
+        _ => None,
+    }
+}
+
+/// the python version of a python-build-standalone asset if it is an install_only build for target
+fn precompiled_version<'a>(asset: &'a str, target: &str) -> Option<&'a str> {
+    let rest = asset.strip_prefix("cpython-")?;
+    let (version, rest) = rest.split_once('+')?;
+    let (_date, rest) = rest.split_once('-')?;
+    match rest == format!("{target}-install_only.tar.gz") {
+        true => Some(version),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_precompiled_version() {
+        let target = precompiled_target("linux", "x64", false).unwrap();
+        assert_eq!(
+            precompiled_version(
+                "cpython-3.11.6+20231002-x86_64-unknown-linux-gnu-install_only.tar.gz",
+                target
+            ),
+            Some("3.11.6")
+        );
+        assert_eq!(
+            precompiled_version(
+                "cpython-3.11.6+20231002-x86_64-unknown-linux-gnu-pgo+lto-full.tar.zst",
+                target
+            ),
+            None
+        );
+        assert_eq!(
+            precompiled_version(
+                "cpython-3.11.6+20231002-aarch64-apple-darwin-install_only.tar.gz",
+                target
+            ),
+            None
+        );
+        assert_eq!(precompiled_version("SHA256SUMS", target), None);
+        assert_eq!(precompiled_target("linux", "arm64", true), None);
+    }
+}
@@ -5,6 +5,7 @@ use std::path::{Path, PathBuf};
 
 use color_eyre::eyre::Result;
 
+use crate::cache::CacheStatus;
 use crate::cmd::CmdLineRunner;
 use crate::config::{Config, Settings};
 use crate::duration::DAILY;
@@ -16,7 +17,7 @@ use crate::plugins::core::CorePlugin;
 use crate::plugins::{Plugin, PluginName};
 use crate::toolset::{ToolVersion, ToolVersionRequest};
 use crate::ui::progress_report::ProgressReport;
-use crate::{cmd, env, file, http};
+use crate::{cmd, env, file, http, platform};
 
 #[derive(Debug)]
 pub struct RubyPlugin {
@@ -75,6 +76,9 @@ impl RubyPlugin {
         file::create_dir_all(tmp.parent().unwrap())?;
         let git = Git::new(tmp.clone());
         git.clone(&env::RTX_RUBY_BUILD_REPO)?;
+        if let Some(version) = &*env::RTX_RUBY_BUILD_VERSION {
+            git.update(Some(format!("v{version}")))?;
+        }
 
         cmd!("sh", "install.sh")
             .env("PREFIX", self.ruby_build_path())
@@ -85,9 +89,11 @@ impl RubyPlugin {
     }
     fn update_ruby_build(&self) -> Result<()> {
         let _lock = self.lock_build_tool();
-        if self.ruby_build_path().exists()
-            && self.ruby_build_version()? == self.latest_ruby_build_version()?
-        {
+        let wanted_version = match &*env::RTX_RUBY_BUILD_VERSION {
+            Some(version) => version.clone(),
+            None => self.latest_ruby_build_version()?,
+        };
+        if self.ruby_build_path().exists() && self.ruby_build_version()? == wanted_version {
             return Ok(());
         }
         debug!(
@@ -247,9 +253,13 @@ impl RubyPlugin {
             CmdLineRunner::new(settings, self.ruby_install_bin())
                 .args(self.install_args_ruby_install(tv)?)
         } else {
-            CmdLineRunner::new(settings, self.ruby_build_bin())
+            let mut cmd = CmdLineRunner::new(settings, self.ruby_build_bin())
                 .args(self.install_args_ruby_build(settings, tv)?)
-                .stdin_string(self.fetch_patches()?)
+                .stdin_string(self.fetch_patches()?);
+            if let Some(opts) = configure_opts(tv) {
+                cmd = cmd.env("RUBY_CONFIGURE_OPTS", opts);
+            }
+            cmd
         };
         Ok(cmd.with_pr(pr))
     }
@@ -284,9 +294,47 @@ impl RubyPlugin {
         args.push("--install-dir".into());
         args.push(tv.install_path().to_string_lossy().to_string());
         args.extend(env::RTX_RUBY_INSTALL_OPTS.clone()?);
+        // ruby-install passes the arguments after "--" to ./configure
+        if let Some(opts) = tv.opts.get("configure_opts") {
+            args.push("--".into());
+            args.extend(shell_words::split(opts)?);
+        }
         Ok(args)
     }
 
+    /// downloads a prebuilt ruby from ruby/ruby-builder, the builds used by the setup-ruby GitHub
+    /// action, false if there is none for this version and platform
+    fn install_precompiled(&self, tv: &ToolVersion, pr: &ProgressReport) -> Result<bool> {
+        let Some(platform) = precompiled_platform() else {
+            debug!("no precompiled rubies for this platform");
+            return Ok(false);
+        };
+        let filename = format!("{}-{platform}.tar.gz", precompiled_name(&tv.version));
+        let url =
+            format!("https://github.com/ruby/ruby-builder/releases/download/toolcache/{filename}");
+        let tarball = tv.download_path().join(&filename);
+        file::create_dir_all(tv.download_path())?;
+        pr.set_message(format!("downloading {filename}"));
+        if let Err(err) = http::Client::new()?.download_file(&url, &tarball, Some(pr)) {
+            debug!("failed to download precompiled ruby {url}: {err:#}");
+            return Ok(false);
+        }
+        pr.set_message(format!("installing {filename}"));
+        // the tarballs have everything in an "x64" or "arm64" directory
+        let extracted = tv.download_path().join("extracted");
+        file::remove_all(&extracted)?;
+        file::create_dir_all(&extracted)?;
+        file::untar(&tarball, &extracted, Some(pr))?;
+        let entries = extracted.read_dir()?.collect::<std::io::Result<Vec<_>>>()?;
+        let root = match entries.as_slice() {
+            [entry] if entry.path().is_dir() => entry.path(),
+            _ => extracted,
+        };
+        file::remove_all(tv.install_path())?;
+        file::rename(root, tv.install_path())?;
+        Ok(true)
+    }
+
     fn verbose_install(&self, settings: &Settings) -> bool {
         let verbose_env = *env::RTX_RUBY_VERBOSE_INSTALL;
         verbose_env == Some(true) || (settings.verbose && verbose_env != Some(false))
@@ -322,6 +370,10 @@ impl Plugin for RubyPlugin {
         &self.core.name
     }
 
+    fn cache_status(&self) -> Vec<CacheStatus> {
+        vec![self.core.remote_version_cache.status()]
+    }
+
     fn list_remote_versions(&self, _settings: &Settings) -> Result<Vec<String>> {
         self.core
             .remote_version_cache
@@ -357,10 +409,28 @@ impl Plugin for RubyPlugin {
         self.update_build_tool()?;
         assert!(matches!(&tv.request, ToolVersionRequest::Version { .. }));
 
-        pr.set_message("running ruby-build");
-        self.install_cmd(&config.settings, tv, pr)?.execute()?;
-
-        self.test_ruby(config, tv, pr)?;
+        // configure_opts can only be used when compiling
+        let precompiled = *env::RTX_RUBY_PRECOMPILED
+            && !tv.opts.contains_key("configure_opts")
+            && self.install_precompiled(tv, pr)?
+            && match self.test_ruby(config, tv, pr) {
+                Ok(()) => true,
+                Err(err) => {
+                    debug!("precompiled ruby {} does not run: {err:#}", tv.version);
+                    false
+                }
+            };
+        if !precompiled {
+            if *env::RTX_RUBY_PRECOMPILED {
+                pr.warn(format!(
+                    "no precompiled ruby {} for this platform, compiling it",
+                    tv.version
+                ));
+            }
+            pr.set_message("running ruby-build");
+            self.install_cmd(&config.settings, tv, pr)?.execute()?;
+            self.test_ruby(config, tv, pr)?;
+        }
         self.install_rubygems_hook(tv)?;
         self.test_gem(config, tv, pr)?;
         self.install_default_gems(&config.settings, tv, pr)?;
@@ -384,6 +454,55 @@ impl Plugin for RubyPlugin {
     }
 }
 
+/// `configure_opts` in the tool options added to `RUBY_CONFIGURE_OPTS` from the environment, e.g.:
+/// ruby = { version = "3.2", configure_opts = "--enable-yjit" }
+fn configure_opts(tv: &ToolVersion) -> Option<String> {
+    let opts = tv.opts.get("configure_opts")?;
+    match env::var("RUBY_CONFIGURE_OPTS") {
+        Ok(env_opts) if !env_opts.is_empty() => Some(format!("{env_opts} {opts}")),
+        _ => Some(opts.clone()),
+    }
+}
+
+/// the name of a version in ruby-builder, e.g.: "ruby-3.2.2" or "jruby-9.4.5.0"
+fn precompiled_name(version: &str) -> String {
+    match version.chars().next() {
+        Some(c) if c.is_ascii_digit() => format!("ruby-{version}"),
+        _ => version.to_string(),
+    }
+}
+
+/// ruby-builder has builds for the ubuntu and macos versions GitHub Actions runs on
+fn precompiled_platform() -> Option<String> {
+    if let Some(platform) = &*env::RTX_RUBY_PRECOMPILED_PLATFORM {
+        return Some(platform.clone());
+    }
+    match (platform::os().as_str(), platform::arch().as_str()) {
+        ("linux", "x64") => {
+            let os_release = file::read_to_string("/etc/os-release").ok()?;
+            ubuntu_platform(&os_release)
+        }
+        ("macos", "x64") => Some("macos-latest".into()),
+        ("macos", "arm64") => Some("macos-13-arm64".into()),
+        _ => None,
+    }
+}
+
+/// e.g.: "ubuntu-22.04"
+fn ubuntu_platform(os_release: &str) -> Option<String> {
+    let field = |key: &str| {
+        os_release.lines().find_map(|l| {
+            l.strip_prefix(key)
+                .and_then(|v| v.strip_prefix('='))
+                .map(|v| v.trim_matches('"').to_string())
+        })
+    };
+    match field("ID")?.as_str() {
+        "ubuntu" => Some(format!("ubuntu-{}", field("VERSION_ID")?)),
+        _ => None,
+    }
+}
+
 fn parse_gemfile(body: &str) -> String {
     let v = body
         .lines()
@@ -417,6 +536,28 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_precompiled_name() {
+        assert_eq!(precompiled_name("3.2.2"), "ruby-3.2.2");
+        assert_eq!(precompiled_name("jruby-9.4.5.0"), "jruby-9.4.5.0");
+    }
+
+    #[test]
+    fn test_ubuntu_platform() {
+        let os_release = indoc! {r#"
+            NAME="Ubuntu"
+            VERSION_ID="22.04"
+            ID=ubuntu
+            ID_LIKE=debian
+        "#};
+        assert_eq!(ubuntu_platform(os_release), Some("ubuntu-22.04".into()));
+        let os_release = indoc! {r#"
+            ID=debian
+            VERSION_ID="12"
+        "#};
+        assert_eq!(ubuntu_platform(os_release), None);
+    }
+
     #[test]
     fn test_parse_gemfile() {
         assert_eq!(
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::Result;
+use itertools::Itertools;
+use toml::Table;
+use versions::Versioning;
+
+use crate::cache::CacheStatus;
+use crate::cmd::CmdLineRunner;
+use crate::config::{Config, Settings};
+use crate::github::GithubRelease;
+use crate::plugins::core::CorePlugin;
+use crate::plugins::{Plugin, PluginName};
+use crate::toolset::ToolVersion;
+use crate::ui::progress_report::ProgressReport;
+use crate::{env, file, http, platform};
+
+/// each version is a separate rustup installation (RUSTUP_HOME and CARGO_HOME) inside of the
+/// install dir with the version as its default toolchain
+#[derive(Debug)]
+pub struct RustPlugin {
+    core: CorePlugin,
+}
+
+/// toolchains which are not release versions, nightly can also be dated, e.g.: nightly-2023-11-01
+const CHANNELS: [&str; 3] = ["stable", "beta", "nightly"];
+
+impl RustPlugin {
+    pub fn new(name: PluginName) -> Self {
+        let core = CorePlugin::new(name);
+        Self { core }
+    }
+
+    fn fetch_remote_versions(&self) -> Result<Vec<String>> {
+        let http = http::Client::new()?;
+        let mut req = http.get("https://api.github.com/repos/rust-lang/rust/releases?per_page=100");
+        if let Some(token) = &*env::GITHUB_API_TOKEN {
+            req = req.header("authorization", format!("token {}", token));
+        }
+        let resp = req.send()?;
+        http.ensure_success(&resp)?;
+        let releases: Vec<GithubRelease> = resp.json()?;
+        let versions = releases
+            .into_iter()
+            .map(|r| r.tag_name)
+            .unique()
+            .sorted_by_cached_key(|s| Versioning::new(s))
+            .collect_vec();
+        // channels first so "latest" is still the newest release
+        Ok(CHANNELS
+            .iter()
+            .map(|c| c.to_string())
+            .chain(versions)
+            .collect())
+    }
+
+    fn cargo_home(&self, tv: &ToolVersion) -> PathBuf {
+        tv.install_path()
+    }
+    fn rustup_home(&self, tv: &ToolVersion) -> PathBuf {
+        tv.install_path().join("rustup")
+    }
+
+    fn rustup_init(&self, pr: &ProgressReport) -> Result<PathBuf> {
+        let triple = target_triple();
+        let path = self.core.cache_path.join(format!("rustup-init-{triple}"));
+        if !path.exists() {
+            let url = format!("https://static.rust-lang.org/rustup/dist/{triple}/rustup-init");
+            http::Client::new()?.download_file(&url, &path, Some(pr))?;
+            file::make_executable(&path)?;
+        }
+        Ok(path)
+    }
+
+    fn install(
+        &self,
+        config: &Config,
+        tv: &ToolVersion,
+        pr: &ProgressReport,
+        rustup_init: &Path,
+    ) -> Result<()> {
+        pr.set_message(format!("rustup-init {}", tv.version));
+        let profile = tv.opts.get("profile").map_or("default", |p| p.as_str());
+        let mut cmd = CmdLineRunner::new(&config.settings, rustup_init)
+            .with_pr(pr)
+            .arg("-y")
+            .arg("--no-modify-path")
+            .arg("--profile")
+            .arg(profile)
+            .arg("--default-toolchain")
+            .arg(&tv.version)
+            .env("RUSTUP_HOME", self.rustup_home(tv))
+            .env("CARGO_HOME", self.cargo_home(tv))
+            .env("RUSTUP_INIT_SKIP_PATH_CHECK", "yes");
+        for component in split_opt(tv, "components") {
+            cmd = cmd.arg("--component").arg(component);
+        }
+        for target in split_opt(tv, "targets") {
+            cmd = cmd.arg("--target").arg(target);
+        }
+        cmd.execute()
+    }
+
+    fn verify(&self, config: &Config, tv: &ToolVersion, pr: &ProgressReport) -> Result<()> {
+        pr.set_message("rustc --version");
+        CmdLineRunner::new(&config.settings, self.cargo_home(tv).join("bin/rustc"))
+            .with_pr(pr)
+            .arg("--version")
+            .envs(self.exec_env(config, tv)?)
+            .execute()
+    }
+}
+
+impl Plugin for RustPlugin {
+    fn name(&self) -> &PluginName {
+        &self.core.name
+    }
+
+    fn cache_status(&self) -> Vec<CacheStatus> {
+        vec![self.core.remote_version_cache.status()]
+    }
+
+    fn list_remote_versions(&self, _settings: &Settings) -> Result<Vec<String>> {
+        self.core
+            .remote_version_cache
+            .get_or_try_init(|| self.fetch_remote_versions())
+            .cloned()
+    }
+
+    fn legacy_filenames(&self, _settings: &Settings) -> Result<Vec<String>> {
+        Ok(vec!["rust-toolchain.toml".into(), "rust-toolchain".into()])
+    }
+
+    fn parse_legacy_file(&self, path: &Path, _settings: &Settings) -> Result<String> {
+        let body = file::read_to_string(path)?;
+        Ok(parse_toolchain_file(&body))
+    }
+
+    fn install_version(
+        &self,
+        config: &Config,
+        tv: &ToolVersion,
+        pr: &ProgressReport,
+    ) -> Result<()> {
+        let rustup_init = self.rustup_init(pr)?;
+        self.install(config, tv, pr, &rustup_init)?;
+        self.verify(config, tv, pr)
+    }
+
+    fn exec_env(&self, _config: &Config, tv: &ToolVersion) -> Result<HashMap<String, String>> {
+        let path = |p: PathBuf| p.to_string_lossy().to_string();
+        Ok(HashMap::from([
+            ("RUSTUP_HOME".into(), path(self.rustup_home(tv))),
+            ("CARGO_HOME".into(), path(self.cargo_home(tv))),
+            // the rustup proxies (cargo, rustc, etc.) would otherwise use rust-toolchain.toml
+            // or the default toolchain, which may be different from the one rtx selected
+            ("RUSTUP_TOOLCHAIN".into(), tv.version.clone()),
+        ]))
+    }
+}
+
+/// comma-separated tool option, e.g.: `rust = { version = "stable", components = "clippy,rustfmt" }`
+fn split_opt<'a>(tv: &'a ToolVersion, key: &str) -> Vec<&'a str> {
+    match tv.opts.get(key) {
+        Some(v) => v
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        None => vec![],
+    }
+}
+
+/// the channel from rust-toolchain.toml, or the legacy rust-toolchain file which is only the channel
+fn parse_toolchain_file(body: &str) -> String {
+    match body.parse::<Table>() {
+        Ok(toml) => toml
+            .get("toolchain")
+            .and_then(|t| t.get("channel"))
+            .and_then(|c| c.as_str())
+            .unwrap_or("stable")
+            .to_string(),
+        Err(_) => body.trim().to_string(),
+    }
+}
+
+fn target_triple() -> String {
+    let arch = match platform::arch().as_str() {
+        "x64" => "x86_64".to_string(),
+        "arm64" => "aarch64".to_string(),
+        arch => arch.to_string(),
+    };
+    let os = match platform::os().as_str() {
+        "macos" => "apple-darwin",
+        _ if platform::is_musl() => "unknown-linux-musl",
+        _ => "unknown-linux-gnu",
+    };
+    format!("{}-{}", arch, os)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_str_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_toolchain_file() {
+        assert_str_eq!(
+            parse_toolchain_file("nightly-2023-11-01\n"),
+            "nightly-2023-11-01"
+        );
+        assert_str_eq!(
+            parse_toolchain_file(indoc! {r#"
+                [toolchain]
+                channel = "1.73.0"
+                components = ["rustfmt", "clippy"]
+            "#}),
+            "1.73.0"
+        );
+        assert_str_eq!(parse_toolchain_file("[toolchain]\n"), "stable");
+    }
+}
@@ -0,0 +1,203 @@
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{eyre, Result};
+use itertools::Itertools;
+use versions::Versioning;
+
+use crate::cache::CacheStatus;
+use crate::cmd::CmdLineRunner;
+use crate::config::{Config, Settings};
+use crate::github::GithubRelease;
+use crate::plugins::core::CorePlugin;
+use crate::plugins::{Plugin, PluginName};
+use crate::toolset::ToolVersion;
+use crate::ui::progress_report::ProgressReport;
+use crate::{cmd, env, file, http, platform};
+
+#[derive(Debug)]
+pub struct SwiftPlugin {
+    core: CorePlugin,
+}
+
+impl SwiftPlugin {
+    pub fn new(name: PluginName) -> Self {
+        let core = CorePlugin::new(name);
+        Self { core }
+    }
+
+    fn fetch_remote_versions(&self) -> Result<Vec<String>> {
+        let http = http::Client::new()?;
+        let mut req = http.get("https://api.github.com/repos/apple/swift/releases?per_page=100");
+        if let Some(token) = &*env::GITHUB_API_TOKEN {
+            req = req.header("authorization", format!("token {}", token));
+        }
+        let resp = req.send()?;
+        http.ensure_success(&resp)?;
+        let releases: Vec<GithubRelease> = resp.json()?;
+        let versions = releases
+            .into_iter()
+            .filter(|r| !r.prerelease)
+            // e.g.: swift-5.9.1-RELEASE
+            .filter_map(|r| {
+                r.tag_name
+                    .strip_prefix("swift-")
+                    .and_then(|v| v.strip_suffix("-RELEASE"))
+                    .map(|v| v.to_string())
+            })
+            .unique()
+            .sorted_by_cached_key(|s| Versioning::new(s))
+            .collect();
+        Ok(versions)
+    }
+
+    fn swift_bin(&self, tv: &ToolVersion) -> PathBuf {
+        tv.install_path().join("usr/bin/swift")
+    }
+
+    fn test_swift(&self, config: &Config, tv: &ToolVersion, pr: &ProgressReport) -> Result<()> {
+        pr.set_message("swift --version");
+        CmdLineRunner::new(&config.settings, self.swift_bin(tv))
+            .with_pr(pr)
+            .arg("--version")
+            .execute()
+    }
+
+    fn download(&self, tv: &ToolVersion, pr: &ProgressReport) -> Result<PathBuf> {
+        let url = url(&tv.version)?;
+        let filename = url.split('/').next_back().unwrap();
+        let tarball_path = tv.download_path().join(filename);
+
+        http::Client::new()?.download_file(&url, &tarball_path, Some(pr))?;
+
+        Ok(tarball_path)
+    }
+
+    fn install(&self, tv: &ToolVersion, pr: &ProgressReport, tarball_path: &Path) -> Result<()> {
+        let tarball = file::basename(tarball_path).unwrap_or_default();
+        pr.set_message(format!("installing {}", tarball));
+        file::remove_all(tv.install_path())?;
+        if let Some(pkg) = tarball.strip_suffix(".pkg") {
+            // the payload of the installer package has usr/bin/swift, like the linux tarballs
+            let expanded = tv.download_path().join("expanded");
+            file::remove_all(&expanded)?;
+            cmd!("pkgutil", "--expand-full", tarball_path, &expanded).run()?;
+            let payload = expanded.join(format!("{pkg}-package.pkg/Payload"));
+            file::rename(payload, tv.install_path())?;
+        } else {
+            file::untar(tarball_path, &tv.download_path(), Some(pr))?;
+            // e.g.: swift-5.9.1-RELEASE-ubuntu22.04.tar.gz extracts to swift-5.9.1-RELEASE-ubuntu22.04/
+            let dir = tarball.trim_end_matches(".tar.gz");
+            file::rename(tv.download_path().join(dir), tv.install_path())?;
+        }
+        Ok(())
+    }
+
+    fn verify(&self, config: &Config, tv: &ToolVersion, pr: &ProgressReport) -> Result<()> {
+        self.test_swift(config, tv, pr)
+    }
+}
+
+impl Plugin for SwiftPlugin {
+    fn name(&self) -> &PluginName {
+        &self.core.name
+    }
+
+    fn cache_status(&self) -> Vec<CacheStatus> {
+        vec![self.core.remote_version_cache.status()]
+    }
+
+    fn list_remote_versions(&self, _settings: &Settings) -> Result<Vec<String>> {
+        self.core
+            .remote_version_cache
+            .get_or_try_init(|| self.fetch_remote_versions())
+            .cloned()
+    }
+
+    fn legacy_filenames(&self, _settings: &Settings) -> Result<Vec<String>> {
+        Ok(vec![".swift-version".into()])
+    }
+
+    fn install_version(
+        &self,
+        config: &Config,
+        tv: &ToolVersion,
+        pr: &ProgressReport,
+    ) -> Result<()> {
+        let tarball_path = self.download(tv, pr)?;
+        self.install(tv, pr, &tarball_path)?;
+        self.verify(config, tv, pr)?;
+
+        Ok(())
+    }
+
+    fn list_bin_paths(&self, _config: &Config, tv: &ToolVersion) -> Result<Vec<PathBuf>> {
+        Ok(vec![tv.install_path().join("usr/bin")])
+    }
+}
+
+/// e.g.: https://download.swift.org/swift-5.9.1-release/ubuntu2204-aarch64/swift-5.9.1-RELEASE/swift-5.9.1-RELEASE-ubuntu22.04-aarch64.tar.gz
+fn url(version: &str) -> Result<String> {
+    let base = format!("https://download.swift.org/swift-{version}-release");
+    let release = format!("swift-{version}-RELEASE");
+    if platform::os() == "macos" {
+        // the macOS installer is universal
+        return Ok(format!("{base}/xcode/{release}/{release}-osx.pkg"));
+    }
+    let distro = match &*env::RTX_SWIFT_PLATFORM {
+        Some(distro) => distro.clone(),
+        None => {
+            let os_release = file::read_to_string("/etc/os-release").unwrap_or_default();
+            linux_platform(&os_release).ok_or_else(|| {
+                eyre!("swift has no builds for this linux distribution, set RTX_SWIFT_PLATFORM to one it has, e.g.: ubuntu22.04")
+            })?
+        }
+    };
+    let arch = match platform::arch().as_str() {
+        "arm64" => "-aarch64",
+        _ => "",
+    };
+    let dir = distro.replace('.', "");
+    Ok(format!(
+        "{base}/{dir}{arch}/{release}/{release}-{distro}{arch}.tar.gz"
+    ))
+}
+
+/// the name swift.org uses for the distribution described by /etc/os-release, e.g.: "ubuntu22.04"
+fn linux_platform(os_release: &str) -> Option<String> {
+    let field = |key: &str| {
+        os_release.lines().find_map(|l| {
+            l.strip_prefix(key)
+                .and_then(|v| v.strip_prefix('='))
+                .map(|v| v.trim_matches('"').to_string())
+        })
+    };
+    let id = field("ID")?;
+    let version = field("VERSION_ID").unwrap_or_default();
+    let major = version.split('.').next().unwrap_or_default();
+    match (id.as_str(), major) {
+        ("ubuntu", _) => Some(format!("ubuntu{version}")),
+        ("amzn", "2") => Some("amazonlinux2".into()),
+        ("centos", "7") => Some("centos7".into()),
+        ("rhel" | "rocky" | "almalinux", "9") => Some("ubi9".into()),
+        ("debian", "12") => Some("debian12".into()),
+        ("fedora", "39") => Some("fedora39".into()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linux_platform() {
+        let ubuntu = "NAME=\"Ubuntu\"\nVERSION_ID=\"22.04\"\nID=ubuntu\nID_LIKE=debian\n";
+        assert_eq!(linux_platform(ubuntu), Some("ubuntu22.04".into()));
+        let rocky = "NAME=\"Rocky Linux\"\nID=\"rocky\"\nID_LIKE=\"rhel centos fedora\"\nVERSION_ID=\"9.3\"\n";
+        assert_eq!(linux_platform(rocky), Some("ubi9".into()));
+        let amzn = "NAME=\"Amazon Linux\"\nVERSION=\"2\"\nID=\"amzn\"\nVERSION_ID=\"2\"\n";
+        assert_eq!(linux_platform(amzn), Some("amazonlinux2".into()));
+        let arch = "NAME=\"Arch Linux\"\nID=arch\n";
+        assert_eq!(linux_platform(arch), None);
+    }
+}
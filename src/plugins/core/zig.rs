@@ -0,0 +1,146 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{eyre, Result};
+use itertools::Itertools;
+use serde_derive::Deserialize;
+use versions::Versioning;
+
+use crate::cache::CacheStatus;
+use crate::cmd::CmdLineRunner;
+use crate::config::{Config, Settings};
+use crate::plugins::core::CorePlugin;
+use crate::plugins::{Plugin, PluginName};
+use crate::toolset::ToolVersion;
+use crate::ui::progress_report::ProgressReport;
+use crate::{file, hash, http, platform};
+
+#[derive(Debug)]
+pub struct ZigPlugin {
+    core: CorePlugin,
+}
+
+/// https://ziglang.org/download/index.json
+/// each release has a key per platform, e.g.: "x86_64-linux", along with metadata like "date"
+type ZigIndex = BTreeMap<String, BTreeMap<String, serde_json::Value>>;
+
+#[derive(Debug, Deserialize)]
+struct ZigTarball {
+    tarball: String,
+    shasum: String,
+}
+
+impl ZigPlugin {
+    pub fn new(name: PluginName) -> Self {
+        let core = CorePlugin::new(name);
+        Self { core }
+    }
+
+    fn fetch_index(&self) -> Result<ZigIndex> {
+        let http = http::Client::new()?;
+        let resp = http.get("https://ziglang.org/download/index.json").send()?;
+        http.ensure_success(&resp)?;
+        Ok(resp.json()?)
+    }
+
+    fn fetch_remote_versions(&self) -> Result<Vec<String>> {
+        let index = self.fetch_index()?;
+        let versions = index
+            .into_iter()
+            .filter(|(_, release)| release.contains_key(&index_key()))
+            .map(|(v, _)| v)
+            .filter(|v| v != "master")
+            .sorted_by_cached_key(|s| Versioning::new(s))
+            .collect_vec();
+        // master first so "latest" is still the newest release
+        Ok(["master".to_string()].into_iter().chain(versions).collect())
+    }
+
+    fn zig_bin(&self, tv: &ToolVersion) -> PathBuf {
+        tv.install_path().join("zig")
+    }
+
+    fn test_zig(&self, config: &Config, tv: &ToolVersion, pr: &ProgressReport) -> Result<()> {
+        pr.set_message("zig version");
+        CmdLineRunner::new(&config.settings, self.zig_bin(tv))
+            .with_pr(pr)
+            .arg("version")
+            .execute()
+    }
+
+    fn download(&self, tv: &ToolVersion, pr: &ProgressReport) -> Result<PathBuf> {
+        let mut index = self.fetch_index()?;
+        let tarball = index
+            .remove(&tv.version)
+            .and_then(|mut release| release.remove(&index_key()))
+            .ok_or_else(|| eyre!("zig {} is not available for {}", tv.version, index_key()))?;
+        let tarball: ZigTarball = serde_json::from_value(tarball)?;
+        let filename = tarball.tarball.split('/').next_back().unwrap();
+        let tarball_path = tv.download_path().join(filename);
+
+        http::Client::new()?.download_file(&tarball.tarball, &tarball_path, Some(pr))?;
+        hash::ensure_checksum_sha256(&tarball_path, &tarball.shasum)?;
+
+        Ok(tarball_path)
+    }
+
+    fn install(&self, tv: &ToolVersion, pr: &ProgressReport, tarball_path: &Path) -> Result<()> {
+        let tarball = file::basename(tarball_path).unwrap_or_default();
+        pr.set_message(format!("installing {}", tarball));
+        file::untar_xz(tarball_path, &tv.download_path())?;
+        // e.g.: zig-linux-x86_64-0.11.0.tar.xz extracts to zig-linux-x86_64-0.11.0/
+        let dir = tarball.trim_end_matches(".tar.xz");
+        file::remove_all(tv.install_path())?;
+        file::rename(tv.download_path().join(dir), tv.install_path())?;
+        Ok(())
+    }
+
+    fn verify(&self, config: &Config, tv: &ToolVersion, pr: &ProgressReport) -> Result<()> {
+        self.test_zig(config, tv, pr)
+    }
+}
+
+impl Plugin for ZigPlugin {
+    fn name(&self) -> &PluginName {
+        &self.core.name
+    }
+
+    fn cache_status(&self) -> Vec<CacheStatus> {
+        vec![self.core.remote_version_cache.status()]
+    }
+
+    fn list_remote_versions(&self, _settings: &Settings) -> Result<Vec<String>> {
+        self.core
+            .remote_version_cache
+            .get_or_try_init(|| self.fetch_remote_versions())
+            .cloned()
+    }
+
+    fn install_version(
+        &self,
+        config: &Config,
+        tv: &ToolVersion,
+        pr: &ProgressReport,
+    ) -> Result<()> {
+        let tarball_path = self.download(tv, pr)?;
+        self.install(tv, pr, &tarball_path)?;
+        self.verify(config, tv, pr)?;
+
+        Ok(())
+    }
+
+    fn list_bin_paths(&self, _config: &Config, tv: &ToolVersion) -> Result<Vec<PathBuf>> {
+        // the zig binary is at the root of the tarball
+        Ok(vec![tv.install_path()])
+    }
+}
+
+/// the index key for this machine, e.g.: "x86_64-linux" or "aarch64-macos"
+fn index_key() -> String {
+    let arch = match platform::arch().as_str() {
+        "x64" => "x86_64".to_string(),
+        "arm64" => "aarch64".to_string(),
+        arch => arch.to_string(),
+    };
+    format!("{}-{}", arch, platform::os())
+}
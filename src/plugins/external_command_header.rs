@@ -0,0 +1,164 @@
+use std::path::Path;
+
+use clap::{Arg, ArgAction, Command};
+use regex::Regex;
+
+use crate::file;
+
+/// help text read from the leading comment block of an asdf plugin's
+/// `lib/commands/command-*.bash` script, e.g.:
+///
+///     #!/usr/bin/env bash
+///     # Prints the tool's changelog
+///     #
+///     #   -n, --limit <COUNT>  Number of entries to show
+///     #       --raw            Do not format the output
+///
+/// the first line is the "about" text, options must be separated from
+/// their description by at least 2 spaces
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ExternalCommandHeader {
+    pub about: Option<String>,
+    pub long_about: Option<String>,
+    pub flags: Vec<ExternalCommandFlag>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExternalCommandFlag {
+    pub short: Option<char>,
+    pub long: String,
+    pub value_name: Option<String>,
+    pub help: String,
+}
+
+impl ExternalCommandHeader {
+    pub fn from_file(path: &Path) -> Self {
+        match file::read_to_string(path) {
+            Ok(script) => Self::parse(&script),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn parse(script: &str) -> Self {
+        let re: &Regex = regex!(r"^\s*(?:-(\w),\s*)?--([\w-]+)(?:[ =]<?([\w-]+)>?)?\s{2,}(\S.*)$");
+        let mut header = Self::default();
+        let mut text = vec![];
+        for line in script.lines().skip_while(|l| l.starts_with("#!")) {
+            let Some(line) = line.strip_prefix('#') else {
+                break;
+            };
+            let line = line.strip_prefix(' ').unwrap_or(line).trim_end();
+            if line.starts_with("shellcheck") {
+                continue;
+            }
+            match re.captures(line) {
+                Some(caps) => header.flags.push(ExternalCommandFlag {
+                    short: caps.get(1).and_then(|s| s.as_str().chars().next()),
+                    long: caps[2].to_string(),
+                    value_name: caps.get(3).map(|v| v.as_str().to_string()),
+                    help: caps[4].to_string(),
+                }),
+                None if line.trim() == "Options:" => {}
+                None => text.push(line),
+            }
+        }
+        while text.last().map_or(false, |l| l.is_empty()) {
+            text.pop();
+        }
+        let text = text
+            .into_iter()
+            .skip_while(|l| l.is_empty())
+            .collect::<Vec<_>>();
+        header.about = text.first().map(|s| s.to_string());
+        if text.len() > 1 {
+            header.long_about = Some(text.join("\n"));
+        }
+        header
+    }
+
+    /// the clap subcommand for the script, positional args are collected into "args"
+    pub fn to_command(&self, name: &str) -> Command {
+        let about = match &self.about {
+            Some(about) => about.clone(),
+            None => format!("{name} command"),
+        };
+        let mut cmd = Command::new(name.to_string()).about(about).arg(
+            Arg::new("args")
+                .num_args(1..)
+                .allow_hyphen_values(true)
+                .trailing_var_arg(true),
+        );
+        if let Some(long_about) = &self.long_about {
+            cmd = cmd.long_about(long_about.clone());
+        }
+        // --help/-h are already provided by clap
+        for flag in self.flags.iter().filter(|f| f.long != "help") {
+            let mut arg = Arg::new(flag.long.clone())
+                .long(flag.long.clone())
+                .help(flag.help.clone());
+            if let Some(short) = flag.short.filter(|s| *s != 'h') {
+                arg = arg.short(short);
+            }
+            arg = match &flag.value_name {
+                Some(value_name) => arg.value_name(value_name.clone()).action(ArgAction::Set),
+                None => arg.action(ArgAction::SetTrue),
+            };
+            cmd = cmd.arg(arg);
+        }
+        cmd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let header = ExternalCommandHeader::parse(indoc! {r#"
+            #!/usr/bin/env bash
+            # shellcheck disable=SC2154
+            # Prints the tool's changelog
+            # for the installed version
+            #
+            # Options:
+            #   -n, --limit <COUNT>  Number of entries to show
+            #       --raw            Do not format the output
+            set -euo pipefail
+            # not part of the header
+        "#});
+        assert_eq!(header.about.unwrap(), "Prints the tool's changelog");
+        assert_eq!(
+            header.long_about.unwrap(),
+            "Prints the tool's changelog\nfor the installed version"
+        );
+        assert_eq!(
+            header.flags,
+            vec![
+                ExternalCommandFlag {
+                    short: Some('n'),
+                    long: "limit".into(),
+                    value_name: Some("COUNT".into()),
+                    help: "Number of entries to show".into(),
+                },
+                ExternalCommandFlag {
+                    short: None,
+                    long: "raw".into(),
+                    value_name: None,
+                    help: "Do not format the output".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_no_header() {
+        let header = ExternalCommandHeader::parse("#!/usr/bin/env bash\necho hi\n");
+        assert_eq!(header, ExternalCommandHeader::default());
+        let cmd = header.to_command("foo");
+        assert_eq!(cmd.get_about().unwrap().to_string(), "foo command");
+    }
+}
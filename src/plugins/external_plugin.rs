@@ -8,27 +8,37 @@ use std::process::exit;
 use clap::Command;
 use color_eyre::eyre::{eyre, Result, WrapErr};
 use console::style;
+use indicatif::HumanDuration;
 use itertools::Itertools;
 use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_derive::Deserialize;
 
-use crate::cache::CacheManager;
+use crate::cache::{CacheManager, CacheStatus};
+use crate::cmd;
 use crate::config::{Config, Settings};
 use crate::env::RTX_FETCH_REMOTE_VERSIONS_TIMEOUT;
 use crate::env_diff::{EnvDiff, EnvDiffOperation};
 use crate::errors::Error::PluginNotInstalled;
-use crate::file::remove_all;
+use crate::file::{display_path, remove_all};
 use crate::git::Git;
 use crate::hash::hash_to_str;
+use crate::hooks::Hook;
+use crate::platform::Libc;
+use crate::plugins::external_command_header::ExternalCommandHeader;
 use crate::plugins::external_plugin_cache::ExternalPluginCache;
+use crate::plugins::quarantine;
 use crate::plugins::rtx_plugin_toml::RtxPluginToml;
 use crate::plugins::Script::{Download, ExecEnv, Install, ParseLegacyFile};
-use crate::plugins::{Plugin, PluginName, PluginType, Script, ScriptManager};
+use crate::plugins::{default_depends, Plugin, PluginName, PluginType, Script, ScriptManager};
+use crate::tera::{get_tera, BASE_CONTEXT};
 use crate::timeout::run_with_timeout;
 use crate::toolset::{ToolVersion, ToolVersionRequest};
 use crate::ui::multi_progress_report::MultiProgressReport;
 use crate::ui::progress_report::ProgressReport;
 use crate::ui::prompt;
-use crate::{dirs, env, file};
+use crate::ui::prompt::{preset_answer, Destructive};
+use crate::{checksums, dirs, env, file, hooks, http, platform};
 
 /// This represents a plugin installed to ~/.local/share/rtx/plugins
 pub struct ExternalPlugin {
@@ -42,6 +52,7 @@ pub struct ExternalPlugin {
     script_man: ScriptManager,
     cache: ExternalPluginCache,
     remote_version_cache: CacheManager<Vec<String>>,
+    remote_version_date_cache: CacheManager<BTreeMap<String, String>>,
     latest_stable_cache: CacheManager<Option<String>>,
     alias_cache: CacheManager<Vec<(String, String)>>,
     legacy_filename_cache: CacheManager<Vec<String>>,
@@ -54,7 +65,8 @@ impl ExternalPlugin {
         let toml_path = plugin_path.join("rtx.plugin.toml");
         let toml = RtxPluginToml::from_file(&toml_path).unwrap();
         Self {
-            script_man: build_script_man(&name, &plugin_path),
+            script_man: build_script_man(&name, &plugin_path)
+                .with_env_passthrough(toml.env_passthrough.clone()),
             downloads_path: dirs::DOWNLOADS.join(&name),
             installs_path: dirs::INSTALLS.join(&name),
             cache: ExternalPluginCache::default(),
@@ -62,6 +74,12 @@ impl ExternalPlugin {
                 .with_fresh_duration(*env::RTX_FETCH_REMOTE_VERSIONS_CACHE)
                 .with_fresh_file(plugin_path.clone())
                 .with_fresh_file(plugin_path.join("bin/list-all")),
+            remote_version_date_cache: CacheManager::new(
+                cache_path.join("remote_version_dates.msgpack.z"),
+            )
+            .with_fresh_duration(*env::RTX_FETCH_REMOTE_VERSIONS_CACHE)
+            .with_fresh_file(plugin_path.clone())
+            .with_fresh_file(plugin_path.join("bin/list-all")),
             latest_stable_cache: CacheManager::new(cache_path.join("latest_stable.msgpack.z"))
                 .with_fresh_duration(*env::RTX_FETCH_REMOTE_VERSIONS_CACHE)
                 .with_fresh_file(plugin_path.clone())
@@ -80,6 +98,26 @@ impl ExternalPlugin {
         }
     }
 
+    /// runs one of asdf's optional plugin callbacks, e.g.: bin/post-plugin-update
+    /// the vars in env are also passed with an ASDF_ prefix as asdf plugins expect
+    fn run_callback(
+        &self,
+        settings: &Settings,
+        script: &Script,
+        env: &[(&str, String)],
+    ) -> Result<()> {
+        if !self.script_man.script_exists(script) {
+            return Ok(());
+        }
+        let mut sm = self.script_man.clone();
+        for (k, v) in env {
+            sm = sm
+                .with_env(k.replacen("RTX_", "ASDF_", 1), v)
+                .with_env(*k, v);
+        }
+        sm.run(settings, script)
+    }
+
     fn get_repo_url(&self, config: &Config) -> Result<String> {
         self.repo_url
             .clone()
@@ -91,11 +129,13 @@ impl ExternalPlugin {
         let repository = self.get_repo_url(config)?;
         let (repo_url, repo_ref) = Git::split_url_and_ref(&repository);
         debug!("install {} {:?}", self.name, repository);
+        dirs::ensure_root_writable(&format!("installing plugin {}", self.name))?;
 
         if self.is_installed() {
-            self.uninstall(pr)?;
+            self.uninstall(config, pr)?;
         }
 
+        hooks::run(&config.settings, Hook::PrePluginAdd, &self.name, &[])?;
         let git = Git::new(self.plugin_path.to_path_buf());
         pr.set_message(format!("cloning {repo_url}"));
         git.clone(&repo_url)?;
@@ -103,9 +143,18 @@ impl ExternalPlugin {
             pr.set_message(format!("checking out {ref_}"));
             git.update(Some(ref_.to_string()))?;
         }
+        let env = [
+            (
+                "RTX_PLUGIN_PATH",
+                self.plugin_path.to_string_lossy().to_string(),
+            ),
+            ("RTX_PLUGIN_SOURCE_URL", repo_url.to_string()),
+        ];
+        self.run_callback(&config.settings, &Script::PostPluginAdd, &env)?;
+        hooks::run(&config.settings, Hook::PostPluginAdd, &self.name, &env)?;
 
         pr.set_message("loading plugin remote versions");
-        if self.has_list_all_script() {
+        if self.has_list_all_script(&config.settings) {
             self.list_remote_versions(&config.settings)?;
         }
         if self.has_list_alias_script() {
@@ -125,7 +174,18 @@ impl ExternalPlugin {
         Ok(())
     }
 
-    fn fetch_remote_versions(&self, settings: &Settings) -> Result<Vec<String>> {
+    /// versions and the release dates of the ones list-all has a date for, which is only
+    /// possible with json-output
+    fn fetch_remote_versions(
+        &self,
+        settings: &Settings,
+    ) -> Result<(Vec<String>, BTreeMap<String, String>)> {
+        http::ensure_network(&format!("listing remote versions of {}", self.name))?;
+        if let Some(url) = self.list_all_url(settings) {
+            let body = http::Client::new()?.get_text(url)?;
+            let versions = self.parse_remote_versions(&body, self.list_all_regex(settings))?;
+            return Ok((versions, BTreeMap::new()));
+        }
         let cmd = self.script_man.cmd(settings, &Script::ListAll);
         let result = run_with_timeout(
             move || {
@@ -157,19 +217,80 @@ impl ExternalPlugin {
             display_stderr();
         }
 
-        Ok(stdout.split_whitespace().map(|v| v.into()).collect())
+        if self.toml.capabilities.json_output {
+            return parse_json_remote_versions(&stdout);
+        }
+        Ok((
+            self.parse_script_list(&Script::ListAll, &stdout)?,
+            BTreeMap::new(),
+        ))
+    }
+
+    /// output of a script which prints a list, a JSON array for plugins with json-output
+    fn parse_script_list(&self, script: &Script, output: &str) -> Result<Vec<String>> {
+        if self.toml.capabilities.json_output {
+            return serde_json::from_str(output)
+                .wrap_err_with(|| format!("{script} did not print a JSON array"));
+        }
+        Ok(output.split_whitespace().map(|v| v.into()).collect())
+    }
+
+    /// output of a script with json-output which prints an object of strings
+    fn parse_script_json_object(
+        &self,
+        script: &Script,
+        output: &str,
+    ) -> Result<BTreeMap<String, String>> {
+        serde_json::from_str(output)
+            .wrap_err_with(|| format!("{script} did not print a JSON object of strings"))
+    }
+
+    /// the url to list versions from instead of bin/list-all, the list_all_urls setting
+    /// overrides `[list-all] url` in rtx.plugin.toml
+    fn list_all_url<'a>(&'a self, settings: &'a Settings) -> Option<&'a str> {
+        settings
+            .list_all_urls
+            .get(&self.name)
+            .or(self.toml.list_all.url.as_ref())
+            .map(|url| url.as_str())
+    }
+
+    fn list_all_regex<'a>(&'a self, settings: &'a Settings) -> Option<&'a str> {
+        match settings.list_all_urls.contains_key(&self.name) {
+            true => settings.list_all_regexes.get(&self.name),
+            false => self.toml.list_all.regex.as_ref(),
+        }
+        .map(|re| re.as_str())
+    }
+
+    /// parses versions from `[list-all]` data or url output
+    /// if `regex` is set, versions are the first capture group (or the whole match) of each match
+    fn parse_remote_versions(&self, data: &str, regex: Option<&str>) -> Result<Vec<String>> {
+        let versions = match regex {
+            Some(re) => {
+                let re = Regex::new(re).wrap_err("invalid list-all regex")?;
+                re.captures_iter(data)
+                    .filter_map(|c| c.get(1).or_else(|| c.get(0)))
+                    .map(|m| m.as_str().to_string())
+                    .unique()
+                    .collect()
+            }
+            None => data.split_whitespace().map(|v| v.into()).collect(),
+        };
+        Ok(versions)
     }
 
     fn fetch_legacy_filenames(&self, settings: &Settings) -> Result<Vec<String>> {
         let stdout = self
             .script_man
             .read(settings, &Script::ListLegacyFilenames)?;
-        Ok(self.parse_legacy_filenames(&stdout))
+        self.parse_script_list(&Script::ListLegacyFilenames, &stdout)
     }
     fn parse_legacy_filenames(&self, data: &str) -> Vec<String> {
         data.split_whitespace().map(|v| v.into()).collect()
     }
     fn fetch_latest_stable(&self, settings: &Settings) -> Result<Option<String>> {
+        http::ensure_network(&format!("fetching latest stable version of {}", self.name))?;
         let latest_stable = self
             .script_man
             .read(settings, &Script::LatestStable)?
@@ -182,8 +303,8 @@ impl ExternalPlugin {
         })
     }
 
-    fn has_list_all_script(&self) -> bool {
-        self.script_man.script_exists(&Script::ListAll)
+    fn has_list_all_script(&self, settings: &Settings) -> bool {
+        self.list_all_url(settings).is_some() || self.script_man.script_exists(&Script::ListAll)
     }
     fn has_list_alias_script(&self) -> bool {
         self.script_man.script_exists(&Script::ListAliases)
@@ -196,6 +317,10 @@ impl ExternalPlugin {
     }
     fn fetch_aliases(&self, settings: &Settings) -> Result<Vec<(String, String)>> {
         let stdout = self.script_man.read(settings, &Script::ListAliases)?;
+        if self.toml.capabilities.json_output {
+            let aliases = self.parse_script_json_object(&Script::ListAliases, &stdout)?;
+            return Ok(aliases.into_iter().collect());
+        }
         Ok(self.parse_aliases(&stdout))
     }
     fn parse_aliases(&self, data: &str) -> Vec<(String, String)> {
@@ -237,6 +362,31 @@ impl ExternalPlugin {
         Ok(())
     }
 
+    /// skips hook-env calls to a plugin whose scripts keep failing, see plugins::quarantine
+    fn with_quarantine<T: Default>(&self, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        if quarantine::is_quarantined(&self.name) {
+            return Ok(T::default());
+        }
+        match f() {
+            Ok(v) => {
+                quarantine::clear(&self.name);
+                Ok(v)
+            }
+            Err(err) => match quarantine::record_failure(&self.name) {
+                Some(window) => {
+                    warn!(
+                        "{} keeps failing, skipping it for {}: {:#}",
+                        style(&self.name).cyan().for_stderr(),
+                        HumanDuration(window),
+                        err
+                    );
+                    Ok(T::default())
+                }
+                None => Err(err),
+            },
+        }
+    }
+
     fn fetch_bin_paths(&self, config: &Config, tv: &ToolVersion) -> Result<Vec<PathBuf>> {
         let list_bin_paths = self.plugin_path.join("bin/list-bin-paths");
         let bin_paths = if matches!(tv.request, ToolVersionRequest::System(_)) {
@@ -246,7 +396,7 @@ impl ExternalPlugin {
                 .script_man_for_tv(config, tv)
                 .cmd(&config.settings, &Script::ListBinPaths)
                 .read()?;
-            output.split_whitespace().map(|f| f.to_string()).collect()
+            self.parse_script_list(&Script::ListBinPaths, &output)?
         } else {
             vec!["bin".into()]
         };
@@ -257,6 +407,13 @@ impl ExternalPlugin {
         Ok(bin_paths)
     }
     fn fetch_exec_env(&self, config: &Config, tv: &ToolVersion) -> Result<HashMap<String, String>> {
+        if self.toml.capabilities.json_output {
+            let output = self
+                .script_man_for_tv(config, tv)
+                .read(&config.settings, &ExecEnv)?;
+            let env = self.parse_script_json_object(&ExecEnv, &output)?;
+            return Ok(env.into_iter().collect());
+        }
         let script = self.script_man_for_tv(config, tv).get_script_path(&ExecEnv);
         let ed = EnvDiff::from_bash_script(&script, &self.script_man_for_tv(config, tv).env)?;
         let env = ed
@@ -271,6 +428,31 @@ impl ExternalPlugin {
         Ok(env)
     }
 
+    /// exec-env declared statically in rtx.plugin.toml's [exec-env.env] or in an exec-env.toml
+    /// in the install dir, read directly instead of running the bash exec-env script
+    fn static_exec_env(&self, tv: &ToolVersion) -> Result<Option<HashMap<String, String>>> {
+        let version_file = tv.install_path().join("exec-env.toml");
+        let vars: BTreeMap<String, String> = if version_file.exists() {
+            let body = file::read_to_string(&version_file)?;
+            toml::from_str(&body)
+                .wrap_err_with(|| format!("failed to parse {}", display_path(&version_file)))?
+        } else if let Some(vars) = &self.toml.exec_env.env {
+            vars.clone()
+        } else {
+            return Ok(None);
+        };
+        let mut ctx = BASE_CONTEXT.clone();
+        ctx.insert("install_path", &tv.install_path());
+        ctx.insert("version", &tv.version);
+        ctx.insert("opts", &tv.opts);
+        let mut tera = get_tera(&tv.install_path());
+        let env = vars
+            .into_iter()
+            .map(|(k, v)| Ok((k, tera.render_str(&v, &ctx)?)))
+            .collect::<Result<_>>()?;
+        Ok(Some(env))
+    }
+
     fn script_man_for_tv(&self, config: &Config, tv: &ToolVersion) -> ScriptManager {
         let mut sm = self.script_man.clone();
         for (key, value) in &tv.opts {
@@ -290,6 +472,10 @@ impl ExternalPlugin {
                 panic!("should not be called for system tool")
             }
         };
+        if !self.depends().is_empty() {
+            // the tools it depends on are run through their shims
+            sm = sm.with_path_prepended(&dirs::SHIMS);
+        }
         let install_version = match &tv.request {
             ToolVersionRequest::Ref(_, v) => v, // should not have "ref:" prefix
             _ => &tv.version,
@@ -314,11 +500,52 @@ impl ExternalPlugin {
             .with_env("RTX_INSTALL_TYPE", install_type)
             .with_env("ASDF_INSTALL_TYPE", install_type)
             .with_env("RTX_INSTALL_VERSION", install_version)
-            .with_env("ASDF_INSTALL_VERSION", install_version);
+            .with_env("ASDF_INSTALL_VERSION", install_version)
+            .with_env("RTX_TARGET_OS", platform::os())
+            .with_env("RTX_TARGET_ARCH", platform::arch());
+        match platform::libc() {
+            Some(Libc::Glibc(_)) => sm = sm.with_env("RTX_TARGET_LIBC", "glibc"),
+            Some(Libc::Musl) => sm = sm.with_env("RTX_TARGET_LIBC", "musl"),
+            None => {}
+        }
         sm
     }
 }
 
+/// an entry of list-all's JSON output, either "1.0.0" or {"version": "1.0.0", "release_date": "..."}
+/// other fields are ignored so plugins can add more for newer versions of rtx
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum JsonRemoteVersion {
+    Version(String),
+    Detailed {
+        version: String,
+        release_date: Option<String>,
+    },
+}
+
+fn parse_json_remote_versions(output: &str) -> Result<(Vec<String>, BTreeMap<String, String>)> {
+    let entries: Vec<JsonRemoteVersion> =
+        serde_json::from_str(output).wrap_err("list-all did not print a JSON array of versions")?;
+    let mut versions = vec![];
+    let mut dates = BTreeMap::new();
+    for entry in entries {
+        match entry {
+            JsonRemoteVersion::Version(version) => versions.push(version),
+            JsonRemoteVersion::Detailed {
+                version,
+                release_date,
+            } => {
+                if let Some(date) = release_date {
+                    dates.insert(version.clone(), date);
+                }
+                versions.push(version);
+            }
+        }
+    }
+    Ok((versions, dates))
+}
+
 fn build_script_man(name: &str, plugin_path: &Path) -> ScriptManager {
     ScriptManager::new(plugin_path.to_path_buf())
         .with_env("RTX_PLUGIN_NAME", name.to_string())
@@ -348,9 +575,39 @@ impl Plugin for ExternalPlugin {
     fn get_type(&self) -> PluginType {
         PluginType::External
     }
+    fn depends(&self) -> Vec<PluginName> {
+        match &self.toml.depends {
+            Some(depends) => depends.clone(),
+            None => default_depends(&self.name),
+        }
+    }
+    fn cache_status(&self) -> Vec<CacheStatus> {
+        let mut status = vec![
+            self.remote_version_cache.status(),
+            self.latest_stable_cache.status(),
+            self.alias_cache.status(),
+            self.legacy_filename_cache.status(),
+        ];
+        if self.toml.capabilities.json_output {
+            status.insert(1, self.remote_version_date_cache.status());
+        }
+        status
+    }
+
     fn list_remote_versions(&self, settings: &Settings) -> Result<Vec<String>> {
+        if let Some(data) = &self.toml.list_all.data {
+            if !settings.list_all_urls.contains_key(&self.name) {
+                return self.parse_remote_versions(data, self.toml.list_all.regex.as_deref());
+            }
+        }
         self.remote_version_cache
-            .get_or_try_init(|| self.fetch_remote_versions(settings))
+            .get_or_try_init(|| {
+                let (versions, dates) = self.fetch_remote_versions(settings)?;
+                if let Err(err) = self.remote_version_date_cache.write(&dates) {
+                    warn!("failed to write release dates of {}: {:#}", self.name, err);
+                }
+                Ok(versions)
+            })
             .map_err(|err| {
                 eyre!(
                     "Failed listing remote versions for plugin {}: {}",
@@ -361,6 +618,18 @@ impl Plugin for ExternalPlugin {
             .cloned()
     }
 
+    fn list_remote_version_dates(&self, settings: &Settings) -> Result<BTreeMap<String, String>> {
+        if !self.toml.capabilities.json_output
+            || self.toml.list_all.data.is_some()
+            || self.list_all_url(settings).is_some()
+        {
+            return Ok(BTreeMap::new());
+        }
+        self.remote_version_date_cache
+            .get_or_try_init(|| Ok(self.fetch_remote_versions(settings)?.1))
+            .cloned()
+    }
+
     fn latest_stable_version(&self, settings: &Settings) -> Result<Option<String>> {
         if !self.has_latest_stable_script() {
             return Ok(None);
@@ -392,6 +661,11 @@ impl Plugin for ExternalPlugin {
         git.current_abbrev_ref()
     }
 
+    fn current_commit_date(&self) -> Result<String> {
+        let git = Git::new(self.plugin_path.to_path_buf());
+        git.current_commit_date()
+    }
+
     fn is_installed(&self) -> bool {
         self.plugin_path.exists()
     }
@@ -406,14 +680,20 @@ impl Plugin for ExternalPlugin {
             if self.is_installed() {
                 return Ok(());
             }
-            if !config.settings.yes && self.repo_url.is_none() {
-                let url = self.get_repo_url(config)?;
-                eprintln!(
-                    "⚠️  {name} is a community-developed plugin: {url}",
-                    name = style(&self.name).cyan(),
-                    url = style(url.trim_end_matches(".git")).yellow(),
-                );
-                if !prompt::confirm(&format!("Would you like to install {}?", self.name))? {
+            if self.repo_url.is_none() {
+                let install = match preset_answer(&config.settings, Destructive::UnknownPlugin) {
+                    Some(answer) => answer,
+                    None => {
+                        let url = self.get_repo_url(config)?;
+                        eprintln!(
+                            "⚠️  {name} is a community-developed plugin: {url}",
+                            name = style(&self.name).cyan(),
+                            url = style(url.trim_end_matches(".git")).yellow(),
+                        );
+                        prompt::confirm(&format!("Would you like to install {}?", self.name))?
+                    }
+                };
+                if !install {
                     Err(PluginNotInstalled(self.name.clone()))?
                 }
             }
@@ -426,7 +706,7 @@ impl Plugin for ExternalPlugin {
         self.install(config, &pr)
     }
 
-    fn update(&self, gitref: Option<String>) -> Result<()> {
+    fn update(&self, config: &Config, gitref: Option<String>) -> Result<()> {
         let plugin_path = self.plugin_path.to_path_buf();
         if plugin_path.is_symlink() {
             warn!(
@@ -443,16 +723,39 @@ impl Plugin for ExternalPlugin {
             );
             return Ok(());
         }
-        // TODO: asdf_run_hook "pre_plugin_update"
-        let (_pre, _post) = git.update(gitref)?;
-        // TODO: asdf_run_hook "post_plugin_update"
-        Ok(())
+        dirs::ensure_root_writable(&format!("updating plugin {}", self.name))?;
+        let path_env = (
+            "RTX_PLUGIN_PATH",
+            self.plugin_path.to_string_lossy().to_string(),
+        );
+        hooks::run(
+            &config.settings,
+            Hook::PrePluginUpdate,
+            &self.name,
+            std::slice::from_ref(&path_env),
+        )?;
+        let (pre, post) = git.update(gitref)?;
+        quarantine::clear(&self.name);
+        let env = [
+            path_env,
+            ("RTX_PLUGIN_PREV_REF", pre),
+            ("RTX_PLUGIN_POST_REF", post),
+        ];
+        self.run_callback(&config.settings, &Script::PostPluginUpdate, &env)?;
+        hooks::run(&config.settings, Hook::PostPluginUpdate, &self.name, &env)
     }
 
-    fn uninstall(&self, pr: &ProgressReport) -> Result<()> {
+    fn uninstall(&self, config: &Config, pr: &ProgressReport) -> Result<()> {
         if !self.is_installed() {
             return Ok(());
         }
+        dirs::ensure_root_writable(&format!("uninstalling plugin {}", self.name))?;
+        let env = [(
+            "RTX_PLUGIN_PATH",
+            self.plugin_path.to_string_lossy().to_string(),
+        )];
+        hooks::run(&config.settings, Hook::PrePluginRemove, &self.name, &env)?;
+        self.run_callback(&config.settings, &Script::PrePluginRemove, &env)?;
         pr.set_message("uninstalling");
 
         let rmdir = |dir: &Path| {
@@ -470,7 +773,7 @@ impl Plugin for ExternalPlugin {
 
         rmdir(&self.plugin_path)?;
 
-        Ok(())
+        hooks::run(&config.settings, Hook::PostPluginRemove, &self.name, &env)
     }
 
     fn get_aliases(&self, settings: &Settings) -> Result<BTreeMap<String, String>> {
@@ -496,6 +799,20 @@ impl Plugin for ExternalPlugin {
         Ok(aliases)
     }
 
+    fn script_manager(&self, config: &Config, tv: Option<&ToolVersion>) -> Option<ScriptManager> {
+        match tv {
+            Some(tv) => Some(self.script_man_for_tv(config, tv)),
+            None => Some(self.script_man.clone()),
+        }
+    }
+
+    fn get_aliases_source(&self) -> String {
+        match &self.toml.list_aliases.data {
+            Some(_) => format!("{} rtx.plugin.toml", self.name),
+            None => format!("{} bin/list-aliases", self.name),
+        }
+    }
+
     fn legacy_filenames(&self, settings: &Settings) -> Result<Vec<String>> {
         if let Some(data) = &self.toml.list_legacy_filenames.data {
             return Ok(self.parse_legacy_filenames(data));
@@ -543,15 +860,14 @@ impl Plugin for ExternalPlugin {
             if !command.starts_with("command-") || !command.ends_with(".bash") {
                 continue;
             }
+            let header = ExternalCommandHeader::from_file(&command_path.join(&command));
             let command = command
                 .strip_prefix("command-")
                 .unwrap()
                 .strip_suffix(".bash")
                 .unwrap()
-                .split('-')
-                .map(|s| s.to_string())
-                .collect::<Vec<String>>();
-            commands.push(command);
+                .to_string();
+            commands.push(header.to_command(&command));
         }
         if commands.is_empty() {
             return Ok(vec![]);
@@ -559,16 +875,7 @@ impl Plugin for ExternalPlugin {
 
         let topic = Command::new(self.name.clone())
             .about(format!("Commands provided by {} plugin", &self.name))
-            .subcommands(commands.into_iter().map(|cmd| {
-                Command::new(cmd.join("-"))
-                    .about(format!("{} command", cmd.join("-")))
-                    .arg(
-                        clap::Arg::new("args")
-                            .num_args(1..)
-                            .allow_hyphen_values(true)
-                            .trailing_var_arg(true),
-                    )
-            }));
+            .subcommands(commands);
         Ok(vec![topic])
     }
 
@@ -587,12 +894,30 @@ impl Plugin for ExternalPlugin {
                 .join(format!("command-{command}.bash")),
             args,
         );
-        let result = self
-            .script_man
-            .cmd(&config.settings, &script)
-            .unchecked()
-            .run()?;
-        exit(result.status.code().unwrap_or(1));
+        let code = cmd::run_foreground(self.script_man.cmd(&config.settings, &script))?;
+        exit(code);
+    }
+
+    fn download_to(
+        &self,
+        config: &Config,
+        tv: &ToolVersion,
+        dir: &Path,
+        pr: &ProgressReport,
+    ) -> Result<()> {
+        let sm = self.script_man_for_tv(config, tv);
+        if !sm.script_exists(&Download) {
+            return Err(eyre!("{} does not have a bin/download script", self.name));
+        }
+        let download_path = dir.join("download").to_string_lossy().to_string();
+        let install_path = dir.join("install").to_string_lossy().to_string();
+        file::create_dir_all(&download_path)?;
+        file::create_dir_all(&install_path)?;
+        sm.with_env("RTX_DOWNLOAD_PATH", &download_path)
+            .with_env("ASDF_DOWNLOAD_PATH", &download_path)
+            .with_env("RTX_INSTALL_PATH", &install_path)
+            .with_env("ASDF_INSTALL_PATH", &install_path)
+            .run_by_line(&config.settings, &Download, pr)
     }
 
     fn install_version(
@@ -609,6 +934,13 @@ impl Plugin for ExternalPlugin {
         if self.script_man_for_tv(config, tv).script_exists(&Download) {
             pr.set_message("downloading");
             run_script(&Download)?;
+            pr.set_message("verifying checksums");
+            checksums::verify(
+                &self.toml.checksums,
+                &self.plugin_path,
+                tv,
+                self.toml.capabilities.checksums,
+            )?;
         }
         pr.set_message("installing");
         run_script(&Install)?;
@@ -625,21 +957,31 @@ impl Plugin for ExternalPlugin {
     }
 
     fn list_bin_paths(&self, config: &Config, tv: &ToolVersion) -> Result<Vec<PathBuf>> {
-        self.cache
-            .list_bin_paths(config, self, tv, || self.fetch_bin_paths(config, tv))
+        self.with_quarantine(|| {
+            self.cache
+                .list_bin_paths(config, self, tv, || self.fetch_bin_paths(config, tv))
+        })
     }
 
     fn exec_env(&self, config: &Config, tv: &ToolVersion) -> Result<HashMap<String, String>> {
         if matches!(tv.request, ToolVersionRequest::System(_)) {
             return Ok(EMPTY_HASH_MAP.clone());
         }
-        if !self.script_man.script_exists(&ExecEnv) || *env::__RTX_SCRIPT {
-            // if the script does not exist, or we're already running from within a script,
-            // the second is to prevent infinite loops
+        if let Some(env) = self.static_exec_env(tv)? {
+            return Ok(env);
+        }
+        if self.toml.capabilities.declarative_env
+            || !self.script_man.script_exists(&ExecEnv)
+            || *env::__RTX_SCRIPT
+        {
+            // if the env is only declared statically, the script does not exist, or we're
+            // already running from within a script, the last is to prevent infinite loops
             return Ok(EMPTY_HASH_MAP.clone());
         }
-        self.cache
-            .exec_env(config, self, tv, || self.fetch_exec_env(config, tv))
+        self.with_quarantine(|| {
+            self.cache
+                .exec_env(config, self, tv, || self.fetch_exec_env(config, tv))
+        })
     }
 }
 
@@ -662,6 +1004,18 @@ static EMPTY_HASH_MAP: Lazy<HashMap<String, String>> = Lazy::new(HashMap::new);
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_json_remote_versions() {
+        let (versions, dates) = parse_json_remote_versions(
+            r#"["1.0.0", {"version": "1.1.0"}, {"version": "2.0.0", "release_date": "2023-06-08", "lts": true}]"#,
+        )
+        .unwrap();
+        assert_eq!(versions, vec!["1.0.0", "1.1.0", "2.0.0"]);
+        assert_eq!(dates.get("2.0.0").unwrap(), "2023-06-08");
+        assert_eq!(dates.len(), 1);
+        assert!(parse_json_remote_versions("1.0.0 1.1.0").is_err());
+    }
+
     #[test]
     fn test_debug() {
         let plugin = ExternalPlugin::new(PluginName::from("dummy"));
@@ -0,0 +1,302 @@
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{eyre, Result};
+use itertools::Itertools;
+use versions::Versioning;
+
+use crate::cache::{CacheManager, CacheStatus};
+use crate::config::{Config, Settings};
+use crate::github::GithubRelease;
+use crate::plugins::{pathname, Plugin, PluginName, PluginType};
+use crate::toolset::ToolVersion;
+use crate::ui::progress_report::ProgressReport;
+use crate::{dirs, env, file, http, platform};
+
+/// prefix of tools installed from GitHub release assets, e.g.: "gh:cli/cli"
+pub const PREFIX: &str = "gh:";
+
+/// installs a tool from the assets of its GitHub releases without a plugin, the asset for this
+/// os and arch is picked by its name
+#[derive(Debug)]
+pub struct GithubReleasePlugin {
+    name: PluginName,
+    /// e.g.: "cli/cli"
+    repo: String,
+    remote_version_cache: CacheManager<Vec<String>>,
+}
+
+impl GithubReleasePlugin {
+    pub fn new(name: PluginName) -> Self {
+        let repo = name.trim_start_matches(PREFIX).to_string();
+        let cache_path = dirs::CACHE.join(pathname(&name));
+        Self {
+            remote_version_cache: CacheManager::new(cache_path.join("remote_versions.msgpack.z"))
+                .with_fresh_duration(*env::RTX_FETCH_REMOTE_VERSIONS_CACHE),
+            name,
+            repo,
+        }
+    }
+
+    fn fetch<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let http = http::Client::new()?;
+        let url = format!("https://api.github.com/repos/{}/{path}", self.repo);
+        let mut req = http.get(url);
+        if let Some(token) = &*env::GITHUB_API_TOKEN {
+            req = req.header("authorization", format!("token {}", token));
+        }
+        let resp = req.send()?;
+        http.ensure_success(&resp)?;
+        Ok(resp.json()?)
+    }
+
+    fn fetch_remote_versions(&self) -> Result<Vec<String>> {
+        let releases: Vec<GithubRelease> = self.fetch("releases?per_page=100")?;
+        let versions = releases
+            .into_iter()
+            .filter(|r| !r.prerelease)
+            .map(|r| version_from_tag(&r.tag_name))
+            .unique()
+            .sorted_by_cached_key(|s| Versioning::new(s))
+            .collect();
+        Ok(versions)
+    }
+
+    /// the release of a version, tags may or may not start with "v"
+    fn fetch_release(&self, version: &str) -> Result<GithubRelease> {
+        self.fetch(&format!("releases/tags/v{version}"))
+            .or_else(|_| self.fetch(&format!("releases/tags/{version}")))
+            .map_err(|err| eyre!("no release {version} in {}: {err:#}", self.repo))
+    }
+
+    /// the name of the executable for assets which are not archives
+    fn bin_name(&self, tv: &ToolVersion) -> String {
+        match tv.opts.get("bin") {
+            Some(bin) => bin.clone(),
+            None => self.repo.rsplit('/').next().unwrap().to_string(),
+        }
+    }
+
+    fn install(&self, tv: &ToolVersion, pr: &ProgressReport, asset: &Path) -> Result<()> {
+        let filename = asset.file_name().unwrap().to_string_lossy().to_lowercase();
+        pr.set_message(format!("installing {filename}"));
+        let dest = tv.download_path().join("extracted");
+        file::remove_all(&dest)?;
+        file::create_dir_all(&dest)?;
+        if filename.ends_with(".tar.gz") || filename.ends_with(".tgz") {
+            file::untar(asset, &dest, Some(pr))?;
+        } else if filename.ends_with(".tar.xz") || filename.ends_with(".txz") {
+            file::untar_xz(asset, &dest)?;
+        } else if filename.ends_with(".zip") {
+            file::unzip(asset, &dest)?;
+        } else {
+            let bin = dest.join(self.bin_name(tv));
+            file::rename(asset, &bin)?;
+            file::make_executable(&bin)?;
+        }
+        // most archives have everything in a directory named after the asset
+        let entries = dest.read_dir()?.collect::<std::io::Result<Vec<_>>>()?;
+        let root = match entries.as_slice() {
+            [entry] if entry.path().is_dir() => entry.path(),
+            _ => dest,
+        };
+        file::remove_all(tv.install_path())?;
+        file::create_dir_all(tv.install_path().parent().unwrap())?;
+        file::rename(root, tv.install_path())
+    }
+}
+
+impl Plugin for GithubReleasePlugin {
+    fn name(&self) -> &PluginName {
+        &self.name
+    }
+
+    fn get_type(&self) -> PluginType {
+        PluginType::GithubRelease
+    }
+
+    fn get_remote_url(&self) -> Option<String> {
+        Some(format!("https://github.com/{}", self.repo))
+    }
+
+    fn cache_status(&self) -> Vec<CacheStatus> {
+        vec![self.remote_version_cache.status()]
+    }
+
+    fn list_remote_versions(&self, _settings: &Settings) -> Result<Vec<String>> {
+        self.remote_version_cache
+            .get_or_try_init(|| self.fetch_remote_versions())
+            .cloned()
+    }
+
+    fn install_version(
+        &self,
+        _config: &Config,
+        tv: &ToolVersion,
+        pr: &ProgressReport,
+    ) -> Result<()> {
+        let release = self.fetch_release(&tv.version)?;
+        let names = release.assets.iter().map(|a| a.name.as_str()).collect_vec();
+        let (os, arch) = (platform::os(), platform::arch());
+        let name = match tv.opts.get("asset") {
+            Some(asset) => asset.replace("{version}", &tv.version),
+            None => pick_asset(&names, &os, &arch, platform::is_musl())
+                .ok_or_else(|| {
+                    eyre!(
+                        "{} has no asset for {os}-{arch}, choose one with the asset option: {}",
+                        release.tag_name,
+                        names.join(", ")
+                    )
+                })?
+                .to_string(),
+        };
+        let asset = release
+            .assets
+            .iter()
+            .find(|a| a.name == name)
+            .ok_or_else(|| eyre!("{} has no asset named {name}", release.tag_name))?;
+        let path = tv.download_path().join(&asset.name);
+        file::create_dir_all(tv.download_path())?;
+        http::Client::new()?.download_file(&asset.browser_download_url, &path, Some(pr))?;
+        self.install(tv, pr, &path)
+    }
+
+    fn list_bin_paths(&self, _config: &Config, tv: &ToolVersion) -> Result<Vec<PathBuf>> {
+        let bin = tv.install_path().join("bin");
+        match bin.is_dir() {
+            true => Ok(vec![bin]),
+            // the executables are at the root of the archive
+            false => Ok(vec![tv.install_path()]),
+        }
+    }
+}
+
+fn version_from_tag(tag: &str) -> String {
+    tag.strip_prefix('v').unwrap_or(tag).to_string()
+}
+
+/// files next to the binaries in releases, e.g.: checksums, signatures and system packages
+const IGNORED_SUFFIXES: &[&str] = &[
+    ".asc",
+    ".apk",
+    ".deb",
+    ".dmg",
+    ".exe",
+    ".json",
+    ".md5",
+    ".msi",
+    ".pem",
+    ".pkg",
+    ".rpm",
+    ".sbom",
+    ".sha256",
+    ".sha256sum",
+    ".sha512",
+    ".sig",
+    ".txt",
+];
+
+fn os_keywords(os: &str) -> &'static [&'static str] {
+    match os {
+        "linux" => &["linux"],
+        "macos" => &["darwin", "macos", "apple", "osx"],
+        _ => &[],
+    }
+}
+
+fn arch_keywords(arch: &str) -> &'static [&'static str] {
+    match arch {
+        "x64" => &["x86_64", "x86-64", "amd64", "x64"],
+        "arm64" => &["arm64", "aarch64"],
+        _ => &[],
+    }
+}
+
+/// arch names in assets, an asset with one of these that is not for this arch is skipped
+const ARCH_KEYWORDS: &[&str] = &[
+    "x86", "x64", "amd64", "386", "i686", "arm", "aarch64", "ppc", "s390", "riscv", "mips",
+];
+
+/// picks the asset for this os and arch, as named by `platform::os()` and `platform::arch()`,
+/// by the names release tools like goreleaser give them, e.g.: gh_2.40.0_linux_amd64.tar.gz
+fn pick_asset<'a>(names: &[&'a str], os: &str, arch: &str, musl: bool) -> Option<&'a str> {
+    let score = |name: &str| -> Option<i32> {
+        let name = name.to_lowercase();
+        if IGNORED_SUFFIXES.iter().any(|s| name.ends_with(s)) {
+            return None;
+        }
+        if !os_keywords(os).iter().any(|k| name.contains(k)) {
+            return None;
+        }
+        let mut score = 0;
+        if arch_keywords(arch).iter().any(|k| name.contains(k)) {
+            score += 10;
+        } else if os == "macos" && name.contains("universal") {
+            score += 5;
+        } else if ARCH_KEYWORDS.iter().any(|k| name.contains(k)) {
+            return None;
+        }
+        // glibc systems can run static musl builds but prefer the glibc ones
+        match (name.contains("musl"), musl) {
+            (true, true) => score += 2,
+            (true, false) => score -= 2,
+            _ => {}
+        }
+        Some(score)
+    };
+    names
+        .iter()
+        .filter_map(|n| score(n).map(|s| (s, *n)))
+        .sorted_by_key(|(s, _)| -s)
+        .map(|(_, n)| n)
+        .next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick_asset() {
+        let names = [
+            "gh_2.40.0_checksums.txt",
+            "gh_2.40.0_linux_386.tar.gz",
+            "gh_2.40.0_linux_amd64.rpm",
+            "gh_2.40.0_linux_amd64.tar.gz",
+            "gh_2.40.0_linux_arm64.tar.gz",
+            "gh_2.40.0_macOS_amd64.zip",
+            "gh_2.40.0_macOS_arm64.zip",
+            "gh_2.40.0_windows_amd64.zip",
+        ];
+        let pick = |os, arch| pick_asset(&names, os, arch, false);
+        assert_eq!(pick("linux", "x64"), Some("gh_2.40.0_linux_amd64.tar.gz"));
+        assert_eq!(pick("linux", "arm64"), Some("gh_2.40.0_linux_arm64.tar.gz"));
+        assert_eq!(pick("macos", "arm64"), Some("gh_2.40.0_macOS_arm64.zip"));
+        assert_eq!(pick("freebsd", "x64"), None);
+
+        let names = [
+            "ripgrep-14.0.3-x86_64-unknown-linux-musl.tar.gz",
+            "ripgrep-14.0.3-x86_64-unknown-linux-gnu.tar.gz",
+            "ripgrep-14.0.3-x86_64-apple-darwin.tar.gz",
+        ];
+        assert_eq!(
+            pick_asset(&names, "linux", "x64", false),
+            Some("ripgrep-14.0.3-x86_64-unknown-linux-gnu.tar.gz")
+        );
+        assert_eq!(
+            pick_asset(&names, "linux", "x64", true),
+            Some("ripgrep-14.0.3-x86_64-unknown-linux-musl.tar.gz")
+        );
+
+        let names = ["jq-linux-amd64", "jq-macos-arm64", "jq-linux-i386"];
+        assert_eq!(
+            pick_asset(&names, "linux", "x64", false),
+            Some("jq-linux-amd64")
+        );
+    }
+
+    #[test]
+    fn test_version_from_tag() {
+        assert_eq!(version_from_tag("v2.40.0"), "2.40.0");
+        assert_eq!(version_from_tag("14.0.3"), "14.0.3");
+    }
+}
@@ -0,0 +1,171 @@
+use std::iter::once;
+use std::path::PathBuf;
+
+use color_eyre::eyre::{eyre, Result};
+use itertools::Itertools;
+use versions::Versioning;
+
+use crate::cache::{CacheManager, CacheStatus};
+use crate::cmd::CmdLineRunner;
+use crate::config::{Config, Settings};
+use crate::plugins::{pathname, Plugin, PluginName, PluginType};
+use crate::toolset::ToolVersion;
+use crate::ui::progress_report::ProgressReport;
+use crate::{dirs, env, http};
+
+/// prefix of tools installed with go install, e.g.: "go:golang.org/x/tools/gopls"
+pub const PREFIX: &str = "go:";
+
+/// installs a go package with `go install` into its own GOBIN
+#[derive(Debug)]
+pub struct GoInstallPlugin {
+    name: PluginName,
+    /// e.g.: "github.com/golangci/golangci-lint/cmd/golangci-lint"
+    package: String,
+    remote_version_cache: CacheManager<Vec<String>>,
+}
+
+impl GoInstallPlugin {
+    pub fn new(name: PluginName) -> Self {
+        let package = name.trim_start_matches(PREFIX).to_string();
+        let cache_path = dirs::CACHE.join(pathname(&name));
+        Self {
+            remote_version_cache: CacheManager::new(cache_path.join("remote_versions.msgpack.z"))
+                .with_fresh_duration(*env::RTX_FETCH_REMOTE_VERSIONS_CACHE),
+            name,
+            package,
+        }
+    }
+
+    /// the versions of the module which contains the package, from the go module proxy
+    fn fetch_remote_versions(&self) -> Result<Vec<String>> {
+        let http = http::Client::new()?;
+        for module in module_candidates(&self.package) {
+            let url = format!("{}/{}/@v/list", *env::GOPROXY, escape_module(module));
+            let Ok(body) = http.get_text(url) else {
+                continue;
+            };
+            let versions = body
+                .lines()
+                .filter_map(|v| v.trim().strip_prefix('v'))
+                .filter(|v| !v.is_empty())
+                .map(|v| v.to_string())
+                .sorted_by_cached_key(|s| Versioning::new(s))
+                .collect_vec();
+            if !versions.is_empty() {
+                return Ok(versions);
+            }
+        }
+        Err(eyre!("no module with versions found for {}", self.package))
+    }
+
+    /// go is found on the shims first so a go installed by rtx is used
+    fn path(&self) -> Result<std::ffi::OsString> {
+        Ok(env::join_paths(
+            once(dirs::SHIMS.clone()).chain(env::PATH.iter().cloned()),
+        )?)
+    }
+}
+
+impl Plugin for GoInstallPlugin {
+    fn name(&self) -> &PluginName {
+        &self.name
+    }
+
+    fn get_type(&self) -> PluginType {
+        PluginType::Go
+    }
+
+    fn get_remote_url(&self) -> Option<String> {
+        Some(format!("https://pkg.go.dev/{}", self.package))
+    }
+
+    fn depends(&self) -> Vec<PluginName> {
+        vec!["go".into()]
+    }
+
+    fn cache_status(&self) -> Vec<CacheStatus> {
+        vec![self.remote_version_cache.status()]
+    }
+
+    fn list_remote_versions(&self, _settings: &Settings) -> Result<Vec<String>> {
+        self.remote_version_cache
+            .get_or_try_init(|| self.fetch_remote_versions())
+            .cloned()
+    }
+
+    fn install_version(
+        &self,
+        config: &Config,
+        tv: &ToolVersion,
+        pr: &ProgressReport,
+    ) -> Result<()> {
+        // versions are listed without the "v" go uses, "v1.55.0" works as well
+        let version = match tv.version.starts_with('v') {
+            true => tv.version.clone(),
+            false => format!("v{}", tv.version),
+        };
+        let package = format!("{}@{version}", self.package);
+        pr.set_message(format!("go install {package}"));
+        CmdLineRunner::new(&config.settings, "go")
+            .with_pr(pr)
+            .env("PATH", self.path()?)
+            .env("GOBIN", tv.install_path().join("bin"))
+            .arg("install")
+            .arg(package)
+            .execute()
+    }
+
+    fn list_bin_paths(&self, _config: &Config, tv: &ToolVersion) -> Result<Vec<PathBuf>> {
+        Ok(vec![tv.install_path().join("bin")])
+    }
+}
+
+/// the package and its parents, one of which is the module it is in
+fn module_candidates(package: &str) -> Vec<&str> {
+    let mut candidates = vec![package];
+    let mut module = package;
+    while let Some((parent, _)) = module.rsplit_once('/') {
+        candidates.push(parent);
+        module = parent;
+    }
+    candidates
+}
+
+/// module paths are case-sensitive so the proxy escapes uppercase letters, e.g.: "!burnt!sushi"
+fn escape_module(module: &str) -> String {
+    module
+        .chars()
+        .map(|c| match c.is_ascii_uppercase() {
+            true => format!("!{}", c.to_ascii_lowercase()),
+            false => c.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_module_candidates() {
+        assert_eq!(
+            module_candidates("github.com/golangci/golangci-lint/cmd/golangci-lint"),
+            vec![
+                "github.com/golangci/golangci-lint/cmd/golangci-lint",
+                "github.com/golangci/golangci-lint/cmd",
+                "github.com/golangci/golangci-lint",
+                "github.com/golangci",
+                "github.com",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_escape_module() {
+        assert_eq!(
+            escape_module("github.com/BurntSushi/toml"),
+            "github.com/!burnt!sushi/toml"
+        );
+    }
+}
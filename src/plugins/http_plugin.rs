@@ -0,0 +1,229 @@
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{bail, eyre, Result};
+
+use crate::config::{Config, Settings};
+use crate::plugins::{Plugin, PluginName, PluginType};
+use crate::toolset::{ToolVersion, ToolVersionOptions};
+use crate::ui::progress_report::ProgressReport;
+use crate::{file, hash, http, platform};
+
+/// prefix of tools downloaded from a url in their options, e.g.: "http:mycli"
+pub const PREFIX: &str = "http:";
+
+/// installs a tool by downloading the archive or binary at the url in its options, e.g.:
+///
+/// ```toml
+/// [tools]
+/// "http:mycli" = { version = "1.2.3", url = "https://example.com/mycli-{version}-{os}-{arch}.tar.gz" }
+/// ```
+#[derive(Debug)]
+pub struct HttpPlugin {
+    name: PluginName,
+    /// e.g.: "mycli"
+    bin_name: String,
+}
+
+impl HttpPlugin {
+    pub fn new(name: PluginName) -> Self {
+        let bin_name = name.trim_start_matches(PREFIX).to_string();
+        Self { name, bin_name }
+    }
+
+    fn url(&self, tv: &ToolVersion) -> Result<String> {
+        let url = tv.opts.get("url").ok_or_else(|| {
+            eyre!(
+                "{} has no url option, e.g.: \"{}\" = {{ version = \"{}\", url = \"https://...\" }}",
+                self.name,
+                self.name,
+                tv.version
+            )
+        })?;
+        Ok(render_url(
+            url,
+            &tv.version,
+            &platform::os(),
+            &platform::arch(),
+        ))
+    }
+
+    fn install(&self, tv: &ToolVersion, pr: &ProgressReport, archive: &Path) -> Result<()> {
+        let format = match tv.opts.get("format") {
+            Some(format) => format.clone(),
+            None => format_from_url(&archive.to_string_lossy()).to_string(),
+        };
+        pr.set_message(format!("extracting {}", archive.display()));
+        let dest = tv.download_path().join("extracted");
+        file::remove_all(&dest)?;
+        file::create_dir_all(&dest)?;
+        match format.as_str() {
+            "tar.gz" | "tgz" => file::untar(archive, &dest, Some(pr))?,
+            "tar.xz" | "txz" => file::untar_xz(archive, &dest)?,
+            "zip" => file::unzip(archive, &dest)?,
+            "raw" => {
+                let bin_name = tv.opts.get("bin").unwrap_or(&self.bin_name);
+                if !is_file_name(bin_name) {
+                    bail!("{}: bin must be a file name, got {bin_name}", self.name);
+                }
+                let bin = dest.join(bin_name);
+                file::rename(archive, &bin)?;
+                file::make_executable(&bin)?;
+            }
+            _ => bail!("unsupported format {format}, expected one of: tar.gz, tar.xz, zip, raw"),
+        }
+        // archives often have everything in a directory named after the archive
+        let entries = dest.read_dir()?.collect::<std::io::Result<Vec<_>>>()?;
+        let root = match entries.as_slice() {
+            [entry] if format != "raw" && entry.path().is_dir() => entry.path(),
+            _ => dest,
+        };
+        file::remove_all(tv.install_path())?;
+        file::create_dir_all(tv.install_path().parent().unwrap())?;
+        file::rename(root, tv.install_path())
+    }
+}
+
+impl Plugin for HttpPlugin {
+    fn name(&self) -> &PluginName {
+        &self.name
+    }
+
+    fn get_type(&self) -> PluginType {
+        PluginType::Http
+    }
+
+    /// there is no way to list the versions at an arbitrary url, so versions must be exact
+    fn list_remote_versions(&self, _settings: &Settings) -> Result<Vec<String>> {
+        Ok(vec![])
+    }
+
+    fn install_version(
+        &self,
+        _config: &Config,
+        tv: &ToolVersion,
+        pr: &ProgressReport,
+    ) -> Result<()> {
+        let url = self.url(tv)?;
+        let filename = filename_from_url(&url).unwrap_or(&self.bin_name);
+        let path = tv.download_path().join(filename);
+        file::create_dir_all(tv.download_path())?;
+        pr.set_message(format!("downloading {url}"));
+        http::Client::new()?.download_file(&url, &path, Some(pr))?;
+        if let Some(checksum) = checksum(&tv.opts, &platform::os(), &platform::arch()) {
+            pr.set_message(format!("verifying {filename}"));
+            let checksum = checksum.strip_prefix("sha256:").unwrap_or(checksum);
+            hash::ensure_checksum_sha256(&path, checksum)?;
+        }
+        self.install(tv, pr, &path)
+    }
+
+    fn list_bin_paths(&self, _config: &Config, tv: &ToolVersion) -> Result<Vec<PathBuf>> {
+        if let Some(bin_path) = tv.opts.get("bin_path") {
+            return Ok(vec![tv.install_path().join(bin_path)]);
+        }
+        let bin = tv.install_path().join("bin");
+        match bin.is_dir() {
+            true => Ok(vec![bin]),
+            // the executables are at the root of the archive
+            false => Ok(vec![tv.install_path()]),
+        }
+    }
+}
+
+/// fills in `{version}`, `{os}` and `{arch}` in the url option
+fn render_url(url: &str, version: &str, os: &str, arch: &str) -> String {
+    url.replace("{version}", version)
+        .replace("{os}", os)
+        .replace("{arch}", arch)
+}
+
+/// the last path segment of the url, without the query which may hold tokens
+fn filename_from_url(url: &str) -> Option<&str> {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    path.rsplit('/').next().filter(|f| is_file_name(f))
+}
+
+/// a name which stays in the directory it is joined to, e.g.: not "../x"
+fn is_file_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\'])
+}
+
+fn format_from_url(url: &str) -> &'static str {
+    let url = url.to_lowercase();
+    if url.ends_with(".tar.gz") || url.ends_with(".tgz") {
+        "tar.gz"
+    } else if url.ends_with(".tar.xz") || url.ends_with(".txz") {
+        "tar.xz"
+    } else if url.ends_with(".zip") {
+        "zip"
+    } else {
+        "raw"
+    }
+}
+
+/// the checksum for this platform, e.g.: "checksum_linux_x64", or else the "checksum" option
+fn checksum<'a>(opts: &'a ToolVersionOptions, os: &str, arch: &str) -> Option<&'a str> {
+    opts.get(&format!("checksum_{os}_{arch}"))
+        .or_else(|| opts.get("checksum"))
+        .map(|s| s.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_url() {
+        assert_eq!(
+            render_url(
+                "https://example.com/v{version}/mycli-{os}-{arch}.tar.gz",
+                "1.2.3",
+                "linux",
+                "x64"
+            ),
+            "https://example.com/v1.2.3/mycli-linux-x64.tar.gz"
+        );
+    }
+
+    #[test]
+    fn test_format_from_url() {
+        assert_eq!(format_from_url("mycli-1.2.3.tar.gz"), "tar.gz");
+        assert_eq!(format_from_url("mycli-1.2.3.TGZ"), "tar.gz");
+        assert_eq!(format_from_url("mycli-1.2.3.tar.xz"), "tar.xz");
+        assert_eq!(format_from_url("mycli-1.2.3.zip"), "zip");
+        assert_eq!(format_from_url("mycli-linux-x64"), "raw");
+    }
+
+    #[test]
+    fn test_filename_from_url() {
+        assert_eq!(
+            filename_from_url("https://example.com/mycli.tar.gz?token=abc/def"),
+            Some("mycli.tar.gz")
+        );
+        assert_eq!(
+            filename_from_url("https://example.com/mycli#sha"),
+            Some("mycli")
+        );
+        assert_eq!(filename_from_url("https://example.com/"), None);
+    }
+
+    #[test]
+    fn test_is_file_name() {
+        assert!(is_file_name("mycli"));
+        assert!(!is_file_name("../mycli"));
+        assert!(!is_file_name("bin/mycli"));
+        assert!(!is_file_name(".."));
+        assert!(!is_file_name(""));
+    }
+
+    #[test]
+    fn test_checksum() {
+        let mut opts = ToolVersionOptions::new();
+        assert_eq!(checksum(&opts, "linux", "x64"), None);
+        opts.insert("checksum".into(), "sha256:abc".into());
+        assert_eq!(checksum(&opts, "linux", "x64"), Some("sha256:abc"));
+        opts.insert("checksum_linux_x64".into(), "def".into());
+        assert_eq!(checksum(&opts, "linux", "x64"), Some("def"));
+        assert_eq!(checksum(&opts, "macos", "arm64"), Some("sha256:abc"));
+    }
+}
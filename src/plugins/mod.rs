@@ -1,14 +1,23 @@
 use std::collections::{BTreeMap, HashMap};
 use std::fmt::Debug;
 use std::path::{Path, PathBuf};
+use std::sync::RwLock;
 
 use clap::Command;
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{eyre, Result};
 use console::style;
+use once_cell::sync::Lazy;
 
+pub use cargo_plugin::CargoPlugin;
 pub use external_plugin::ExternalPlugin;
+pub use github_release_plugin::GithubReleasePlugin;
+pub use go_install_plugin::GoInstallPlugin;
+pub use http_plugin::HttpPlugin;
+pub use npm_plugin::NpmPlugin;
+pub use pipx_plugin::PipxPlugin;
 pub use script_manager::{Script, ScriptManager};
 
+use crate::cache::CacheStatus;
 use crate::config::{Config, Settings};
 use crate::file;
 use crate::file::display_path;
@@ -17,10 +26,18 @@ use crate::toolset::ToolVersion;
 use crate::ui::multi_progress_report::MultiProgressReport;
 use crate::ui::progress_report::{ProgressReport, PROG_TEMPLATE};
 
+pub mod cargo_plugin;
 pub mod core;
+mod external_command_header;
 mod external_plugin;
 mod external_plugin_cache;
-mod rtx_plugin_toml;
+pub mod github_release_plugin;
+pub mod go_install_plugin;
+pub mod http_plugin;
+pub mod npm_plugin;
+pub mod pipx_plugin;
+mod quarantine;
+pub mod rtx_plugin_toml;
 mod script_manager;
 
 pub type PluginName = String;
@@ -31,9 +48,22 @@ pub trait Plugin: Debug + Send + Sync {
         PluginType::Core
     }
     fn list_remote_versions(&self, settings: &Settings) -> Result<Vec<String>>;
+    /// release dates by version, for plugins which know them
+    fn list_remote_version_dates(&self, _settings: &Settings) -> Result<BTreeMap<String, String>> {
+        Ok(BTreeMap::new())
+    }
     fn latest_stable_version(&self, _settings: &Settings) -> Result<Option<String>> {
         Ok(None)
     }
+    /// plugins which need to be installed before versions of this one, `rtx install` installs
+    /// them first when they are installed together
+    fn depends(&self) -> Vec<PluginName> {
+        default_depends(self.name())
+    }
+    /// freshness of the plugin's caches, see `rtx cache status`
+    fn cache_status(&self) -> Vec<CacheStatus> {
+        vec![]
+    }
     fn get_remote_url(&self) -> Option<String> {
         None
     }
@@ -43,6 +73,9 @@ pub trait Plugin: Debug + Send + Sync {
     fn current_abbrev_ref(&self) -> Result<String> {
         Ok(String::from(""))
     }
+    fn current_commit_date(&self) -> Result<String> {
+        Ok(String::from(""))
+    }
     fn is_installed(&self) -> bool {
         true
     }
@@ -54,15 +87,24 @@ pub trait Plugin: Debug + Send + Sync {
     ) -> Result<()> {
         Ok(())
     }
-    fn update(&self, _git_ref: Option<String>) -> Result<()> {
+    fn update(&self, _config: &Config, _git_ref: Option<String>) -> Result<()> {
         Ok(())
     }
-    fn uninstall(&self, _pr: &ProgressReport) -> Result<()> {
+    fn uninstall(&self, _config: &Config, _pr: &ProgressReport) -> Result<()> {
         Ok(())
     }
     fn get_aliases(&self, _settings: &Settings) -> Result<BTreeMap<String, String>> {
         Ok(BTreeMap::new())
     }
+    /// where get_aliases gets them from, shown when tracing alias resolution
+    fn get_aliases_source(&self) -> String {
+        format!("{} core plugin", self.name())
+    }
+    /// runs the plugin's scripts with the env rtx sets for them, None for core plugins
+    /// `tv` adds the version specific vars, e.g.: ASDF_INSTALL_PATH
+    fn script_manager(&self, _config: &Config, _tv: Option<&ToolVersion>) -> Option<ScriptManager> {
+        None
+    }
     fn legacy_filenames(&self, _settings: &Settings) -> Result<Vec<String>> {
         Ok(vec![])
     }
@@ -83,6 +125,20 @@ pub trait Plugin: Debug + Send + Sync {
     }
     fn install_version(&self, config: &Config, tv: &ToolVersion, pr: &ProgressReport)
         -> Result<()>;
+    /// runs only the download step for a version with its download/install paths in `dir`
+    /// used to generate checksum manifests without touching the real installs
+    fn download_to(
+        &self,
+        _config: &Config,
+        _tv: &ToolVersion,
+        _dir: &Path,
+        _pr: &ProgressReport,
+    ) -> Result<()> {
+        Err(eyre!(
+            "{} does not have a separate download step",
+            self.name()
+        ))
+    }
     fn uninstall_version(&self, _config: &Config, _tv: &ToolVersion) -> Result<()> {
         Ok(())
     }
@@ -122,18 +178,104 @@ pub trait Plugin: Debug + Send + Sync {
     }
 }
 
+/// asdf plugin names which are called something else in rtx, extended by the
+/// plugin_aliases setting
+static PLUGIN_ALIASES: Lazy<RwLock<HashMap<String, &'static str>>> = Lazy::new(|| {
+    RwLock::new(HashMap::from([
+        ("nodejs".to_string(), "node"),
+        ("golang".to_string(), "go"),
+    ]))
+});
+
+/// dependencies of common asdf plugins which can't install without another tool,
+/// plugins can declare their own with `depends` in rtx.plugin.toml
+const DEFAULT_DEPENDS: &[(&str, &[&str])] = &[
+    ("elixir", &["erlang"]),
+    ("gleam", &["erlang"]),
+    ("rebar", &["erlang"]),
+    ("pnpm", &["node"]),
+    ("yarn", &["node"]),
+    ("poetry", &["python"]),
+];
+
+pub fn default_depends(plugin_name: &str) -> Vec<PluginName> {
+    DEFAULT_DEPENDS
+        .iter()
+        .find(|(name, _)| *name == plugin_name)
+        .map(|(_, deps)| deps.iter().map(|d| d.to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// the directory name for a plugin's installs, downloads and caches, names of tools from other
+/// backends like "gh:cli/cli" can't be used as one
+pub fn pathname(plugin_name: &str) -> String {
+    plugin_name.replace([':', '/'], "-")
+}
+
 pub fn unalias_plugin(plugin_name: &str) -> &str {
-    match plugin_name {
-        "nodejs" => "node",
-        "golang" => "go",
-        _ => plugin_name,
+    match PLUGIN_ALIASES.read().unwrap().get(plugin_name) {
+        Some(name) => name,
+        None => plugin_name,
     }
 }
 
+/// e.g.: ("nodejs", "node"), including the plugin_aliases setting
+pub fn list_plugin_aliases() -> Vec<(String, String)> {
+    PLUGIN_ALIASES
+        .read()
+        .unwrap()
+        .iter()
+        .map(|(from, to)| (from.clone(), to.to_string()))
+        .collect()
+}
+
+/// adds the plugin_aliases setting, returns true if this changed any alias
+pub fn add_plugin_aliases(aliases: &BTreeMap<String, String>) -> bool {
+    let mut plugin_aliases = PLUGIN_ALIASES.write().unwrap();
+    let mut changed = false;
+    for (from, to) in aliases {
+        if plugin_aliases.get(from).map_or(true, |t| *t != to.as_str()) {
+            // leaked so unalias_plugin can hand out &str, there are only a few of these
+            let to: &'static str = Box::leak(to.clone().into_boxed_str());
+            plugin_aliases.insert(from.clone(), to);
+            changed = true;
+        }
+    }
+    changed
+}
+
 pub enum PluginType {
     #[allow(dead_code)]
     Core,
     External,
+    /// "cargo:<crate>", installed with cargo install
+    Cargo,
+    /// "gh:<owner>/<repo>", installed from GitHub release assets
+    GithubRelease,
+    /// "go:<package>", installed with go install
+    Go,
+    /// "http:<name>", downloaded from the url in its options
+    Http,
+    /// "npm:<package>", installed with npm install -g
+    Npm,
+    /// "pipx:<package>" or "pip:<package>", installed into a virtualenv
+    Pipx,
+}
+
+impl PluginType {
+    /// as shown by `rtx plugins ls` and `rtx plugins info`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PluginType::Core => "core",
+            PluginType::External => "external",
+            PluginType::Cargo => "cargo",
+            PluginType::GithubRelease => "github-release",
+            PluginType::Go => "go",
+            PluginType::Http => "http",
+            PluginType::Npm => "npm",
+            PluginType::Pipx => "pipx",
+        }
+    }
 }
 
 #[cfg(test)]
@@ -161,6 +303,16 @@ mod tests {
         assert_str_eq!(version, "3.1.0");
     }
 
+    #[test]
+    fn test_unalias_plugin() {
+        assert_str_eq!(unalias_plugin("nodejs"), "node");
+        assert_str_eq!(unalias_plugin("test-unalias"), "test-unalias");
+        let aliases = BTreeMap::from([("test-unalias".to_string(), "tiny".to_string())]);
+        assert!(add_plugin_aliases(&aliases));
+        assert!(!add_plugin_aliases(&aliases));
+        assert_str_eq!(unalias_plugin("test-unalias"), "tiny");
+    }
+
     #[test]
     fn test_latest_stable() {
         let settings = Settings::default();
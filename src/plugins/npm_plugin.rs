@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+use std::iter::once;
+use std::path::PathBuf;
+
+use color_eyre::eyre::Result;
+use itertools::Itertools;
+use serde_derive::Deserialize;
+use versions::Versioning;
+
+use crate::cache::{CacheManager, CacheStatus};
+use crate::cmd::CmdLineRunner;
+use crate::config::{Config, Settings};
+use crate::plugins::{pathname, Plugin, PluginName, PluginType};
+use crate::toolset::ToolVersion;
+use crate::ui::progress_report::ProgressReport;
+use crate::{dirs, env, http};
+
+/// prefix of tools installed from the npm registry, e.g.: "npm:prettier"
+pub const PREFIX: &str = "npm:";
+
+/// installs an npm package and the bins it declares into its own prefix with `npm install -g`
+#[derive(Debug)]
+pub struct NpmPlugin {
+    name: PluginName,
+    /// e.g.: "prettier" or "@antfu/ni"
+    package: String,
+    remote_version_cache: CacheManager<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NpmPackage {
+    versions: BTreeMap<String, serde_json::Value>,
+}
+
+impl NpmPlugin {
+    pub fn new(name: PluginName) -> Self {
+        let package = name.trim_start_matches(PREFIX).to_string();
+        let cache_path = dirs::CACHE.join(pathname(&name));
+        Self {
+            remote_version_cache: CacheManager::new(cache_path.join("remote_versions.msgpack.z"))
+                .with_fresh_duration(*env::RTX_FETCH_REMOTE_VERSIONS_CACHE),
+            name,
+            package,
+        }
+    }
+
+    fn fetch_remote_versions(&self) -> Result<Vec<String>> {
+        let http = http::Client::new()?;
+        // scoped packages are "@scope%2fname" in registry urls
+        let url = format!(
+            "https://registry.npmjs.org/{}",
+            self.package.replace('/', "%2f")
+        );
+        // the abbreviated metadata only has what installing needs, which includes the versions
+        let resp = http
+            .get(url)
+            .header("accept", "application/vnd.npm.install-v1+json")
+            .send()?;
+        http.ensure_success(&resp)?;
+        let package: NpmPackage = resp.json()?;
+        let versions = package
+            .versions
+            .into_keys()
+            .sorted_by_cached_key(|s| Versioning::new(s))
+            .collect();
+        Ok(versions)
+    }
+
+    /// npm and node are found on the shims first so the node installed by rtx is used
+    fn path(&self) -> Result<std::ffi::OsString> {
+        Ok(env::join_paths(
+            once(dirs::SHIMS.clone()).chain(env::PATH.iter().cloned()),
+        )?)
+    }
+}
+
+impl Plugin for NpmPlugin {
+    fn name(&self) -> &PluginName {
+        &self.name
+    }
+
+    fn get_type(&self) -> PluginType {
+        PluginType::Npm
+    }
+
+    fn get_remote_url(&self) -> Option<String> {
+        Some(format!("https://www.npmjs.com/package/{}", self.package))
+    }
+
+    fn depends(&self) -> Vec<PluginName> {
+        vec!["node".into()]
+    }
+
+    fn cache_status(&self) -> Vec<CacheStatus> {
+        vec![self.remote_version_cache.status()]
+    }
+
+    fn list_remote_versions(&self, _settings: &Settings) -> Result<Vec<String>> {
+        self.remote_version_cache
+            .get_or_try_init(|| self.fetch_remote_versions())
+            .cloned()
+    }
+
+    fn install_version(
+        &self,
+        config: &Config,
+        tv: &ToolVersion,
+        pr: &ProgressReport,
+    ) -> Result<()> {
+        pr.set_message(format!("npm install {}@{}", self.package, tv.version));
+        CmdLineRunner::new(&config.settings, "npm")
+            .with_pr(pr)
+            .env("PATH", self.path()?)
+            .arg("install")
+            .arg("--global")
+            .arg("--prefix")
+            .arg(tv.install_path())
+            .arg(format!("{}@{}", self.package, tv.version))
+            .execute()
+    }
+
+    fn list_bin_paths(&self, _config: &Config, tv: &ToolVersion) -> Result<Vec<PathBuf>> {
+        // npm links the bins the package declares into <prefix>/bin
+        Ok(vec![tv.install_path().join("bin")])
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::BTreeMap;
+use std::iter::once;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{eyre, Result};
+use itertools::Itertools;
+use serde_derive::Deserialize;
+use versions::Versioning;
+
+use crate::cache::{CacheManager, CacheStatus};
+use crate::cmd::CmdLineRunner;
+use crate::config::{Config, Settings};
+use crate::plugins::{pathname, Plugin, PluginName, PluginType};
+use crate::toolset::ToolVersion;
+use crate::ui::progress_report::ProgressReport;
+use crate::{cmd, dirs, env, file, http};
+
+/// prefixes of tools installed from PyPI, e.g.: "pipx:black" or "pip:black"
+pub const PREFIXES: &[&str] = &["pipx:", "pip:"];
+
+/// installs a python package into a virtualenv for each version, like pipx does, and exposes
+/// only the scripts of the package itself so python and pip of the virtualenv do not get shims
+#[derive(Debug)]
+pub struct PipxPlugin {
+    name: PluginName,
+    /// e.g.: "black"
+    package: String,
+    remote_version_cache: CacheManager<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PypiPackage {
+    releases: BTreeMap<String, Vec<PypiFile>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PypiFile {
+    #[serde(default)]
+    yanked: bool,
+}
+
+impl PipxPlugin {
+    pub fn new(name: PluginName) -> Self {
+        let package = PREFIXES
+            .iter()
+            .find_map(|p| name.strip_prefix(p))
+            .unwrap_or(&name)
+            .to_string();
+        let cache_path = dirs::CACHE.join(pathname(&name));
+        Self {
+            remote_version_cache: CacheManager::new(cache_path.join("remote_versions.msgpack.z"))
+                .with_fresh_duration(*env::RTX_FETCH_REMOTE_VERSIONS_CACHE),
+            name,
+            package,
+        }
+    }
+
+    fn fetch_remote_versions(&self) -> Result<Vec<String>> {
+        let http = http::Client::new()?;
+        let url = format!("https://pypi.org/pypi/{}/json", self.package);
+        let resp = http.get(url).send()?;
+        http.ensure_success(&resp)?;
+        let package: PypiPackage = resp.json()?;
+        let versions = package
+            .releases
+            .into_iter()
+            // releases without files or with only yanked ones cannot be installed
+            .filter(|(_, files)| files.iter().any(|f| !f.yanked))
+            .map(|(v, _)| v)
+            .sorted_by_cached_key(|s| Versioning::new(s))
+            .collect();
+        Ok(versions)
+    }
+
+    fn venv_path(&self, tv: &ToolVersion) -> PathBuf {
+        tv.install_path().join("venv")
+    }
+
+    /// python is found on the shims first so a python installed by rtx is used
+    fn path(&self) -> Result<std::ffi::OsString> {
+        Ok(env::join_paths(
+            once(dirs::SHIMS.clone()).chain(env::PATH.iter().cloned()),
+        )?)
+    }
+
+    /// links the scripts the package installed into the virtualenv into install_path/bin
+    fn link_scripts(&self, tv: &ToolVersion) -> Result<()> {
+        let python = self.venv_path(tv).join("bin/python");
+        let output = cmd!(python, "-m", "pip", "show", "--files", &self.package).read()?;
+        let scripts = package_scripts(&output);
+        if scripts.is_empty() {
+            return Err(eyre!("{} has no scripts to run", self.package));
+        }
+        let bin = tv.install_path().join("bin");
+        file::create_dir_all(&bin)?;
+        for script in scripts {
+            let target = Path::new("../venv/bin").join(&script);
+            file::make_symlink(&target, &bin.join(&script))?;
+        }
+        Ok(())
+    }
+}
+
+impl Plugin for PipxPlugin {
+    fn name(&self) -> &PluginName {
+        &self.name
+    }
+
+    fn get_type(&self) -> PluginType {
+        PluginType::Pipx
+    }
+
+    fn get_remote_url(&self) -> Option<String> {
+        Some(format!("https://pypi.org/project/{}", self.package))
+    }
+
+    fn depends(&self) -> Vec<PluginName> {
+        vec!["python".into()]
+    }
+
+    fn cache_status(&self) -> Vec<CacheStatus> {
+        vec![self.remote_version_cache.status()]
+    }
+
+    fn list_remote_versions(&self, _settings: &Settings) -> Result<Vec<String>> {
+        self.remote_version_cache
+            .get_or_try_init(|| self.fetch_remote_versions())
+            .cloned()
+    }
+
+    fn install_version(
+        &self,
+        config: &Config,
+        tv: &ToolVersion,
+        pr: &ProgressReport,
+    ) -> Result<()> {
+        let venv = self.venv_path(tv);
+        pr.set_message("python -m venv");
+        CmdLineRunner::new(&config.settings, "python3")
+            .with_pr(pr)
+            .env("PATH", self.path()?)
+            .arg("-m")
+            .arg("venv")
+            .arg(&venv)
+            .execute()?;
+        pr.set_message(format!("pip install {}=={}", self.package, tv.version));
+        CmdLineRunner::new(&config.settings, venv.join("bin/python"))
+            .with_pr(pr)
+            .arg("-m")
+            .arg("pip")
+            .arg("install")
+            .arg("--disable-pip-version-check")
+            .arg(format!("{}=={}", self.package, tv.version))
+            .execute()?;
+        self.link_scripts(tv)
+    }
+
+    fn list_bin_paths(&self, _config: &Config, tv: &ToolVersion) -> Result<Vec<PathBuf>> {
+        Ok(vec![tv.install_path().join("bin")])
+    }
+}
+
+/// the names of the scripts in the files listed by `pip show --files`, which are relative to
+/// site-packages, e.g.: "../../../bin/black"
+fn package_scripts(pip_show: &str) -> Vec<String> {
+    pip_show
+        .lines()
+        .skip_while(|l| !l.starts_with("Files:"))
+        .skip(1)
+        .map(|l| Path::new(l.trim()))
+        .filter(|p| p.parent().map_or(false, |d| d.ends_with("bin")))
+        .filter_map(|p| p.file_name())
+        .map(|f| f.to_string_lossy().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_scripts() {
+        let output = r#"Name: black
+Version: 23.11.0
+Location: /home/user/.local/share/rtx/installs/pipx-black/23.11.0/venv/lib/python3.11/site-packages
+Requires: click, mypy-extensions, packaging, pathspec, platformdirs
+Files:
+  ../../../bin/black
+  ../../../bin/blackd
+  black/__init__.py
+  black-23.11.0.dist-info/entry_points.txt
+"#;
+        assert_eq!(package_scripts(output), vec!["black", "blackd"]);
+        assert!(package_scripts("Name: black\n").is_empty());
+    }
+}
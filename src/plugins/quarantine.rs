@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use color_eyre::eyre::Result;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::duration::{Duration, DAILY};
+use crate::{dirs, env, file};
+
+/// a plugin whose exec-env or list-bin-paths keeps failing is skipped by hook-env for a while so
+/// one broken plugin doesn't print the same error and add latency on every prompt.
+/// The retry window doubles on every failure after that up to a day, a success resets it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Quarantine {
+    failures: u32,
+    /// unix time in seconds the plugin is skipped until
+    until: u64,
+}
+
+/// consecutive failures before a plugin is quarantined
+const THRESHOLD: u32 = 3;
+const MIN_WINDOW: Duration = Duration::from_secs(60);
+
+/// true if hook-env should skip running the plugin's scripts
+pub fn is_quarantined(plugin: &str) -> bool {
+    is_hook_env() && read(plugin).map_or(false, |q| q.until > now())
+}
+
+/// records a failed script run, returns how long the plugin is now skipped for if this failure
+/// quarantined it. Only failures in hook-env count, other commands always show the error.
+pub fn record_failure(plugin: &str) -> Option<Duration> {
+    if !is_hook_env() || *dirs::STATE_READ_ONLY {
+        return None;
+    }
+    let mut q = read(plugin).unwrap_or_default();
+    q.failures += 1;
+    if q.failures < THRESHOLD {
+        let _ = write(plugin, &q);
+        return None;
+    }
+    let window = window(q.failures);
+    q.until = now() + window.as_secs();
+    if let Err(err) = write(plugin, &q) {
+        debug!("failed to quarantine {plugin}: {err:#}");
+        return None;
+    }
+    Some(window)
+}
+
+/// called after the plugin's scripts succeed, or it was updated
+pub fn clear(plugin: &str) {
+    let path = path(plugin);
+    if path.exists() {
+        let _ = file::remove_file(path);
+    }
+}
+
+/// 1m after the threshold is hit, then 2m, 4m, ... up to a day
+fn window(failures: u32) -> Duration {
+    let exp = failures.saturating_sub(THRESHOLD).min(16);
+    (MIN_WINDOW * 2u32.pow(exp)).min(DAILY)
+}
+
+fn is_hook_env() -> bool {
+    env::ARGS.get(1).map_or(false, |arg| arg == "hook-env")
+}
+
+fn path(plugin: &str) -> PathBuf {
+    dirs::STATE
+        .join("quarantine")
+        .join(format!("{plugin}.json"))
+}
+
+fn read(plugin: &str) -> Option<Quarantine> {
+    let body = file::read_to_string(path(plugin)).ok()?;
+    serde_json::from_str(&body).ok()
+}
+
+fn write(plugin: &str, q: &Quarantine) -> Result<()> {
+    let path = path(plugin);
+    file::create_dir_all(path.parent().unwrap())?;
+    file::write(path, serde_json::to_string(q)?)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window() {
+        assert_eq!(window(3), Duration::from_secs(60));
+        assert_eq!(window(4), Duration::from_secs(120));
+        assert_eq!(window(6), Duration::from_secs(480));
+        assert_eq!(window(30), DAILY);
+    }
+
+    #[test]
+    fn test_not_quarantined_outside_hook_env() {
+        assert_eq!(record_failure("tiny"), None);
+        assert!(!is_quarantined("tiny"));
+    }
+}
@@ -1,23 +1,64 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use color_eyre::eyre::eyre;
 use color_eyre::{Result, Section};
 use toml_edit::{Document, Item, Value};
 
+use crate::checksums::Checksums;
+use crate::file::display_path;
 use crate::{file, parse_error};
 
 #[derive(Debug, Default, Clone)]
 pub struct RtxPluginTomlScriptConfig {
     pub cache_key: Option<Vec<String>>,
     pub data: Option<String>,
+    pub env: Option<BTreeMap<String, String>>,
+}
+
+/// `[list-all]` lists versions from static data or a url instead of running bin/list-all
+#[derive(Debug, Default, Clone)]
+pub struct RtxPluginTomlListAllConfig {
+    pub data: Option<String>,
+    pub url: Option<String>,
+    /// versions are the first capture group (or the whole match) of each match in data or the
+    /// body of url, otherwise they're split on whitespace
+    pub regex: Option<String>,
+}
+
+/// the newest plugin protocol this rtx supports, see `protocol` in rtx.plugin.toml
+pub const PROTOCOL_VERSION: i64 = 1;
+
+/// optional features a plugin declares with `protocol = 1` and `capabilities = [...]` so rtx can
+/// skip work it does for plain asdf plugins, which have none of them
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RtxPluginCapabilities {
+    /// "json-output": list-all, list-bin-paths and list-legacy-filenames print a JSON array,
+    /// list-aliases and exec-env print a JSON object. exec-env is run like the other scripts
+    /// instead of being sourced by bash to diff the env before and after.
+    pub json_output: bool,
+    /// "declarative-env": all env vars are in [exec-env.env] or exec-env.toml, bin/exec-env is
+    /// only there for asdf and never run
+    pub declarative_env: bool,
+    /// "checksums": checksums are shipped for every version, a version without any is warned about
+    pub checksums: bool,
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct RtxPluginToml {
+    /// 0 for plugins which don't declare it
+    pub protocol: i64,
+    pub capabilities: RtxPluginCapabilities,
     pub exec_env: RtxPluginTomlScriptConfig,
+    pub list_all: RtxPluginTomlListAllConfig,
     pub list_aliases: RtxPluginTomlScriptConfig,
     pub list_bin_paths: RtxPluginTomlScriptConfig,
     pub list_legacy_filenames: RtxPluginTomlScriptConfig,
+    pub env_passthrough: Vec<String>,
+    pub checksums: Checksums,
+    /// plugins which need to be installed before this one can install versions, e.g.: ["erlang"]
+    /// for elixir. None if not declared, `depends = []` opts out of rtx's built-in list.
+    pub depends: Option<Vec<String>>,
 }
 
 impl RtxPluginToml {
@@ -29,6 +70,14 @@ impl RtxPluginToml {
         let mut rf = Self::init();
         let body = file::read_to_string(path).suggestion("ensure file exists and can be read")?;
         rf.parse(&body)?;
+        if rf.protocol > PROTOCOL_VERSION {
+            warn!(
+                "{} uses plugin protocol {} but this rtx only supports {}, update rtx with `rtx self-update`",
+                display_path(path),
+                rf.protocol,
+                PROTOCOL_VERSION
+            );
+        }
         Ok(rf)
     }
 
@@ -40,20 +89,43 @@ impl RtxPluginToml {
 
     fn parse(&mut self, s: &str) -> Result<()> {
         let doc: Document = s.parse().suggestion("ensure file is valid TOML")?;
+        let mut capabilities = vec![];
         for (k, v) in doc.iter() {
             match k {
+                "protocol" => match v.as_integer() {
+                    Some(protocol) => self.protocol = protocol,
+                    _ => parse_error!(k, v, "integer")?,
+                },
+                "capabilities" => capabilities = self.parse_string_array(k, v)?,
                 "exec-env" => self.exec_env = self.parse_script_config(k, v)?,
+                "list-all" => self.list_all = self.parse_list_all_config(k, v)?,
                 "list-aliases" => self.list_aliases = self.parse_script_config(k, v)?,
                 "list-bin-paths" => self.list_bin_paths = self.parse_script_config(k, v)?,
                 "list-legacy-filenames" => {
                     self.list_legacy_filenames = self.parse_script_config(k, v)?
                 }
+                "env-passthrough" => self.env_passthrough = self.parse_string_array(k, v)?,
+                "checksums" => self.checksums = self.parse_checksums(k, v)?,
+                "depends" => self.depends = Some(self.parse_string_array(k, v)?),
                 // this is an old key used in rtx-python
                 // this file is invalid, so just stop parsing entirely if we see it
                 "legacy-filenames" => return Ok(()),
                 _ => Err(eyre!("unknown key: {}", k))?,
             }
         }
+        if !capabilities.is_empty() && self.protocol < 1 {
+            warn!("rtx.plugin.toml: capabilities are ignored without `protocol = 1`");
+            return Ok(());
+        }
+        for capability in capabilities {
+            match capability.as_str() {
+                "json-output" => self.capabilities.json_output = true,
+                "declarative-env" => self.capabilities.declarative_env = true,
+                "checksums" => self.capabilities.checksums = true,
+                // from a newer protocol, the plugin still has to work without it
+                _ => debug!("rtx.plugin.toml: unknown capability: {}", capability),
+            }
+        }
         Ok(())
     }
 
@@ -69,7 +141,8 @@ impl RtxPluginToml {
                             Some(v) => config.data = Some(self.parse_string(k, v)?),
                             _ => parse_error!(key, v, "string")?,
                         },
-                        _ => parse_error!(key, v, "one of: cache-key")?,
+                        "env" => config.env = Some(self.parse_string_table(&key, v)?),
+                        _ => parse_error!(key, v, "one of: cache-key, data, env")?,
                     }
                 }
                 Ok(config)
@@ -78,6 +151,43 @@ impl RtxPluginToml {
         }
     }
 
+    fn parse_list_all_config(&mut self, key: &str, v: &Item) -> Result<RtxPluginTomlListAllConfig> {
+        match v.as_table_like() {
+            Some(table) => {
+                let mut config = RtxPluginTomlListAllConfig::default();
+                for (k, v) in table.iter() {
+                    let key = format!("{}.{}", key, k);
+                    let value = match v.as_value() {
+                        Some(v) => self.parse_string(k, v)?,
+                        _ => parse_error!(key, v, "string")?,
+                    };
+                    match k {
+                        "data" => config.data = Some(value),
+                        "url" => config.url = Some(value),
+                        "regex" => config.regex = Some(value),
+                        _ => parse_error!(key, v, "one of: data, url, regex")?,
+                    }
+                }
+                Ok(config)
+            }
+            _ => parse_error!(key, v, "table")?,
+        }
+    }
+
+    fn parse_checksums(&mut self, k: &str, v: &Item) -> Result<Checksums> {
+        match v.as_table_like() {
+            Some(table) => {
+                let mut out = Checksums::new();
+                for (version, v) in table.iter() {
+                    let key = format!("{k}.{version}");
+                    out.insert(version.to_string(), self.parse_string_table(&key, v)?);
+                }
+                Ok(out)
+            }
+            _ => parse_error!(k, v, "table")?,
+        }
+    }
+
     fn parse_string_array(&mut self, k: &str, v: &Item) -> Result<Vec<String>> {
         match v.as_array() {
             Some(arr) => {
@@ -91,6 +201,22 @@ impl RtxPluginToml {
         }
     }
 
+    fn parse_string_table(&mut self, k: &str, v: &Item) -> Result<BTreeMap<String, String>> {
+        match v.as_table_like() {
+            Some(table) => {
+                let mut out = BTreeMap::new();
+                for (key, v) in table.iter() {
+                    match v.as_value() {
+                        Some(v) => out.insert(key.to_string(), self.parse_string(key, v)?),
+                        _ => parse_error!(format!("{k}.{key}"), v, "string")?,
+                    };
+                }
+                Ok(out)
+            }
+            _ => parse_error!(k, v, "table")?,
+        }
+    }
+
     fn parse_string(&mut self, k: &str, v: &Value) -> Result<String> {
         match v.as_str() {
             Some(v) => Ok(v.to_string()),
@@ -137,8 +263,114 @@ mod tests {
                 ],
             ),
             data: None,
+            env: None,
+        }
+        "###);
+    }
+
+    #[test]
+    fn test_exec_env_static() {
+        let cf = parse(&formatdoc! {r#"
+        [exec-env.env]
+        JAVA_HOME = "{{{{install_path}}}}"
+        "#});
+
+        assert_debug_snapshot!(cf.exec_env.env, @r###"
+        Some(
+            {
+                "JAVA_HOME": "{{install_path}}",
+            },
+        )
+        "###);
+    }
+
+    #[test]
+    fn test_checksums() {
+        let cf = parse(&formatdoc! {r#"
+        [checksums."1.0.0"]
+        "tool-1.0.0-linux.tar.gz" = "sha256:abc"
+        "tool-1.0.0-macos.tar.gz" = "sha256:def"
+        "#});
+
+        assert_debug_snapshot!(cf.checksums, @r###"
+        {
+            "1.0.0": {
+                "tool-1.0.0-linux.tar.gz": "sha256:abc",
+                "tool-1.0.0-macos.tar.gz": "sha256:def",
+            },
+        }
+        "###);
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let cf = parse(&formatdoc! {r#"
+        protocol = 1
+        capabilities = ["json-output", "declarative-env", "from-the-future"]
+        "#});
+
+        assert_eq!(cf.protocol, 1);
+        assert_debug_snapshot!(cf.capabilities, @r###"
+        RtxPluginCapabilities {
+            json_output: true,
+            declarative_env: true,
+            checksums: false,
+        }
+        "###);
+
+        let cf = parse(&formatdoc! {r#"
+        capabilities = ["json-output"]
+        "#});
+        assert_eq!(cf.capabilities, RtxPluginCapabilities::default());
+    }
+
+    #[test]
+    fn test_env_passthrough() {
+        let cf = parse(&formatdoc! {r#"
+        env-passthrough = ["GITHUB_API_TOKEN", "AWS_*"]
+        "#});
+
+        assert_eq!(cf.env_passthrough, vec!["GITHUB_API_TOKEN", "AWS_*"]);
+    }
+
+    #[test]
+    fn test_depends() {
+        let cf = parse(&formatdoc! {r#"
+        depends = ["erlang"]
+        "#});
+        assert_eq!(cf.depends, Some(vec!["erlang".to_string()]));
+
+        let cf = parse("");
+        assert_eq!(cf.depends, None);
+    }
+
+    #[test]
+    fn test_list_all() {
+        let cf = parse(&formatdoc! {r#"
+        [list-all]
+        url = "https://example.com/releases.txt"
+        regex = 'v(\d+\.\d+\.\d+)'
+        "#});
+
+        assert_debug_snapshot!(cf.list_all, @r###"
+        RtxPluginTomlListAllConfig {
+            data: None,
+            url: Some(
+                "https://example.com/releases.txt",
+            ),
+            regex: Some(
+                "v(\\d+\\.\\d+\\.\\d+)",
+            ),
         }
         "###);
+
+        let err = RtxPluginToml::init()
+            .parse(&formatdoc! {r#"
+            [exec-env]
+            url = "https://example.com/releases.txt"
+            "#})
+            .unwrap_err();
+        assert!(err.to_string().contains("exec-env.url"));
     }
 
     fn parse(s: &str) -> RtxPluginToml {
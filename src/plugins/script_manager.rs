@@ -1,9 +1,11 @@
 use crate::fake_asdf::get_path_with_fake_asdf;
 use std::collections::HashMap;
-use std::ffi::OsString;
+use std::env::{join_paths, split_paths};
+use std::ffi::{OsStr, OsString};
 use std::fmt;
 use std::fmt::{Display, Formatter};
-use std::path::PathBuf;
+use std::iter::once;
+use std::path::{Path, PathBuf};
 use std::process::Output;
 
 use color_eyre::eyre::{Context, Result};
@@ -24,6 +26,8 @@ pub struct ScriptManager {
     pub plugin_path: PathBuf,
     pub plugin_name: String,
     pub env: HashMap<OsString, OsString>,
+    /// env vars the plugin declared it needs in rtx.plugin.toml
+    pub env_passthrough: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -39,6 +43,9 @@ pub enum Script {
     ListAll,
     ListLegacyFilenames,
     ParseLegacyFile(String),
+    PostPluginAdd,
+    PostPluginUpdate,
+    PrePluginRemove,
 
     // RuntimeVersion
     Download,
@@ -47,6 +54,9 @@ pub enum Script {
     ListBinPaths,
     RunExternalCommand(PathBuf, Vec<String>),
     Uninstall,
+
+    /// any script in bin/ with its arguments, run by `rtx plugins exec`
+    Custom(String, Vec<String>),
 }
 
 impl Display for Script {
@@ -58,6 +68,9 @@ impl Display for Script {
             Script::ListLegacyFilenames => write!(f, "list-legacy-filenames"),
             Script::ListAliases => write!(f, "list-aliases"),
             Script::ParseLegacyFile(_) => write!(f, "parse-legacy-file"),
+            Script::PostPluginAdd => write!(f, "post-plugin-add"),
+            Script::PostPluginUpdate => write!(f, "post-plugin-update"),
+            Script::PrePluginRemove => write!(f, "pre-plugin-remove"),
 
             // RuntimeVersion
             Script::Install => write!(f, "install"),
@@ -66,6 +79,8 @@ impl Display for Script {
             Script::RunExternalCommand(_, _) => write!(f, "run-external-command"),
             Script::ExecEnv => write!(f, "exec-env"),
             Script::Download => write!(f, "download"),
+
+            Script::Custom(name, _) => write!(f, "{}", name),
         }
     }
 }
@@ -91,6 +106,53 @@ static INITIAL_ENV: Lazy<HashMap<OsString, OsString>> = Lazy::new(|| {
     env
 });
 
+/// env vars from the user's shell that are passed to plugin scripts by default
+/// anything else (e.g.: tokens or cloud credentials) needs to be added with the
+/// plugin_env_passthrough setting or declared by the plugin in rtx.plugin.toml
+const DEFAULT_ENV_PASSTHROUGH: &[&str] = &[
+    "ALL_PROXY",
+    "ARCHFLAGS",
+    "CC",
+    "CFLAGS",
+    "COLORTERM",
+    "CPP",
+    "CPPFLAGS",
+    "CURL_CA_BUNDLE",
+    "CXX",
+    "CXXFLAGS",
+    "DEVELOPER_DIR",
+    "HOME",
+    "HTTPS_PROXY",
+    "HTTP_PROXY",
+    "LANG",
+    "LANGUAGE",
+    "LC_*",
+    "LDFLAGS",
+    "LIBS",
+    "LOGNAME",
+    "MACOSX_DEPLOYMENT_TARGET",
+    "MAKEFLAGS",
+    "NO_PROXY",
+    "PATH",
+    "PKG_CONFIG_PATH",
+    "PWD",
+    "SDKROOT",
+    "SHELL",
+    "SSL_CERT_DIR",
+    "SSL_CERT_FILE",
+    "TEMP",
+    "TERM",
+    "TMP",
+    "TMPDIR",
+    "TZ",
+    "USER",
+    "XDG_*",
+    "all_proxy",
+    "http_proxy",
+    "https_proxy",
+    "no_proxy",
+];
+
 impl ScriptManager {
     pub fn new(plugin_path: PathBuf) -> Self {
         let mut env = INITIAL_ENV.clone();
@@ -101,10 +163,16 @@ impl ScriptManager {
         Self {
             plugin_name: basename(&plugin_path).expect("invalid plugin path"),
             env,
+            env_passthrough: vec![],
             plugin_path,
         }
     }
 
+    pub fn with_env_passthrough(mut self, env_passthrough: Vec<String>) -> Self {
+        self.env_passthrough = env_passthrough;
+        self
+    }
+
     pub fn with_env<K, V>(mut self, k: K, v: V) -> Self
     where
         K: Into<OsString>,
@@ -114,6 +182,18 @@ impl ScriptManager {
         self
     }
 
+    /// puts `dir` in front of the PATH scripts get
+    pub fn with_path_prepended(mut self, dir: &Path) -> Self {
+        let path = self
+            .env
+            .get(OsStr::new("PATH"))
+            .cloned()
+            .unwrap_or_default();
+        let paths = once(dir.to_path_buf()).chain(split_paths(&path));
+        self.env.insert("PATH".into(), join_paths(paths).unwrap());
+        self
+    }
+
     pub fn get_script_path(&self, script: &Script) -> PathBuf {
         match script {
             Script::RunExternalCommand(path, _) => path.clone(),
@@ -129,15 +209,17 @@ impl ScriptManager {
         let args = match script {
             Script::ParseLegacyFile(filename) => vec![filename.clone()],
             Script::RunExternalCommand(_, args) => args.clone(),
+            Script::Custom(_, args) => args.clone(),
             _ => vec![],
         };
         let script_path = self.get_script_path(script);
         // if !script_path.exists() {
         //     return Err(PluginNotInstalled(self.plugin_name.clone()).into());
         // }
-        let mut cmd = cmd(script_path, args).full_env(&self.env);
-        if !settings.raw {
+        let mut cmd = cmd(script_path, args).full_env(self.script_env(settings, script));
+        if !settings.raw && !matches!(script, Script::RunExternalCommand(..)) {
             // ignore stdin, otherwise a prompt may show up where the user won't see it
+            // external commands are run by the user directly so they keep the terminal
             cmd = cmd.stdin_null();
         }
         cmd
@@ -173,7 +255,7 @@ impl ScriptManager {
         let cmd = CmdLineRunner::new(settings, self.get_script_path(script))
             .with_pr(pr)
             .env_clear()
-            .envs(&self.env);
+            .envs(self.script_env(settings, script));
         if let Err(e) = cmd.execute() {
             let status = match e.downcast_ref::<Error>() {
                 Some(ScriptFailed(_, status)) => *status,
@@ -184,6 +266,39 @@ impl ScriptManager {
         }
         Ok(())
     }
+
+    /// the env for a script, with vars from the user's shell filtered down to the
+    /// passthrough list. vars set by rtx and external commands run by the user are not filtered
+    pub fn script_env(&self, settings: &Settings, script: &Script) -> HashMap<OsString, OsString> {
+        if matches!(script, Script::RunExternalCommand(..)) {
+            return self.env.clone();
+        }
+        let patterns = DEFAULT_ENV_PASSTHROUGH
+            .iter()
+            .copied()
+            .chain(settings.plugin_env_passthrough.iter().map(|s| s.as_str()))
+            .chain(self.env_passthrough.iter().map(|s| s.as_str()))
+            .collect::<Vec<_>>();
+        self.env
+            .iter()
+            .filter(|(k, _)| {
+                let k = k.to_string_lossy();
+                !env::PRISTINE_ENV.contains_key(k.as_ref())
+                    || k.starts_with("RTX_")
+                    || k.starts_with("ASDF_")
+                    || patterns.iter().any(|p| env_matches(p, &k))
+            })
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+}
+
+/// "FOO" matches only FOO, "FOO_*" matches anything starting with FOO_ and "*" matches everything
+fn env_matches(pattern: &str, key: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => key.starts_with(prefix),
+        None => pattern == key,
+    }
 }
 
 #[cfg(test)]
@@ -215,4 +330,12 @@ mod tests {
         let script = Script::RunExternalCommand(PathBuf::from("/bin/ls"), vec!["-l".to_string()]);
         test(&script, PathBuf::from("/bin/ls"));
     }
+
+    #[test]
+    fn test_env_matches() {
+        assert!(env_matches("HOME", "HOME"));
+        assert!(!env_matches("HOME", "HOMEBREW_PREFIX"));
+        assert!(env_matches("LC_*", "LC_ALL"));
+        assert!(env_matches("*", "AWS_SECRET_ACCESS_KEY"));
+    }
 }
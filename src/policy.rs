@@ -0,0 +1,203 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use globset::Glob;
+use serde_derive::Deserialize;
+use versions::Versioning;
+
+use crate::toolset::ToolVersion;
+use crate::{env, file};
+
+/// restrictions on the tools which can be installed, set by an organization in a file users don't
+/// edit, e.g.: /etc/rtx/policy.toml
+///
+/// ```toml
+/// allowed_tools = ["node", "python", "npm:*"]
+///
+/// [tools.node]
+/// allowed_versions = [">=18, <22"]
+/// banned_versions = ["20.1.0"]
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    /// tools which can be installed, "*" matches anything, e.g.: "npm:*" for every npm package
+    /// every tool is allowed if this is not set
+    allowed_tools: Option<Vec<String>>,
+    #[serde(default)]
+    tools: BTreeMap<String, ToolPolicy>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ToolPolicy {
+    /// a version must match one of these, e.g.: ">=18, <22" or "20" for 20.x
+    allowed_versions: Option<Vec<String>>,
+    /// versions which can't be installed, e.g.: "20.1.0" or "16" for 16.x
+    #[serde(default)]
+    banned_versions: Vec<String>,
+}
+
+impl Policy {
+    /// the policy in `RTX_POLICY_FILE`, none if it doesn't exist
+    /// a policy which can't be read is an error so it can't be bypassed by breaking it
+    pub fn load() -> Result<Option<Self>> {
+        let path = &*env::RTX_POLICY_FILE;
+        if !path.exists() {
+            return Ok(None);
+        }
+        Self::from_file(path)
+            .wrap_err_with(|| format!("failed to read policy file {}", path.display()))
+            .map(Some)
+    }
+
+    fn from_file(path: &Path) -> Result<Self> {
+        let raw = file::read_to_string(path)?;
+        Self::parse(&raw)
+    }
+
+    fn parse(s: &str) -> Result<Self> {
+        Ok(toml::from_str(s)?)
+    }
+
+    /// errors with the reason if the policy does not allow installing tv
+    pub fn check(&self, tv: &ToolVersion) -> Result<()> {
+        self.check_version(&tv.plugin_name, &tv.version)
+            .map_err(|reason| {
+                eyre!(
+                    "policy violation: {tv} {reason}\nthe policy is set in {}",
+                    env::RTX_POLICY_FILE.display()
+                )
+            })
+    }
+
+    fn check_version(&self, tool: &str, version: &str) -> std::result::Result<(), String> {
+        if let Some(allowed) = &self.allowed_tools {
+            if !allowed.iter().any(|p| matches_glob(p, tool)) {
+                return Err(format!("is not an allowed tool ({})", allowed.join(", ")));
+            }
+        }
+        let Some(tp) = self.tools.get(tool) else {
+            return Ok(());
+        };
+        if let Some(banned) = tp
+            .banned_versions
+            .iter()
+            .find(|b| matches_prefix(b, version))
+        {
+            return Err(format!("is a banned version ({banned})"));
+        }
+        if let Some(allowed) = &tp.allowed_versions {
+            if !allowed.iter().any(|r| matches_range(r, version)) {
+                return Err(format!(
+                    "is not an allowed version ({})",
+                    allowed.join(" or ")
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn matches_glob(pattern: &str, s: &str) -> bool {
+    match Glob::new(pattern) {
+        Ok(glob) => glob.compile_matcher().is_match(s),
+        Err(_) => pattern == s,
+    }
+}
+
+/// "20" matches 20.x, "20.1.0" only matches itself
+fn matches_prefix(prefix: &str, version: &str) -> bool {
+    version == prefix || version.starts_with(&format!("{prefix}."))
+}
+
+/// true if version satisfies every comparison in range, e.g.: ">=18, <22"
+/// a comparison without an operator is a prefix, e.g.: "20" for 20.x
+fn matches_range(range: &str, version: &str) -> bool {
+    let Some(v) = Versioning::new(version) else {
+        return false;
+    };
+    range.split(',').map(|c| c.trim()).all(|c| {
+        let (op, req) = match c.find(|ch: char| !"<>=".contains(ch)) {
+            Some(i) => c.split_at(i),
+            None => return false,
+        };
+        let req = req.trim();
+        let Some(r) = Versioning::new(req) else {
+            return false;
+        };
+        match op {
+            ">=" => v >= r,
+            ">" => v > r,
+            "<=" => v <= r,
+            "<" => v < r,
+            "=" | "==" => v == r,
+            "" => matches_prefix(req, version),
+            _ => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_version() {
+        let policy = Policy::parse(
+            r#"
+            allowed_tools = ["node", "python", "npm:*"]
+
+            [tools.node]
+            allowed_versions = [">=18, <22"]
+            banned_versions = ["20.1.0", "19"]
+
+            [tools.python]
+            allowed_versions = ["3.11", "3.12"]
+            "#,
+        )
+        .unwrap();
+        let check = |tool, version| policy.check_version(tool, version);
+        assert!(check("node", "20.10.0").is_ok());
+        assert!(check("node", "21.0.0").is_ok());
+        assert!(check("npm:prettier", "3.1.0").is_ok());
+        assert!(check("python", "3.11.6").is_ok());
+        assert_eq!(
+            check("ruby", "3.2.2"),
+            Err("is not an allowed tool (node, python, npm:*)".into())
+        );
+        assert_eq!(
+            check("node", "16.20.2"),
+            Err("is not an allowed version (>=18, <22)".into())
+        );
+        assert_eq!(
+            check("node", "22.0.0"),
+            Err("is not an allowed version (>=18, <22)".into())
+        );
+        assert_eq!(
+            check("node", "20.1.0"),
+            Err("is a banned version (20.1.0)".into())
+        );
+        assert_eq!(
+            check("node", "19.9.0"),
+            Err("is a banned version (19)".into())
+        );
+        assert_eq!(
+            check("python", "3.10.13"),
+            Err("is not an allowed version (3.11 or 3.12)".into())
+        );
+        assert!(check("python", "3.1.0").is_err());
+    }
+
+    #[test]
+    fn test_no_restrictions() {
+        let policy = Policy::parse("").unwrap();
+        assert!(policy.check_version("ruby", "3.2.2").is_ok());
+    }
+
+    #[test]
+    fn test_unknown_fields() {
+        assert!(Policy::parse("allowed_plugins = [\"node\"]").is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::Result;
+
+use crate::{dirs, file};
+
+/// records where installs were made so they can be fixed up if RTX_DATA_DIR is moved,
+/// e.g.: copied to another machine or user
+fn marker_path() -> PathBuf {
+    dirs::INSTALLS.join(".rtx-installs-path")
+}
+
+/// called after installing, an existing record is kept until `rtx relocate` runs
+pub fn record() -> Result<()> {
+    let marker = marker_path();
+    if !marker.exists() {
+        file::write(marker, dirs::INSTALLS.to_string_lossy().as_bytes())?;
+    }
+    Ok(())
+}
+
+/// the old installs directory if RTX_DATA_DIR was moved since tools were installed
+pub fn previous_path() -> Option<PathBuf> {
+    let recorded = file::read_to_string(marker_path()).ok()?;
+    let recorded = PathBuf::from(recorded.trim());
+    (recorded != *dirs::INSTALLS).then_some(recorded)
+}
+
+#[derive(Debug, Default)]
+pub struct Relocation {
+    /// scripts with a shebang which pointed into the old directory
+    pub fixed: Vec<PathBuf>,
+    /// executables and libraries with the old path compiled in, e.g.: an RPATH
+    pub unfixable: Vec<PathBuf>,
+}
+
+/// rewrites shebangs pointing into `from` to point into the current installs directory
+/// and logs what was changed to RTX_STATE_DIR/relocations.log
+pub fn relocate(from: &Path, dry_run: bool) -> Result<Relocation> {
+    let relocation = relocate_dir(&dirs::INSTALLS, from, &dirs::INSTALLS, dry_run)?;
+    if !dry_run {
+        log(from, &relocation)?;
+        file::write(marker_path(), dirs::INSTALLS.to_string_lossy().as_bytes())?;
+    }
+    Ok(relocation)
+}
+
+fn relocate_dir(dir: &Path, from: &Path, to: &Path, dry_run: bool) -> Result<Relocation> {
+    let from = from.to_string_lossy().to_string();
+    let to = to.to_string_lossy().to_string();
+    let mut relocation = Relocation::default();
+    let mut files = vec![];
+    list_files(dir, &mut files);
+    for path in files {
+        let Ok(bytes) = fs::read(&path) else {
+            continue;
+        };
+        if !contains(&bytes, from.as_bytes()) {
+            continue;
+        }
+        if bytes.starts_with(b"#!") {
+            let end = bytes
+                .iter()
+                .position(|b| *b == b'\n')
+                .unwrap_or(bytes.len());
+            let shebang = String::from_utf8_lossy(&bytes[..end]);
+            if shebang.contains(&from) {
+                if !dry_run {
+                    let mut contents = shebang.replace(&from, &to).into_bytes();
+                    contents.extend_from_slice(&bytes[end..]);
+                    file::write(&path, contents)?;
+                }
+                relocation.fixed.push(path);
+                continue;
+            }
+        }
+        // other text files (e.g.: pkg-config files) are left alone, they rarely break a tool
+        if file::is_executable(&path) {
+            relocation.unfixable.push(path);
+        }
+    }
+    Ok(relocation)
+}
+
+fn log(from: &Path, relocation: &Relocation) -> Result<()> {
+    file::create_dir_all(&*dirs::STATE)?;
+    let mut log = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dirs::STATE.join("relocations.log"))?;
+    writeln!(
+        log,
+        "relocated {} to {}",
+        from.display(),
+        dirs::INSTALLS.display()
+    )?;
+    for path in &relocation.fixed {
+        writeln!(log, "  fixed {}", path.display())?;
+    }
+    for path in &relocation.unfixable {
+        writeln!(log, "  not fixed {}", path.display())?;
+    }
+    Ok(())
+}
+
+/// symlinks are skipped, they point to files which are checked anyways
+fn list_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = dir.read_dir() else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            list_files(&entry.path(), files);
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relocate_dir() {
+        let dir = dirs::CACHE.join("test-relocate");
+        file::remove_all(&dir).unwrap();
+        file::create_dir_all(dir.join("bin")).unwrap();
+        let script = dir.join("bin/npm");
+        let binary = dir.join("bin/node");
+        file::write(
+            &script,
+            "#!/old/installs/node/20/bin/node\nrequire('/old/installs')\n",
+        )
+        .unwrap();
+        file::write(&binary, b"\x7fELF /old/installs/node/20/lib").unwrap();
+        file::make_executable(&binary).unwrap();
+        file::write(dir.join("bin/README"), "nothing to see").unwrap();
+
+        let relocation =
+            relocate_dir(&dir, Path::new("/old/installs"), Path::new("/new"), false).unwrap();
+        assert_eq!(relocation.fixed, vec![script.clone()]);
+        assert_eq!(relocation.unfixable, vec![binary]);
+        assert_eq!(
+            file::read_to_string(&script).unwrap(),
+            "#!/new/node/20/bin/node\nrequire('/old/installs')\n"
+        );
+        file::remove_all(&dir).unwrap();
+    }
+}
@@ -9,13 +9,35 @@ use versions::Versioning;
 
 use crate::config::Config;
 use crate::file::make_symlink;
+use crate::lock_file::LockFile;
+use crate::plugins::PluginName;
 use crate::tool::Tool;
+use crate::toolset::{ToolVersion, ToolVersionRequest};
 use crate::{dirs, file};
 
 pub fn rebuild(config: &Config) -> Result<()> {
-    for plugin in config.tools.values() {
+    rebuild_tools(config, None)
+}
+
+/// like `rebuild` but only touches the install directories of the given tools
+/// each tool is locked so concurrent rtx processes do not step on each other
+pub fn rebuild_tools(config: &Config, tools: Option<&[PluginName]>) -> Result<()> {
+    if *dirs::ROOT_READ_ONLY {
+        debug!("RTX_DATA_DIR is read-only, not updating runtime symlinks");
+        return Ok(());
+    }
+    let plugins = config
+        .tools
+        .values()
+        .filter(|p| tools.map_or(true, |tools| tools.contains(&p.name)));
+    for plugin in plugins {
+        let installs_dir = &plugin.installs_path;
+        let _lock = LockFile::new(installs_dir)
+            .with_callback(|l| {
+                trace!("runtime symlinks callback {}", l.display());
+            })
+            .lock()?;
         let symlinks = list_symlinks(config, plugin)?;
-        let installs_dir = dirs::INSTALLS.join(&plugin.name);
         for (from, to) in symlinks {
             let from = installs_dir.join(from);
             if from.exists() {
@@ -30,7 +52,7 @@ pub fn rebuild(config: &Config) -> Result<()> {
         }
         remove_missing_symlinks(plugin)?;
         // attempt to remove the installs dir (will fail if not empty)
-        let _ = file::remove_dir(&installs_dir);
+        let _ = file::remove_dir(installs_dir);
     }
     Ok(())
 }
@@ -79,7 +101,7 @@ fn installed_versions(plugin: &Tool) -> Result<Vec<String>> {
 }
 
 fn remove_missing_symlinks(plugin: &Tool) -> Result<()> {
-    let installs_dir = dirs::INSTALLS.join(&plugin.name);
+    let installs_dir = &plugin.installs_path;
     if !installs_dir.exists() {
         return Ok(());
     }
@@ -94,6 +116,56 @@ fn remove_missing_symlinks(plugin: &Tool) -> Result<()> {
     Ok(())
 }
 
+/// removes runtime symlinks which point at nothing in the install directories of every tool,
+/// including tools without a plugin anymore, and the install directories left empty.
+/// Returns the removed paths
+pub fn remove_orphans() -> Result<Vec<PathBuf>> {
+    if *dirs::ROOT_READ_ONLY || !dirs::INSTALLS.exists() {
+        return Ok(vec![]);
+    }
+    let mut removed = vec![];
+    for entry in dirs::INSTALLS.read_dir()? {
+        let installs_dir = entry?.path();
+        if !installs_dir.is_dir() {
+            continue;
+        }
+        let _lock = LockFile::new(&installs_dir)
+            .with_callback(|l| {
+                trace!("remove orphaned symlinks callback {}", l.display());
+            })
+            .lock()?;
+        for entry in installs_dir.read_dir()? {
+            let path = entry?.path();
+            if is_runtime_symlink(&path) && !path.exists() {
+                trace!("Removing orphaned symlink: {}", path.display());
+                file::remove_file(&path)?;
+                removed.push(path);
+            }
+        }
+        // attempt to remove the installs dir (will fail if not empty)
+        if file::remove_dir(&installs_dir).is_ok() {
+            removed.push(installs_dir);
+        }
+    }
+    Ok(removed)
+}
+
+/// the runtime symlink for the version `tv` was requested as, e.g.: installs/node/20 for node@20
+/// which resolved to 20.3.0. None if there is no symlink to that version, e.g.: because a newer
+/// 20.x is installed as well
+pub fn stable_path(tv: &ToolVersion) -> Option<PathBuf> {
+    let name = match &tv.request {
+        ToolVersionRequest::Version(_, v) | ToolVersionRequest::Prefix(_, v) => v,
+        _ => return None,
+    };
+    if name == &tv.version || name.contains('/') {
+        return None;
+    }
+    let path = tv.install_path().with_file_name(name);
+    let target = path.read_link().ok()?;
+    (is_runtime_symlink(&path) && target == Path::new(".").join(&tv.version)).then_some(path)
+}
+
 pub fn is_runtime_symlink(path: &Path) -> bool {
     if let Ok(link) = path.read_link() {
         return link.starts_with("./");
@@ -17,6 +17,8 @@ impl Shell for Bash {
         }
         out.push_str(&formatdoc! {r#"
             export RTX_SHELL=bash
+            export __RTX_SESSION=$$
+            unset __RTX_WATCH
 
             rtx() {{
               local command
@@ -52,6 +54,29 @@ impl Shell for Bash {
         out
     }
 
+    fn command_not_found_handler(&self) -> String {
+        formatdoc! {r#"
+            if declare -F command_not_found_handle >/dev/null && ! declare -F _rtx_orig_command_not_found_handle >/dev/null; then
+              eval "_rtx_orig_$(declare -f command_not_found_handle)"
+            fi
+            command_not_found_handle() {{
+              if [[ "$1" != "rtx" ]] && [[ "$1" != "rtx-"* ]]; then
+                command rtx hook-not-found -- "$@"
+                local status=$?
+                if [ $status != 127 ]; then
+                  return $status
+                fi
+              fi
+              if declare -F _rtx_orig_command_not_found_handle >/dev/null; then
+                _rtx_orig_command_not_found_handle "$@"
+                return $?
+              fi
+              printf "bash: %s: command not found\n" "$1" >&2
+              return 127
+            }}
+            "#}
+    }
+
     fn deactivate(&self) -> String {
         formatdoc! {r#"
             PROMPT_COMMAND="${{PROMPT_COMMAND//_rtx_hook;/}}"
@@ -109,4 +134,9 @@ mod tests {
         let deactivate = Bash::default().deactivate();
         assert_snapshot!(replace_path(&deactivate));
     }
+
+    #[test]
+    fn test_command_not_found_handler() {
+        assert_snapshot!(Bash::default().command_not_found_handler());
+    }
 }
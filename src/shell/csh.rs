@@ -0,0 +1,83 @@
+use std::path::Path;
+
+use indoc::formatdoc;
+
+use crate::shell::{is_dir_in_path, is_dir_not_in_nix, Shell};
+
+/// csh and tcsh
+///
+/// output is consumed with ``eval `rtx ...` `` which joins lines, so every command ends with ";"
+/// tcsh re-evaluates the environment before every prompt, plain csh only when activated
+#[derive(Default)]
+pub struct Csh {}
+
+fn csh_escape(input: &str) -> String {
+    let escaped = input.replace('\'', r#"'\''"#).replace('!', r"\!");
+    format!("'{escaped}'")
+}
+
+impl Shell for Csh {
+    fn activate(&self, exe: &Path, status: bool) -> String {
+        let dir = exe.parent().unwrap();
+        let exe = exe.to_string_lossy();
+        let status = if status { " --status" } else { "" };
+        let mut out = String::new();
+        if is_dir_not_in_nix(dir) && !is_dir_in_path(dir) {
+            out.push_str(&format!(
+                "setenv PATH {}:\"$PATH\";\n",
+                csh_escape(&dir.to_string_lossy())
+            ));
+        }
+        out.push_str(&formatdoc! {r#"
+            setenv RTX_SHELL csh;
+            setenv __RTX_SESSION $$;
+            unsetenv __RTX_WATCH;
+            alias precmd 'eval `{exe} hook-env{status} -s csh`';
+            eval `{exe} hook-env{status} -s csh`;
+            "#});
+        out
+    }
+
+    fn deactivate(&self) -> String {
+        formatdoc! {r#"
+            unalias precmd;
+            unsetenv RTX_SHELL;
+        "#}
+    }
+
+    fn set_env(&self, k: &str, v: &str) -> String {
+        format!("setenv {k} {};\n", csh_escape(v))
+    }
+
+    fn unset_env(&self, k: &str) -> String {
+        format!("unsetenv {k};\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+
+    #[test]
+    fn test_hook_init() {
+        let csh = Csh::default();
+        let exe = Path::new("/some/dir/rtx");
+        assert_snapshot!(csh.activate(exe, true));
+    }
+
+    #[test]
+    fn test_set_env() {
+        assert_snapshot!(Csh::default().set_env("FOO", "it's 1!"));
+    }
+
+    #[test]
+    fn test_unset_env() {
+        assert_snapshot!(Csh::default().unset_env("FOO"));
+    }
+
+    #[test]
+    fn test_deactivate() {
+        assert_snapshot!(Csh::default().deactivate());
+    }
+}
@@ -22,6 +22,8 @@ impl Shell for Fish {
         // https://github.com/direnv/direnv/blob/cb5222442cb9804b1574954999f6073cc636eff0/internal/cmd/shell_fish.go#L14-L36
         out.push_str(&formatdoc! {r#"
             set -gx RTX_SHELL fish
+            set -gx __RTX_SESSION $fish_pid
+            set -e __RTX_WATCH
 
             function rtx
               if test (count $argv) -eq 0
@@ -80,6 +82,28 @@ impl Shell for Fish {
         out
     }
 
+    fn command_not_found_handler(&self) -> String {
+        formatdoc! {r#"
+            if functions -q fish_command_not_found; and not functions -q __rtx_orig_fish_command_not_found
+              functions -c fish_command_not_found __rtx_orig_fish_command_not_found
+            end
+            function fish_command_not_found
+              if test "$argv[1]" != "rtx"; and not string match -q -- "rtx-*" $argv[1]
+                command rtx hook-not-found -- $argv
+                set -l exit_status $status
+                if test $exit_status -ne 127
+                  return $exit_status
+                end
+              end
+              if functions -q __rtx_orig_fish_command_not_found
+                __rtx_orig_fish_command_not_found $argv
+              else
+                __fish_default_command_not_found_handler $argv
+              end
+            end
+            "#}
+    }
+
     fn deactivate(&self) -> String {
         formatdoc! {r#"
           functions --erase __rtx_env_eval
@@ -4,6 +4,7 @@ use std::path::Path;
 use crate::env;
 
 mod bash;
+mod csh;
 mod fish;
 mod nushell;
 mod xonsh;
@@ -12,6 +13,8 @@ mod zsh;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
 pub enum ShellType {
     Bash,
+    #[value(alias = "tcsh")]
+    Csh,
     Fish,
     Nu,
     Xonsh,
@@ -23,6 +26,8 @@ impl ShellType {
         let shell = env::var("RTX_SHELL").or(env::var("SHELL")).ok()?;
         if shell.ends_with("bash") {
             Some(ShellType::Bash)
+        } else if shell.ends_with("csh") {
+            Some(ShellType::Csh)
         } else if shell.ends_with("fish") {
             Some(ShellType::Fish)
         } else if shell.ends_with("nu") {
@@ -41,6 +46,7 @@ impl Display for ShellType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Bash => write!(f, "bash"),
+            Self::Csh => write!(f, "csh"),
             Self::Fish => write!(f, "fish"),
             Self::Nu => write!(f, "nu"),
             Self::Xonsh => write!(f, "xonsh"),
@@ -50,7 +56,14 @@ impl Display for ShellType {
 }
 
 pub trait Shell {
+    /// besides the hooks, this exports __RTX_SESSION as the shell's pid and unsets __RTX_WATCH
+    /// so state inherited from a parent shell doesn't make the first hook-env exit early
     fn activate(&self, exe: &Path, status: bool) -> String;
+    /// handler that asks rtx whether an installed tool provides a command that was not found
+    /// shells without a command-not-found hook return an empty string
+    fn command_not_found_handler(&self) -> String {
+        String::new()
+    }
     fn deactivate(&self) -> String;
     fn set_env(&self, k: &str, v: &str) -> String;
     fn unset_env(&self, k: &str) -> String;
@@ -59,6 +72,7 @@ pub trait Shell {
 pub fn get_shell(shell: Option<ShellType>) -> Option<Box<dyn Shell>> {
     match shell.or_else(ShellType::load) {
         Some(ShellType::Bash) => Some(Box::<bash::Bash>::default()),
+        Some(ShellType::Csh) => Some(Box::<csh::Csh>::default()),
         Some(ShellType::Fish) => Some(Box::<fish::Fish>::default()),
         Some(ShellType::Nu) => Some(Box::<nushell::Nushell>::default()),
         Some(ShellType::Xonsh) => Some(Box::<xonsh::Xonsh>::default()),
@@ -39,6 +39,8 @@ impl Shell for Nushell {
         out.push_str(&formatdoc! {r#"
           export-env {{
             $env.RTX_SHELL = "nu"
+            $env.__RTX_SESSION = ($nu.pid | into string)
+            hide-env -i __RTX_WATCH
             
             $env.config = ($env.config | upsert hooks {{
                 pre_prompt: ($env.config.hooks.pre_prompt ++
@@ -49,15 +49,20 @@ impl Shell for Xonsh {
         // meanwhile, save variables twice: in shell env + in os env
         // use xonsh API instead of $.xsh to allow use inside of .py configs, which start faster due to being compiled to .pyc
         out.push_str(&formatdoc! {r#"
-            from os               import environ
+            from os               import environ, getpid
             from xonsh.built_ins  import XSH
 
+            envx = XSH.env
+            envx[   '__RTX_SESSION'] = str(getpid())
+            environ['__RTX_SESSION'] = envx.get_detyped('__RTX_SESSION')
+            envx.pop(   '__RTX_WATCH',None)
+            environ.pop('__RTX_WATCH',None)
+
         "#});
         if is_dir_not_in_nix(dir) && !is_dir_in_path(dir) {
             let dir_str = dir.to_string_lossy();
             let dir_esc = xonsh_escape_sq(&dir_str);
             out.push_str(&formatdoc! {r#"
-                envx = XSH.env
                 envx['PATH'].add('{dir_esc}')
                 environ['PATH'] = envx.get_detyped('PATH')
 
@@ -21,6 +21,8 @@ impl Shell for Zsh {
         }
         out.push_str(&formatdoc! {r#"
             export RTX_SHELL=zsh
+            export __RTX_SESSION=$$
+            unset __RTX_WATCH
 
             rtx() {{
               local command
@@ -59,6 +61,29 @@ impl Shell for Zsh {
         out
     }
 
+    fn command_not_found_handler(&self) -> String {
+        formatdoc! {r#"
+            if (( $+functions[command_not_found_handler] )) && ! (( $+functions[_rtx_orig_command_not_found_handler] )); then
+              functions[_rtx_orig_command_not_found_handler]=$functions[command_not_found_handler]
+            fi
+            command_not_found_handler() {{
+              if [[ "$1" != "rtx" ]] && [[ "$1" != "rtx-"* ]]; then
+                command rtx hook-not-found -- "$@"
+                local exit_status=$?
+                if [ $exit_status != 127 ]; then
+                  return $exit_status
+                fi
+              fi
+              if (( $+functions[_rtx_orig_command_not_found_handler] )); then
+                _rtx_orig_command_not_found_handler "$@"
+                return $?
+              fi
+              print -u2 "zsh: command not found: $1"
+              return 127
+            }}
+            "#}
+    }
+
     fn deactivate(&self) -> String {
         formatdoc! {r#"
         precmd_functions=( ${{precmd_functions:#_rtx_hook}} )
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use std::ffi::OsString;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -17,9 +17,10 @@ use crate::fake_asdf;
 use crate::file::{create_dir_all, remove_all};
 use crate::lock_file::LockFile;
 use crate::output::Output;
+use crate::plugins::PluginName;
 use crate::tool::Tool;
 use crate::toolset::{ToolVersion, Toolset, ToolsetBuilder};
-use crate::{dirs, file};
+use crate::{bin_providers, dirs, file, http, install_manifest, last_used};
 
 // executes as if it was a shim if the command is not "rtx", e.g.: "node"
 #[allow(dead_code)]
@@ -28,13 +29,21 @@ pub fn handle_shim(mut config: Config, args: &[String], out: &mut Output) -> Res
     if bin_name == "rtx" {
         return Ok(config);
     }
+    http::disable_network();
     let mut args: Vec<OsString> = args.iter().map(OsString::from).collect();
     args[0] = which_shim(&mut config, bin_name)?.into();
+    // which_shim already checked the shim's tool, the others aren't needed to run it
+    config.settings.exec_strict = false;
     let exec = Exec {
         tool: vec![],
         c: None,
         command: Some(args),
-        cd: None,
+        emit_env_file: None,
+        each: false,
+        snapshot: None,
+        docker: None,
+        docker_tools: None,
+        strict: false,
     };
     exec.run(config, out)?;
     exit(0);
@@ -46,9 +55,16 @@ fn which_shim(config: &mut Config, bin_name: &str) -> Result<PathBuf> {
         let ts = ToolsetBuilder::new().build(config)?;
         if let Some((p, tv)) = ts.which(config, bin_name) {
             if let Some(bin) = p.which(config, &tv, bin_name)? {
+                install_manifest::check(&config.settings, &tv)?;
+                last_used::touch(&tv);
                 return Ok(bin);
             }
         }
+        if config.settings.exec_strict {
+            // a missing version must not fall through to another one on PATH
+            let tvs = ts.list_rtvs_with_bin(config, bin_name)?;
+            err_missing_versions(config, &ts, bin_name, &tvs)?;
+        }
         // fallback for "system"
         for path in &*env::PATH {
             if fs::canonicalize(path).unwrap_or_default()
@@ -64,35 +80,60 @@ fn which_shim(config: &mut Config, bin_name: &str) -> Result<PathBuf> {
         let tvs = ts.list_rtvs_with_bin(config, bin_name)?;
         err_no_version_set(config, ts, bin_name, tvs)?;
     }
-    Err(eyre!("{} is not a valid shim", bin_name))
+    match bin_providers::hint(bin_name) {
+        Some(hint) => Err(eyre!("{} is not a valid shim\n{}", bin_name, hint)),
+        None => Err(eyre!("{} is not a valid shim", bin_name)),
+    }
 }
 
 pub fn reshim(config: &Config, ts: &Toolset) -> Result<()> {
+    reshim_tools(config, ts, None)
+}
+
+/// like `reshim` but only adds/removes the shims of the given tools
+/// if `tools` is None, the entire shim farm is rebuilt
+pub fn reshim_tools(config: &Config, ts: &Toolset, tools: Option<&[PluginName]>) -> Result<()> {
+    if *dirs::SHIMS_READ_ONLY {
+        debug!("{} is read-only, not updating shims", dirs::SHIMS.display());
+        return Ok(());
+    }
     let _lock = LockFile::new(&dirs::SHIMS)
         .with_callback(|l| {
             trace!("reshim callback {}", l.display());
         })
         .lock();
 
+    let in_scope = |name: &str| tools.map_or(true, |tools| tools.iter().any(|t| t == name));
     let rtx_bin = file::which("rtx").unwrap_or(env::RTX_EXE.clone());
 
     create_dir_all(&*dirs::SHIMS)?;
     let existing_shims = list_executables_in_dir(&dirs::SHIMS)?;
 
-    let shims: HashSet<String> = ts
-        .list_installed_versions(config)?
-        .into_par_iter()
-        .flat_map(|(t, tv)| match list_tool_bins(config, &t, &tv) {
-            Ok(paths) => paths,
-            Err(e) => {
-                warn!("Error listing bin paths for {}: {:#}", tv, e);
-                Vec::new()
-            }
-        })
+    let bins = list_bins(config, ts, in_scope)?;
+    let bypassed = |bin: &str| is_bypassed(&config.settings.shim_bypass, bin);
+    let shims: HashSet<String> = bins
+        .iter()
+        .map(|(bin, _)| bin.clone())
+        .filter(|bin| !bypassed(bin))
         .collect();
 
     let shims_to_add = shims.difference(&existing_shims);
-    let shims_to_remove = existing_shims.difference(&shims);
+    let shims_to_remove = match tools {
+        None => existing_shims.difference(&shims).collect_vec(),
+        Some(_) => {
+            // only remove shims that no tool outside of `tools` is known to provide
+            let providers = bin_providers::read_cache();
+            existing_shims
+                .difference(&shims)
+                .filter(|shim| {
+                    bypassed(shim)
+                        || providers
+                            .get(*shim)
+                            .map_or(false, |p| p.iter().all(|p| in_scope(p)))
+                })
+                .collect_vec()
+        }
+    };
 
     for shim in shims_to_add {
         let symlink_path = dirs::SHIMS.join(shim);
@@ -109,12 +150,18 @@ pub fn reshim(config: &Config, ts: &Toolset) -> Result<()> {
         let symlink_path = dirs::SHIMS.join(shim);
         remove_all(&symlink_path)?;
     }
-    for plugin in config.tools.values() {
+    if let Err(err) = bin_providers::update_cache(&bins) {
+        warn!("failed to update bin providers cache: {:#}", err);
+    }
+    for plugin in config.tools.values().filter(|p| in_scope(&p.name)) {
         match plugin.plugin_path.join("shims").read_dir() {
             Ok(files) => {
                 for bin in files {
                     let bin = bin?;
                     let bin_name = bin.file_name().into_string().unwrap();
+                    if bypassed(&bin_name) {
+                        continue;
+                    }
                     let symlink_path = dirs::SHIMS.join(bin_name);
                     make_shim(&bin.path(), &symlink_path)?;
                 }
@@ -128,8 +175,99 @@ pub fn reshim(config: &Config, ts: &Toolset) -> Result<()> {
     Ok(())
 }
 
+/// removes shims which no installed version of any tool provides, e.g.: left behind after
+/// uninstalling a version when the bin was thought to be provided by another tool as well,
+/// and shims which point at nothing. Returns the removed shims
+pub fn remove_orphans(config: &Config, ts: &Toolset) -> Result<Vec<PathBuf>> {
+    if *dirs::SHIMS_READ_ONLY || !dirs::SHIMS.exists() {
+        return Ok(vec![]);
+    }
+    let _lock = LockFile::new(&dirs::SHIMS)
+        .with_callback(|l| {
+            trace!("remove orphaned shims callback {}", l.display());
+        })
+        .lock()?;
+
+    let bins = list_bins(config, ts, |_| true)?;
+    let mut provided: HashSet<String> = bins.iter().map(|(bin, _)| bin.clone()).collect();
+    for plugin in config.tools.values() {
+        if let Ok(files) = plugin.plugin_path.join("shims").read_dir() {
+            provided.extend(
+                files
+                    .flatten()
+                    .filter_map(|f| f.file_name().into_string().ok()),
+            );
+        }
+    }
+    let mut removed = vec![];
+    for entry in dirs::SHIMS.read_dir()? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        let orphaned = !provided.contains(&name)
+            || is_bypassed(&config.settings.shim_bypass, &name)
+            || !path.exists(); // dangling symlink
+        if orphaned {
+            trace!("removing orphaned shim: {}", path.display());
+            remove_all(&path)?;
+            removed.push(path);
+        }
+    }
+    // every installed tool was listed so bins of uninstalled tools can be forgotten
+    if let Err(err) = bin_providers::write_cache(&bins) {
+        warn!("failed to update bin providers cache: {:#}", err);
+    }
+    Ok(removed)
+}
+
+/// lists the bins of the installed versions of the tools that are `in_scope`
+fn list_bins<F>(config: &Config, ts: &Toolset, in_scope: F) -> Result<Vec<(String, PluginName)>>
+where
+    F: Fn(&str) -> bool,
+{
+    let bins = ts
+        .list_installed_versions(config)?
+        .into_iter()
+        .filter(|(t, _)| in_scope(&t.name))
+        .collect_vec()
+        .into_par_iter()
+        .flat_map(|(t, tv)| match list_tool_bins(config, &t, &tv) {
+            Ok(paths) => paths.into_iter().map(|p| (p, t.name.clone())).collect(),
+            Err(e) => {
+                warn!("Error listing bin paths for {}: {:#}", tv, e);
+                Vec::new()
+            }
+        })
+        .collect();
+    Ok(bins)
+}
+
+/// true if `bin` matches one of the `shim_bypass` patterns, "*" matches any characters
+fn is_bypassed(patterns: &BTreeSet<String>, bin: &str) -> bool {
+    patterns.iter().any(|pattern| {
+        let mut parts = pattern.split('*');
+        let first = parts.next().unwrap_or_default();
+        let Some(mut rest) = bin.strip_prefix(first) else {
+            return false;
+        };
+        let parts = parts.collect_vec();
+        let Some((last, middle)) = parts.split_last() else {
+            return rest.is_empty(); // no "*" in pattern
+        };
+        for part in middle {
+            match rest.find(part) {
+                Some(i) => rest = &rest[i + part.len()..],
+                None => return false,
+            }
+        }
+        rest.ends_with(last)
+    })
+}
+
 // lists all the paths to bins in a tv that shims will be needed for
-fn list_tool_bins(config: &Config, t: &Tool, tv: &ToolVersion) -> Result<Vec<String>> {
+pub fn list_tool_bins(config: &Config, t: &Tool, tv: &ToolVersion) -> Result<Vec<String>> {
     Ok(t.list_bin_paths(config, tv)?
         .into_iter()
         .par_bridge()
@@ -190,6 +328,22 @@ fn err_no_version_set(
     if tvs.is_empty() {
         return Ok(());
     }
+    err_missing_versions(config, &ts, bin_name, &tvs)?;
+    let mut msg = format!("No version is set for shim: {}\n", bin_name);
+    msg.push_str("Set a global default version with one of the following:\n");
+    for tv in tvs {
+        msg.push_str(&format!("rtx use -g {}@{}\n", tv.plugin_name, tv.version));
+    }
+    Err(eyre!(msg.trim().to_string()))
+}
+
+/// errors if a version is set for any of the tools which provide bin_name but is not installed
+fn err_missing_versions(
+    config: &Config,
+    ts: &Toolset,
+    bin_name: &str,
+    tvs: &[ToolVersion],
+) -> Result<()> {
     let missing_plugins = tvs.iter().map(|tv| &tv.plugin_name).collect::<HashSet<_>>();
     let mut missing_tools = ts
         .list_missing_versions(config)
@@ -197,22 +351,32 @@ fn err_no_version_set(
         .filter(|t| missing_plugins.contains(&t.plugin_name))
         .collect_vec();
     if missing_tools.is_empty() {
-        let mut msg = format!("No version is set for shim: {}\n", bin_name);
-        msg.push_str("Set a global default version with one of the following:\n");
-        for tv in tvs {
-            msg.push_str(&format!("rtx use -g {}@{}\n", tv.plugin_name, tv.version));
-        }
-        Err(eyre!(msg.trim().to_string()))
-    } else {
-        let mut msg = format!(
-            "Tool{} not installed for shim: {}\n",
-            if missing_tools.len() > 1 { "s" } else { "" },
-            bin_name
-        );
-        for t in missing_tools.drain(..) {
-            msg.push_str(&format!("Missing tool version: {}\n", t));
-        }
-        msg.push_str("Install all missing tools with: rtx install\n");
-        Err(eyre!(msg.trim().to_string()))
+        return Ok(());
+    }
+    let mut msg = format!(
+        "Tool{} not installed for shim: {}\n",
+        if missing_tools.len() > 1 { "s" } else { "" },
+        bin_name
+    );
+    for t in missing_tools.drain(..) {
+        msg.push_str(&format!("Missing tool version: {}\n", t));
+    }
+    msg.push_str("Install all missing tools with: rtx install\n");
+    Err(eyre!(msg.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_bypassed() {
+        let patterns = BTreeSet::from(["python-config".to_string(), "*-config".to_string()]);
+        assert!(is_bypassed(&patterns, "python-config"));
+        assert!(is_bypassed(&patterns, "python3.11-config"));
+        assert!(!is_bypassed(&patterns, "python"));
+        let patterns = BTreeSet::from(["py*doc*".to_string()]);
+        assert!(is_bypassed(&patterns, "pydoc3"));
+        assert!(!is_bypassed(&patterns, "python"));
     }
 }
@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{bail, Result};
+
+use crate::config::Config;
+
+/// a task from `[tasks]` in .rtx.toml, run with `rtx run`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Task {
+    pub name: String,
+    pub description: Option<String>,
+    /// script run with `sh -c`, empty for tasks which only run their dependencies
+    pub run: String,
+    /// tasks to run before this one
+    pub depends: Vec<String>,
+    /// globs of the files the task reads, relative to its directory,
+    /// `rtx watch` runs it again when one of them changes
+    pub sources: Vec<String>,
+    /// the config file which defines it, tasks run in its directory
+    pub config_path: PathBuf,
+}
+
+impl Task {
+    pub fn dir(&self) -> &Path {
+        self.config_path.parent().unwrap()
+    }
+}
+
+/// tasks from all config files, a task in a config file closer to the current directory
+/// replaces one with the same name from a parent directory or the global config
+pub fn load(config: &Config) -> BTreeMap<String, Task> {
+    let mut tasks = BTreeMap::new();
+    for cf in config.config_files.values().rev() {
+        for task in cf.tasks() {
+            tasks.insert(task.name.clone(), task);
+        }
+    }
+    tasks
+}
+
+/// `name` and its dependencies in the order they need to run, each task only once
+pub fn run_order<'a>(tasks: &'a BTreeMap<String, Task>, name: &str) -> Result<Vec<&'a Task>> {
+    let mut order = vec![];
+    visit(tasks, name, &mut vec![], &mut order)?;
+    Ok(order)
+}
+
+fn visit<'a>(
+    tasks: &'a BTreeMap<String, Task>,
+    name: &str,
+    stack: &mut Vec<String>,
+    order: &mut Vec<&'a Task>,
+) -> Result<()> {
+    if order.iter().any(|t| t.name == name) {
+        return Ok(());
+    }
+    if stack.iter().any(|n| n == name) {
+        bail!("circular task dependency: {} -> {name}", stack.join(" -> "));
+    }
+    let Some(task) = tasks.get(name) else {
+        match stack.last() {
+            Some(parent) => bail!("task {parent} depends on {name} which is not defined"),
+            None => bail!("task {name} is not defined, see `rtx tasks ls`"),
+        }
+    };
+    stack.push(name.to_string());
+    for dep in &task.depends {
+        visit(tasks, dep, stack, order)?;
+    }
+    stack.pop();
+    order.push(task);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn tasks(defs: &[(&str, &[&str])]) -> BTreeMap<String, Task> {
+        defs.iter()
+            .map(|(name, depends)| {
+                let task = Task {
+                    name: name.to_string(),
+                    depends: depends.iter().map(|d| d.to_string()).collect(),
+                    ..Default::default()
+                };
+                (name.to_string(), task)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_run_order() {
+        let tasks = tasks(&[
+            ("build", &["lint", "codegen"]),
+            ("lint", &["codegen"]),
+            ("codegen", &[]),
+            ("test", &["build"]),
+        ]);
+        let order = run_order(&tasks, "test").unwrap();
+        let names = order.iter().map(|t| t.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["codegen", "lint", "build", "test"]);
+    }
+
+    #[test]
+    fn test_run_order_errors() {
+        let tasks = tasks(&[("a", &["b"]), ("b", &["a"]), ("c", &["missing"])]);
+        let err = run_order(&tasks, "a").unwrap_err();
+        assert_eq!(err.to_string(), "circular task dependency: a -> b -> a");
+        let err = run_order(&tasks, "c").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "task c depends on missing which is not defined"
+        );
+        assert!(run_order(&tasks, "nope").is_err());
+    }
+}
@@ -12,14 +12,16 @@ use itertools::Itertools;
 use regex::Regex;
 use versions::Versioning;
 
-use crate::config::{Config, Settings};
+use crate::config::{Config, Settings, VerifyInstalls};
 use crate::file::{display_path, remove_all, remove_all_with_warning};
-use crate::plugins::{ExternalPlugin, Plugin};
+use crate::hooks::Hook;
+use crate::plugins::{pathname, ExternalPlugin, Plugin};
+use crate::policy::Policy;
 use crate::runtime_symlinks::is_runtime_symlink;
-use crate::toolset::{ToolVersion, ToolVersionRequest};
+use crate::toolset::{ToolVersion, ToolVersionOptions, ToolVersionRequest};
 use crate::ui::multi_progress_report::MultiProgressReport;
 use crate::ui::progress_report::{ProgressReport, PROG_TEMPLATE};
-use crate::{dirs, file};
+use crate::{build_deps, dirs, file, hooks, install_manifest, last_used, relocate};
 
 pub struct Tool {
     pub name: String,
@@ -33,10 +35,10 @@ pub struct Tool {
 impl Tool {
     pub fn new(name: String, plugin: Box<dyn Plugin>) -> Self {
         Self {
-            plugin_path: dirs::PLUGINS.join(&name),
-            installs_path: dirs::INSTALLS.join(&name),
-            cache_path: dirs::CACHE.join(&name),
-            downloads_path: dirs::DOWNLOADS.join(&name),
+            plugin_path: dirs::PLUGINS.join(pathname(&name)),
+            installs_path: dirs::INSTALLS.join(pathname(&name)),
+            cache_path: dirs::CACHE.join(pathname(&name)),
+            downloads_path: dirs::DOWNLOADS.join(pathname(&name)),
             name,
             plugin,
         }
@@ -68,6 +70,10 @@ impl Tool {
         self.plugin.current_abbrev_ref()
     }
 
+    pub fn current_commit_date(&self) -> Result<String> {
+        self.plugin.current_commit_date()
+    }
+
     pub fn list_installed_versions(&self) -> Result<Vec<String>> {
         Ok(match self.installs_path.exists() {
             true => file::dir_subdirs(&self.installs_path)?
@@ -98,11 +104,44 @@ impl Tool {
         self.plugin.list_remote_versions(settings)
     }
 
+    pub fn list_remote_version_dates(
+        &self,
+        settings: &Settings,
+    ) -> Result<BTreeMap<String, String>> {
+        self.plugin.list_remote_version_dates(settings)
+    }
+
     pub fn list_versions_matching(&self, settings: &Settings, query: &str) -> Result<Vec<String>> {
         let versions = self.list_remote_versions(settings)?;
         self.fuzzy_match_filter(versions, query)
     }
 
+    /// like list_versions_matching but first applies the `version_filter` tool option if set
+    pub fn list_versions_matching_with_opts(
+        &self,
+        settings: &Settings,
+        query: &str,
+        opts: &ToolVersionOptions,
+    ) -> Result<Vec<String>> {
+        let versions = self.list_remote_versions(settings)?;
+        let versions = apply_version_filter(versions, opts)?;
+        self.fuzzy_match_filter(versions, query)
+    }
+
+    /// latest version respecting the `version_filter` tool option
+    /// without a filter this is the same as `latest_version(settings, None)`
+    pub fn latest_version_with_opts(
+        &self,
+        settings: &Settings,
+        opts: &ToolVersionOptions,
+    ) -> Result<Option<String>> {
+        if !opts.contains_key("version_filter") {
+            return self.latest_version(settings, None);
+        }
+        let matches = self.list_versions_matching_with_opts(settings, "latest", opts)?;
+        Ok(matches.last().cloned())
+    }
+
     pub fn latest_version(
         &self,
         settings: &Settings,
@@ -144,16 +183,21 @@ impl Tool {
         self.plugin.get_aliases(settings)
     }
 
+    pub fn get_aliases_source(&self) -> String {
+        self.plugin.get_aliases_source()
+    }
+
     pub fn legacy_filenames(&self, settings: &Settings) -> Result<Vec<String>> {
         self.plugin.legacy_filenames(settings)
     }
 
     fn latest_stable_version(&self, settings: &Settings) -> Result<Option<String>> {
         if let Some(latest) = self.plugin.latest_stable_version(settings)? {
-            Ok(Some(latest))
-        } else {
-            self.latest_version(settings, Some("latest".into()))
+            return Ok(Some(latest));
         }
+        // no bin/latest-stable, pick the highest stable version from the remote list
+        let versions = self.list_remote_versions(settings)?;
+        latest_stable_in_list(versions, settings.prerelease_regex.as_deref())
     }
 
     pub fn decorate_progress_bar(&self, pr: &mut ProgressReport, tv: Option<&ToolVersion>) {
@@ -165,8 +209,9 @@ impl Tool {
         pr.set_prefix(format!(
             "{} {} ",
             style("rtx").dim().for_stderr(),
-            style(tool).cyan().for_stderr(),
+            style(&tool).cyan().for_stderr(),
         ));
+        pr.set_tool(&tool);
         pr.enable_steady_tick();
     }
 
@@ -212,22 +257,39 @@ impl Tool {
         pr: &mut ProgressReport,
         force: bool,
     ) -> Result<()> {
-        if self.is_version_installed(tv) {
-            if force {
+        if !force && self.is_version_installed(tv) {
+            return Ok(());
+        }
+        // every install goes through here, checked on the resolved version since "latest" and
+        // fuzzy requests may resolve to a version the policy does not allow
+        if let Some(policy) = Policy::load()? {
+            policy.check(tv)?;
+        }
+        self.decorate_progress_bar(pr, Some(tv));
+        dirs::ensure_root_writable(&format!("installing {tv}"))?;
+        build_deps::check(&self.name)?;
+        // --force also takes the lock so it cannot remove files another install is using
+        let _lock = self.get_lock(&tv.install_path(), false)?;
+        if force {
+            if self.is_version_installed(tv) {
                 self.uninstall_version(config, tv, pr, false)?;
             } else {
-                return Ok(());
+                // leftovers from a failed or partial install would otherwise be reused
+                remove_all(tv.download_path())?;
+                remove_all(tv.cache_path())?;
             }
         }
-        self.decorate_progress_bar(pr, Some(tv));
-        let _lock = self.get_lock(&tv.install_path(), force)?;
         self.create_install_dirs(tv)?;
 
-        if let Err(e) = self.plugin.install_version(config, tv, pr) {
+        let hook_env = hook_env(tv);
+        let install = hooks::run(&config.settings, Hook::PreInstall, &self.name, &hook_env)
+            .and_then(|_| self.plugin.install_version(config, tv, pr));
+        if let Err(e) = install {
             self.cleanup_install_dirs_on_error(&config.settings, tv);
             return Err(e);
         }
         self.cleanup_install_dirs(&config.settings, tv);
+        hooks::run(&config.settings, Hook::PostInstall, &self.name, &hook_env)?;
         // attempt to touch all the .tool-version files to trigger updates in hook-env
         let mut touch_dirs = vec![dirs::ROOT.to_path_buf()];
         touch_dirs.extend(config.config_files.keys().cloned());
@@ -240,6 +302,17 @@ impl Tool {
         if let Err(err) = file::remove_file(self.incomplete_file_path(tv)) {
             debug!("error removing incomplete file: {:?}", err);
         }
+        if let Err(err) = relocate::record() {
+            debug!("error recording installs path: {:?}", err);
+        }
+        if config.settings.verify_installs != VerifyInstalls::Off {
+            let record = self
+                .list_bin_paths(config, tv)
+                .and_then(|bin_paths| install_manifest::record(tv, &bin_paths));
+            if let Err(err) = record {
+                warn!("failed to record install manifest for {tv}: {err:#}");
+            }
+        }
         pr.set_message("");
         pr.finish();
 
@@ -255,7 +328,10 @@ impl Tool {
     ) -> Result<()> {
         pr.set_message(format!("uninstall {tv}"));
 
+        let hook_env = hook_env(tv);
         if !dryrun {
+            dirs::ensure_root_writable(&format!("uninstalling {tv}"))?;
+            hooks::run(&config.settings, Hook::PreUninstall, &self.name, &hook_env)?;
             self.plugin.uninstall_version(config, tv)?;
         }
         let rmdir = |dir: &Path| {
@@ -271,6 +347,11 @@ impl Tool {
         rmdir(&tv.install_path())?;
         rmdir(&tv.download_path())?;
         rmdir(&tv.cache_path())?;
+        if !dryrun {
+            install_manifest::remove(tv)?;
+            last_used::remove(tv)?;
+            hooks::run(&config.settings, Hook::PostUninstall, &self.name, &hook_env)?;
+        }
         Ok(())
     }
 
@@ -282,11 +363,11 @@ impl Tool {
     ) -> Result<()> {
         self.plugin.ensure_installed(config, mpr, force)
     }
-    pub fn update(&self, git_ref: Option<String>) -> Result<()> {
-        self.plugin.update(git_ref)
+    pub fn update(&self, config: &Config, git_ref: Option<String>) -> Result<()> {
+        self.plugin.update(config, git_ref)
     }
-    pub fn uninstall(&self, pr: &ProgressReport) -> Result<()> {
-        self.plugin.uninstall(pr)
+    pub fn uninstall(&self, config: &Config, pr: &ProgressReport) -> Result<()> {
+        self.plugin.uninstall(config, pr)
     }
     pub fn purge(&self, pr: &ProgressReport) -> Result<()> {
         rmdir(&self.installs_path, pr)?;
@@ -360,11 +441,22 @@ impl Tool {
         }
     }
     fn cleanup_install_dirs(&self, settings: &Settings, tv: &ToolVersion) {
-        if !settings.always_keep_download && !settings.always_keep_install {
+        if !self.keep_download(settings) {
             let _ = remove_all_with_warning(tv.download_path());
         }
     }
 
+    /// never_keep_download_tools wins over the other settings, e.g.: to always discard python
+    /// build trees while keeping node tarballs with always_keep_download
+    fn keep_download(&self, settings: &Settings) -> bool {
+        if settings.never_keep_download_tools.contains(&self.name) {
+            return false;
+        }
+        settings.always_keep_download
+            || settings.always_keep_install
+            || settings.always_keep_download_tools.contains(&self.name)
+    }
+
     fn get_lock(&self, path: &Path, force: bool) -> Result<Option<fslock::LockFile>> {
         self.plugin.get_lock(path, force)
     }
@@ -375,16 +467,13 @@ impl Tool {
             query = "[0-9].*";
         }
         let query_regex = Regex::new(&format!("^{}([-.].+)?$", query))?;
-        let version_regex = regex!(
-            r"(^Available versions:|-src|-dev|-latest|-stm|[-\\.]rc|-milestone|-alpha|-beta|[-\\.]pre|-next|(a|b|c)[0-9]+|snapshot|master)"
-        );
         let versions = versions
             .into_iter()
             .filter(|v| {
                 if query == v {
                     return true;
                 }
-                if version_regex.is_match(v) {
+                if is_prerelease(v) {
                     return false;
                 }
                 query_regex.is_match(v)
@@ -423,6 +512,50 @@ impl Debug for Tool {
     }
 }
 
+fn is_prerelease(v: &str) -> bool {
+    let prerelease_regex = regex!(
+        r"(^Available versions:|-src|-dev|-latest|-stm|[-\\.]rc|-milestone|-alpha|-beta|[-\\.]pre|-next|(a|b|c)[0-9]+|snapshot|master)"
+    );
+    prerelease_regex.is_match(v)
+}
+
+/// highest version by semver that starts with a digit and is not a prerelease
+/// `prerelease_regex` replaces the built-in prerelease detection when set
+fn latest_stable_in_list(
+    versions: Vec<String>,
+    prerelease_regex: Option<&str>,
+) -> Result<Option<String>> {
+    let prerelease_regex = prerelease_regex
+        .map(|re| Regex::new(re).wrap_err_with(|| format!("invalid prerelease_regex: {}", re)))
+        .transpose()?;
+    let is_stable = |v: &str| match &prerelease_regex {
+        Some(re) => !re.is_match(v),
+        None => !is_prerelease(v),
+    };
+    let latest = versions
+        .into_iter()
+        .filter(|v| v.starts_with(|c: char| c.is_ascii_digit()) && is_stable(v))
+        .max_by_key(|v| Versioning::new(v).unwrap_or_default());
+    Ok(latest)
+}
+
+/// filters remote versions with the `version_filter` tool option which can either be a regex
+/// or "stable-only" to drop prereleases, so resolving a version the filter removes fails even
+/// when it is requested exactly, unless it is already installed
+/// e.g.: `node = {version = "20", version_filter = '^\d*[02468]\.'}`
+fn apply_version_filter(versions: Vec<String>, opts: &ToolVersionOptions) -> Result<Vec<String>> {
+    let versions = match opts.get("version_filter").map(|f| f.as_str()) {
+        None => versions,
+        Some("stable-only") => versions.into_iter().filter(|v| !is_prerelease(v)).collect(),
+        Some(filter) => {
+            let re = Regex::new(filter)
+                .wrap_err_with(|| format!("invalid version_filter regex: {}", filter))?;
+            versions.into_iter().filter(|v| re.is_match(v)).collect()
+        }
+    };
+    Ok(versions)
+}
+
 fn find_match_in_list(list: &[String], query: &str) -> Option<String> {
     let v = match list.contains(&query.to_string()) {
         true => Some(query.to_string()),
@@ -445,6 +578,17 @@ impl Ord for Tool {
 
 impl Eq for Tool {}
 
+/// vars describing the tool version to install/uninstall hooks
+fn hook_env(tv: &ToolVersion) -> [(&'static str, String); 2] {
+    [
+        ("RTX_INSTALL_VERSION", tv.version.clone()),
+        (
+            "RTX_INSTALL_PATH",
+            tv.install_path().to_string_lossy().to_string(),
+        ),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use crate::plugins::PluginName;
@@ -461,4 +605,43 @@ mod tests {
         assert!(debug.contains("installs_path"));
         assert!(debug.contains("plugin"));
     }
+
+    #[test]
+    fn test_apply_version_filter() {
+        let versions = || {
+            ["18.0.0", "19.0.0", "20.0.0", "21.0.0-rc.1"]
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+        };
+        let opts = |f: &str| ToolVersionOptions::from([("version_filter".into(), f.into())]);
+        assert_eq!(
+            apply_version_filter(versions(), &opts(r"^\d*[02468]\.")).unwrap(),
+            vec!["18.0.0", "20.0.0"]
+        );
+        assert_eq!(
+            apply_version_filter(versions(), &opts("stable-only")).unwrap(),
+            vec!["18.0.0", "19.0.0", "20.0.0"]
+        );
+        assert_eq!(
+            apply_version_filter(versions(), &ToolVersionOptions::new()).unwrap(),
+            versions()
+        );
+    }
+
+    #[test]
+    fn test_latest_stable_in_list() {
+        let versions = ["1.10.0", "1.9.0", "2.0.0-rc.1", "2.0.0-lts", "latest"]
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            latest_stable_in_list(versions.clone(), None).unwrap(),
+            Some("2.0.0-lts".to_string())
+        );
+        assert_eq!(
+            latest_stable_in_list(versions, Some("-")).unwrap(),
+            Some("1.10.0".to_string())
+        );
+    }
 }
@@ -5,7 +5,7 @@ use std::collections::BTreeMap;
 use crate::cli::args::tool::ToolArg;
 use crate::config::Config;
 use crate::env;
-use crate::toolset::{ToolSource, ToolVersionRequest, Toolset};
+use crate::toolset::{ToolSource, ToolVersionOptions, ToolVersionRequest, Toolset};
 use crate::ui::multi_progress_report::MultiProgressReport;
 
 #[derive(Debug, Default)]
@@ -83,18 +83,24 @@ impl ToolsetBuilder {
         if self.global_only {
             return;
         }
-        for (k, v) in env {
+        for (k, v) in &env {
             if k.starts_with("RTX_") && k.ends_with("_VERSION") && k != "RTX_VERSION" {
-                let plugin_name = k[4..k.len() - 8].to_lowercase();
+                let plugin_key = &k[4..k.len() - 8];
+                let plugin_name = plugin_key.to_lowercase();
                 if plugin_name == "install" {
                     // ignore RTX_INSTALL_VERSION
                     continue;
                 }
-                let source = ToolSource::Environment(k, v.clone());
+                // e.g.: RTX_NODE_OPTS=corepack=true,foo=bar
+                let opts = env
+                    .get(&format!("RTX_{plugin_key}_OPTS"))
+                    .map(|opts| parse_env_opts(opts))
+                    .unwrap_or_default();
+                let source = ToolSource::Environment(k.clone(), v.clone());
                 let mut env_ts = Toolset::new(source);
                 for v in v.split_whitespace() {
                     let tvr = ToolVersionRequest::new(plugin_name.clone(), v);
-                    env_ts.add_version(tvr, Default::default());
+                    env_ts.add_version(tvr, opts.clone());
                 }
                 ts.merge(&env_ts);
             }
@@ -116,3 +122,33 @@ impl ToolsetBuilder {
         }
     }
 }
+
+fn parse_env_opts(s: &str) -> ToolVersionOptions {
+    s.split(',')
+        .filter_map(|kv| kv.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_runtime_env_opts() {
+        let env = BTreeMap::from([
+            ("RTX_TINY_VERSION".to_string(), "2".to_string()),
+            (
+                "RTX_TINY_OPTS".to_string(),
+                "corepack=true, foo=bar".to_string(),
+            ),
+        ]);
+        let mut ts = Toolset::default();
+        ToolsetBuilder::new().load_runtime_env(&mut ts, env);
+        let tvl = ts.versions.get("tiny").unwrap();
+        assert_eq!(tvl.source.to_string(), "RTX_TINY_VERSION=2");
+        let (_, opts) = &tvl.requests[0];
+        assert_eq!(opts.get("corepack").unwrap(), "true");
+        assert_eq!(opts.get("foo").unwrap(), "bar");
+    }
+}
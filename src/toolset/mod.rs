@@ -1,11 +1,11 @@
-use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 use std::env::join_paths;
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
 
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{eyre, Result};
 use console::style;
 use dialoguer::theme::ColorfulTheme;
 use dialoguer::MultiSelect;
@@ -21,6 +21,8 @@ pub use tool_version_request::ToolVersionRequest;
 
 use crate::config::{Config, MissingRuntimeBehavior};
 use crate::env;
+use crate::eol;
+use crate::http;
 use crate::plugins::PluginName;
 use crate::runtime_symlinks;
 use crate::shims;
@@ -142,44 +144,142 @@ impl Toolset {
         mpr: &MultiProgressReport,
         force: bool,
     ) -> Result<()> {
+        for tv in &versions {
+            eol::check(&config.settings, tv)?;
+        }
+        // installs are always explicit, even when triggered from hook-env or a shim
+        http::enable_network();
         self.latest_versions = true;
-        let queue: Vec<_> = versions
-            .into_iter()
-            .group_by(|v| v.plugin_name.clone())
+        // versions of a tool are installed one after another by the same job, they can share
+        // plugin state (e.g.: a build tool checkout) which is not safe to use concurrently
+        let mut grouped: IndexMap<PluginName, Vec<ToolVersion>> = IndexMap::new();
+        for tv in versions {
+            grouped.entry(tv.plugin_name.clone()).or_default().push(tv);
+        }
+        let mut grouped: IndexMap<_, _> = grouped
             .into_iter()
-            .map(|(pn, v)| (config.get_or_create_tool(&pn), v.collect_vec()))
+            .map(|(pn, v)| (pn.clone(), (config.get_or_create_tool(&pn), v)))
             .collect();
-        for (t, _) in &queue {
+        let tools = grouped.keys().cloned().collect_vec();
+        for (t, _) in grouped.values() {
             if !t.is_installed() {
                 t.ensure_installed(config, Some(mpr), false)?;
             }
         }
-        let queue = Arc::new(Mutex::new(queue));
-        thread::scope(|s| {
-            (0..config.settings.jobs)
+        // plugins need to be installed to know what they depend on
+        let depends = self.install_dependencies(config, &tools);
+        let queue: VecDeque<_> = sort_by_dependencies(&tools, &depends)?
+            .into_iter()
+            .map(|pn| grouped.remove(&pn).unwrap())
+            .collect();
+        let jobs = config.settings.jobs.min(queue.len()).max(1);
+        let queue = Mutex::new(InstallQueue {
+            pending: queue,
+            finished: HashMap::new(),
+        });
+        let finished = Condvar::new();
+        let ts = &*self;
+        // a failed install skips the remaining versions of that tool and the tools which depend
+        // on it but not other tools
+        let mut errors = thread::scope(|s| {
+            (0..jobs)
                 .map(|_| {
-                    let queue = queue.clone();
+                    let (queue, finished, depends) = (&queue, &finished, &depends);
                     let config = &*config;
                     s.spawn(move || {
-                        let next_job = || queue.lock().unwrap().pop();
-                        while let Some((t, versions)) = next_job() {
+                        let next_job = || {
+                            let mut queue = queue.lock().unwrap();
+                            loop {
+                                if queue.pending.is_empty() {
+                                    return None;
+                                }
+                                if let Some(job) = queue.next_ready(depends) {
+                                    return Some(job);
+                                }
+                                queue = finished.wait(queue).unwrap();
+                            }
+                        };
+                        let mut errors = vec![];
+                        while let Some(((t, versions), failed_dep)) = next_job() {
+                            let mut ok = true;
                             for tv in versions {
-                                let tv = tv.request.resolve(config, &t, tv.opts.clone(), true)?;
-                                let mut pr = mpr.add();
-                                t.install_version(config, &tv, &mut pr, force)?;
+                                let install = || {
+                                    if let Some(dep) = &failed_dep {
+                                        return Err(eyre!("{dep} failed to install"));
+                                    }
+                                    let tv =
+                                        tv.request.resolve(config, &t, tv.opts.clone(), true)?;
+                                    let mut pr = mpr.add();
+                                    t.install_version(config, &tv, &mut pr, force)
+                                };
+                                if let Err(err) = install() {
+                                    errors.push(err.wrap_err(format!("failed to install {tv}")));
+                                    ok = false;
+                                    break;
+                                }
+                            }
+                            // dependents find it through the shims while they install
+                            let is_dependency = depends.values().any(|d| d.contains(&t.name));
+                            if ok && is_dependency {
+                                if let Err(err) = shims::reshim_tools(
+                                    config,
+                                    ts,
+                                    Some(std::slice::from_ref(&t.name)),
+                                ) {
+                                    warn!("failed to reshim {}: {err:#}", t.name);
+                                }
                             }
+                            queue.lock().unwrap().finished.insert(t.name.clone(), ok);
+                            finished.notify_all();
                         }
-                        Ok(())
+                        errors
                     })
                 })
                 .collect_vec()
                 .into_iter()
-                .map(|t| t.join().unwrap())
-                .collect::<Result<Vec<()>>>()
-        })?;
+                .flat_map(|t| t.join().unwrap())
+                .collect_vec()
+        });
         self.resolve(config);
-        shims::reshim(config, self)?;
-        runtime_symlinks::rebuild(config)
+        shims::reshim_tools(config, self, Some(&tools))?;
+        runtime_symlinks::rebuild_tools(config, Some(&tools))?;
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.pop().unwrap()),
+            n => Err(eyre!(
+                "{n} installs failed:\n{}",
+                errors.iter().map(|e| format!("  {e:#}")).join("\n")
+            )),
+        }
+    }
+    /// what each of `tools` depends on among `tools`, warns about dependencies which are not
+    /// being installed and have no versions installed
+    fn install_dependencies(
+        &self,
+        config: &mut Config,
+        tools: &[PluginName],
+    ) -> HashMap<PluginName, Vec<PluginName>> {
+        let mut depends = HashMap::new();
+        for name in tools {
+            let mut deps = config.get_or_create_tool(name).plugin.depends();
+            deps.retain(|dep| {
+                if tools.contains(dep) {
+                    return true;
+                }
+                let dep_tool = config.get_or_create_tool(dep);
+                let installed = dep_tool
+                    .list_installed_versions()
+                    .map_or(false, |v| !v.is_empty());
+                if !installed {
+                    warn!(
+                        "{name} needs {dep} to install, but no version of it is installed. Install it with: rtx use {dep}@latest"
+                    );
+                }
+                false
+            });
+            depends.insert(name.clone(), deps);
+        }
+        depends
     }
     pub fn list_missing_versions(&self, config: &Config) -> Vec<&ToolVersion> {
         self.versions
@@ -305,12 +405,33 @@ impl Toolset {
         entries
     }
     pub fn path_env(&self, config: &Config) -> String {
+        self.path_env_with(config, env::PATH.clone())
+    }
+    fn path_env_with(&self, config: &Config, base: Vec<PathBuf>) -> String {
         let installs = self.list_paths(config);
-        join_paths([config.path_dirs.clone(), installs, env::PATH.clone()].concat())
+        join_paths([config.path_dirs.clone(), installs, base].concat())
             .unwrap()
             .to_string_lossy()
             .into()
     }
+    /// the complete env for commands rtx runs, e.g.: `rtx exec` and tasks
+    /// with the env_clear setting only a few vars from the shell are kept and PATH only has the
+    /// tools and the system directories so the result does not depend on the user's shell setup
+    pub fn full_env(&self, config: &Config) -> BTreeMap<String, String> {
+        // vars which are not unicode can't be passed along with the others
+        let vars = env::vars_os()
+            .filter_map(|(k, v)| Some((k.into_string().ok()?, v.into_string().ok()?)));
+        if !config.settings.env_clear {
+            let mut env: BTreeMap<String, String> = vars.collect();
+            env.extend(self.env_with_path(config));
+            return env;
+        }
+        let mut env: BTreeMap<String, String> = vars.filter(|(k, _)| is_minimal_env(k)).collect();
+        env.extend(self.env(config));
+        let system_path = env::split_paths(MINIMAL_PATH).collect();
+        env.insert("PATH".into(), self.path_env_with(config, system_path));
+        env
+    }
     pub fn list_paths(&self, config: &Config) -> Vec<PathBuf> {
         self.list_current_installed_versions(config)
             .into_par_iter()
@@ -362,6 +483,82 @@ impl Display for Toolset {
     }
 }
 
+type InstallJob = (Arc<Tool>, Vec<ToolVersion>);
+
+/// PATH with the env_clear setting, before the directories of the tools
+const MINIMAL_PATH: &str = "/usr/local/bin:/usr/bin:/bin:/usr/sbin:/sbin";
+
+/// vars from the shell kept with the env_clear setting, anything else has to be set in [env]
+fn is_minimal_env(key: &str) -> bool {
+    const KEYS: &[&str] = &[
+        "HOME", "LANG", "LOGNAME", "PWD", "SHELL", "TERM", "TMPDIR", "TZ", "USER",
+    ];
+    KEYS.contains(&key) || key.starts_with("LC_")
+}
+
+/// tools waiting to be installed by `install_versions` and the result of those which are done
+struct InstallQueue {
+    pending: VecDeque<InstallJob>,
+    /// true if all versions installed
+    finished: HashMap<PluginName, bool>,
+}
+
+impl InstallQueue {
+    /// removes the first job whose dependencies are finished, along with the first of them
+    /// that failed
+    fn next_ready(
+        &mut self,
+        depends: &HashMap<PluginName, Vec<PluginName>>,
+    ) -> Option<(InstallJob, Option<PluginName>)> {
+        let idx = self.pending.iter().position(|(t, _)| {
+            depends[&t.name]
+                .iter()
+                .all(|d| self.finished.contains_key(d))
+        })?;
+        let job = self.pending.remove(idx).unwrap();
+        let failed_dep = depends[&job.0.name]
+            .iter()
+            .find(|d| !self.finished[*d])
+            .cloned();
+        Some((job, failed_dep))
+    }
+}
+
+/// orders tools so each comes after the tools it depends on, otherwise keeps their order
+fn sort_by_dependencies(
+    tools: &[PluginName],
+    depends: &HashMap<PluginName, Vec<PluginName>>,
+) -> Result<Vec<PluginName>> {
+    fn visit(
+        name: &PluginName,
+        depends: &HashMap<PluginName, Vec<PluginName>>,
+        stack: &mut Vec<PluginName>,
+        sorted: &mut Vec<PluginName>,
+    ) -> Result<()> {
+        if sorted.contains(name) {
+            return Ok(());
+        }
+        if stack.contains(name) {
+            return Err(eyre!(
+                "circular dependency between plugins: {} -> {name}",
+                stack.join(" -> ")
+            ));
+        }
+        stack.push(name.clone());
+        for dep in depends.get(name).into_iter().flatten() {
+            visit(dep, depends, stack, sorted)?;
+        }
+        stack.pop();
+        sorted.push(name.clone());
+        Ok(())
+    }
+    let mut sorted = vec![];
+    for name in tools {
+        visit(name, depends, &mut vec![], &mut sorted)?;
+    }
+    Ok(sorted)
+}
+
 fn display_versions(versions: &[ToolVersion]) -> String {
     let display_versions = versions
         .iter()
@@ -383,3 +580,37 @@ fn prompt_for_versions(versions: &[ToolVersion]) -> Result<Vec<ToolVersion>> {
         .map(|i| versions[i].clone())
         .collect())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_minimal_env() {
+        assert!(is_minimal_env("HOME"));
+        assert!(is_minimal_env("LC_ALL"));
+        assert!(!is_minimal_env("PATH"));
+        assert!(!is_minimal_env("AWS_SECRET_ACCESS_KEY"));
+    }
+
+    #[test]
+    fn test_sort_by_dependencies() {
+        let tools = ["elixir", "node", "erlang", "yarn"].map(String::from);
+        let depends = HashMap::from([
+            ("elixir".to_string(), vec!["erlang".to_string()]),
+            ("yarn".to_string(), vec!["node".to_string()]),
+        ]);
+        let sorted = sort_by_dependencies(&tools, &depends).unwrap();
+        assert_eq!(sorted, vec!["erlang", "elixir", "node", "yarn"]);
+
+        let depends = HashMap::from([
+            ("elixir".to_string(), vec!["erlang".to_string()]),
+            ("erlang".to_string(), vec!["elixir".to_string()]),
+        ]);
+        let err = sort_by_dependencies(&tools, &depends).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "circular dependency between plugins: elixir -> erlang -> elixir"
+        );
+    }
+}
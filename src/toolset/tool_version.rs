@@ -2,13 +2,13 @@ use std::fmt::{Display, Formatter};
 use std::fs;
 use std::path::PathBuf;
 
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{eyre, Report, Result};
 use versions::{Chunk, Version};
 
 use crate::config::Config;
 use crate::dirs;
 use crate::hash::hash_to_str;
-use crate::plugins::PluginName;
+use crate::plugins::{self, PluginName};
 use crate::tool::Tool;
 use crate::toolset::{ToolVersionOptions, ToolVersionRequest};
 
@@ -78,23 +78,31 @@ impl ToolVersion {
             ToolVersionRequest::Path(_, p) => p.to_string_lossy().to_string(),
             _ => self.tv_pathname(),
         };
-        dirs::INSTALLS.join(&self.plugin_name).join(pathname)
+        dirs::INSTALLS
+            .join(plugins::pathname(&self.plugin_name))
+            .join(pathname)
     }
     pub fn cache_path(&self) -> PathBuf {
-        dirs::CACHE.join(&self.plugin_name).join(self.tv_pathname())
+        dirs::CACHE
+            .join(plugins::pathname(&self.plugin_name))
+            .join(self.tv_pathname())
     }
     pub fn download_path(&self) -> PathBuf {
         dirs::DOWNLOADS
-            .join(&self.plugin_name)
+            .join(plugins::pathname(&self.plugin_name))
             .join(self.tv_pathname())
     }
     pub fn latest_version(&self, config: &Config, tool: &Tool) -> Result<String> {
+        if self.opts.get("pin").map_or(false, |v| v == "true") {
+            // pinned versions are never upgraded to a newer match of a fuzzy request
+            return Ok(self.version.clone());
+        }
         let tv = self
             .request
             .resolve(config, tool, self.opts.clone(), true)?;
         Ok(tv.version)
     }
-    fn tv_pathname(&self) -> String {
+    pub fn tv_pathname(&self) -> String {
         match &self.request {
             ToolVersionRequest::Version(_, _) => self.version.to_string(),
             ToolVersionRequest::Prefix(_, _) => self.version.to_string(),
@@ -148,7 +156,7 @@ impl ToolVersion {
                     return build(v);
                 }
             }
-            if let Some(v) = tool.latest_version(&config.settings, None)? {
+            if let Some(v) = tool.latest_version_with_opts(&config.settings, &opts)? {
                 return build(v);
             }
         }
@@ -161,7 +169,7 @@ impl ToolVersion {
                 return build(v.clone());
             }
         }
-        let matches = tool.list_versions_matching(&config.settings, &v)?;
+        let matches = tool.list_versions_matching_with_opts(&config.settings, &v, &opts)?;
         if matches.contains(&v) {
             return build(v);
         }
@@ -179,10 +187,12 @@ impl ToolVersion {
         opts: ToolVersionOptions,
     ) -> Result<Self> {
         let v = match v {
-            "latest" => tool.latest_version(&config.settings, None)?.unwrap(),
+            "latest" => tool
+                .latest_version_with_opts(&config.settings, &opts)?
+                .ok_or_else(|| no_versions_error(tool, v, &opts))?,
             _ => config.resolve_alias(&tool.name, v)?,
         };
-        let v = version_sub(&v, sub);
+        let v = version_sub(&v, sub)?;
         Self::resolve_version(config, tool, request, latest_versions, &v, opts)
     }
 
@@ -193,9 +203,13 @@ impl ToolVersion {
         prefix: &str,
         opts: ToolVersionOptions,
     ) -> Result<Self> {
-        let matches = tool.list_versions_matching(&config.settings, prefix)?;
+        let matches = tool.list_versions_matching_with_opts(&config.settings, prefix, &opts)?;
         let v = match matches.last() {
             Some(v) => v,
+            // the filter removed every match, using the request as-is would bypass it
+            None if opts.contains_key("version_filter") => {
+                return Err(no_versions_error(tool, prefix, &opts));
+            }
             None => prefix,
             // None => Err(VersionNotFound(plugin.name.clone(), prefix.to_string()))?,
         };
@@ -222,20 +236,33 @@ impl Display for ToolVersion {
     }
 }
 
+fn no_versions_error(tool: &Tool, query: &str, opts: &ToolVersionOptions) -> Report {
+    match opts.get("version_filter") {
+        Some(filter) => eyre!(
+            "no versions of {} match {query} with version_filter {filter}",
+            tool.name
+        ),
+        None => eyre!("no versions of {} match {query}", tool.name),
+    }
+}
+
 /// subtracts sub from orig and removes suffix
 /// e.g. version_sub("18.2.3", "2") -> "16"
 /// e.g. version_sub("18.2.3", "0.1") -> "18.1"
-fn version_sub(orig: &str, sub: &str) -> String {
-    let mut orig = Version::new(orig).unwrap();
-    let sub = Version::new(sub).unwrap();
-    while orig.chunks.0.len() > sub.chunks.0.len() {
-        orig.chunks.0.pop();
+/// errors if a chunk would go below 0, e.g. version_sub("3.11", "5")
+fn version_sub(orig: &str, sub: &str) -> Result<String> {
+    let err = || eyre!("cannot subtract {sub} from {orig}");
+    let mut v = Version::new(orig).ok_or_else(err)?;
+    let sub_v = Version::new(sub).ok_or_else(err)?;
+    while v.chunks.0.len() > sub_v.chunks.0.len() {
+        v.chunks.0.pop();
     }
-    for (i, orig_chunk) in orig.clone().chunks.0.iter().enumerate() {
-        let m = sub.nth(i).unwrap();
-        orig.chunks.0[i] = Chunk::Numeric(orig_chunk.single_digit().unwrap() - m);
+    for (i, orig_chunk) in v.clone().chunks.0.iter().enumerate() {
+        let m = sub_v.nth(i).ok_or_else(err)?;
+        let n = orig_chunk.single_digit().ok_or_else(err)?;
+        v.chunks.0[i] = Chunk::Numeric(n.checked_sub(m).ok_or_else(err)?);
     }
-    orig.to_string()
+    Ok(v.to_string())
 }
 
 #[cfg(test)]
@@ -246,7 +273,11 @@ mod tests {
 
     #[test]
     fn test_version_sub() {
-        assert_str_eq!(version_sub("18.2.3", "2"), "16");
-        assert_str_eq!(version_sub("18.2.3", "0.1"), "18.1");
+        assert_str_eq!(version_sub("18.2.3", "2").unwrap(), "16");
+        assert_str_eq!(version_sub("18.2.3", "0.1").unwrap(), "18.1");
+        assert_str_eq!(
+            version_sub("3.11", "5").unwrap_err().to_string(),
+            "cannot subtract 5 from 3.11"
+        );
     }
 }
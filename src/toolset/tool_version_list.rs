@@ -1,4 +1,5 @@
 use crate::config::Config;
+use crate::lockfile;
 use crate::toolset::tool_version_request::ToolVersionRequest;
 use crate::toolset::{ToolSource, ToolVersion, ToolVersionOptions};
 
@@ -30,6 +31,20 @@ impl ToolVersionList {
             }
         };
         for (tvr, opts) in &mut self.requests {
+            // `rtx upgrade` and `rtx outdated` look for newer versions than the locked ones
+            if !latest_versions {
+                let locked =
+                    lockfile::locked_version(&self.source, &self.plugin_name, &tvr.version());
+                if let Some(version) = locked {
+                    self.versions.push(ToolVersion::new(
+                        plugin,
+                        tvr.clone(),
+                        opts.clone(),
+                        version,
+                    ));
+                    continue;
+                }
+            }
             match tvr.resolve(config, plugin, opts.clone(), latest_versions) {
                 Ok(v) => self.versions.push(v),
                 Err(err) => warn!("failed to resolve tool version: {:#}", err),
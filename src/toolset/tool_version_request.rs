@@ -26,6 +26,8 @@ impl ToolVersionRequest {
     pub fn new(plugin_name: PluginName, s: &str) -> Self {
         let s = match s.split_once('-') {
             Some(("ref", r)) => format!("ref:{}", r),
+            // `latest-1` is shorthand for `sub-1:latest`
+            Some(("latest", sub)) if is_partial_version(sub) => format!("sub-{}:latest", sub),
             _ => s.to_string(),
         };
         match s.split_once(':') {
@@ -88,3 +90,33 @@ impl Display for ToolVersionRequest {
         write!(f, "{}@{}", self.plugin_name(), self.version())
     }
 }
+
+fn is_partial_version(s: &str) -> bool {
+    !s.is_empty()
+        && s.split('.')
+            .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_sub() {
+        let sub = |sub: &str, orig_version: &str| ToolVersionRequest::Sub {
+            plugin_name: "node".into(),
+            sub: sub.into(),
+            orig_version: orig_version.into(),
+        };
+        let tvr = |s| ToolVersionRequest::new("node".into(), s);
+        assert_eq!(tvr("sub-1:lts"), sub("1", "lts"));
+        assert_eq!(tvr("latest-1"), sub("1", "latest"));
+        assert_eq!(tvr("latest-0.1"), sub("0.1", "latest"));
+        assert_eq!(
+            tvr("latest-foo"),
+            ToolVersionRequest::Version("node".into(), "latest-foo".into())
+        );
+    }
+}
@@ -1,3 +1,4 @@
 pub mod multi_progress_report;
+pub mod progress_events;
 pub mod progress_report;
 pub mod prompt;
@@ -0,0 +1,66 @@
+use std::fs::File;
+use std::io::Write;
+use std::os::unix::io::FromRawFd;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use color_eyre::eyre::{bail, Result};
+use once_cell::sync::OnceCell;
+use serde_json::json;
+
+/// machine-readable progress enabled with `--progress-format json`
+/// one JSON object per line is written to the fd given by RTX_PROGRESS_FD
+static EVENTS: OnceCell<Mutex<File>> = OnceCell::new();
+
+pub fn enable(fd: i32) -> Result<()> {
+    if unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+        bail!("RTX_PROGRESS_FD={fd} is not an open file descriptor");
+    }
+    // the fd is owned by whoever started rtx, write to a copy so dropping the file does not close it
+    let dup = unsafe { libc::dup(fd) };
+    if dup == -1 {
+        bail!(
+            "failed to duplicate RTX_PROGRESS_FD={fd}: {}",
+            std::io::Error::last_os_error()
+        );
+    }
+    let file = unsafe { File::from_raw_fd(dup) };
+    let _ = EVENTS.set(Mutex::new(file));
+    Ok(())
+}
+
+/// event is one of "start", "message", "progress", "finish" or "error"
+/// percent is set once the size of the current phase (e.g.: a download) is known
+pub fn emit(event: &str, tool: &str, message: &str, percent: Option<u64>) {
+    let Some(events) = EVENTS.get() else {
+        return;
+    };
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let line = json!({
+        "event": event,
+        "tool": tool,
+        "message": console::strip_ansi_codes(message),
+        "percent": percent,
+        "time": time,
+    });
+    if let Ok(mut f) = events.lock() {
+        let _ = writeln!(f, "{line}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enable_closed_fd() {
+        let err = enable(999).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RTX_PROGRESS_FD=999 is not an open file descriptor"
+        );
+    }
+}
@@ -1,14 +1,21 @@
 use std::borrow::Cow;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 
 use console::style;
 use indicatif::{ProgressBar, ProgressStyle};
 use once_cell::sync::Lazy;
 
+use crate::ui::progress_events;
+
 #[derive(Debug)]
 pub struct ProgressReport {
     pub pb: Option<ProgressBar>,
     prefix: String,
+    tool: Option<String>,
+    /// for the percent of `--progress-format json` events, 0 when the size is unknown
+    length: AtomicU64,
+    percent: AtomicU64,
 }
 
 pub static PROG_TEMPLATE: Lazy<ProgressStyle> = Lazy::new(|| {
@@ -16,6 +23,14 @@ pub static PROG_TEMPLATE: Lazy<ProgressStyle> = Lazy::new(|| {
         .unwrap()
 });
 
+/// for phases with a known size, e.g.: downloads
+pub static BYTES_TEMPLATE: Lazy<ProgressStyle> = Lazy::new(|| {
+    ProgressStyle::with_template(
+        "{prefix}{wide_msg} {bytes}/{total_bytes} {binary_bytes_per_sec:.dim} eta {eta:.dim}",
+    )
+    .unwrap()
+});
+
 pub static SUCCESS_TEMPLATE: Lazy<ProgressStyle> = Lazy::new(|| {
     let tmpl = format!(
         "{{prefix}}{{wide_msg}} {} {{elapsed:3.dim.italic}}",
@@ -41,6 +56,28 @@ impl ProgressReport {
         ProgressReport {
             pb,
             prefix: String::new(),
+            tool: None,
+            length: AtomicU64::new(0),
+            percent: AtomicU64::new(0),
+        }
+    }
+
+    /// the tool or plugin this reports on, used for `--progress-format json` events
+    pub fn set_tool(&mut self, tool: &str) {
+        self.tool = Some(tool.to_string());
+        self.emit("start", "");
+    }
+
+    fn emit(&self, event: &str, message: &str) {
+        if let Some(tool) = &self.tool {
+            let percent = match event {
+                "finish" => Some(100),
+                _ if self.length.load(Ordering::Relaxed) > 0 => {
+                    Some(self.percent.load(Ordering::Relaxed))
+                }
+                _ => None,
+            };
+            progress_events::emit(event, tool, message, percent);
         }
     }
 
@@ -76,7 +113,38 @@ impl ProgressReport {
             None => (),
         }
     }
+    /// shows the bytes done out of length with the rate and ETA instead of the spinner until
+    /// reset_length is called
+    pub fn set_length(&self, length: u64) {
+        self.length.store(length, Ordering::Relaxed);
+        self.percent.store(0, Ordering::Relaxed);
+        if let Some(pb) = &self.pb {
+            pb.set_length(length);
+            pb.set_position(0);
+            pb.reset_eta();
+            pb.set_style(BYTES_TEMPLATE.clone());
+        }
+    }
+    pub fn set_position(&self, position: u64) {
+        let length = self.length.load(Ordering::Relaxed);
+        if let Some(percent) = (position.min(length) * 100).checked_div(length) {
+            if self.percent.swap(percent, Ordering::Relaxed) != percent {
+                self.emit("progress", "");
+            }
+        }
+        if let Some(pb) = &self.pb {
+            pb.set_position(position);
+        }
+    }
+    /// back to the spinner once the phase with a known size is done
+    pub fn reset_length(&self) {
+        self.length.store(0, Ordering::Relaxed);
+        if let Some(pb) = &self.pb {
+            pb.set_style(PROG_TEMPLATE.clone());
+        }
+    }
     pub fn set_message<S: AsRef<str>>(&self, message: S) {
+        self.emit("message", message.as_ref());
         match &self.pb {
             Some(pb) => pb.set_message(message.as_ref().replace('\r', "")),
             None => eprintln!("{}", message.as_ref()),
@@ -95,6 +163,7 @@ impl ProgressReport {
         }
     }
     pub fn error<S: AsRef<str>>(&self, message: S) {
+        self.emit("error", message.as_ref());
         match &self.pb {
             Some(pb) => {
                 self.set_message(format!(
@@ -109,6 +178,7 @@ impl ProgressReport {
         }
     }
     pub fn finish(&self) {
+        self.emit("finish", "");
         match &self.pb {
             Some(pb) => {
                 pb.set_style(SUCCESS_TEMPLATE.clone());
@@ -118,12 +188,14 @@ impl ProgressReport {
         }
     }
     pub fn finish_with_message(&self, message: impl Into<Cow<'static, str>>) {
+        let message = message.into();
+        self.emit("finish", &message);
         match &self.pb {
             Some(pb) => {
                 pb.set_style(SUCCESS_TEMPLATE.clone());
                 pb.finish_with_message(message);
             }
-            None => eprintln!("{}", message.into()),
+            None => eprintln!("{}", message),
         }
     }
     // pub fn clear(&self) {
@@ -144,6 +216,10 @@ mod tests {
         pr.set_prefix("prefix");
         assert_eq!(pr.prefix(), "prefix");
         pr.set_message("message");
+        pr.set_length(100);
+        pr.set_position(50);
+        assert_eq!(pr.pb.as_ref().unwrap().position(), 50);
+        pr.reset_length();
         pr.finish_with_message("message");
     }
 
@@ -153,6 +229,9 @@ mod tests {
         pr.set_prefix("prefix");
         assert_eq!(pr.prefix(), "prefix");
         pr.set_message("message");
+        pr.set_length(100);
+        pr.set_position(50);
+        pr.reset_length();
         pr.finish_with_message("message");
     }
 }
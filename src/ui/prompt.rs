@@ -2,6 +2,7 @@ use std::io;
 
 use dialoguer::Confirm;
 
+use crate::config::{ConfirmPolicy, Settings};
 use crate::env;
 
 pub fn confirm(message: &str) -> io::Result<bool> {
@@ -18,3 +19,72 @@ pub fn confirm(message: &str) -> io::Result<bool> {
         Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
     }
 }
+
+/// operations which are hard to undo, the confirm_policy setting decides which of them prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Destructive {
+    /// `rtx uninstall --all`
+    UninstallAll,
+    /// `rtx prune`
+    Prune,
+    /// `rtx implode`
+    Implode,
+    /// installing a plugin which is not in the shorthands
+    UnknownPlugin,
+    /// `rtx self-update`
+    #[cfg_attr(not(feature = "self_update"), allow(dead_code))]
+    SelfUpdate,
+}
+
+/// the answer for `op` if it is decided by the settings, None if the user should be asked
+pub fn preset_answer(settings: &Settings, op: Destructive) -> Option<bool> {
+    match settings.confirm_policy {
+        ConfirmPolicy::Yes => Some(true),
+        ConfirmPolicy::No => Some(false),
+        ConfirmPolicy::Paranoid => None,
+        // uninstall --all did not prompt before confirm_policy existed
+        ConfirmPolicy::Default if settings.yes || op == Destructive::UninstallAll => Some(true),
+        ConfirmPolicy::Default => None,
+    }
+}
+
+pub fn confirm_destructive(
+    settings: &Settings,
+    op: Destructive,
+    message: &str,
+) -> io::Result<bool> {
+    match preset_answer(settings, op) {
+        Some(answer) => Ok(answer),
+        None => confirm(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_answer() {
+        let mut settings = Settings {
+            yes: true,
+            ..Default::default()
+        };
+        assert_eq!(preset_answer(&settings, Destructive::Prune), Some(true));
+        settings.confirm_policy = ConfirmPolicy::Paranoid;
+        assert_eq!(preset_answer(&settings, Destructive::Prune), None);
+        assert_eq!(preset_answer(&settings, Destructive::UninstallAll), None);
+        settings.confirm_policy = ConfirmPolicy::No;
+        assert_eq!(
+            preset_answer(&settings, Destructive::SelfUpdate),
+            Some(false)
+        );
+
+        settings.yes = false;
+        settings.confirm_policy = ConfirmPolicy::Default;
+        assert_eq!(preset_answer(&settings, Destructive::Prune), None);
+        assert_eq!(
+            preset_answer(&settings, Destructive::UninstallAll),
+            Some(true)
+        );
+    }
+}
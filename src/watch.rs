@@ -0,0 +1,145 @@
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::Duration;
+
+use color_eyre::eyre::Result;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use notify::{RecursiveMode, Watcher};
+
+use crate::file::display_path;
+
+/// globs of the files to watch, e.g.: "/project/src/**/*.ts". `*` does not match `/`, `**` does.
+pub struct Sources {
+    patterns: Vec<String>,
+    globs: GlobSet,
+}
+
+impl Sources {
+    /// `sources` are (directory, glob relative to it) pairs
+    pub fn new<'a>(sources: impl IntoIterator<Item = (&'a Path, &'a str)>) -> Result<Self> {
+        let patterns = sources
+            .into_iter()
+            .map(|(dir, glob)| dir.join(glob).to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        let mut builder = GlobSetBuilder::new();
+        for pattern in &patterns {
+            builder.add(GlobBuilder::new(pattern).literal_separator(true).build()?);
+        }
+        Ok(Self {
+            globs: builder.build()?,
+            patterns,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    pub fn is_match(&self, path: &Path) -> bool {
+        self.globs.is_match(path)
+    }
+
+    /// the directories to watch recursively, the closest existing directory above each glob
+    fn roots(&self) -> Vec<PathBuf> {
+        let roots = self
+            .patterns
+            .iter()
+            .map(|p| {
+                let mut root = glob_root(p);
+                while !root.is_dir() && root.pop() {}
+                root
+            })
+            .collect::<BTreeSet<_>>();
+        // sorted so a directory comes before the directories inside it
+        let mut deduped: Vec<PathBuf> = vec![];
+        for root in roots {
+            if !deduped.iter().any(|r| root.starts_with(r)) {
+                deduped.push(root);
+            }
+        }
+        deduped
+    }
+}
+
+/// the part of a glob before the first component with a wildcard, e.g.: "/project/src" for
+/// "/project/src/**/*.ts"
+fn glob_root(pattern: &str) -> PathBuf {
+    Path::new(pattern)
+        .components()
+        .take_while(|c| match c {
+            Component::Normal(s) => !s.to_string_lossy().contains(['*', '?', '[', '{']),
+            _ => true,
+        })
+        .collect()
+}
+
+/// calls `on_change` with the changed files each time files matching `sources` change, changes
+/// are collected until none happen for `debounce`. Runs until `on_change` fails.
+pub fn watch<F>(sources: &Sources, debounce: Duration, mut on_change: F) -> Result<()>
+where
+    F: FnMut(Vec<PathBuf>) -> Result<()>,
+{
+    let (tx, rx) = channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    for root in sources.roots() {
+        debug!("watching {}", display_path(&root));
+        watcher.watch(&root, RecursiveMode::Recursive)?;
+    }
+    let mut changed = BTreeSet::new();
+    loop {
+        let event = if changed.is_empty() {
+            rx.recv().map_err(|_| RecvTimeoutError::Disconnected)
+        } else {
+            rx.recv_timeout(debounce)
+        };
+        match event {
+            Ok(event) => {
+                let event = event?;
+                if event.kind.is_access() {
+                    continue;
+                }
+                for path in event.paths {
+                    if sources.is_match(&path) {
+                        trace!("changed: {}", display_path(&path));
+                        changed.insert(path);
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                on_change(std::mem::take(&mut changed).into_iter().collect())?;
+                // changes made by the task itself are not a reason to run it again
+                while rx.try_recv().is_ok() {}
+            }
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_root() {
+        assert_eq!(
+            glob_root("/project/src/**/*.ts"),
+            PathBuf::from("/project/src")
+        );
+        assert_eq!(
+            glob_root("/project/package.json"),
+            PathBuf::from("/project/package.json")
+        );
+        assert_eq!(glob_root("/project/*.{js,ts}"), PathBuf::from("/project"));
+    }
+
+    #[test]
+    fn test_sources() {
+        let dir = Path::new("/project");
+        let sources = Sources::new([(dir, "src/*.ts"), (dir, "test/**/*.ts")]).unwrap();
+        assert!(sources.is_match(Path::new("/project/src/index.ts")));
+        assert!(!sources.is_match(Path::new("/project/src/lib/index.ts")));
+        assert!(sources.is_match(Path::new("/project/test/lib/index.test.ts")));
+        assert!(!sources.is_match(Path::new("/project/README.md")));
+    }
+}